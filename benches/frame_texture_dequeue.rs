@@ -46,7 +46,7 @@ async fn setup_benchmark() -> (Arc<Engine>, FrameTexture<RGBA8UNorm>) {
         mipmaps: false,
     };
 
-    let frame_texture = FrameTexture::<RGBA8UNorm>::new(&device, config, |_| Unorm4 {
+    let frame_texture = FrameTexture::<RGBA8UNorm>::new(device, config, |_| Unorm4 {
         r: 0,
         g: 0,
         b: 0,
@@ -98,7 +98,8 @@ async fn setup_benchmark() -> (Arc<Engine>, FrameTexture<RGBA8UNorm>) {
             false,
             false,
         ))
-        .await;
+        .await
+        .expect("Failed to add pass");
 
         port.force_render().await;
     }
@@ -130,6 +131,7 @@ async fn setup_benchmark() -> (Arc<Engine>, FrameTexture<RGBA8UNorm>) {
 use criterion::{Criterion, criterion_group, criterion_main};
 
 #[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::await_holding_refcell_ref)] // single-threaded executor, nothing else borrows
 fn bench_dequeue(c: &mut Criterion) {
     #[cfg(feature = "exfiltrate")]
     exfiltrate::begin();
@@ -305,7 +305,9 @@ async fn run_animated_rendering_with_engine_arc(
     // Step 4: Register render pass with engine
     logwise::info_sync!("Adding render pass to engine...");
     let port = engine.main_port();
-    port.add_fixed_pass(pass_descriptor).await;
+    port.add_fixed_pass(pass_descriptor)
+        .await
+        .expect("Failed to add pass");
 
     // Step 5: Animation loop with dynamic buffer updates
     logwise::info_sync!("Starting animation loop...");
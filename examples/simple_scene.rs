@@ -287,7 +287,7 @@ async fn run_rendering_with_engine_arc(
     // For real applications with vertex buffers, you would:
     // 1. Create vertex/index buffers: Buffer::new(device, data, usage, name, initial_fn)
     // 2. Define vertex layouts: VertexLayout describing attribute locations
-    // 3. Bind resources: bind_style.bind_static_vertex_buffer(buffer)
+    // 3. Bind resources: bind_style.bind_static_vertex_buffer(slot, buffer, layout, StepMode::Vertex)
     // 4. Reference in shaders: @location(0) position: vec3<f32>
 
     // Step 3: Create render pass descriptor with complete pipeline configuration
@@ -307,7 +307,9 @@ async fn run_rendering_with_engine_arc(
     logwise::info_sync!("About to add render pass to engine...");
     let port = engine.main_port(); // Get exclusive port access
     logwise::info_sync!("Got main port mut, about to add fixed pass...");
-    port.add_fixed_pass(pass_descriptor).await;
+    port.add_fixed_pass(pass_descriptor)
+        .await
+        .expect("Failed to add pass");
     logwise::info_sync!("Render pass added to engine successfully!");
 
     // Step 5: Execute main rendering loop with frame timing
@@ -47,13 +47,13 @@ at the cost of conversion overhead.
 - Most common pattern for rendering
 - Currently implemented
 
-**Reverse** (GPU→CPU) - *planned*:
+**Reverse** (GPU→CPU):
 - Read data back from GPU
-- For compute results, screenshots
+- For compute results, histograms, queries
 
-**Sideways** (GPU→GPU) - *planned*:
+**Sideways** (GPU→GPU):
 - Transfer between GPU resources
-- For render-to-texture, compute pipelines
+- For render-to-texture, post-processing, shadow maps
 
 **Omnidirectional** (CPU↔GPU) - *planned*:
 - Bidirectional data flow
@@ -80,6 +80,8 @@ This module is organized hierarchically by direction, then by mutability:
 - `forward/` - CPU to GPU transfers
   - `static/` - Immutable resources
   - `dynamic/` - Mutable resources
+- `reverse/` - GPU to CPU transfers
+- `sideways/` - GPU to GPU transfers
 - `software/` - CPU-side texture operations
 - Additional utilities for binding, visibility, and resource tracking
 
@@ -93,6 +95,8 @@ pub(crate) mod buffer_access;
 pub mod coordinates;
 pub(crate) mod dirty_tracking;
 pub(crate) mod resource_tracking;
+pub mod reverse;
 pub mod sampler;
+pub mod sideways;
 pub mod software;
 pub mod visible_to;
@@ -9,10 +9,13 @@
 //! # Key Concepts
 //!
 //! - **Bind Slots**: Resources are bound to numbered slots that correspond to binding
-//!   locations in shaders
-//! - **Shader Stages**: Resources can be bound to vertex or fragment shader stages
-//! - **Resource Types**: Supports static/dynamic buffers, textures, samplers, and
-//!   special bindings like camera matrices and frame counters
+//!   locations in shaders, in `@group(0)` unless the slot names another group
+//! - **Global Group**: The port's camera, frame counter and time can be bound as one
+//!   shared group with [`BindStyle::bind_global_group`]
+//! - **Shader Stages**: Resources can be bound to vertex, fragment, or compute shader stages
+//! - **Resource Types**: Supports static/dynamic buffers, textures, samplers, reverse
+//!   buffers written by shaders, render textures written by earlier passes, and special
+//!   bindings like camera matrices, frame counters and time
 //!
 //! # Example
 //!
@@ -44,9 +47,10 @@
 use crate::bindings::forward::dynamic::buffer::ErasedRenderSide;
 use crate::bindings::forward::dynamic::frame_texture::ErasedTextureRenderSide;
 use crate::bindings::sampler::SamplerType;
+use crate::bindings::visible_to::GPUBufferUsage;
 use std::collections::HashMap;
 use std::fmt::Debug;
-/// Describes how resources are bound for a render pass.
+/// Describes how resources are bound for a render or compute pass.
 ///
 /// This struct collects all resource bindings that will be used during rendering.
 /// It maintains a mapping of bind slots to resources and tracks special bindings
@@ -57,8 +61,11 @@ use std::fmt::Debug;
 /// the actual GPU bindings.
 #[derive(Debug, Clone, PartialEq)]
 pub struct BindStyle {
-    pub(crate) binds: HashMap<u32, BindInfo>,
-    pub(crate) index_buffer: Option<crate::imp::GPUableBufferStatic>,
+    pub(crate) binds: HashMap<BindSlot, BindInfo>,
+    pub(crate) index_buffer: Option<IndexBinding>,
+    pub(crate) indirect_buffer: Option<IndirectBinding>,
+    /// The group the port's global group is bound to, if any.
+    pub(crate) global_group: Option<u32>,
 }
 
 /// Internal enumeration of all possible binding targets.
@@ -75,6 +82,8 @@ pub(crate) enum BindTarget {
     Camera,
     /// A frame counter that increments each frame
     FrameCounter,
+    /// The seconds since the port was created, as an `f32`
+    Time,
     /// A dynamic texture that can be updated between frames
     DynamicTexture(ErasedTextureRenderSide),
     /// A static texture with optional sampler configuration
@@ -83,12 +92,106 @@ pub(crate) enum BindTarget {
     /// A texture sampler configuration
     #[allow(dead_code)] //nop implementation does not use
     Sampler(SamplerType),
-    /// A static vertex buffer with its layout description
+    /// A static vertex buffer with its layout description and step mode
     #[allow(dead_code)] //nop implementation does not use
-    VB(VertexLayout, crate::imp::GPUableBufferStatic),
-    /// A dynamic vertex buffer with its layout description
+    VB(VertexLayout, StepMode, crate::imp::GPUableBufferStatic),
+    /// A dynamic vertex buffer with its layout description and step mode
     #[allow(dead_code)] //nop implementation does not use
-    DynamicVB(VertexLayout, ErasedRenderSide),
+    DynamicVB(VertexLayout, StepMode, ErasedRenderSide),
+    /// A storage buffer that shaders write and the CPU reads back
+    ReverseBuffer(crate::bindings::reverse::buffer::RenderSide),
+    /// The color attachment of a render texture, rendered by an earlier pass
+    #[allow(dead_code)] //nop implementation does not use
+    RenderTexture(crate::imp::RenderTexture, Option<SamplerType>),
+    /// The depth attachment of a render texture, rendered by an earlier pass
+    #[allow(dead_code)] //nop implementation does not use
+    RenderTextureDepth(crate::imp::RenderTexture),
+}
+
+impl BindTarget {
+    /// Returns the render texture this binding reads, if any.
+    pub(crate) fn render_texture(&self) -> Option<&crate::imp::RenderTexture> {
+        match self {
+            BindTarget::RenderTexture(texture, _) | BindTarget::RenderTextureDepth(texture) => {
+                Some(texture)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The index buffer of a pass, with the format of its indices.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IndexBinding {
+    pub(crate) target: IndexTarget,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) format: IndexFormat,
+}
+
+/// The buffer an index buffer binding reads.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum IndexTarget {
+    /// A static buffer that doesn't change during rendering
+    #[allow(dead_code)] //nop implementation does not use
+    Static(crate::imp::GPUableBufferStatic),
+    /// A dynamic buffer that can be updated between frames
+    Dynamic(ErasedRenderSide),
+}
+
+/// The buffer an indirect draw reads its arguments from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IndirectBinding {
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) buffer: crate::imp::GPUableBufferStatic,
+    /// Whether the buffer holds [`DrawIndexedIndirectArgs`](crate::images::render_pass::DrawIndexedIndirectArgs).
+    pub(crate) indexed: bool,
+    /// The number of arguments the buffer holds.
+    pub(crate) len: usize,
+}
+
+pub(crate) use sealed::IndexFormat;
+
+pub(crate) mod sealed {
+    /// The size of each index in an index buffer.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum IndexFormat {
+        Uint16,
+        Uint32,
+    }
+
+    /// Types that can be used as indices in an index buffer.
+    ///
+    /// This trait is sealed; it is implemented for `u16` and `u32`.
+    pub trait IndexElement: Send + Sync + 'static {
+        #[allow(dead_code)] //nop implementation does not use
+        const FORMAT: IndexFormat;
+    }
+
+    impl IndexElement for u16 {
+        const FORMAT: IndexFormat = IndexFormat::Uint16;
+    }
+
+    impl IndexElement for u32 {
+        const FORMAT: IndexFormat = IndexFormat::Uint32;
+    }
+
+    /// Types that hold the arguments of an indirect draw.
+    ///
+    /// This trait is sealed; it is implemented for
+    /// [`DrawIndirectArgs`](crate::images::render_pass::DrawIndirectArgs) and
+    /// [`DrawIndexedIndirectArgs`](crate::images::render_pass::DrawIndexedIndirectArgs).
+    pub trait IndirectArgs: Send + Sync + 'static {
+        /// Whether the arguments draw from an index buffer.
+        const INDEXED: bool;
+    }
+
+    impl IndirectArgs for crate::images::render_pass::DrawIndirectArgs {
+        const INDEXED: bool = false;
+    }
+
+    impl IndirectArgs for crate::images::render_pass::DrawIndexedIndirectArgs {
+        const INDEXED: bool = true;
+    }
 }
 
 /// Information about a single resource binding.
@@ -99,6 +202,17 @@ pub(crate) struct BindInfo {
     #[allow(dead_code)] //nop implementation does not use
     pub(crate) stage: Stage,
     pub(crate) target: BindTarget,
+    /// The bound buffer's usage and sizes, or `None` if the target is not a buffer.
+    pub(crate) buffer: Option<BufferInfo>,
+}
+
+/// The usage and sizes of a bound buffer, which shaders are checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BufferInfo {
+    pub(crate) usage: GPUBufferUsage,
+    /// The size of each element, `size_of::<Element>()`.
+    pub(crate) element_size: usize,
+    pub(crate) byte_size: usize,
 }
 
 /// Configuration for a texture sampler binding.
@@ -127,21 +241,119 @@ impl BindStyle {
         BindStyle {
             binds: HashMap::new(),
             index_buffer: None,
+            indirect_buffer: None,
+            global_group: None,
         }
     }
 
+    /// Returns how many bind groups the pass uses, including empty groups below the
+    /// highest group bound.
+    ///
+    /// Vertex buffers are not part of any bind group, so their slots don't count.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn group_count(&self) -> u32 {
+        self.binds
+            .iter()
+            .filter(|(_, info)| {
+                !matches!(info.target, BindTarget::VB(..) | BindTarget::DynamicVB(..))
+            })
+            .map(|(slot, _)| slot.group + 1)
+            .chain(self.global_group.map(|group| group + 1))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the slots of the vertex buffers, in the order they are assigned to the
+    /// vertex shader.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn vertex_buffer_slots(&self) -> Vec<BindSlot> {
+        let mut slots: Vec<BindSlot> = self
+            .binds
+            .iter()
+            .filter(|(_, info)| {
+                matches!(info.target, BindTarget::VB(..) | BindTarget::DynamicVB(..))
+            })
+            .map(|(slot, _)| *slot)
+            .collect();
+        slots.sort_unstable();
+        slots
+    }
+
     /// Internal method to bind a resource to a slot.
     ///
     /// # Panics
     ///
-    /// Panics if a resource is already bound to the specified slot.
+    /// Panics if a resource is already bound to the specified slot, or if the slot is in
+    /// the global group.
     fn bind(&mut self, slot: BindSlot, stage: Stage, target: BindTarget) {
-        let old = self
-            .binds
-            .insert(slot.pass_index, BindInfo { stage, target });
+        self.bind_info(
+            slot,
+            BindInfo {
+                stage,
+                target,
+                buffer: None,
+            },
+        );
+    }
+
+    /// Like [`bind`](Self::bind), for targets that are buffers.
+    fn bind_buffer(
+        &mut self,
+        slot: BindSlot,
+        stage: Stage,
+        target: BindTarget,
+        buffer: BufferInfo,
+    ) {
+        self.bind_info(
+            slot,
+            BindInfo {
+                stage,
+                target,
+                buffer: Some(buffer),
+            },
+        );
+    }
+
+    fn bind_info(&mut self, slot: BindSlot, info: BindInfo) {
+        assert!(
+            self.global_group != Some(slot.group),
+            "{slot} is in the global group"
+        );
+        let old = self.binds.insert(slot, info);
         assert!(old.is_none(), "Already bound to slot {slot:?}");
     }
 
+    /// Binds the port's global group to `group`.
+    ///
+    /// The global group holds the resources every pass shares, and is bound once for
+    /// all the passes of a port that use it, rather than with each pass:
+    ///
+    /// ```wgsl
+    /// @group(1) @binding(0) var<uniform> camera: mat4x4<f32>;
+    /// @group(1) @binding(1) var<uniform> frame: u32;
+    /// @group(1) @binding(2) var<uniform> time: f32;
+    /// ```
+    ///
+    /// These are the same values as [`bind_camera_matrix`](Self::bind_camera_matrix),
+    /// [`bind_frame_counter`](Self::bind_frame_counter) and [`bind_time`](Self::bind_time),
+    /// and are visible to every shader stage.  Shaders may declare only the bindings they
+    /// use.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the global group is already bound, or if a resource is bound in `group`.
+    pub fn bind_global_group(&mut self, group: u32) {
+        assert!(
+            self.global_group.is_none(),
+            "Already bound the global group"
+        );
+        assert!(
+            self.binds.keys().all(|slot| slot.group != group),
+            "Already bound resources in group {group}"
+        );
+        self.global_group = Some(group);
+    }
+
     /// Binds the camera transformation matrix to the specified slot.
     ///
     /// The camera matrix is a special binding that is resolved at render time
@@ -173,6 +385,25 @@ impl BindStyle {
         self.bind(slot, stage, BindTarget::FrameCounter);
     }
 
+    /// Binds the time to the specified slot.
+    ///
+    /// The time is an `f32` holding the seconds since the port was created, sampled
+    /// once at the start of each frame.  Like the frame counter, it does not cause
+    /// frames to render on its own.
+    ///
+    /// # Parameters
+    ///
+    /// * `slot` - The binding slot to use
+    /// * `stage` - The shader stage where the time will be accessible
+    pub fn bind_time(&mut self, slot: BindSlot, stage: Stage) {
+        self.bind(slot, stage, BindTarget::Time);
+    }
+
+    /// Binds a sampler to the specified slot, apart from any texture.
+    pub(crate) fn bind_sampler(&mut self, slot: BindSlot, stage: Stage, sampler: SamplerType) {
+        self.bind(slot, stage, BindTarget::Sampler(sampler));
+    }
+
     /// Binds a static buffer to the specified slot.
     ///
     /// Static buffers contain data that doesn't change during rendering. They are
@@ -193,7 +424,12 @@ impl BindStyle {
         stage: Stage,
         buffer: &crate::bindings::forward::r#static::buffer::Buffer<Element>,
     ) {
-        self.bind(slot, stage, BindTarget::StaticBuffer(buffer.imp.clone()));
+        self.bind_buffer(
+            slot,
+            stage,
+            BindTarget::StaticBuffer(buffer.imp.clone()),
+            buffer.buffer_info(),
+        );
     }
 
     /// Binds a dynamic buffer to the specified slot.
@@ -219,13 +455,44 @@ impl BindStyle {
     ) where
         Element: Send + Sync + 'static,
     {
-        self.bind(
+        self.bind_buffer(
             slot,
             stage,
             BindTarget::DynamicBuffer(buffer.render_side().erased_render_side()),
+            buffer.buffer_info(),
         );
     }
 
+    /// Binds a reverse buffer to the specified slot.
+    ///
+    /// Reverse buffers are read-write storage buffers that shaders write into. After
+    /// each frame, the results are copied back so the CPU can read them with
+    /// [`Buffer::access_read`](crate::bindings::reverse::buffer::Buffer::access_read).
+    ///
+    /// # Parameters
+    ///
+    /// * `slot` - The binding slot to use
+    /// * `stage` - The shader stage that writes the buffer
+    /// * `buffer` - The reverse buffer to bind
+    ///
+    /// # Type Parameters
+    ///
+    /// * `Element` - The type of elements stored in the buffer
+    pub fn bind_reverse_buffer<Element>(
+        &mut self,
+        slot: BindSlot,
+        stage: Stage,
+        buffer: &crate::bindings::reverse::buffer::Buffer<Element>,
+    ) {
+        let render_side = buffer.render_side();
+        let info = BufferInfo {
+            usage: GPUBufferUsage::ShaderReadWrite,
+            element_size: render_side.element_size,
+            byte_size: render_side.byte_size,
+        };
+        self.bind_buffer(slot, stage, BindTarget::ReverseBuffer(render_side), info);
+    }
+
     /// Binds a static texture to the specified slot.
     ///
     /// Static textures contain image data that doesn't change during rendering.
@@ -238,7 +505,8 @@ impl BindStyle {
     /// * `stage` - The shader stage where the texture will be accessible
     /// * `texture` - The static texture to bind
     /// * `sampler_type` - Optional sampler configuration. If provided, the sampler
-    ///   will be bound to the slot specified in `SamplerInfo::pass_index`
+    ///   will be bound to the slot specified in `SamplerInfo::pass_index`, in the
+    ///   texture's group
     ///
     /// # Type Parameters
     ///
@@ -260,13 +528,86 @@ impl BindStyle {
         );
         if let Some(sampler) = sampler_type {
             self.bind(
-                BindSlot::new(sampler.pass_index),
+                BindSlot::in_group(slot.group, sampler.pass_index),
+                stage,
+                BindTarget::Sampler(sampler.sampler_type),
+            );
+        }
+    }
+
+    /// Binds the color attachment of a render texture to the specified slot.
+    ///
+    /// Render textures are rendered by earlier passes in the same frame; the port runs
+    /// the passes that render into `texture` before this one.  As with static textures,
+    /// you can optionally specify sampler settings.
+    ///
+    /// # Parameters
+    ///
+    /// * `slot` - The binding slot for the texture
+    /// * `stage` - The shader stage where the texture will be accessible
+    /// * `texture` - The render texture to bind
+    /// * `sampler_type` - Optional sampler configuration. If provided, the sampler
+    ///   will be bound to the slot specified in `SamplerInfo::pass_index`, in the
+    ///   texture's group
+    ///
+    /// # Type Parameters
+    ///
+    /// * `Format` - The pixel format of the texture
+    pub fn bind_render_texture<Format>(
+        &mut self,
+        slot: BindSlot,
+        stage: Stage,
+        texture: &crate::bindings::sideways::RenderTexture<Format>,
+        sampler_type: Option<SamplerInfo>,
+    ) {
+        self.bind(
+            slot,
+            stage,
+            BindTarget::RenderTexture(
+                texture.imp.clone(),
+                sampler_type.as_ref().map(|x| x.sampler_type),
+            ),
+        );
+        if let Some(sampler) = sampler_type {
+            self.bind(
+                BindSlot::in_group(slot.group, sampler.pass_index),
                 stage,
                 BindTarget::Sampler(sampler.sampler_type),
             );
         }
     }
 
+    /// Binds the depth attachment of a render texture to the specified slot.
+    ///
+    /// In WGSL, the binding is a `texture_depth_2d`.  This is how a shadow map rendered
+    /// by an earlier pass is read.
+    ///
+    /// # Parameters
+    ///
+    /// * `slot` - The binding slot for the texture
+    /// * `stage` - The shader stage where the texture will be accessible
+    /// * `texture` - The render texture to bind
+    ///
+    /// # Panics
+    ///
+    /// Panics if `texture` has no depth attachment.
+    pub fn bind_render_texture_depth<Format>(
+        &mut self,
+        slot: BindSlot,
+        stage: Stage,
+        texture: &crate::bindings::sideways::RenderTexture<Format>,
+    ) {
+        assert!(
+            texture.has_depth(),
+            "render texture has no depth attachment to bind"
+        );
+        self.bind(
+            slot,
+            stage,
+            BindTarget::RenderTextureDepth(texture.imp.clone()),
+        );
+    }
+
     /// Binds a dynamic texture to the specified slot.
     ///
     /// Dynamic textures can be updated between frames. They are useful for
@@ -302,11 +643,16 @@ impl BindStyle {
     /// and are always bound to the vertex shader stage. The layout parameter describes
     /// how the buffer data should be interpreted.
     ///
+    /// Vertex buffers are assigned to the vertex shader in slot order, and their fields
+    /// take consecutive `@location`s: if the buffer in the lowest slot has two fields,
+    /// the first field of the next buffer is `@location(2)`.
+    ///
     /// # Parameters
     ///
     /// * `slot` - The binding slot to use
     /// * `buffer` - The static buffer containing vertex data
     /// * `layout` - Description of the vertex data layout
+    /// * `step_mode` - Whether the buffer holds one element per vertex or per instance
     ///
     /// # Type Parameters
     ///
@@ -316,24 +662,29 @@ impl BindStyle {
         slot: BindSlot,
         buffer: &crate::bindings::forward::r#static::buffer::Buffer<Element>,
         layout: VertexLayout,
+        step_mode: StepMode,
     ) {
-        self.bind(
+        self.bind_buffer(
             slot,
             Stage::Vertex,
-            BindTarget::VB(layout, buffer.imp.clone()),
+            BindTarget::VB(layout, step_mode, buffer.imp.clone()),
+            buffer.buffer_info(),
         );
     }
 
     /// Binds a dynamic vertex buffer to the specified slot.
     ///
     /// Dynamic vertex buffers can be updated between frames, useful for animated
-    /// geometry or procedurally generated meshes.
+    /// geometry or procedurally generated meshes.  With [`StepMode::Instance`], a dynamic
+    /// buffer of per-instance data (such as transforms) can move every copy of a mesh
+    /// drawn with an instanced [`DrawCommand`](crate::images::render_pass::DrawCommand).
     ///
     /// # Parameters
     ///
     /// * `slot` - The binding slot to use
     /// * `buffer` - The dynamic buffer containing vertex data
     /// * `layout` - Description of the vertex data layout
+    /// * `step_mode` - Whether the buffer holds one element per vertex or per instance
     ///
     /// # Type Parameters
     ///
@@ -343,33 +694,109 @@ impl BindStyle {
         slot: BindSlot,
         buffer: &crate::bindings::forward::dynamic::buffer::Buffer<Element>,
         layout: VertexLayout,
+        step_mode: StepMode,
     ) where
         Element: Send + Sync + 'static,
     {
-        self.bind(
+        self.bind_buffer(
             slot,
             Stage::Vertex,
-            BindTarget::DynamicVB(layout, buffer.render_side().erased_render_side()),
+            BindTarget::DynamicVB(layout, step_mode, buffer.render_side().erased_render_side()),
+            buffer.buffer_info(),
         );
     }
 
     /// Binds a static index buffer for indexed drawing.
     ///
     /// Index buffers contain indices that reference vertices in vertex buffers,
-    /// allowing for efficient reuse of vertex data. This method currently only
-    /// supports 16-bit indices.
+    /// allowing for efficient reuse of vertex data. Indices may be `u16` or `u32`;
+    /// use `u32` for meshes with more than 65,536 vertices.
     ///
     /// Unlike other bindings, index buffers don't use slots - there can only be
     /// one index buffer per draw call.
     ///
     /// # Parameters
     ///
-    /// * `buffer` - The buffer containing 16-bit indices
-    pub fn bind_static_index_buffer(
+    /// * `buffer` - The buffer containing the indices
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index buffer is already bound.
+    pub fn bind_static_index_buffer<Index>(
         &mut self,
-        buffer: &crate::bindings::forward::r#static::buffer::Buffer<u16>,
-    ) {
-        self.index_buffer = Some(buffer.imp.clone())
+        buffer: &crate::bindings::forward::r#static::buffer::Buffer<Index>,
+    ) where
+        Index: sealed::IndexElement,
+    {
+        self.bind_index(IndexBinding {
+            target: IndexTarget::Static(buffer.imp.clone()),
+            format: Index::FORMAT,
+        });
+    }
+
+    /// Binds a dynamic index buffer for indexed drawing.
+    ///
+    /// Like [`bind_static_index_buffer`](Self::bind_static_index_buffer), but the indices
+    /// can be rewritten between frames, for example to rebuild an index list for a new
+    /// level of detail, or after culling, without recreating the pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `buffer` - The dynamic buffer containing the indices
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index buffer is already bound.
+    pub fn bind_dynamic_index_buffer<Index>(
+        &mut self,
+        buffer: &crate::bindings::forward::dynamic::buffer::Buffer<Index>,
+    ) where
+        Index: sealed::IndexElement,
+    {
+        self.bind_index(IndexBinding {
+            target: IndexTarget::Dynamic(buffer.render_side().erased_render_side()),
+            format: Index::FORMAT,
+        });
+    }
+
+    fn bind_index(&mut self, binding: IndexBinding) {
+        assert!(self.index_buffer.is_none(), "Already bound an index buffer");
+        self.index_buffer = Some(binding);
+    }
+
+    /// Binds the buffer that [`DrawCommand::Indirect`](crate::images::render_pass::DrawCommand::Indirect)
+    /// and [`DrawCommand::IndexedIndirect`](crate::images::render_pass::DrawCommand::IndexedIndirect)
+    /// read their draw arguments from.
+    ///
+    /// The buffer should be created with [`GPUBufferUsage::Indirect`](crate::bindings::visible_to::GPUBufferUsage::Indirect).
+    /// A compute pass can bind the same buffer to write the arguments, for example after
+    /// culling, and this pass draws whatever it wrote without a round trip to the CPU.
+    ///
+    /// Like index buffers, indirect buffers don't use slots - there can only be one per pass.
+    ///
+    /// # Parameters
+    ///
+    /// * `buffer` - The buffer of [`DrawIndirectArgs`](crate::images::render_pass::DrawIndirectArgs)
+    ///   or [`DrawIndexedIndirectArgs`](crate::images::render_pass::DrawIndexedIndirectArgs)
+    ///
+    /// # Panics
+    ///
+    /// Panics if an indirect buffer is already bound.
+    pub fn bind_indirect_buffer<Args>(
+        &mut self,
+        buffer: &crate::bindings::forward::r#static::buffer::Buffer<Args>,
+    ) where
+        Args: sealed::IndirectArgs,
+    {
+        assert!(
+            self.indirect_buffer.is_none(),
+            "Already bound an indirect buffer"
+        );
+        self.indirect_buffer = Some(IndirectBinding {
+            buffer: buffer.imp.clone(),
+            indexed: Args::INDEXED,
+            len: buffer.len(),
+        });
     }
 }
 
@@ -377,7 +804,7 @@ impl BindStyle {
 ///
 /// Resources can be made available to different stages of the graphics pipeline.
 /// This enum allows you to specify whether a resource should be accessible from
-/// the vertex shader, fragment shader, or both.
+/// the vertex shader, fragment shader, or both, or from a compute shader.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum Stage {
@@ -385,6 +812,9 @@ pub enum Stage {
     Fragment,
     /// Resource will be bound to vertex shaders.
     Vertex,
+    /// Resource will be bound to compute shaders, see
+    /// [`ComputePassDescriptor`](crate::images::compute_pass::ComputePassDescriptor).
+    Compute,
 }
 
 // Boilerplate implementations for Stage
@@ -395,6 +825,7 @@ impl std::fmt::Display for Stage {
         match self {
             Stage::Fragment => write!(f, "fragment"),
             Stage::Vertex => write!(f, "vertex"),
+            Stage::Compute => write!(f, "compute"),
         }
     }
 }
@@ -405,6 +836,10 @@ impl std::fmt::Display for Stage {
 /// shaders. When you bind a resource to a slot, it becomes available at that
 /// binding location in the shader.
 ///
+/// Slots created with [`BindSlot::new`] are in `@group(0)`; use [`BindSlot::in_group`]
+/// to bind into other groups, for example to match the group conventions of shaders
+/// written for another engine.
+///
 /// # Example
 ///
 /// ```
@@ -448,19 +883,32 @@ impl std::fmt::Display for Stage {
 /// # }, "bind_style_debug_doctest");
 /// # }
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct BindSlot {
+    pub(crate) group: u32,
     pub(crate) pass_index: u32,
 }
 
 impl BindSlot {
-    /// Creates a new binding slot with the specified index.
+    /// Creates a new binding slot with the specified index, in `@group(0)`.
     ///
     /// # Parameters
     ///
     /// * `pass_index` - The numeric index of the binding slot
     pub fn new(pass_index: u32) -> Self {
-        Self { pass_index }
+        Self::in_group(0, pass_index)
+    }
+
+    /// Creates a binding slot at `@group(group) @binding(pass_index)`.
+    ///
+    /// Groups don't need to be consecutive; the groups in between are bound empty.
+    ///
+    /// # Parameters
+    ///
+    /// * `group` - The bind group of the slot
+    /// * `pass_index` - The numeric index of the binding slot within the group
+    pub fn in_group(group: u32, pass_index: u32) -> Self {
+        Self { group, pass_index }
     }
 }
 
@@ -476,9 +924,13 @@ impl Default for BindSlot {
 }
 
 impl std::fmt::Display for BindSlot {
-    /// Formats the binding slot as "slot N" for readable output in logs and debugging.
+    /// Formats the binding slot as "slot N", or "group G slot N" outside group 0, for
+    /// readable output in logs and debugging.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "slot {}", self.pass_index)
+        match self.group {
+            0 => write!(f, "slot {}", self.pass_index),
+            group => write!(f, "group {group} slot {}", self.pass_index),
+        }
     }
 }
 
@@ -502,4 +954,4 @@ impl AsRef<u32> for BindSlot {
     }
 }
 
-use crate::images::vertex_layout::VertexLayout;
+use crate::images::vertex_layout::{StepMode, VertexLayout};
@@ -55,6 +55,11 @@ This module provides dynamic resource types:
 - Procedural textures
 - Dynamic environment maps
 
+### `Immediates` - Small per-pass values
+- Object IDs
+- Tint colors
+- LOD indices
+
 ## Multibuffering Strategy
 
 Dynamic resources automatically handle multibuffering to prevent:
@@ -151,3 +156,4 @@ For immutable data, use `static` resources for better performance.
 */
 pub mod buffer;
 pub mod frame_texture;
+pub mod immediates;
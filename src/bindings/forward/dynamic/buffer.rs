@@ -87,6 +87,7 @@
 //! - [`forward::dynamic::FrameTexture`](crate::bindings::forward::dynamic::frame_texture::FrameTexture) - For dynamic image data
 //! - [`bindings`](crate::bindings) module documentation - For understanding the full type organization
 
+use crate::bindings::bind_style::BufferInfo;
 use crate::bindings::dirty_tracking::DirtyReceiver;
 use crate::bindings::visible_to::GPUBufferUsage;
 use crate::images::BoundDevice;
@@ -191,6 +192,7 @@ impl Debug for Shared {
 pub struct Buffer<Element> {
     shared: Arc<Shared>,
     count: usize,
+    usage: GPUBufferUsage,
     debug_name: String,
     _phantom: PhantomData<Element>,
 }
@@ -533,6 +535,7 @@ impl<Element> Buffer<Element> {
                 ),
             }),
             count: size,
+            usage,
             debug_name: debug_name.to_string(),
             _phantom: PhantomData,
         })
@@ -586,6 +589,16 @@ impl<Element> Buffer<Element> {
         }
     }
 
+    /// Returns the number of elements in the buffer.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the buffer has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
     #[allow(dead_code)] //nop implementation does not use
     pub(crate) fn gpu_dirty_receiver(&self) -> DirtyReceiver {
         self.shared.multibuffer.gpu_dirty_receiver()
@@ -604,6 +617,15 @@ impl<Element> Buffer<Element> {
             _phantom: PhantomData,
         }
     }
+
+    /// Returns the usage and sizes shaders are checked against.
+    pub(crate) fn buffer_info(&self) -> BufferInfo {
+        BufferInfo {
+            usage: self.usage,
+            element_size: std::mem::size_of::<Element>(),
+            byte_size: self.count * std::mem::size_of::<Element>(),
+        }
+    }
 }
 
 /// Marker trait for types with C-compatible memory representation.
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Small values a pass reads without a buffer, such as object IDs, tints or LOD indices.
+//!
+//! [`Immediates`] holds one [`CRepr`] value, which the CPU may replace at any time; each frame
+//! draws with the latest value.  Attach it to a pass with
+//! [`PassDescriptor::with_immediates`](crate::images::render_pass::PassDescriptor::with_immediates),
+//! and declare it in WGSL as a `var<immediate>`:
+//!
+//! ```wgsl
+//! var<immediate> tint: vec4<f32>;
+//! ```
+//!
+//! Unlike a dynamic [`Buffer`](super::buffer::Buffer), there is no multibuffering and no
+//! write guard to await; setting the value only copies it.
+//!
+//! # Backend support
+//!
+//! Devices with immediate data (push constants) set the value directly with each draw.
+//! Elsewhere, including WebGPU in most browsers, the port emulates it with a small uniform
+//! buffer, bound at the group after the last one the pass's
+//! [`BindStyle`](crate::bindings::BindStyle) uses, and declares the block as a
+//! `var<uniform>` there.  Since the emulated block follows uniform layout rules, prefer
+//! scalars and vectors to arrays.
+//!
+//! # Example
+//!
+//! ```
+//! use images_and_words::bindings::forward::dynamic::buffer::CRepr;
+//! use images_and_words::bindings::forward::dynamic::immediates::Immediates;
+//!
+//! #[derive(Clone, Copy, Debug, PartialEq)]
+//! #[repr(C)]
+//! struct Tint {
+//!     rgba: [f32; 4],
+//! }
+//! // Safety: Tint is repr(C) and contains only f32s
+//! unsafe impl CRepr for Tint {}
+//!
+//! let tint = Immediates::new(Tint { rgba: [1.0, 0.0, 0.0, 1.0] });
+//! // later, for the next frame
+//! tint.set(Tint { rgba: [0.0, 1.0, 0.0, 1.0] });
+//! assert_eq!(tint.get().rgba, [0.0, 1.0, 0.0, 1.0]);
+//! ```
+
+use crate::bindings::dirty_tracking::{DirtyReceiver, DirtySender};
+use crate::bindings::forward::dynamic::buffer::CRepr;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use wasm_safe_thread::Mutex;
+
+/// Immediate data is set in multiples of this many bytes.
+const ALIGNMENT: usize = 4;
+
+#[derive(Debug)]
+struct Shared {
+    /// The value, padded to a multiple of [`ALIGNMENT`].
+    bytes: Mutex<Vec<u8>>,
+    dirty_sender: DirtySender,
+}
+
+/// A value passes read as immediate data.
+///
+/// Cloning an `Immediates` shares the value, so a pass sees every `set` through any clone.
+/// See the [module documentation](self) for how shaders declare it.
+pub struct Immediates<T> {
+    shared: Arc<Shared>,
+    _marker: PhantomData<fn(T) -> T>,
+}
+
+impl<T: CRepr> Immediates<T> {
+    /// Creates immediate data holding `value`.
+    pub fn new(value: T) -> Self {
+        let immediates = Immediates {
+            shared: Arc::new(Shared {
+                bytes: Mutex::new(Vec::new()),
+                dirty_sender: DirtySender::new(false, "Immediates"),
+            }),
+            _marker: PhantomData,
+        };
+        *immediates.shared.bytes.lock_sync() = Self::encode(&value);
+        immediates
+    }
+
+    /// Replaces the value, and renders a new frame with it in ports whose passes use it.
+    pub fn set(&self, value: T) {
+        *self.shared.bytes.lock_sync() = Self::encode(&value);
+        self.shared.dirty_sender.mark_dirty(true);
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> T {
+        let bytes = self.shared.bytes.lock_sync();
+        //safety: CRepr types are plain data, and the bytes were encoded from a T
+        unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) }
+    }
+
+    fn encode(value: &T) -> Vec<u8> {
+        let size = std::mem::size_of::<T>();
+        //safety: CRepr types are plain data
+        let bytes = unsafe { std::slice::from_raw_parts(value as *const T as *const u8, size) };
+        let mut padded = bytes.to_vec();
+        padded.resize(size.next_multiple_of(ALIGNMENT), 0);
+        padded
+    }
+
+    pub(crate) fn erased(&self) -> ErasedImmediates {
+        ErasedImmediates {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+/// Immediate data without its type, as a pass holds it.
+#[derive(Debug, Clone)]
+pub(crate) struct ErasedImmediates {
+    shared: Arc<Shared>,
+}
+
+impl ErasedImmediates {
+    /// The size of the value in bytes, padded to a multiple of 4.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn size(&self) -> u32 {
+        self.shared.bytes.lock_sync().len() as u32
+    }
+
+    /// Returns the current value's bytes, and marks it as drawn.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        let bytes = self.shared.bytes.lock_sync().clone();
+        self.shared.dirty_sender.mark_dirty(false);
+        bytes
+    }
+
+    pub(crate) fn dirty_receiver(&self) -> DirtyReceiver {
+        DirtyReceiver::new(&self.shared.dirty_sender)
+    }
+}
+
+impl PartialEq for ErasedImmediates {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+// Boilerplate implementations for Immediates
+
+impl<T> Clone for Immediates<T> {
+    fn clone(&self) -> Self {
+        Immediates {
+            shared: self.shared.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Debug for Immediates<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Immediates")
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T> PartialEq for Immediates<T> {
+    /// Two `Immediates` are equal if they share a value.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
@@ -69,7 +69,9 @@
 //! - [`forward::static::Texture`](crate::bindings::forward::static::texture::Texture) - For immutable image data
 //! - [`bindings`](crate::bindings) module documentation - For understanding the full type organization
 
+use crate::bindings::bind_style::BufferInfo;
 use crate::bindings::forward::dynamic::buffer::CRepr;
+use crate::bindings::visible_to::GPUBufferUsage;
 use crate::images::BoundDevice;
 use crate::imp;
 use std::marker::PhantomData;
@@ -126,6 +128,7 @@ pub struct Buffer<Element> {
     pub(crate) imp: imp::GPUableBufferStatic,
     #[allow(dead_code)] //nop implementation does not use
     count: usize,
+    usage: GPUBufferUsage,
     element: PhantomData<Element>,
 }
 
@@ -256,7 +259,7 @@ impl<Element> Buffer<Element> {
     pub async fn new(
         device: Arc<BoundDevice>,
         count: usize,
-        usage: crate::bindings::visible_to::GPUBufferUsage,
+        usage: GPUBufferUsage,
         debug_name: &str,
         initializer: impl Fn(usize) -> Element,
     ) -> Result<Self, Error>
@@ -277,9 +280,29 @@ impl<Element> Buffer<Element> {
         Ok(Self {
             imp,
             count,
+            usage,
             element: PhantomData,
         })
     }
+
+    /// Returns the number of elements in the buffer.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the buffer has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the usage and sizes shaders are checked against.
+    pub(crate) fn buffer_info(&self) -> BufferInfo {
+        BufferInfo {
+            usage: self.usage,
+            element_size: std::mem::size_of::<Element>(),
+            byte_size: self.count * std::mem::size_of::<Element>(),
+        }
+    }
 }
 
 // Boilerplate
@@ -291,6 +314,7 @@ impl<Element> Clone for Buffer<Element> {
         Self {
             imp: self.imp.clone(),
             count: self.count,
+            usage: self.usage,
             element: PhantomData,
         }
     }
@@ -18,6 +18,11 @@
 //! - `CPU_WRITE`: Resource is mapped for CPU write access
 //! - `GPU`: Resource is in use by the GPU
 //! - `PENDING_WRITE_TO_GPU`: CPU write completed, awaiting GPU transfer
+//! - `GPU_WRITE`: The GPU is copying its results into the resource
+//! - `PENDING_READ_BY_CPU`: GPU write completed, awaiting a CPU read
+//!
+//! Forward resources cycle through `CPU_WRITE`, `PENDING_WRITE_TO_GPU` and `GPU`.
+//! Reverse resources cycle through `GPU_WRITE`, `PENDING_READ_BY_CPU` and `CPU_READ`.
 //!
 //! # Internal Usage
 //!
@@ -43,33 +48,47 @@ const CPU_READ: u8 = 1;
 const CPU_WRITE: u8 = 2;
 const GPU: u8 = 3;
 const PENDING_WRITE_TO_GPU: u8 = 4;
-//
-// /// Guard providing immutable CPU access to a tracked resource
-// ///
-// /// This guard ensures exclusive read access to the resource while held.
-// /// The resource is automatically unmapped when the guard is dropped.
-// ///
-// /// # Safety
-// ///
-// /// The guard maintains the invariant that the resource is in `CPU_READ` state
-// /// for its entire lifetime, preventing concurrent GPU or CPU write access.
-// #[derive(Debug)]
-// pub struct CPUReadGuard<'a, Resource>
-// where
-//     Resource: sealed::Mappable,
-// {
-//     tracker: &'a ResourceTrackerInternal<Resource>,
-// }
-//
-// impl<Resource> Deref for CPUReadGuard<'_, Resource>
-// where
-//     Resource: sealed::Mappable,
-// {
-//     type Target = Resource;
-//     fn deref(&self) -> &Self::Target {
-//         unsafe { &*self.tracker.resource.get() }
-//     }
-// }
+const GPU_WRITE: u8 = 5;
+const PENDING_READ_BY_CPU: u8 = 6;
+
+/// Guard providing immutable CPU access to a tracked resource
+///
+/// This guard ensures exclusive read access to the resource while held.
+/// The resource is automatically unmapped when the guard is dropped.
+///
+/// # Safety
+///
+/// The guard maintains the invariant that the resource is in `CPU_READ` state
+/// for its entire lifetime, preventing concurrent GPU or CPU write access.
+#[derive(Debug)]
+pub struct CPUReadGuard<'a, Resource>
+where
+    Resource: sealed::Mappable,
+{
+    tracker: &'a ResourceTrackerInternal<Resource>,
+}
+
+impl<Resource> Deref for CPUReadGuard<'_, Resource>
+where
+    Resource: sealed::Mappable,
+{
+    type Target = Resource;
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.tracker.resource.get() }
+    }
+}
+
+impl<Resource> Drop for CPUReadGuard<'_, Resource>
+where
+    Resource: sealed::Mappable,
+{
+    fn drop(&mut self) {
+        //safety: it's the guard's responsibility to ensure the lock is held
+        unsafe {
+            self.tracker.unuse_cpu();
+        }
+    }
+}
 
 /// Guard providing mutable CPU access to a tracked resource
 ///
@@ -128,8 +147,12 @@ where
 ///
 /// # State Transitions
 ///
-/// Can only be acquired when the resource is in `PENDING_WRITE_TO_GPU` state.
-/// Transitions to `GPU` state when acquired and back to `UNUSED` when dropped.
+/// A guard for reading is acquired in `PENDING_WRITE_TO_GPU` state.
+/// It transitions to `GPU` state when acquired and back to `UNUSED` when dropped.
+///
+/// A guard for writing is acquired in `UNUSED` or `PENDING_READ_BY_CPU` state.
+/// It transitions to `GPU_WRITE` state when acquired and to `PENDING_READ_BY_CPU` when dropped,
+/// so it must only be dropped once the GPU has finished writing.
 #[derive(Debug)]
 pub struct GPUGuard<Resource> {
     tracker: Arc<ResourceTrackerInternal<Resource>>,
@@ -179,6 +202,8 @@ impl Debug for NotAvailable {
             CPU_WRITE => "CPU_WRITE",
             GPU => "GPU",
             PENDING_WRITE_TO_GPU => "PENDING_WRITE_TO_GPU",
+            GPU_WRITE => "GPU_WRITE",
+            PENDING_READ_BY_CPU => "PENDING_READ_BY_CPU",
             _ => "UNKNOWN",
         };
         write!(f, "NotAvailable {{ read_state: {state} }}")
@@ -195,11 +220,11 @@ pub(crate) mod sealed {
     /// to be tracked by the resource tracking system. It provides the
     /// necessary operations for mapping/unmapping memory for CPU access.
     pub trait Mappable {
-        // /// Maps the resource for read-only CPU access
-        // ///
-        // /// This operation is asynchronous as it may need to wait for
-        // /// GPU operations to complete or for data to be transferred.
-        // fn map_read(&mut self) -> impl Future<Output = ()> + BackendSend;
+        /// Maps the resource for read-only CPU access
+        ///
+        /// This operation is asynchronous as it may need to wait for
+        /// GPU operations to complete or for data to be transferred.
+        fn map_read(&mut self) -> impl Future<Output = ()> + BackendSend;
 
         /// Maps the resource for read-write CPU access
         ///
@@ -226,7 +251,10 @@ struct ResourceTrackerInternal<Resource> {
     resource: UnsafeCell<Resource>,
     debug_label: String,
     pending_cpu_write: Mutex<Vec<r#continue::Sender<()>>>,
+    pending_cpu_read: Mutex<Vec<r#continue::Sender<()>>>,
     dirty_pending_cpu_to_gpu: DirtySender,
+    /// Dirty while a CPU reader waits for the GPU to write the resource.
+    dirty_pending_gpu_to_cpu: DirtySender,
 }
 
 impl<Resource> Debug for ResourceTrackerInternal<Resource> {
@@ -270,10 +298,12 @@ impl<Resource> ResourceTrackerInternal<Resource> {
             state: AtomicU8::new(initial_state),
             resource: UnsafeCell::new(resource),
             pending_cpu_write: Mutex::new(Vec::new()),
+            pending_cpu_read: Mutex::new(Vec::new()),
             dirty_pending_cpu_to_gpu: DirtySender::new(
                 Self::dirty_state_for_state(initial_state),
                 debug_label.clone(),
             ),
+            dirty_pending_gpu_to_cpu: DirtySender::new(false, format!("{debug_label}_read")),
             debug_label,
         }
     }
@@ -351,10 +381,84 @@ impl<Resource> ResourceTrackerInternal<Resource> {
         }
     }
 
+    /// Acquires the resource for CPU read access
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(CPUReadGuard)` if the GPU has written the resource since it was last read
+    /// - `Err(NotAvailable)` otherwise
+    ///
+    /// # State Transitions
+    ///
+    /// Can acquire from: `PENDING_READ_BY_CPU`
+    /// Transitions to: `CPU_READ`
+    /// On guard drop: Transitions to `UNUSED`
+    fn cpu_read_or(&self) -> Result<CPUReadGuard<'_, Resource>, NotAvailable>
+    where
+        Resource: sealed::Mappable,
+    {
+        match self.state.compare_exchange(
+            PENDING_READ_BY_CPU,
+            CPU_READ,
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {
+                self.entered_cpu_read();
+                Ok(CPUReadGuard { tracker: self })
+            }
+            Err(other) => Err(NotAvailable { read_state: other }),
+        }
+    }
+
+    /// Waits for the GPU to write the resource, then acquires it for CPU read access
+    ///
+    /// While waiting, the resource's pending GPU-to-CPU receiver is dirty, which asks
+    /// a running port to render the frame that writes it.
+    async fn cpu_read(&self) -> CPUReadGuard<'_, Resource>
+    where
+        Resource: sealed::Mappable,
+    {
+        loop {
+            //isolate lock to a scope
+            let o = {
+                let mut wakelist_lock = self.pending_cpu_read.lock().unwrap();
+                match self.cpu_read_or() {
+                    Ok(guard) => Ok(guard),
+                    Err(NotAvailable {
+                        read_state: _read_state,
+                    }) => {
+                        logwise::trace_sync!(
+                            "Waiting for GPU to write resource in state {read_state}",
+                            read_state = _read_state
+                        );
+                        let (s, r) = r#continue::continuation();
+                        wakelist_lock.push(s);
+                        self.dirty_pending_gpu_to_cpu.mark_dirty(true);
+                        Err(r)
+                    }
+                }
+            };
+            match o {
+                Ok(guard) => {
+                    //safety: we hold the lock and the resource is in CPU_READ state
+                    unsafe {
+                        let resource = &mut *self.resource.get();
+                        resource.map_read().await;
+                    }
+                    return guard;
+                }
+                Err(r) => {
+                    r.await; //next loop
+                }
+            }
+        }
+    }
+
     fn dirty_state_for_state(state: u8) -> bool {
         match state {
             PENDING_WRITE_TO_GPU => true,
-            CPU_READ | CPU_WRITE | GPU | UNUSED => false,
+            CPU_READ | CPU_WRITE | GPU | UNUSED | GPU_WRITE | PENDING_READ_BY_CPU => false,
             _ => panic!("Invalid state for dirty tracking: {state}"),
         }
     }
@@ -373,10 +477,10 @@ impl<Resource> ResourceTrackerInternal<Resource> {
         }
     }
 
-    // fn entered_cpu_read(&self) {
-    //     self.dirty_pending_cpu_to_gpu
-    //         .mark_dirty(Self::dirty_state_for_state(CPU_READ));
-    // }
+    fn entered_cpu_read(&self) {
+        self.dirty_pending_cpu_to_gpu
+            .mark_dirty(Self::dirty_state_for_state(CPU_READ));
+    }
     fn entered_cpu_write(&self) {
         self.dirty_pending_cpu_to_gpu
             .mark_dirty(Self::dirty_state_for_state(CPU_WRITE));
@@ -385,6 +489,26 @@ impl<Resource> ResourceTrackerInternal<Resource> {
         self.dirty_pending_cpu_to_gpu
             .mark_dirty(Self::dirty_state_for_state(GPU));
     }
+    fn entered_gpu_write(&self) {
+        self.dirty_pending_cpu_to_gpu
+            .mark_dirty(Self::dirty_state_for_state(GPU_WRITE));
+        //the write any waiting reader asked for is underway
+        self.dirty_pending_gpu_to_cpu.mark_dirty(false);
+    }
+
+    fn entered_pending_read_by_cpu(&self) {
+        self.dirty_pending_cpu_to_gpu
+            .mark_dirty(Self::dirty_state_for_state(PENDING_READ_BY_CPU));
+        let take = self
+            .pending_cpu_read
+            .lock()
+            .expect("Failed to lock pending_cpu_read")
+            .drain(..)
+            .collect::<Vec<_>>();
+        for sender in take {
+            sender.send(());
+        }
+    }
 
     fn entered_pending_write_to_gpu(&self) {
         self.dirty_pending_cpu_to_gpu
@@ -436,6 +560,40 @@ impl<Resource> ResourceTrackerInternal<Resource> {
         }
     }
 
+    /// Acquires the resource for the GPU to write into
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(GPUGuard)` if the resource is not in use by the CPU or GPU
+    /// - `Err(NotAvailable)` if the resource is in use
+    ///
+    /// # State Transitions
+    ///
+    /// Can acquire from: `UNUSED`, `PENDING_READ_BY_CPU` (discarding the unread results)
+    /// Transitions to: `GPU_WRITE`
+    /// On guard drop: Transitions to `PENDING_READ_BY_CPU`
+    pub fn poll_gpu_write(self: &Arc<Self>) -> Result<GPUGuard<Resource>, NotAvailable>
+    where
+        Resource: sealed::Mappable,
+    {
+        match self.state.fetch_update(
+            Ordering::Acquire,
+            Ordering::Relaxed,
+            |current| match current {
+                UNUSED | PENDING_READ_BY_CPU => Some(GPU_WRITE),
+                _ => None,
+            },
+        ) {
+            Ok(_) => {
+                self.entered_gpu_write();
+                Ok(GPUGuard {
+                    tracker: self.clone(),
+                })
+            }
+            Err(other) => Err(NotAvailable { read_state: other }),
+        }
+    }
+
     /// Releases CPU access to the resource
     ///
     /// # Safety
@@ -480,14 +638,27 @@ impl<Resource> ResourceTrackerInternal<Resource> {
     }
     /// Releases GPU access to the resource
     ///
-    /// Transitions the resource from `GPU` state back to `UNUSED`.
-    /// Panics if the resource was not in GPU state.
+    /// Transitions the resource from `GPU` state back to `UNUSED`,
+    /// or from `GPU_WRITE` state to `PENDING_READ_BY_CPU`.
+    /// Panics if the resource was not in either state.
     fn unuse_gpu(&self) {
-        let o = self
+        let old_state = self
             .state
-            .swap(UNUSED, std::sync::atomic::Ordering::Release);
-        assert_ne!(o, UNUSED, "Resource was not in use");
-        self.entered_unused();
+            .fetch_update(
+                Ordering::Release,
+                Ordering::Relaxed,
+                |current| match current {
+                    GPU => Some(UNUSED),
+                    GPU_WRITE => Some(PENDING_READ_BY_CPU),
+                    _ => None,
+                },
+            )
+            .unwrap_or_else(|state| panic!("unuse_gpu called from invalid state: {state}"));
+        if old_state == GPU_WRITE {
+            self.entered_pending_read_by_cpu();
+        } else {
+            self.entered_unused();
+        }
     }
 }
 
@@ -518,12 +689,15 @@ impl<Resource> ResourceTracker<Resource> {
             internal: Arc::new(ResourceTrackerInternal::new(resource, state, debug_label)),
         }
     }
-    // /// Acquires the resource for CPU read access
-    // ///
-    // /// See [`ResourceTrackerInternal::cpu_read`] for details.
-    // pub async fn cpu_read(&self) -> Result<CPUReadGuard<Resource>,NotAvailable> where Resource: sealed::Mappable {
-    //     self.internal.cpu_read().await
-    // }
+    /// Acquires the resource for CPU read access
+    ///
+    /// See [`ResourceTrackerInternal::cpu_read`] for details.
+    pub async fn cpu_read(&self) -> CPUReadGuard<'_, Resource>
+    where
+        Resource: sealed::Mappable,
+    {
+        self.internal.cpu_read().await
+    }
 
     /// Acquires the resource for CPU write access
     ///
@@ -545,10 +719,24 @@ impl<Resource> ResourceTracker<Resource> {
         self.internal.poll_gpu()
     }
 
+    /// Acquires the resource for the GPU to write into
+    ///
+    /// See [`ResourceTrackerInternal::poll_gpu_write`] for details.
+    pub(crate) fn poll_gpu_write(&self) -> Result<GPUGuard<Resource>, NotAvailable>
+    where
+        Resource: sealed::Mappable,
+    {
+        self.internal.poll_gpu_write()
+    }
+
     pub fn dirty_pending_cpu_to_gpu(&self) -> &DirtySender {
         &self.internal.dirty_pending_cpu_to_gpu
     }
 
+    pub fn dirty_pending_gpu_to_cpu(&self) -> &DirtySender {
+        &self.internal.dirty_pending_gpu_to_cpu
+    }
+
     /// Unsafely accesses the underlying resource
     ///
     /// # Safety
@@ -574,6 +762,8 @@ impl std::fmt::Display for NotAvailable {
             CPU_WRITE => "CPU_WRITE",
             GPU => "GPU",
             PENDING_WRITE_TO_GPU => "PENDING_WRITE_TO_GPU",
+            GPU_WRITE => "GPU_WRITE",
+            PENDING_READ_BY_CPU => "PENDING_READ_BY_CPU",
             _ => "UNKNOWN",
         };
         write!(f, "resource not available; current state: {state}")
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+/*! Resources that pass data in the 'reverse' direction, that is, GPU=>CPU.
+
+# Overview
+
+Reverse resources let shaders produce data that the CPU reads back, such as:
+- Compute results
+- GPU-side histograms and reductions
+- Query results
+
+This is the mirror image of [`forward`](crate::bindings::forward): instead of the CPU
+writing and the GPU reading, shaders write into a storage buffer and the CPU awaits
+the results.
+
+## Synchronization
+
+Reading back is asynchronous.  After each frame that writes a reverse resource, its
+contents are copied to CPU-visible memory, and [`buffer::Buffer::access_read`] resolves once
+that copy has finished.  While the CPU holds a read guard, new frames keep rendering and
+simply skip the copy, so a slow reader never stalls the frame loop.
+
+## Examples
+
+```
+# if cfg!(not(feature="backend_wgpu")) { return; }
+# #[cfg(feature = "testing")]
+# {
+use images_and_words::bindings::reverse::buffer::Buffer;
+# use images_and_words::images::projection::WorldCoord;
+# use images_and_words::images::view::View;
+# test_executors::spawn_local(async {
+# let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+# let device = engine.bound_device();
+// 256 bins, filled in by a shader
+let histogram = Buffer::<u32>::new(device.clone(), 256, "histogram", |_| 0)
+    .await
+    .expect("Failed to create histogram");
+# }, "reverse_module_doctest");
+# }
+```
+*/
+
+pub mod buffer;
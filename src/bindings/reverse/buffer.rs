@@ -0,0 +1,469 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Reverse buffer implementation for GPU results that the CPU reads back.
+//!
+//! This module provides the [`Buffer`] type, a storage buffer that shaders write into
+//! and the CPU reads after the GPU finishes.
+//!
+//! # Overview
+//!
+//! Reverse buffers are designed for data produced on the GPU, such as:
+//! - Compute results
+//! - Histograms and other reductions
+//! - Query results
+//!
+//! Bind the buffer to a pass with
+//! [`BindStyle::bind_reverse_buffer`](crate::bindings::BindStyle::bind_reverse_buffer).  In WGSL
+//! it is a `var<storage, read_write>` binding.
+//!
+//! # Architecture
+//!
+//! The module mirrors [`forward::dynamic::buffer`](crate::bindings::forward::dynamic::buffer),
+//! with the data flowing the other way:
+//!
+//! - [`Buffer`] - The main public interface for creating and reading reverse buffers
+//! - [`CPUReadAccess`] - A guard over the results of one frame
+//! - `RenderSide` - GPU-side handle used during rendering
+//! - `Shared` - Shared state managing the multibuffer synchronization
+//!
+//! After each frame that binds the buffer, the GPU side is copied into CPU-visible memory,
+//! unless the CPU is still reading the previous results, in which case that frame's copy
+//! is skipped rather than waiting on the reader.
+//!
+//! # Example
+//!
+//! ```
+//! # if cfg!(not(feature="backend_wgpu")) { return; }
+//! # #[cfg(feature = "testing")]
+//! # {
+//! use images_and_words::bindings::BindStyle;
+//! use images_and_words::bindings::bind_style::{BindSlot, Stage};
+//! use images_and_words::bindings::reverse::buffer::Buffer;
+//! use images_and_words::images::projection::WorldCoord;
+//! use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+//! use images_and_words::images::shader::{FragmentShader, VertexShader};
+//! use images_and_words::images::view::View;
+//! # test_executors::spawn_local(async {
+//! # let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+//! # let device = engine.bound_device();
+//! # let port = engine.main_port();
+//! let results = Buffer::<u32>::new(device.clone(), 1, "results", |_| 0)
+//!     .await
+//!     .expect("Failed to create buffer");
+//!
+//! let vertex_shader = VertexShader::new("fullscreen", "
+//!     @vertex fn main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4f {
+//!         let positions = array(vec2f(-1.0, -1.0), vec2f(3.0, -1.0), vec2f(-1.0, 3.0));
+//!         return vec4f(positions[i], 0.0, 1.0);
+//!     }".to_string());
+//! let fragment_shader = FragmentShader::new("write", "
+//!     @group(0) @binding(0) var<storage, read_write> results: array<u32>;
+//!     @fragment fn main() -> @location(0) vec4f {
+//!         results[0] = 42u;
+//!         return vec4f(1.0);
+//!     }".to_string());
+//! let mut bind_style = BindStyle::new();
+//! bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Fragment, &results);
+//! port.add_fixed_pass(PassDescriptor::new("write".to_string(), vertex_shader,
+//!     fragment_shader, bind_style, DrawCommand::TriangleList(1), false, false))
+//!     .await
+//!     .expect("Failed to add pass");
+//! port.force_render().await;
+//!
+//! let read_guard = results.access_read().await;
+//! assert_eq!(read_guard.get(0), 42);
+//! # }, "reverse_buffer_doctest");
+//! # }
+//! ```
+
+use crate::bindings::dirty_tracking::DirtyReceiver;
+use crate::bindings::forward::dynamic::buffer::CRepr;
+use crate::images::BoundDevice;
+use crate::imp;
+use crate::imp::SendPhantom;
+use crate::multibuffer::Multibuffer;
+use crate::multibuffer::{CPUReadGuard, GPUGuard};
+use std::fmt::{Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Shared state between CPU and GPU sides of a reverse buffer.
+///
+/// This struct contains the multibuffer that coordinates access between
+/// GPU writes and CPU reads, ensuring proper synchronization.
+struct Shared {
+    multibuffer: Multibuffer<imp::MappableReadBuffer, imp::GPUableBuffer>,
+}
+impl Debug for Shared {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Shared")
+            .field("multibuffer", &self.multibuffer)
+            .finish()
+    }
+}
+
+/// A GPU buffer that shaders write into and the CPU reads back.
+///
+/// `Buffer<T>` is bound to passes as a read-write storage buffer.  After each frame that
+/// binds it, the results are copied back, and [`Buffer::access_read`] waits for them.
+///
+/// # Type Parameter
+///
+/// The type parameter `Element` must implement [`CRepr`] to ensure C-compatible
+/// memory layout for GPU interoperability.
+///
+/// # Thread Safety
+///
+/// This type is `Clone` and can be safely shared between threads. The underlying
+/// synchronization ensures that GPU writes and CPU reads are properly coordinated.
+#[derive(Debug, Clone)]
+pub struct Buffer<Element> {
+    shared: Arc<Shared>,
+    count: usize,
+    _phantom: PhantomData<Element>,
+}
+
+/// Read access to the results of one frame.
+///
+/// Returned by [`Buffer::access_read`].  While this guard is held, frames continue to
+/// render, but their results are not copied back; the next read sees the first frame
+/// rendered after this guard is dropped.
+pub struct CPUReadAccess<'a, Element> {
+    guard: CPUReadGuard<'a, imp::MappableReadBuffer, imp::GPUableBuffer>,
+    _marker: SendPhantom<Element>,
+    count: usize,
+}
+
+impl<Element> Debug for CPUReadAccess<'_, Element> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CPUReadAccess")
+            .field("guard", &self.guard)
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+impl<Element> CPUReadAccess<'_, Element> {
+    /// Returns the number of elements in the buffer.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the buffer has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Reads the element at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> Element
+    where
+        Element: CRepr,
+    {
+        assert!(
+            index < self.count,
+            "index {index} is out of bounds for a buffer of {count} elements",
+            count = self.count
+        );
+        let bytes = self.guard.as_slice();
+        //safety: CRepr types are plain data and the index was checked above
+        unsafe {
+            std::ptr::read_unaligned(
+                bytes.as_ptr().add(index * std::mem::size_of::<Element>()) as *const Element
+            )
+        }
+    }
+
+    /// Copies every element out of the buffer.
+    pub fn to_vec(&self) -> Vec<Element>
+    where
+        Element: CRepr,
+    {
+        (0..self.count).map(|index| self.get(index)).collect()
+    }
+}
+
+/// GPU-side handle for a reverse buffer.
+///
+/// This type is used internally by the render pass system and is not directly
+/// accessible to users.  Unlike the forward `RenderSide`, it needs no element type,
+/// since the render system never interprets the contents.
+#[derive(Debug, Clone)]
+pub(crate) struct RenderSide {
+    shared: Arc<Shared>,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) element_size: usize,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) byte_size: usize,
+}
+
+/// Guards access to the underlying GPU buffer during rendering.
+///
+/// When a readback destination is present, the render system copies the GPU buffer into it
+/// after the frame's passes, and keeps this guard alive until the GPU has finished.
+/// Dropping it then hands the results to the CPU.
+#[derive(Debug)]
+pub(crate) struct GPUAccess {
+    readback_guard: Option<crate::bindings::resource_tracking::GPUGuard<imp::MappableReadBuffer>>,
+    underlying_guard: GPUGuard<imp::MappableReadBuffer, imp::GPUableBuffer>,
+}
+
+impl GPUAccess {
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn as_ref(&self) -> &imp::GPUableBuffer {
+        self.underlying_guard.as_imp()
+    }
+
+    /// Returns the GPU buffer, and the CPU-side buffer to copy this frame's results into.
+    ///
+    /// Returns `None` when the CPU is still reading earlier results, in which case
+    /// this frame's results are not copied back.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn readback(
+        &mut self,
+    ) -> Option<(&imp::GPUableBuffer, &mut imp::MappableReadBuffer)> {
+        let destination = self.readback_guard.as_deref_mut()?;
+        Some((self.underlying_guard.as_imp(), destination))
+    }
+}
+
+impl RenderSide {
+    /// Acquires the GPU buffer for a frame that writes it.
+    ///
+    /// # Safety
+    ///
+    /// The caller must keep the returned guard alive for the entire duration
+    /// of GPU operations using this buffer.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) unsafe fn acquire_gpu_buffer(&self) -> GPUAccess {
+        let mut underlying_guard = unsafe { self.shared.multibuffer.access_gpu_write() };
+        //we release the readback guard ourselves, once the copy has executed
+        let readback_guard = underlying_guard.take_dirty_guard();
+        GPUAccess {
+            readback_guard,
+            underlying_guard,
+        }
+    }
+
+    /// Returns a receiver that is dirty while the CPU waits for results.
+    ///
+    /// A running port renders when this is dirty, so a pending read is answered.
+    pub(crate) fn dirty_receiver(&self) -> DirtyReceiver {
+        self.shared.multibuffer.cpu_dirty_receiver()
+    }
+
+    /// Returns the underlying GPU buffer.
+    ///
+    /// # Safety
+    ///
+    /// This method bypasses all synchronization. The caller must ensure
+    /// no data races occur.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) unsafe fn unsafe_imp(&self) -> &imp::GPUableBuffer {
+        unsafe { self.shared.multibuffer.access_gpu_unsafe() }
+    }
+}
+
+impl PartialEq for RenderSide {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+/// Error type for reverse buffer operations.
+///
+/// This error wraps underlying implementation errors that can occur during
+/// buffer creation, such as running out of memory or GPU device errors.
+#[derive(thiserror::Error, Debug)]
+pub struct Error(#[from] imp::Error);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<Element> Buffer<Element> {
+    /// Creates a new reverse buffer with the specified size.
+    ///
+    /// # Parameters
+    ///
+    /// * `bound_device` - The GPU device to create the buffer on
+    /// * `size` - Number of elements in the buffer
+    /// * `debug_name` - Human-readable name for debugging and profiling
+    /// * `initialize_with` - Function to initialize each element by index.  Shaders see these
+    ///   values until they overwrite them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0, as zero-sized buffers are not allowed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// use images_and_words::bindings::reverse::buffer::Buffer;
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// # use images_and_words::images::view::View;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+    /// # let device = engine.bound_device();
+    /// let buffer = Buffer::<f32>::new(device.clone(), 64, "compute_results", |_| 0.0)
+    ///     .await
+    ///     .expect("Failed to create buffer");
+    /// # }, "reverse_buffer_new_doctest");
+    /// # }
+    /// ```
+    pub async fn new(
+        bound_device: Arc<BoundDevice>,
+        size: usize,
+        debug_name: &str,
+        initialize_with: impl Fn(usize) -> Element,
+    ) -> Result<Self, Error>
+    where
+        Element: CRepr,
+    {
+        let byte_size = size * std::mem::size_of::<Element>();
+        assert_ne!(byte_size, 0, "Zero-sized buffers are not allowed");
+
+        let mut initial = Vec::with_capacity(byte_size);
+        let gpu_buffer = imp::GPUableBuffer::new_gpu_writable(
+            bound_device.clone(),
+            byte_size,
+            debug_name,
+            |byte_array| {
+                let bytes = crate::bindings::forward::r#static::buffer::initialize_byte_array_with(
+                    size,
+                    byte_array,
+                    initialize_with,
+                );
+                initial.extend_from_slice(bytes);
+                bytes
+            },
+        )
+        .await;
+        let read_buffer =
+            imp::MappableReadBuffer::new(bound_device, byte_size, debug_name, initial).await?;
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                multibuffer: Multibuffer::new(
+                    read_buffer,
+                    gpu_buffer,
+                    false,
+                    debug_name.to_string(),
+                ),
+            }),
+            count: size,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the number of elements in the buffer.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the buffer has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Waits for the GPU to write the buffer, then acquires read access to the results.
+    ///
+    /// This resolves after the next frame that binds the buffer has finished on the GPU.
+    /// Frames rendered before this call, whose results were not yet read, also count; so
+    /// calling this in a loop observes each frame's results at most once.
+    ///
+    /// If the port is running, a pending read causes it to render a frame.  Otherwise,
+    /// this waits until a frame is rendered, for example with
+    /// [`Port::force_render`](crate::images::port::Port::force_render).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// use images_and_words::bindings::reverse::buffer::Buffer;
+    /// # use images_and_words::bindings::BindStyle;
+    /// # use images_and_words::bindings::bind_style::{BindSlot, Stage};
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// # use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+    /// # use images_and_words::images::shader::{FragmentShader, VertexShader};
+    /// # use images_and_words::images::view::View;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+    /// # let device = engine.bound_device();
+    /// # let port = engine.main_port();
+    /// let buffer = Buffer::<u32>::new(device.clone(), 4, "untouched", |i| i as u32)
+    ///     .await
+    ///     .expect("Failed to create buffer");
+    /// # let vertex_shader = VertexShader::new("none", "@vertex fn main() -> @builtin(position) vec4f { return vec4f(0.0); }".to_string());
+    /// # let fragment_shader = FragmentShader::new("keep", "@group(0) @binding(0) var<storage, read_write> b: array<u32>;
+    /// #     @fragment fn main() -> @location(0) vec4f { b[0] = b[0]; return vec4f(0.0); }".to_string());
+    /// # let mut bind_style = BindStyle::new();
+    /// # bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Fragment, &buffer);
+    /// # port.add_fixed_pass(PassDescriptor::new("keep".to_string(), vertex_shader,
+    /// #     fragment_shader, bind_style, DrawCommand::TriangleList(1), false, false))
+    /// #     .await
+    /// #     .expect("Failed to add pass");
+    /// port.force_render().await;
+    /// let read_guard = buffer.access_read().await;
+    /// assert_eq!(read_guard.to_vec(), vec![0, 1, 2, 3]);
+    /// # }, "reverse_buffer_access_read_doctest");
+    /// # }
+    /// ```
+    pub async fn access_read(&self) -> CPUReadAccess<'_, Element> {
+        let guard = self.shared.multibuffer.access_read().await;
+        CPUReadAccess {
+            guard,
+            _marker: SendPhantom::new(),
+            count: self.count,
+        }
+    }
+
+    /// Creates a render-side handle for use in GPU operations.
+    ///
+    /// This method is used internally by the binding system to create a handle
+    /// that can be used during rendering.
+    pub(crate) fn render_side(&self) -> RenderSide {
+        RenderSide {
+            shared: self.shared.clone(),
+            element_size: std::mem::size_of::<Element>(),
+            byte_size: self.count * std::mem::size_of::<Element>(),
+        }
+    }
+}
+
+// Boilerplate trait implementations
+
+impl<Element> PartialEq for Buffer<Element> {
+    /// Two buffers are equal if they reference the same underlying shared buffer.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<Element> Eq for Buffer<Element> {}
+
+impl<Element> std::hash::Hash for Buffer<Element> {
+    /// Hashes the buffer based on its shared data pointer.
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.shared).hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<super::Buffer<u8>>();
+    }
+}
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+/*! Resources that pass data in the 'sideways' direction, that is, GPU=>GPU.
+
+# Overview
+
+Sideways resources let one pass's output feed a later pass, without a round trip
+through the CPU.  Use them for:
+- Post-processing (render the scene, then filter it)
+- Shadow maps (render depth from a light, then sample it)
+- Any multi-pass technique
+
+## Pass ordering
+
+A pass renders into a [`RenderTexture`] when it is the pass's target
+(see [`PassDescriptor::with_render_target`](crate::images::render_pass::PassDescriptor::with_render_target)),
+and reads it when it is bound with
+[`BindStyle::bind_render_texture`](crate::bindings::BindStyle::bind_render_texture).
+The port runs every pass that renders into a texture before the passes that read it,
+whatever order they were added in.  Otherwise, passes run in the order they were added.
+
+Each frame, a render texture is cleared before the first pass that renders into it.
+
+## Examples
+
+```
+# if cfg!(not(feature="backend_wgpu")) { return; }
+# #[cfg(feature = "testing")]
+# {
+use images_and_words::bindings::sideways::RenderTexture;
+use images_and_words::pixel_formats::RGBA8UNorm;
+# use images_and_words::images::projection::WorldCoord;
+# use images_and_words::images::view::View;
+# test_executors::spawn_local(async {
+# let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+# let device = engine.bound_device();
+// The scene, rendered at reduced resolution for a blur pass
+let scene = RenderTexture::<RGBA8UNorm>::new(device, 200, 150, true, "scene")
+    .await
+    .expect("Failed to create render texture");
+# }, "sideways_module_doctest");
+# }
+```
+*/
+
+pub mod render_texture;
+
+pub use render_texture::RenderTexture;
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Textures that passes render into and later passes sample.
+//!
+//! This module provides [`RenderTexture`], a color attachment (with an optional depth
+//! attachment) that lives entirely on the GPU.
+//!
+//! # Example
+//!
+//! A post-processing chain: the first pass renders into a texture, and the second samples
+//! it onto the screen.
+//!
+//! ```
+//! # if cfg!(not(feature="backend_wgpu")) { return; }
+//! # #[cfg(feature = "testing")]
+//! # {
+//! use images_and_words::bindings::BindStyle;
+//! use images_and_words::bindings::bind_style::{BindSlot, Stage};
+//! use images_and_words::bindings::sideways::RenderTexture;
+//! use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+//! use images_and_words::images::shader::{FragmentShader, VertexShader};
+//! use images_and_words::pixel_formats::RGBA8UNorm;
+//! # use images_and_words::images::projection::WorldCoord;
+//! # use images_and_words::images::view::View;
+//! # test_executors::spawn_local(async {
+//! # let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+//! # let device = engine.bound_device();
+//! # let port = engine.main_port();
+//! let scene = RenderTexture::<RGBA8UNorm>::new(device, 64, 64, false, "scene")
+//!     .await
+//!     .expect("Failed to create render texture");
+//! let fullscreen = "@vertex fn main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4f {
+//!     let uv = vec2f(f32((i << 1u) & 2u), f32(i & 2u));
+//!     return vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+//! }";
+//!
+//! // Added first, but runs second, since it samples `scene`
+//! let mut post_binds = BindStyle::new();
+//! post_binds.bind_render_texture(BindSlot::new(0), Stage::Fragment, &scene, None);
+//! port.add_fixed_pass(PassDescriptor::new(
+//!     "post".to_string(),
+//!     VertexShader::new("fullscreen", fullscreen.to_string()),
+//!     FragmentShader::new("invert", "
+//!         @group(0) @binding(0) var scene: texture_2d<f32>;
+//!         @fragment fn main(@builtin(position) p: vec4f) -> @location(0) vec4f {
+//!             let color = textureLoad(scene, vec2u(p.xy) % 64u, 0);
+//!             return vec4f(1.0 - color.rgb, 1.0);
+//!         }".to_string()),
+//!     post_binds,
+//!     DrawCommand::TriangleList(1),
+//!     false,
+//!     false,
+//! )).await.expect("Failed to add pass");
+//!
+//! port.add_fixed_pass(PassDescriptor::new(
+//!     "scene".to_string(),
+//!     VertexShader::new("fullscreen", fullscreen.to_string()),
+//!     FragmentShader::new("red", "@fragment fn main() -> @location(0) vec4f { return vec4f(1.0, 0.0, 0.0, 1.0); }".to_string()),
+//!     BindStyle::new(),
+//!     DrawCommand::TriangleList(1),
+//!     false,
+//!     false,
+//! ).with_render_target(&scene)).await.expect("Failed to add pass");
+//! port.force_render().await;
+//! # }, "render_texture_doctest");
+//! # }
+//! ```
+
+use crate::images::BoundDevice;
+use crate::imp;
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A texture that passes render into, and later passes sample.
+///
+/// Render into it by making it a pass's target with
+/// [`PassDescriptor::with_render_target`](crate::images::render_pass::PassDescriptor::with_render_target).
+/// Sample it by binding it with
+/// [`BindStyle::bind_render_texture`](crate::bindings::BindStyle::bind_render_texture), or
+/// its depth attachment with
+/// [`BindStyle::bind_render_texture_depth`](crate::bindings::BindStyle::bind_render_texture_depth).
+///
+/// The contents never leave the GPU.  They are cleared at the start of each frame, before
+/// the first pass that renders into them.
+///
+/// # Type Parameter
+///
+/// `Format` is the pixel format of the color attachment, which must be renderable.
+/// Depth attachments are always 32-bit floats, read in WGSL as `texture_depth_2d`.
+///
+/// # Thread Safety
+///
+/// This type is `Clone`, and clones refer to the same texture.
+#[derive(Debug, Clone)]
+pub struct RenderTexture<Format> {
+    pub(crate) imp: imp::RenderTexture,
+    width: u16,
+    height: u16,
+    _format: PhantomData<Format>,
+}
+
+/// Error type for render texture creation.
+#[derive(Debug, thiserror::Error)]
+pub struct Error(#[from] imp::Error);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl<Format: crate::pixel_formats::sealed::PixelFormat> RenderTexture<Format> {
+    /// Creates a new render texture.
+    ///
+    /// # Parameters
+    ///
+    /// * `bound_device` - The GPU device to create the texture on
+    /// * `width`, `height` - The size of the attachments, in pixels
+    /// * `depth` - Whether to create a depth attachment.  Passes that render into the
+    ///   texture with depth testing need one.
+    /// * `debug_name` - Human-readable name for debugging and profiling
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// use images_and_words::bindings::sideways::RenderTexture;
+    /// use images_and_words::pixel_formats::RGBA8UNorm;
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// # use images_and_words::images::view::View;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+    /// # let device = engine.bound_device();
+    /// // A shadow map only needs its depth, but every render texture has a color attachment
+    /// let shadow_map = RenderTexture::<RGBA8UNorm>::new(device, 1024, 1024, true, "shadow_map")
+    ///     .await
+    ///     .expect("Failed to create render texture");
+    /// assert!(shadow_map.has_depth());
+    /// # }, "render_texture_new_doctest");
+    /// # }
+    /// ```
+    pub async fn new(
+        bound_device: &Arc<BoundDevice>,
+        width: u16,
+        height: u16,
+        depth: bool,
+        debug_name: &str,
+    ) -> Result<Self, Error> {
+        let imp = imp::RenderTexture::new::<Format>(bound_device, width, height, depth, debug_name)
+            .await?;
+        Ok(RenderTexture {
+            imp,
+            width,
+            height,
+            _format: PhantomData,
+        })
+    }
+}
+
+impl<Format> RenderTexture<Format> {
+    /// Returns the width of the texture, in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the texture, in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns whether the texture has a depth attachment.
+    pub fn has_depth(&self) -> bool {
+        self.imp.has_depth()
+    }
+}
+
+// Boilerplate trait implementations
+
+impl<Format> PartialEq for RenderTexture<Format> {
+    /// Two render textures are equal if they refer to the same texture.
+    fn eq(&self, other: &Self) -> bool {
+        self.imp == other.imp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<super::RenderTexture<crate::pixel_formats::RGBA8UNorm>>();
+    }
+}
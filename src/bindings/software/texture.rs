@@ -552,6 +552,32 @@ impl<Format: PixelFormat> Texture<Format> {
             data: vec,
         }
     }
+    /// Creates a texture from tightly packed pixels, in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is not exactly `width * height` pixels long.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn from_bytes(width: u16, height: u16, bytes: &[u8]) -> Self {
+        let pixel_size = std::mem::size_of::<Format::CPixel>();
+        assert_eq!(
+            bytes.len(),
+            width as usize * height as usize * pixel_size,
+            "Expected {width}x{height} pixels"
+        );
+        let data = bytes
+            .chunks_exact(pixel_size)
+            //safe because CPixel is ReprC, and the chunk is exactly one pixel
+            .map(|pixel| unsafe {
+                std::ptr::read_unaligned(pixel.as_ptr() as *const Format::CPixel)
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
     /// Creates a new texture with pixels initialized by a function, computed in parallel.
     ///
     /// This is useful for expensive per-pixel computations that can benefit from
@@ -700,6 +726,51 @@ impl<Format: PixelFormat> Texture<Format> {
         }
     }
 
+    /// Encodes the texture as a PNG image.
+    ///
+    /// This is the inverse of [`new_from_path`](Self::new_from_path), and is available for
+    /// the same pixel formats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use images_and_words::bindings::software::texture::Texture;
+    /// use images_and_words::pixel_formats::R8UNorm;
+    ///
+    /// let texture = Texture::<R8UNorm>::new(4, 4, 128);
+    /// let png = texture.encode_png();
+    /// assert_eq!(&png[1..4], b"PNG");
+    /// ```
+    pub fn encode_png(&self) -> Vec<u8>
+    where
+        Format: PngPixelFormat,
+    {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width as u32, self.height as u32);
+        encoder.set_color(Format::png_color_type());
+        encoder.set_depth(Format::png_bit_depth());
+        let mut writer = encoder.write_header().expect("Writing to a Vec can't fail");
+        writer
+            .write_image_data(crate::pixel_formats::pixel_as_bytes(&self.data))
+            .expect("Texture size matches the PNG header");
+        writer.finish().expect("Writing to a Vec can't fail");
+        png
+    }
+
+    /// Writes the texture to a PNG file.
+    ///
+    /// See [`encode_png`](Self::encode_png).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save_png(&self, path: &Path) -> std::io::Result<()>
+    where
+        Format: PngPixelFormat,
+    {
+        std::fs::write(path, self.encode_png())
+    }
+
     /// Creates a new texture by copying data from any type implementing `VTexture`.
     ///
     /// This is useful for converting between different texture representations
@@ -108,6 +108,9 @@ pub enum TextureUsage {
 ///
 /// // A uniform buffer accessed by the fragment shader
 /// let uniform_usage = GPUBufferUsage::FragmentShaderRead;
+///
+/// // A storage buffer that a compute shader updates in place
+/// let storage_usage = GPUBufferUsage::ShaderReadWrite;
 /// ```
 #[non_exhaustive]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -124,6 +127,19 @@ pub enum GPUBufferUsage {
     /// by fragment shaders.
     FragmentShaderRead,
 
+    /// The buffer will be read as data in compute shaders.
+    ///
+    /// Use for uniform buffers, storage buffers, or other data accessed
+    /// by compute shaders.
+    ComputeShaderRead,
+
+    /// The buffer is a storage buffer that shaders both read and write.
+    ///
+    /// Compute and fragment shaders bind it as `var<storage, read_write>`.  Vertex
+    /// shaders cannot write storage buffers, so there it binds as `var<storage, read>`.
+    /// Use this for data a compute pass produces for later passes, or updates in place.
+    ShaderReadWrite,
+
     /// The buffer contains vertex attribute data.
     ///
     /// This buffer will be bound as a vertex buffer and its contents will be
@@ -135,6 +151,13 @@ pub enum GPUBufferUsage {
     /// This buffer will be bound as an index buffer and used to specify the
     /// order in which vertices are assembled into primitives.
     Index,
+
+    /// The buffer holds the arguments of indirect draws.
+    ///
+    /// Bind it with [`BindStyle::bind_indirect_buffer`](crate::bindings::BindStyle::bind_indirect_buffer).
+    /// Shaders can also write it like a [`ShaderReadWrite`](Self::ShaderReadWrite) buffer,
+    /// so a compute pass can produce the arguments that later passes draw with.
+    Indirect,
 }
 
 /// Describes the CPU's access pattern for a GPU resource.
@@ -4,7 +4,9 @@
 //!
 //! This module provides custom commands for the exfiltrate debugging tool.
 
-use exfiltrate::command::{Command, Response};
+use crate::images::port::CaptureRequest;
+use exfiltrate::command::{Command, ImageInfo, Response};
+use exfiltrate::rgb::RGBA8;
 use std::time::Duration;
 use wasm_safe_mutex::mpsc;
 
@@ -14,6 +16,10 @@ use std::time::Instant;
 #[cfg(target_arch = "wasm32")]
 use web_time::Instant;
 
+/// Captures requested by commands, served by the next port to render.
+pub(crate) static PENDING_CAPTURES: wasm_safe_thread::Mutex<Vec<CaptureRequest>> =
+    wasm_safe_thread::Mutex::new(Vec::new());
+
 /// Custom command to capture a screenshot of the main port.
 ///
 /// This command waits for the next frame to be rendered and returns it as an image.
@@ -45,68 +51,52 @@ impl Command for IwDumpMainportScreenshot {
     }
 
     fn execute(&self, _args: Vec<String>) -> Result<Response, Response> {
-        // Create a oneshot channel for receiving the frame data
         let (tx, rx) = mpsc::channel();
-
-        // Set the global DUMP_NEXT_FRAME to our sender
-        {
-            use crate::imp::DUMP_NEXT_FRAME;
-            let mut dump_frame = DUMP_NEXT_FRAME.lock_sync();
-            if dump_frame.is_some() {
-                return Err(Response::from("A frame dump is already pending"));
-            }
-            *dump_frame = Some(tx);
-        }
-
-        // Wait for the frame data with a 10 second timeout
-        let mut images = Vec::new();
-        let mut expected_count = 2; // Default to 2 (color + depth) if no Expect message received (backward compat/robustness)
-
-        let start = Instant::now();
-        let timeout = Duration::from_secs(10);
-
-        loop {
-            if images.len() >= expected_count {
-                break;
-            }
-
-            let remaining = timeout
-                .checked_sub(start.elapsed())
-                .unwrap_or(Duration::ZERO);
-            if remaining.is_zero() {
+        //served by whichever port renders next, alongside its own captures
+        PENDING_CAPTURES
+            .lock_sync()
+            .push(CaptureRequest::channel(tx));
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let frame = match rx.recv_sync_timeout(deadline) {
+            Ok(Ok(frame)) => frame,
+            Ok(Err(err)) => return Err(Response::from(format!("Failed to capture frame: {err}"))),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
                 return Err(Response::from(
                     "Timeout waiting for frame. Ensure the render loop is active and rendering frames.",
                 ));
             }
-
-            match rx.recv_sync_timeout(Instant::now() + remaining) {
-                Ok(msg) => {
-                    use crate::imp::DumpMessage;
-                    match msg {
-                        DumpMessage::Expect(count) => {
-                            expected_count = count;
-                        }
-                        DumpMessage::Image(img) => {
-                            images.push(img);
-                        }
-                    }
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    return Err(Response::from(
-                        "Timeout waiting for frame. Ensure the render loop is active and rendering frames.",
-                    ));
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    // If we have at least one image, return what we have?
-                    // Or is it an error if we expected more?
-                    // For now, let's error if we didn't get what we expected.
-                    return Err(Response::from(
-                        "Channel disconnected before receiving all expected images.",
-                    ));
-                }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(Response::from(
+                    "Capture was dropped before a frame was rendered.",
+                ));
             }
+        };
+
+        let width = frame.color.width() as u32;
+        let color = frame
+            .color
+            .texture_data()
+            .iter()
+            .map(|p| RGBA8::new(p.r, p.g, p.b, p.a))
+            .collect();
+        let mut images = vec![ImageInfo::new(
+            color,
+            width,
+            Some("Color buffer".to_string()),
+        )];
+        if let Some(depth) = frame.depth_image() {
+            let depth = depth
+                .texture_data()
+                .iter()
+                .map(|&d| RGBA8::new(d, d, d, 255))
+                .collect();
+            images.push(ImageInfo::new(
+                depth,
+                width,
+                Some("depth buffer".to_string()),
+            ));
         }
-
         Ok(Response::Images(images))
     }
 }
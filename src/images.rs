@@ -12,7 +12,8 @@
 //! - **[`Engine`](crate::images::Engine)**: The main entry point for rendering operations, managing the GPU device
 //!   and rendering context
 //! - **[`render_pass`](crate::images::render_pass)**: Configuration for GPU draw operations including shaders and draw commands
-//! - **[`shader`](crate::images::shader)**: Vertex and fragment shader types for GPU programming
+//! - **[`compute_pass`](crate::images::compute_pass)**: Configuration for compute shaders that run before the render passes
+//! - **[`shader`](crate::images::shader)**: Vertex, fragment, and compute shader types for GPU programming
 //! - **[`view`](crate::images::view)**: Display surface abstraction for rendering targets
 //! - **[`port`](crate::images::port)**: Viewport and camera management for 3D rendering
 //! - **[`projection`](crate::images::projection)**: Coordinate systems and projection matrices
@@ -48,7 +49,9 @@
 
 pub use engine::CreateError;
 pub use engine::Engine;
+pub use engine::EngineOptions;
 
+pub mod compute_pass;
 pub mod render_pass;
 
 pub(crate) mod device;
@@ -60,6 +63,7 @@ pub(crate) mod camera;
 mod frame;
 pub mod index_algorithms;
 pub mod projection;
+#[cfg(feature = "backend_wgpu")]
 pub(crate) mod request_animation_frame;
 pub mod shader;
 pub mod vertex_algorithms;
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Compute pass configuration.
+//!
+//! A compute pass runs a compute shader over some number of workgroups, without drawing
+//! anything.  Compute passes are added to a [`Port`](crate::images::port::Port) alongside
+//! render passes, and run before them each frame, so render passes can use their results.
+//!
+//! # Key Components
+//!
+//! - [`ComputePassDescriptor`]: Configures a compute pass, including its shader and
+//!   resource bindings
+//! - [`Dispatch`]: Specifies how many workgroups the pass runs
+//!
+//! # Example
+//!
+//! ```
+//! use images_and_words::images::compute_pass::{ComputePassDescriptor, Dispatch};
+//! use images_and_words::images::shader::ComputeShader;
+//! use images_and_words::bindings::BindStyle;
+//!
+//! let shader = ComputeShader::new("noop",
+//!     "@compute @workgroup_size(64) fn cs_main() {}".to_string());
+//!
+//! // Enough workgroups of 64 invocations to cover 1000 elements
+//! let pass = ComputePassDescriptor::new(
+//!     "noop_pass".to_string(),
+//!     shader,
+//!     BindStyle::new(),
+//!     Dispatch::for_elements(1000, 64),
+//! );
+//! ```
+
+use crate::bindings::BindStyle;
+use crate::images::shader::ComputeShader;
+use crate::images::shader::validation::ShaderError;
+
+/// Configuration for a compute pass.
+///
+/// A compute pass dispatches a compute shader once per frame, before the port's render
+/// passes.  Bind resources with [`Stage::Compute`](crate::bindings::bind_style::Stage::Compute);
+/// storage buffers the shader writes should be created with
+/// [`GPUBufferUsage::ShaderReadWrite`](crate::bindings::visible_to::GPUBufferUsage::ShaderReadWrite),
+/// or be [reverse buffers](crate::bindings::reverse::buffer::Buffer) to read the results
+/// back on the CPU.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputePassDescriptor {
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) name: String,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) shader: ComputeShader,
+    pub(crate) bind_style: BindStyle,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) dispatch: Dispatch,
+}
+
+impl ComputePassDescriptor {
+    /// Creates a new compute pass descriptor.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - A descriptive name for debugging and profiling
+    /// * `shader` - The compute shader to run
+    /// * `bind_style` - Resource bindings configuration (buffers, textures, etc.)
+    /// * `dispatch` - How many workgroups to run
+    pub fn new(
+        name: String,
+        shader: ComputeShader,
+        bind_style: BindStyle,
+        dispatch: Dispatch,
+    ) -> Self {
+        Self {
+            name,
+            shader,
+            bind_style,
+            dispatch,
+        }
+    }
+
+    /// Checks the compute shader against the pass's bindings, and its constants against its
+    /// `override`s, as
+    /// [`PassDescriptor::validate`](crate::images::render_pass::PassDescriptor::validate)
+    /// does for render passes.
+    ///
+    /// Ports on the wgpu backend call this when a compute pass is added or replaced, and
+    /// refuse the pass with [`PassError::Shader`](crate::images::render_pass::PassError::Shader)
+    /// if it fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first mistake found; see [`ShaderError`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::compute_pass::{ComputePassDescriptor, Dispatch};
+    /// use images_and_words::images::shader::ComputeShader;
+    /// use images_and_words::images::shader::validation::ShaderError;
+    ///
+    /// let shader = ComputeShader::new("clear",
+    ///     "@group(0) @binding(0) var<storage, read_write> values: array<f32>;
+    ///     @compute @workgroup_size(64) fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    ///         values[id.x] = 0.0;
+    ///     }".to_string());
+    /// let pass = ComputePassDescriptor::new("clear".to_string(), shader, BindStyle::new(),
+    ///     Dispatch::Workgroups(1, 1, 1));
+    ///
+    /// // The pass forgot to bind the values
+    /// assert!(matches!(pass.validate(), Err(ShaderError::Unbound { .. })));
+    /// ```
+    pub fn validate(&self) -> Result<(), ShaderError> {
+        crate::images::shader::validation::validate_compute_pass(self)
+    }
+
+    /// Returns the name of this compute pass.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the resource bindings configuration for this compute pass.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn bind_style(&self) -> &BindStyle {
+        &self.bind_style
+    }
+}
+
+/// Specifies how many workgroups a compute pass runs.
+///
+/// # Examples
+///
+/// ```
+/// use images_and_words::images::compute_pass::Dispatch;
+///
+/// // An 8x8 grid of workgroups
+/// let grid = Dispatch::Workgroups(8, 8, 1);
+/// assert_eq!(grid.workgroup_count(), (8, 8, 1));
+///
+/// // One invocation per element, in workgroups of 64
+/// let per_element = Dispatch::for_elements(1000, 64);
+/// assert_eq!(per_element.workgroup_count(), (16, 1, 1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Dispatch {
+    /// Runs a fixed number of workgroups in the x, y and z dimensions.
+    Workgroups(u32, u32, u32),
+
+    /// Runs enough workgroups along x to cover `elements` invocations.
+    ///
+    /// `workgroup_size` must match the x dimension of `@workgroup_size` in the shader.
+    /// The last workgroup may run past the end, so the shader should check its index
+    /// against the length of the data.
+    Elements {
+        /// The number of invocations needed, usually the length of a buffer.
+        elements: u32,
+        /// The number of invocations in each workgroup.
+        workgroup_size: u32,
+    },
+}
+
+impl Dispatch {
+    /// Runs one invocation per element, in workgroups of `workgroup_size`.
+    ///
+    /// Pass the length of the buffer the shader processes as `elements`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workgroup_size` is zero, or `elements` does not fit in a `u32`.
+    pub fn for_elements(elements: usize, workgroup_size: u32) -> Self {
+        assert!(workgroup_size > 0, "workgroup_size must be nonzero");
+        Dispatch::Elements {
+            elements: elements
+                .try_into()
+                .expect("too many elements for one dispatch"),
+            workgroup_size,
+        }
+    }
+
+    /// Returns the number of workgroups to run in the x, y and z dimensions.
+    pub fn workgroup_count(&self) -> (u32, u32, u32) {
+        match *self {
+            Dispatch::Workgroups(x, y, z) => (x, y, z),
+            Dispatch::Elements {
+                elements,
+                workgroup_size,
+            } => (elements.div_ceil(workgroup_size), 1, 1),
+        }
+    }
+}
@@ -65,6 +65,34 @@ impl BoundDevice {
             .map_err(BindError)?;
         Ok(Self(bind))
     }
+
+    /// Returns whether passes on this device can use `polygon_mode`.
+    ///
+    /// [`PolygonMode::Fill`] is always supported.  Line and point modes depend on the
+    /// GPU, and are unavailable in browsers.
+    ///
+    /// [`PolygonMode::Fill`]: crate::images::render_pass::PolygonMode::Fill
+    pub fn supports_polygon_mode(
+        &self,
+        polygon_mode: crate::images::render_pass::PolygonMode,
+    ) -> bool {
+        self.0.supports_polygon_mode(polygon_mode)
+    }
+
+    /// Returns whether passes on this device can use
+    /// [unclipped depth](crate::images::render_pass::RasterizerState::with_unclipped_depth).
+    pub fn supports_unclipped_depth(&self) -> bool {
+        self.0.supports_unclipped_depth()
+    }
+
+    /// Returns whether indirect draws on this device honor a non-zero `first_instance` in
+    /// [`DrawIndirectArgs`](crate::images::render_pass::DrawIndirectArgs) and
+    /// [`DrawIndexedIndirectArgs`](crate::images::render_pass::DrawIndexedIndirectArgs).
+    ///
+    /// It depends on the GPU.  Elsewhere, `first_instance` must be 0.
+    pub fn supports_indirect_first_instance(&self) -> bool {
+        self.0.supports_indirect_first_instance()
+    }
 }
 
 // Boilerplate implementations
@@ -6,6 +6,7 @@ use crate::images::port::Port;
 use crate::images::projection::WorldCoord;
 use crate::images::view::View;
 use crate::imp;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::OnceLock;
 
@@ -47,8 +48,19 @@ impl Engine {
     /// # Returns
     /// An Arc-wrapped engine instance, or an error if initialization fails.
     pub async fn rendering_to(
+        view: View,
+        initial_camera_position: WorldCoord,
+    ) -> Result<Arc<Self>, CreateError> {
+        Self::rendering_to_with_options(view, initial_camera_position, EngineOptions::new()).await
+    }
+
+    /// Creates a new rendering engine targeting the specified view, configured by `options`.
+    ///
+    /// See [`Engine::rendering_to`].
+    pub async fn rendering_to_with_options(
         mut view: View,
         initial_camera_position: WorldCoord,
+        options: EngineOptions,
     ) -> Result<Arc<Self>, CreateError> {
         // Register exfiltrate commands on first engine creation
         #[cfg(feature = "exfiltrate")]
@@ -87,6 +99,10 @@ impl Engine {
         let bound_device = Arc::new(BoundDevice::bind(unbound_device, entry_point.clone()).await?);
         logwise::info_sync!("Device bound successfully");
 
+        if let Some(directory) = &options.pipeline_cache {
+            bound_device.0.load_pipeline_cache(directory);
+        }
+
         logwise::info_sync!("Creating implementation engine...");
         let imp = crate::imp::Engine::rendering_to_view(&bound_device).await;
         logwise::info_sync!("Implementation engine created successfully");
@@ -140,6 +156,54 @@ impl Engine {
     pub fn bound_device(&self) -> &Arc<BoundDevice> {
         &self.device
     }
+
+    /// Writes the pipeline cache to disk now, rather than waiting for the engine to drop.
+    ///
+    /// Does nothing without a [pipeline cache](EngineOptions::with_pipeline_cache), or where
+    /// the device does not support one.
+    pub fn save_pipeline_cache(&self) -> Result<(), std::io::Error> {
+        self.device.0.save_pipeline_cache()
+    }
+}
+
+/// Options for creating an [`Engine`], see [`Engine::rendering_to_with_options`].
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::images::EngineOptions;
+///
+/// let options = EngineOptions::new().with_pipeline_cache("target/pipeline_cache");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct EngineOptions {
+    /// The directory to load and save the pipeline cache in, if any.
+    pub(crate) pipeline_cache: Option<PathBuf>,
+}
+
+impl EngineOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caches compiled pipelines in `directory` across runs of the app.
+    ///
+    /// Compiling a pipeline can take long enough to notice at launch when an app has many
+    /// passes.  With a cache, the engine loads the pipelines it compiled last time, and
+    /// saves the cache back when it drops, or on
+    /// [`Engine::save_pipeline_cache`].
+    ///
+    /// Each adapter and driver gets its own file in `directory`, and within it the driver
+    /// looks pipelines up by their shaders and state, so an edited shader or descriptor only
+    /// recompiles the pipelines it changes.  A missing or stale file starts an empty cache.
+    ///
+    /// Only some devices can cache pipelines (currently Vulkan); elsewhere this does nothing.
+    pub fn with_pipeline_cache(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.pipeline_cache = Some(directory.into());
+        self
+    }
 }
 
 // Boilerplate section
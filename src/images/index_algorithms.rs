@@ -40,10 +40,12 @@ use images_and_words::images::index_algorithms::IndexGenerator;
 // Create a 4x3 grid
 let generator = IndexGenerator::new(4, 3);
 
-// Populate an index buffer
-let mut indices = Vec::with_capacity(generator.num_indices());
+// Populate an index buffer.  u32 indices fit grids of any size; u16 halves the
+// buffer for grids of at most 65,536 vertices.  The buffer's element type picks
+// the index format it is bound with.
+let mut indices: Vec<u32> = Vec::with_capacity(generator.num_indices());
 for i in 0..generator.num_indices() {
-    indices.push(generator.index_for(i) as u16);
+    indices.push(generator.index_for(i) as u32);
 }
 
 // The indices are now ready to be uploaded to the GPU
@@ -37,27 +37,37 @@
 //! When any bound resource (buffer, texture, camera) is modified, the port
 //! schedules a new frame to be rendered.
 
-use crate::bindings::bind_style::BindTarget;
+use crate::bindings::bind_style::{BindTarget, IndexBinding, IndexTarget};
 use crate::bindings::dirty_tracking::{DirtyAggregateReceiver, DirtyReceiver, DirtySender};
+use crate::bindings::software::texture::Texture;
 use crate::bittricks::{u16s_to_u32, u32_to_u16s};
 use crate::images::Engine;
 use crate::images::camera::Camera;
+use crate::images::compute_pass::ComputePassDescriptor;
 use crate::images::frame::Frame;
 use crate::images::projection::{Projection, WorldCoord};
-use crate::images::render_pass::PassDescriptor;
-use crate::images::view::View;
+use crate::images::render_pass::{LoadOp, PassDescriptor, PassError, PassHandle, PassList};
+use crate::images::shader::file::read_wgsl;
+use crate::images::shader::validation::ShaderError;
+use crate::images::view::{Offscreen, View};
 use crate::imp;
+use crate::pixel_formats::sealed::PixelFormat;
+use crate::pixel_formats::{Float4, R8UNorm, R32Float, RGBA8UnormSRGB};
 use await_values::{Observer, Value};
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
 use wasm_safe_thread::Mutex;
 
 //for some reason we don't understand, using web_time here triggers
 //safari to reload the page eventually
 //mt2-782
 #[cfg(target_arch = "wasm32")]
-mod perf {
+pub(crate) mod perf {
     use std::time::Duration;
 
     fn performance() -> web_sys::Performance {
@@ -102,7 +112,7 @@ mod perf {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-mod perf {
+pub(crate) mod perf {
     use std::sync::LazyLock;
     use std::time::Duration;
 
@@ -233,6 +243,9 @@ impl FrameInfo {
 /// - Use different shaders
 /// - Draw different geometry
 ///
+/// Compute passes, added with [`Port::add_compute_pass`], run before the render passes
+/// each frame.
+///
 /// # Frame Synchronization
 ///
 /// The port uses dirty tracking to automatically render new frames when bound
@@ -241,26 +254,191 @@ impl FrameInfo {
 pub struct Port {
     imp: crate::imp::Port,
     port_reporter: PortReporter,
-    /// Render pass descriptors, wrapped in Mutex for interior mutability.
-    descriptors: wasm_safe_thread::Mutex<Vec<PassDescriptor>>,
+    /// Render passes, wrapped in Mutex for interior mutability.
+    passes: wasm_safe_thread::Mutex<PassList>,
+    /// Compute passes, in the order they run, kept for dirty tracking like `passes`.
+    compute_passes: wasm_safe_thread::Mutex<Vec<(PassHandle, ComputePassDescriptor)>>,
     camera: Camera,
     engine: Arc<Engine>,
     stop_signal: DirtySender,
     stopped_signal: DirtySender,
+    offscreen: Option<Offscreen>,
+    /// Callers waiting in [`Port::capture_next_frame`].
+    capture_requests: wasm_safe_thread::Mutex<Vec<CaptureRequest>>,
+    /// Marked dirty when a capture is requested, so the render loop draws a frame for it.
+    capture_signal: DirtySender,
+    /// Marked dirty when shaders are reloaded, so the render loop draws a frame with them.
+    reload_signal: DirtySender,
+    /// What the last [`Port::reload_shaders`] could not apply.
+    reload_problems: wasm_safe_thread::Mutex<ReloadProblems>,
+    /// Marked dirty to stop [`Port::watch_shaders`].
+    watch_stop_signal: DirtySender,
 }
 
 /// Error type for port operations.
-#[derive(Debug)]
-pub struct Error(imp::Error);
+#[derive(Debug, Clone)]
+pub struct Error(ErrorKind);
+
+#[derive(Debug, Clone)]
+enum ErrorKind {
+    //shared, so a failed capture can be reported to every waiting caller
+    Imp(Arc<imp::Error>),
+    NotOffscreen,
+    PortDropped,
+    FormatMismatch {
+        view: &'static str,
+        requested: &'static str,
+    },
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.0, f)
+        match &self.0 {
+            ErrorKind::Imp(e) => std::fmt::Display::fmt(e, f),
+            ErrorKind::NotOffscreen => {
+                write!(f, "Only ports rendering to an offscreen view can be read")
+            }
+            ErrorKind::PortDropped => {
+                write!(f, "The port was dropped before it rendered the next frame")
+            }
+            ErrorKind::FormatMismatch { view, requested } => write!(
+                f,
+                "Framebuffer was requested as {requested}, but the view renders {view}"
+            ),
+        }
     }
 }
 impl std::error::Error for Error {}
 impl From<imp::Error> for Error {
     fn from(e: imp::Error) -> Self {
-        Self(e)
+        Self(ErrorKind::Imp(Arc::new(e)))
+    }
+}
+
+/// The contents of an offscreen port's render target, read back to the CPU.
+///
+/// Returned by [`Port::read_framebuffer`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Framebuffer<Format: PixelFormat> {
+    /// The color attachment.
+    pub color: Texture<Format>,
+    /// The depth attachment, with values in `0.0..=1.0`.
+    ///
+    /// This is `Some` when any pass on the port uses depth, and the port renders one
+    /// [sample](Port::set_sample_count).
+    pub depth: Option<Texture<R32Float>>,
+}
+
+/// A framebuffer as read back by the backend, before it is typed.
+#[derive(Debug, Clone)]
+#[allow(dead_code)] //nop implementation does not use
+pub(crate) struct FramebufferData {
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    /// Color pixels, tightly packed.
+    ///
+    /// For [`Port::read_framebuffer`] these are in the view's format.  For captures they
+    /// are [`RGBA8UnormSRGB`].
+    pub(crate) color: Vec<u8>,
+    pub(crate) depth: Option<Vec<f32>>,
+}
+
+impl FramebufferData {
+    fn depth_texture(&self) -> Option<Texture<R32Float>> {
+        self.depth.as_ref().map(|depth| {
+            Texture::new_with(self.width, self.height, |texel| {
+                depth[texel.y as usize * self.width as usize + texel.x as usize]
+            })
+        })
+    }
+}
+
+/// A frame rendered by a port, as returned by [`Port::capture_next_frame`].
+///
+/// Unlike [`Framebuffer`], a capture works for any view.  The color is converted from
+/// whatever format the surface uses, so it can be saved or compared directly.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct CapturedFrame {
+    /// The color attachment, as displayed.
+    ///
+    /// Float surfaces are encoded to sRGB; normalized surfaces are copied as-is.
+    pub color: Texture<RGBA8UnormSRGB>,
+    /// The depth attachment, with values in `0.0..=1.0`.
+    ///
+    /// This is `Some` when any pass on the port uses depth, and the port renders one
+    /// [sample](Port::set_sample_count).
+    pub depth: Option<Texture<R32Float>>,
+}
+
+impl CapturedFrame {
+    fn new(data: &FramebufferData) -> Self {
+        CapturedFrame {
+            color: Texture::from_bytes(data.width, data.height, &data.color),
+            depth: data.depth_texture(),
+        }
+    }
+
+    /// Writes the color attachment to a PNG file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save_png(&self, path: &Path) -> std::io::Result<()> {
+        self.color.save_png(path)
+    }
+
+    /// Returns the depth attachment as a grayscale image, with near values dark.
+    ///
+    /// This is intended for debugging; use [`Texture::save_png`] to write it out.
+    pub fn depth_image(&self) -> Option<Texture<R8UNorm>> {
+        self.depth.as_ref().map(|depth| {
+            Texture::new_with(depth.width(), depth.height(), |texel| {
+                (depth[texel].clamp(0.0, 1.0) * 255.0).round() as u8
+            })
+        })
+    }
+}
+
+/// A caller waiting for the next frame of some port.
+///
+/// Dropping a request without a frame reports that the port was dropped.
+#[derive(Debug)]
+pub(crate) struct CaptureRequest(Option<CaptureReply>);
+
+#[derive(Debug)]
+enum CaptureReply {
+    Future(r#continue::Sender<Result<CapturedFrame, Error>>),
+    //exfiltrate commands run synchronously, so they wait on a channel
+    #[cfg(feature = "exfiltrate")]
+    Channel(wasm_safe_mutex::mpsc::Sender<Result<CapturedFrame, Error>>),
+}
+
+impl CaptureRequest {
+    #[cfg(feature = "exfiltrate")]
+    pub(crate) fn channel(
+        sender: wasm_safe_mutex::mpsc::Sender<Result<CapturedFrame, Error>>,
+    ) -> Self {
+        CaptureRequest(Some(CaptureReply::Channel(sender)))
+    }
+
+    fn reply(&mut self, result: Result<CapturedFrame, Error>) {
+        match self.0.take() {
+            Some(CaptureReply::Future(sender)) => sender.send(result),
+            #[cfg(feature = "exfiltrate")]
+            Some(CaptureReply::Channel(sender)) => {
+                //the command may have timed out, which is fine
+                let _ = sender.send_sync(result);
+            }
+            None => {}
+        }
+    }
+}
+
+impl Drop for CaptureRequest {
+    fn drop(&mut self) {
+        self.reply(Err(Error(ErrorKind::PortDropped)));
     }
 }
 
@@ -274,6 +452,8 @@ impl From<imp::Error> for Error {
 /// - CPU preparation time
 /// - Minimum elapsed time between frames
 ///
+/// It also reports [shader reload](Port::reload_shaders) errors.
+///
 /// # Example
 ///
 /// ```
@@ -309,6 +489,7 @@ pub struct PortReporter {
     ms: Observer<i32>,
     cpu_ms: Observer<i32>,
     min_elapsed_ms: Observer<i32>,
+    shader_error: Observer<Option<ShaderError>>,
 }
 impl PortReporter {
     /// Returns the frame number of the most recently started frame.
@@ -366,6 +547,14 @@ impl PortReporter {
         &self.min_elapsed_ms
     }
 
+    /// Returns an observer for the error that kept the port's last shader reload from
+    /// applying, or `None` once every pass reloads.
+    ///
+    /// See [`Port::reload_shaders`].
+    pub fn shader_error(&self) -> &Observer<Option<ShaderError>> {
+        &self.shader_error
+    }
+
     /// Awaits the completion of the next frame.
     ///
     /// This method blocks until the next frame has been rendered and presented.
@@ -382,6 +571,7 @@ pub(crate) struct PortReporterImpl {
     ms: Value<i32>,
     cpu_ms: Value<i32>,
     min_elapsed_ms: Value<i32>,
+    shader_error: Value<Option<ShaderError>>,
     frame_history: Mutex<Vec<FrameInfo>>,
 }
 impl PortReporterImpl {
@@ -456,6 +646,12 @@ impl PortReporterSend {
         self.imp.frame_begun.store(frame, Ordering::Relaxed);
         self.imp.create_frame_guard()
     }
+
+    /// Reports the error that kept a pass from being prepared, or `None` once none does.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn shader_error(&self, error: Option<ShaderError>) {
+        self.imp.shader_error.set(error);
+    }
 }
 
 fn port_reporter(initial_frame: u32, camera: &Camera) -> (PortReporterSend, PortReporter) {
@@ -463,11 +659,13 @@ fn port_reporter(initial_frame: u32, camera: &Camera) -> (PortReporterSend, Port
     let ms = Value::new(0);
     let cpu_ms = Value::new(0);
     let min_elapsed_ms = Value::new(16); //16ms is ~60FPS
+    let shader_error = Value::new(None);
 
     let fps_observer = fps.observe();
     let ms_observer = ms.observe();
     let cpu_ms_observer = cpu_ms.observe();
     let min_elapsed_ms_observer = min_elapsed_ms.observe();
+    let shader_error_observer = shader_error.observe();
 
     let imp = Arc::new(PortReporterImpl {
         frame_begun: AtomicU32::new(initial_frame),
@@ -476,6 +674,7 @@ fn port_reporter(initial_frame: u32, camera: &Camera) -> (PortReporterSend, Port
         ms,
         cpu_ms,
         min_elapsed_ms,
+        shader_error,
         frame_history: Mutex::new(Vec::new()),
     });
 
@@ -488,10 +687,61 @@ fn port_reporter(initial_frame: u32, camera: &Camera) -> (PortReporterSend, Port
             ms: ms_observer,
             cpu_ms: cpu_ms_observer,
             min_elapsed_ms: min_elapsed_ms_observer,
+            shader_error: shader_error_observer,
         },
     )
 }
 
+/// Returns where the compute pass `handle` is in `passes`, or [`PassError::StaleHandle`].
+fn compute_pass_index(
+    passes: &[(PassHandle, ComputePassDescriptor)],
+    handle: PassHandle,
+) -> Result<usize, PassError> {
+    passes
+        .iter()
+        .position(|(pass, _)| *pass == handle)
+        .ok_or(PassError::StaleHandle { handle })
+}
+
+/// What a [`Port::reload_shaders`] could not apply, so that polling warns about each
+/// problem once.
+#[derive(Debug, Default)]
+struct ReloadProblems {
+    /// Shader files that could not be read.
+    unreadable: HashSet<PathBuf>,
+    /// The reloaded descriptors that were refused, by pass.
+    refused: HashMap<PassHandle, PassDescriptor>,
+}
+
+/// Reads the shader file at `path` for [`Port::reload_shaders`], once per reload.
+///
+/// Returns `None` if the file can't be read, and warns unless the `previous` reload
+/// couldn't read it either.
+async fn read_reloaded(
+    files: &mut HashMap<PathBuf, Option<String>>,
+    path: &Path,
+    previous: &ReloadProblems,
+    problems: &mut ReloadProblems,
+) -> Option<String> {
+    if !files.contains_key(path) {
+        let wgsl = match read_wgsl(path, crate::Priority::Utility).await {
+            Ok(wgsl) => Some(wgsl),
+            Err(error) => {
+                if !previous.unreadable.contains(path) {
+                    logwise::warn_sync!(
+                        "Can't reload a shader: {error}",
+                        error = logwise::privacy::LogIt(&error)
+                    );
+                }
+                problems.unreadable.insert(path.to_path_buf());
+                None
+            }
+        };
+        files.insert(path.to_path_buf(), wgsl);
+    }
+    files[path].clone()
+}
+
 impl Port {
     /// Creates a new port for rendering to the specified view.
     ///
@@ -535,23 +785,31 @@ impl Port {
     ) -> Result<Self, Error> {
         let camera = Camera::new(window_size, initial_camera_position);
         let (port_sender, port_reporter) = port_reporter(0, &camera);
+        let offscreen = view.offscreen_config().copied();
 
         Ok(Self {
-            imp: crate::imp::Port::new(engine, view, camera.clone(), port_sender)
-                .await
-                .map_err(Error)?,
+            imp: crate::imp::Port::new(engine, view, camera.clone(), port_sender).await?,
             port_reporter,
-            descriptors: wasm_safe_thread::Mutex::new(Vec::new()),
+            passes: wasm_safe_thread::Mutex::new(PassList::default()),
+            compute_passes: wasm_safe_thread::Mutex::new(Vec::new()),
             camera,
             engine: engine.clone(),
             stop_signal: DirtySender::new(false, "port_stop"),
             stopped_signal: DirtySender::new(false, "port_stopped"),
+            offscreen,
+            capture_requests: wasm_safe_thread::Mutex::new(Vec::new()),
+            capture_signal: DirtySender::new(false, "port_capture"),
+            reload_signal: DirtySender::new(false, "port_shader_reload"),
+            reload_problems: wasm_safe_thread::Mutex::new(ReloadProblems::default()),
+            watch_stop_signal: DirtySender::new(false, "port_watch_stop"),
         })
     }
-    /// Adds a fixed render pass to the port.
+    /// Adds a fixed render pass to the port, and returns a handle to change it later.
     ///
     /// Render passes are executed in the order they were added. Each pass
-    /// defines its own shaders, bindings, and draw commands.
+    /// defines its own shaders, bindings, and draw commands.  Use the handle with
+    /// [`remove_pass`](Self::remove_pass), [`replace_pass`](Self::replace_pass),
+    /// [`move_pass`](Self::move_pass) and [`set_pass_enabled`](Self::set_pass_enabled).
     ///
     /// # Example
     ///
@@ -587,18 +845,26 @@ impl Port {
     ///     false   // depth write
     /// );
     ///
-    /// port.add_fixed_pass(pass).await;
+    /// port.add_fixed_pass(pass).await.expect("Failed to add pass");
     /// # }, "port_individual_doctest");
     /// # }
     /// ```
     ///
+    /// # Errors
+    ///
+    /// Returns an error, and does not add the pass, if the port could not run it; see
+    /// [`PassError`].
+    ///
     /// # Limitations
     ///
     /// - Currently cannot add passes while the port is running (mt2-242)
-    /// - There is no way to remove passes once added (mt2-243)
-    pub async fn add_fixed_pass(&self, descriptor: PassDescriptor) {
-        self.imp.add_fixed_pass(descriptor.clone()).await;
-        self.descriptors.lock_async().await.push(descriptor);
+    pub async fn add_fixed_pass(
+        &self,
+        descriptor: PassDescriptor,
+    ) -> Result<PassHandle, PassError> {
+        self.check_pass(&descriptor)?;
+        self.try_update_passes(|passes| passes.add(descriptor))
+            .await
     }
 
     /// Adds multiple fixed render passes to the port.
@@ -607,13 +873,495 @@ impl Port {
     /// Passes are executed in the order they appear in the vector.
     ///
     /// See [`add_fixed_pass`](Self::add_fixed_pass) for details and limitations.
-    pub async fn add_fixed_passes(&self, descriptors: Vec<PassDescriptor>) {
-        for descriptor in descriptors {
-            self.imp.add_fixed_pass(descriptor.clone()).await;
-            self.descriptors.lock_async().await.push(descriptor);
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error, and adds none of the passes, if the port could not run one.
+    pub async fn add_fixed_passes(
+        &self,
+        descriptors: Vec<PassDescriptor>,
+    ) -> Result<Vec<PassHandle>, PassError> {
+        for descriptor in &descriptors {
+            self.check_pass(descriptor)?;
+        }
+        self.try_update_passes(|passes| {
+            let mut added = passes.clone();
+            let handles = descriptors
+                .into_iter()
+                .map(|descriptor| added.add(descriptor))
+                .collect::<Result<_, _>>()?;
+            *passes = added;
+            Ok(handles)
+        })
+        .await
+    }
+
+    /// Removes a render pass from the port.
+    ///
+    /// The other passes keep their prepared pipelines, unless removing the pass changes the
+    /// view's depth buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PassError::StaleHandle`] if the pass was already removed, or was added to
+    /// another port.
+    pub async fn remove_pass(&self, handle: PassHandle) -> Result<(), PassError> {
+        self.try_update_passes(|passes| passes.remove(handle)).await
+    }
+
+    /// Replaces the descriptor of a render pass, for example after editing its shaders.
+    ///
+    /// The pass keeps its place in the port's order and whether it is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and keeps the pass's previous descriptor, if the port could not
+    /// run the new one; see [`PassError`].  Returns [`PassError::StaleHandle`] if the pass
+    /// was removed, or was added to another port.
+    pub async fn replace_pass(
+        &self,
+        handle: PassHandle,
+        descriptor: PassDescriptor,
+    ) -> Result<(), PassError> {
+        self.check_pass(&descriptor)?;
+        self.try_update_passes(|passes| passes.replace(handle, descriptor))
+            .await
+    }
+
+    /// Moves a render pass to `index` in the port's order, counting disabled passes.  An
+    /// `index` past the last pass moves it last.
+    ///
+    /// Passes into [render textures](crate::bindings::sideways::RenderTexture) still run
+    /// before the passes that sample them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PassError::StaleHandle`] if the pass was removed, or was added to another
+    /// port.
+    pub async fn move_pass(&self, handle: PassHandle, index: usize) -> Result<(), PassError> {
+        self.try_update_passes(|passes| passes.move_to(handle, index))
+            .await
+    }
+
+    /// Enables or disables a render pass.  Passes are enabled when added.
+    ///
+    /// A disabled pass keeps its place in the port's order but does not run, and its
+    /// resources don't schedule new frames.  The wgpu backend keeps the pipelines of disabled
+    /// passes, so toggling a pass, such as a debug overlay, does not prepare it again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// # use images_and_words::images::{Engine, view::View};
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// # use images_and_words::images::render_pass::{PassDescriptor, DrawCommand};
+    /// # use images_and_words::images::shader::{VertexShader, FragmentShader};
+    /// # use images_and_words::bindings::BindStyle;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 10.0))
+    /// #     .await.expect("Failed to create engine");
+    /// # let port = engine.main_port();
+    /// # let vertex_shader = VertexShader::new("overlay",
+    /// #     "@vertex fn vs_main() -> @builtin(position) vec4<f32> {
+    /// #         return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    /// #     }".to_string());
+    /// # let fragment_shader = FragmentShader::new("overlay",
+    /// #     "@fragment fn fs_main() -> @location(0) vec4<f32> {
+    /// #         return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+    /// #     }".to_string());
+    /// let overlay = port.add_fixed_pass(PassDescriptor::new(
+    ///     "debug_overlay".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     false,
+    ///     false,
+    /// )).await.expect("Failed to add pass");
+    /// port.set_pass_enabled(overlay, false).await.expect("Failed to disable pass");
+    /// # }, "port_set_pass_enabled_doctest");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PassError::StaleHandle`] if the pass was removed, or was added to another
+    /// port.
+    pub async fn set_pass_enabled(
+        &self,
+        handle: PassHandle,
+        enabled: bool,
+    ) -> Result<(), PassError> {
+        self.try_update_passes(|passes| passes.set_enabled(handle, enabled))
+            .await
+    }
+
+    /// Checks that the port's backend can run `descriptor`.
+    fn check_pass(&self, descriptor: &PassDescriptor) -> Result<(), PassError> {
+        let device = self.engine.bound_device();
+        let rasterizer = descriptor.rasterizer;
+        if !device.supports_polygon_mode(rasterizer.polygon_mode) {
+            return Err(PassError::PolygonMode {
+                pass: descriptor.name().to_string(),
+                polygon_mode: rasterizer.polygon_mode,
+            });
+        }
+        if rasterizer.unclipped_depth && !device.supports_unclipped_depth() {
+            return Err(PassError::UnclippedDepth {
+                pass: descriptor.name().to_string(),
+            });
+        }
+        device.0.check_pass(descriptor)
+    }
+
+    /// Changes the port's passes with `change`, and hands the result to the backend.
+    async fn update_passes<R>(&self, change: impl FnOnce(&mut PassList) -> R) -> R {
+        let Ok(result) = self
+            .try_update_passes(|passes| Ok::<_, std::convert::Infallible>(change(passes)))
+            .await;
+        result
+    }
+
+    /// Changes the port's passes with `change`, and hands the result to the backend unless
+    /// `change` fails.  A failed `change` leaves the passes as they were.
+    ///
+    /// The lock is held until the backend has the passes, so concurrent changes reach the
+    /// backend in the order they were made.
+    async fn try_update_passes<R, E>(
+        &self,
+        change: impl FnOnce(&mut PassList) -> Result<R, E>,
+    ) -> Result<R, E> {
+        let mut passes = self.passes.lock_async().await;
+        let result = change(&mut passes)?;
+        self.imp.set_passes(passes.clone()).await;
+        Ok(result)
+    }
+
+    /// Reads the files of the port's file-backed shaders again, and replaces the passes
+    /// whose shaders changed.
+    ///
+    /// Shaders created with [`VertexShader::from_path`](crate::images::shader::VertexShader::from_path)
+    /// or [`FragmentShader::from_path`](crate::images::shader::FragmentShader::from_path) are
+    /// read again.  Only the passes whose files changed are prepared again, and a running
+    /// [`start`](Self::start) loop renders a frame with them.
+    ///
+    /// A change that doesn't compile, or that breaks a pass that matched its bindings, is not
+    /// applied: the pass keeps its last good shaders and pipeline.  The error is logged, and
+    /// reported by [`PortReporter::shader_error`] until every pass reloads.  Files that can't
+    /// be read, as while an editor saves them, are logged and tried again on the next call.
+    /// Each problem is logged once, however often the same files are reloaded.
+    ///
+    /// On the wgpu backend, the device may still refuse to build a pipeline from a change
+    /// that passed these checks.  The next frame that prepares the pass then logs and
+    /// reports the error the same way, and keeps drawing the last good pipeline, unless the
+    /// port's configuration, such as its sample count, changed since; then the pass is
+    /// skipped until its shaders change again.
+    ///
+    /// Returns how many passes were replaced.
+    pub async fn reload_shaders(&self) -> usize {
+        let entries = self.passes.lock_async().await.entries().to_vec();
+        let previous = std::mem::take(&mut *self.reload_problems.lock_async().await);
+        let mut problems = ReloadProblems::default();
+        let mut files = HashMap::new();
+        let mut reloaded = Vec::new();
+        for entry in entries {
+            let current = &entry.descriptor;
+            let mut descriptor = current.clone();
+            if let Some(path) = &current.vertex_shader.path
+                && let Some(wgsl) = read_reloaded(&mut files, path, &previous, &mut problems).await
+            {
+                descriptor.vertex_shader.wgsl_code = wgsl;
+            }
+            if let Some(path) = &current.fragment_shader.path
+                && let Some(wgsl) = read_reloaded(&mut files, path, &previous, &mut problems).await
+            {
+                descriptor.fragment_shader.wgsl_code = wgsl;
+            }
+            if descriptor == *current {
+                continue;
+            }
+            //passes that never matched their bindings may still reload, as they were added
+            if let Err(error) = descriptor.validate()
+                && (error.is_in_shader() || current.validate().is_ok())
+            {
+                if previous.refused.get(&entry.handle) != Some(&descriptor) {
+                    logwise::warn_sync!(
+                        "Keeping the last good shaders of pass {name}: {error}",
+                        name = logwise::privacy::LogIt(&current.name),
+                        error = logwise::privacy::LogIt(&error)
+                    );
+                }
+                self.port_reporter.imp.shader_error.set(Some(error));
+                problems.refused.insert(entry.handle, descriptor);
+                continue;
+            }
+            logwise::info_sync!(
+                "Reloaded the shaders of pass {name}",
+                name = logwise::privacy::LogIt(&current.name)
+            );
+            reloaded.push((entry.handle, entry.descriptor, descriptor));
+        }
+        let count = if reloaded.is_empty() {
+            0
+        } else {
+            self.update_passes(|passes| {
+                let mut count = 0;
+                for (handle, previous, descriptor) in reloaded {
+                    //the pass may have been removed or replaced while the files were read
+                    if passes
+                        .get(handle)
+                        .is_some_and(|entry| entry.descriptor == previous)
+                        && passes.replace(handle, descriptor).is_ok()
+                    {
+                        count += 1;
+                    }
+                }
+                count
+            })
+            .await
+        };
+        if count > 0 {
+            self.reload_signal.mark_dirty(true);
+        }
+        //errors from building pipelines are cleared as the pipelines build
+        if problems.refused.is_empty() && !previous.refused.is_empty() {
+            self.port_reporter.imp.shader_error.set(None);
+        }
+        *self.reload_problems.lock_async().await = problems;
+        count
+    }
+
+    /// Reloads the port's file-backed shaders every `interval`, as
+    /// [`reload_shaders`](Self::reload_shaders) does.
+    ///
+    /// The files are polled, so no file watching service is needed.  Run it alongside
+    /// [`start`](Self::start); it returns within `interval` of [`stop`](Self::stop) or
+    /// [`stop_watching_shaders`](Self::stop_watching_shaders).
+    pub async fn watch_shaders(&self, interval: Duration) {
+        self.watch_stop_signal.mark_dirty(false);
+        let stop = DirtyReceiver::new(&self.watch_stop_signal);
+        loop {
+            self.reload_shaders().await;
+            portable_async_sleep::async_sleep(interval).await;
+            if stop.is_dirty() {
+                return;
+            }
         }
     }
 
+    /// Stops [`watch_shaders`](Self::watch_shaders), without stopping the render loop.
+    pub fn stop_watching_shaders(&self) {
+        self.watch_stop_signal.mark_dirty(true);
+    }
+
+    /// Adds a compute pass to the port, and returns a handle to change it later.
+    ///
+    /// Compute passes run every frame, in the order they were added, before any render
+    /// pass.  Use the handle with [`remove_compute_pass`](Self::remove_compute_pass) and
+    /// [`replace_compute_pass`](Self::replace_compute_pass).  Render passes can therefore read what a compute pass writes in the same
+    /// frame, for example a [`ShaderReadWrite`](crate::bindings::visible_to::GPUBufferUsage::ShaderReadWrite)
+    /// buffer.  To read the results on the CPU, bind a
+    /// [reverse buffer](crate::bindings::reverse::buffer::Buffer).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::bindings::bind_style::{BindSlot, Stage};
+    /// use images_and_words::bindings::reverse::buffer::Buffer;
+    /// use images_and_words::images::compute_pass::{ComputePassDescriptor, Dispatch};
+    /// use images_and_words::images::shader::ComputeShader;
+    /// # use images_and_words::images::{Engine, view::View};
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 10.0))
+    /// #     .await.expect("Failed to create engine");
+    /// # let port = engine.main_port();
+    /// let squares = Buffer::<u32>::new(engine.bound_device().clone(), 100, "squares", |_| 0)
+    ///     .await
+    ///     .expect("Failed to create buffer");
+    /// let shader = ComputeShader::new("square",
+    ///     "@group(0) @binding(0) var<storage, read_write> squares: array<u32>;
+    ///     @compute @workgroup_size(64) fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    ///         if (id.x < arrayLength(&squares)) {
+    ///             squares[id.x] = id.x * id.x;
+    ///         }
+    ///     }".to_string());
+    /// let mut bind_style = BindStyle::new();
+    /// bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Compute, &squares);
+    ///
+    /// port.add_compute_pass(ComputePassDescriptor::new(
+    ///     "square".to_string(),
+    ///     shader,
+    ///     bind_style,
+    ///     Dispatch::for_elements(squares.len(), 64),
+    /// )).await.expect("Failed to add compute pass");
+    /// port.force_render().await;
+    /// assert_eq!(squares.access_read().await.get(7), 49);
+    /// # }, "port_add_compute_pass_doctest");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and does not add the pass, if the port could not run it; see
+    /// [`PassError`].
+    pub async fn add_compute_pass(
+        &self,
+        descriptor: ComputePassDescriptor,
+    ) -> Result<PassHandle, PassError> {
+        self.engine
+            .bound_device()
+            .0
+            .check_compute_pass(&descriptor)?;
+        let handle = PassHandle::new();
+        self.update_compute_passes(|passes| {
+            passes.push((handle, descriptor));
+            Ok(handle)
+        })
+        .await
+    }
+
+    /// Removes a compute pass from the port.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PassError::StaleHandle`] if the pass was already removed, or was added to
+    /// another port.
+    pub async fn remove_compute_pass(&self, handle: PassHandle) -> Result<(), PassError> {
+        self.update_compute_passes(|passes| {
+            let index = compute_pass_index(passes, handle)?;
+            passes.remove(index);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replaces the descriptor of a compute pass, keeping its place in the port's order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and keeps the pass's previous descriptor, if the port could not
+    /// run the new one; see [`PassError`].  Returns [`PassError::StaleHandle`] if the pass
+    /// was removed, or was added to another port.
+    pub async fn replace_compute_pass(
+        &self,
+        handle: PassHandle,
+        descriptor: ComputePassDescriptor,
+    ) -> Result<(), PassError> {
+        self.engine
+            .bound_device()
+            .0
+            .check_compute_pass(&descriptor)?;
+        self.update_compute_passes(|passes| {
+            let index = compute_pass_index(passes, handle)?;
+            passes[index].1 = descriptor;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Changes the port's compute passes with `change`, and hands the result to the backend
+    /// unless `change` fails, as [`try_update_passes`](Self::try_update_passes) does.
+    async fn update_compute_passes<R>(
+        &self,
+        change: impl FnOnce(&mut Vec<(PassHandle, ComputePassDescriptor)>) -> Result<R, PassError>,
+    ) -> Result<R, PassError> {
+        let mut passes = self.compute_passes.lock_async().await;
+        let result = change(&mut passes)?;
+        let descriptors = passes
+            .iter()
+            .map(|(_, descriptor)| descriptor.clone())
+            .collect();
+        self.imp.set_compute_passes(descriptors).await;
+        Ok(result)
+    }
+
+    /// Sets how many samples each pixel of the view is rendered with, for multisample
+    /// anti-aliasing (MSAA).
+    ///
+    /// `sample_count` must be 1, 2, 4 or 8; the default is 1.  Devices don't support every
+    /// count, so the port uses the largest supported count up to `sample_count`, and returns
+    /// it.  Passes into the view render into multisampled attachments, which are resolved into
+    /// the view at the end of each frame.  Passes into
+    /// [render textures](crate::bindings::sideways::RenderTexture) always take one sample.
+    ///
+    /// Multisampled depth can't be read on every device, so frames rendered with more than
+    /// one sample are read back and captured without depth.  The software backend always takes
+    /// one sample.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// # use images_and_words::images::{Engine, view::View};
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 10.0))
+    /// #     .await.expect("Failed to create engine");
+    /// # let port = engine.main_port();
+    /// let sample_count = port.set_sample_count(4).await;
+    /// assert!(sample_count <= 4);
+    /// # }, "port_set_sample_count_doctest");
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sample_count` is not 1, 2, 4 or 8.
+    pub async fn set_sample_count(&self, sample_count: u32) -> u32 {
+        self.imp.set_sample_count(sample_count).await
+    }
+
+    /// Sets what happens to the view's color at the start of each frame.
+    ///
+    /// The default, `LoadOp::Clear` with transparent black, starts every frame afresh; clear
+    /// to another color for a fixed background without a full-screen pass.  [`LoadOp::Load`]
+    /// keeps the previous frame, so passes accumulate what they draw across frames.  Passes
+    /// can override this with [`PassDescriptor::with_color_load`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// # use images_and_words::images::{Engine, view::View};
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// use images_and_words::images::render_pass::LoadOp;
+    /// use images_and_words::pixel_formats::Float4;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 10.0))
+    /// #     .await.expect("Failed to create engine");
+    /// # let port = engine.main_port();
+    /// let sky = Float4 { r: 0.2, g: 0.4, b: 0.8, a: 1.0 };
+    /// port.set_color_load(LoadOp::Clear(sky)).await;
+    /// # }, "port_set_color_load_doctest");
+    /// # }
+    /// ```
+    pub async fn set_color_load(&self, load: LoadOp<Float4>) {
+        self.imp.set_color_load(load).await
+    }
+
+    /// Sets what happens to the view's depth (and stencil, which clears to `0`) at the start
+    /// of each frame.
+    ///
+    /// By default depth clears to the value the first depth pass's
+    /// [compare function](crate::images::render_pass::DepthStencilState::with_compare)
+    /// needs.  [`LoadOp::Load`] keeps the previous frame's depth.  Passes can override this
+    /// with [`PassDescriptor::with_depth_load`].
+    pub async fn set_depth_load(&self, load: LoadOp<f32>) {
+        self.imp.set_depth_load(load).await
+    }
+
     /// Returns the bound device associated with this port's engine.
     ///
     /// The bound device is used to create GPU resources like buffers and textures.
@@ -632,21 +1380,110 @@ impl Port {
     pub async fn force_render(&self) {
         //force render the next frame, even if nothing is dirty
         //let frame_time = logwise::perfwarn_begin!("Port::force_render");
-        self.imp.render_frame().await;
+        //clear the signal first, so a request that arrives mid-frame renders another one
+        self.capture_signal.mark_dirty(false);
+        self.reload_signal.mark_dirty(false);
+        #[allow(unused_mut)] //only exfiltrate appends
+        let mut requests = std::mem::take(&mut *self.capture_requests.lock_async().await);
+        #[cfg(feature = "exfiltrate")]
+        requests.append(&mut crate::exfiltrate_commands::PENDING_CAPTURES.lock_sync());
+
+        let capture = self.imp.render_frame(!requests.is_empty()).await;
+        if let Some(capture) = capture {
+            let capture = capture.map_err(Error::from);
+            for mut request in requests {
+                request.reply(
+                    capture
+                        .as_ref()
+                        .map(CapturedFrame::new)
+                        .map_err(Clone::clone),
+                );
+            }
+        }
         //drop(frame_time);
     }
 
+    /// Captures the next frame this port renders.
+    ///
+    /// The color attachment is converted to [`RGBA8UnormSRGB`] regardless of the surface
+    /// format, and the depth attachment is included when any pass uses depth.  This works
+    /// for any view, including windows.
+    ///
+    /// Requesting a capture wakes a running [`start`](Self::start) loop, so the future
+    /// resolves even if nothing else is dirty.  If the port is not running, the capture
+    /// resolves on the next call to [`force_render`](Self::force_render).  Any number of
+    /// captures may be pending at once; they all receive the same frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend fails to read the frame, or if the port is dropped
+    /// before it renders again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # test_executors::spawn_local(async {
+    /// use images_and_words::images::{Engine, view::View};
+    /// use images_and_words::images::projection::WorldCoord;
+    /// use images_and_words::images::render_pass::{PassDescriptor, DrawCommand};
+    /// use images_and_words::images::shader::{VertexShader, FragmentShader};
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::bindings::software::texture::Texel;
+    ///
+    /// let engine = Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 10.0))
+    ///     .await
+    ///     .expect("Failed to create engine");
+    /// let port = engine.main_port();
+    /// # let vertex_shader = VertexShader::new("fullscreen",
+    /// #     "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    /// #         let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+    /// #         return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    /// #     }".to_string());
+    /// # let fragment_shader = FragmentShader::new("red",
+    /// #     "@fragment fn fs_main() -> @location(0) vec4<f32> {
+    /// #         return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+    /// #     }".to_string());
+    /// # port.add_fixed_pass(PassDescriptor::new("fullscreen".to_string(), vertex_shader,
+    /// #     fragment_shader, BindStyle::new(), DrawCommand::TriangleList(1), false, false))
+    /// #     .await
+    /// #     .expect("Failed to add pass");
+    /// let capture = port.capture_next_frame();
+    /// port.force_render().await;
+    /// let frame = capture.await.expect("Capture frame");
+    ///
+    /// let pixel = frame.color[Texel { x: 0, y: 0 }];
+    /// assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+    /// frame.save_png(&std::env::temp_dir().join("capture_next_frame_doctest.png")).unwrap();
+    /// # }, "capture_next_frame_doctest");
+    /// ```
+    pub fn capture_next_frame(
+        &self,
+    ) -> impl Future<Output = Result<CapturedFrame, Error>> + Send + use<> {
+        let (sender, future) = r#continue::continuation();
+        self.capture_requests
+            .lock_sync()
+            .push(CaptureRequest(Some(CaptureReply::Future(sender))));
+        self.capture_signal.mark_dirty(true);
+        future
+    }
+
     fn collect_dirty_receivers(&self) -> Vec<DirtyReceiver> {
         //we need to figure out all the dirty stuff
         let mut dirty_receivers = Vec::new();
-        let descriptors = self.descriptors.lock_sync();
-        for pass in descriptors.iter() {
-            for bind in pass.bind_style.binds.values() {
+        let passes = self.passes.lock_sync();
+        let compute_passes = self.compute_passes.lock_sync();
+        let bind_styles = compute_passes
+            .iter()
+            .map(|(_, pass)| &pass.bind_style)
+            .chain(passes.enabled().map(|pass| &pass.descriptor.bind_style));
+        for bind_style in bind_styles {
+            for bind in bind_style.binds.values() {
                 match &bind.target {
                     BindTarget::DynamicBuffer(a) => {
                         dirty_receivers.push(a.dirty_receiver());
                     }
-                    BindTarget::DynamicVB(_, a) => {
+                    BindTarget::DynamicVB(_, _, a) => {
                         dirty_receivers.push(a.dirty_receiver());
                     }
                     BindTarget::Camera => {
@@ -655,15 +1492,40 @@ impl Port {
                     BindTarget::DynamicTexture(texture) => {
                         dirty_receivers.push(texture.gpu_dirty_receiver())
                     }
+                    BindTarget::ReverseBuffer(buffer) => {
+                        //a pending read waits on the next frame
+                        dirty_receivers.push(buffer.dirty_receiver())
+                    }
                     BindTarget::StaticBuffer(_) => { /* nothing to do, not considered dirty */ }
-                    BindTarget::FrameCounter => { /* nothing to do - not considered dirty */ }
+                    BindTarget::FrameCounter | BindTarget::Time => {
+                        /* nothing to do - not considered dirty */
+                    }
 
                     BindTarget::StaticTexture(_, _) => { /* also not considered dirty the 2nd+ time */
                     }
                     BindTarget::Sampler(_) => { /* also not considered dirty */ }
+                    BindTarget::RenderTexture(..) | BindTarget::RenderTextureDepth(_) => {
+                        /* rendered by another pass in the same frame */
+                    }
                     BindTarget::VB(..) => { /* also not considered dirty */ }
                 }
             }
+            if bind_style.global_group.is_some() {
+                dirty_receivers.push(self.camera.dirty_receiver());
+            }
+            if let Some(IndexBinding {
+                target: IndexTarget::Dynamic(a),
+                ..
+            }) = &bind_style.index_buffer
+            {
+                dirty_receivers.push(a.dirty_receiver());
+            }
+        }
+        for immediates in passes
+            .enabled()
+            .filter_map(|pass| pass.descriptor.immediates.as_ref())
+        {
+            dirty_receivers.push(immediates.dirty_receiver());
         }
         dirty_receivers
     }
@@ -687,7 +1549,7 @@ impl Port {
     /// #     .await.expect("Failed to create engine");
     /// # let mut port = engine.main_port_mut();
     /// // Add render passes first
-    /// // port.add_fixed_pass(pass).await;
+    /// // port.add_fixed_pass(pass).await?;
     ///
     /// // Start rendering - this runs forever
     /// // port.start().await?;
@@ -710,6 +1572,8 @@ impl Port {
         loop {
             let mut dirty_receivers = self.collect_dirty_receivers();
             dirty_receivers.push(DirtyReceiver::new(&self.stop_signal));
+            dirty_receivers.push(DirtyReceiver::new(&self.capture_signal));
+            dirty_receivers.push(DirtyReceiver::new(&self.reload_signal));
             let receiver = DirtyAggregateReceiver::new(dirty_receivers);
             logwise::trace_sync!("waiting for dirty");
 
@@ -733,13 +1597,15 @@ impl Port {
 
     /// Signals the port's rendering loop to stop.
     ///
-    /// This signals the render loop to stop at the next frame boundary.
+    /// This signals the render loop to stop at the next frame boundary, and
+    /// [`watch_shaders`](Self::watch_shaders) to stop watching.
     /// After calling `stop()`, the port can be started again by calling `start()`.
     ///
     /// Note: This method returns immediately after signaling. Use [`stop_and_wait()`](Self::stop_and_wait)
     /// if you need to wait for the render loop to actually complete.
     pub fn stop(&self) {
         self.stop_signal.mark_dirty(true);
+        self.watch_stop_signal.mark_dirty(true);
     }
 
     /// Stops the port's rendering loop and waits for it to complete.
@@ -781,4 +1647,77 @@ impl Port {
     pub fn camera(&self) -> &Camera {
         &self.camera
     }
+
+    /// Reads the most recently rendered frame back to the CPU.
+    ///
+    /// This is only available for ports rendering to a [`View::offscreen`] view, and
+    /// `Format` must be the format the view was created with.  If no frame has been
+    /// rendered yet, the framebuffer is transparent black.
+    ///
+    /// The depth buffer is included when any pass uses depth.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the view is not offscreen, if `Format` does not match the view,
+    /// or if the backend fails to read the texture.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # test_executors::spawn_local(async {
+    /// use images_and_words::images::{Engine, view::View};
+    /// use images_and_words::images::projection::WorldCoord;
+    /// use images_and_words::images::render_pass::{PassDescriptor, DrawCommand};
+    /// use images_and_words::images::shader::{VertexShader, FragmentShader};
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::bindings::software::texture::Texel;
+    /// use images_and_words::pixel_formats::RGBA8UNorm;
+    ///
+    /// let view = View::offscreen(16, 16, RGBA8UNorm);
+    /// let engine = Engine::rendering_to(view, WorldCoord::new(0.0, 0.0, 10.0))
+    ///     .await
+    ///     .expect("Failed to create engine");
+    /// let port = engine.main_port();
+    /// let vertex_shader = VertexShader::new("fullscreen",
+    ///     "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    ///         let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+    ///         return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    ///     }".to_string());
+    /// let fragment_shader = FragmentShader::new("red",
+    ///     "@fragment fn fs_main() -> @location(0) vec4<f32> {
+    ///         return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+    ///     }".to_string());
+    /// port.add_fixed_pass(PassDescriptor::new(
+    ///     "fullscreen".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     false,
+    ///     false,
+    /// )).await.expect("Failed to add pass");
+    /// port.force_render().await;
+    ///
+    /// let framebuffer = port.read_framebuffer::<RGBA8UNorm>().await.unwrap();
+    /// let pixel = framebuffer.color[Texel { x: 8, y: 8 }];
+    /// assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+    /// # }, "read_framebuffer_doctest");
+    /// ```
+    pub async fn read_framebuffer<Format: PixelFormat>(
+        &self,
+    ) -> Result<Framebuffer<Format>, Error> {
+        let offscreen = self.offscreen.ok_or(Error(ErrorKind::NotOffscreen))?;
+        if offscreen.format != TypeId::of::<Format>() {
+            return Err(Error(ErrorKind::FormatMismatch {
+                view: offscreen.format_name,
+                requested: std::any::type_name::<Format>(),
+            }));
+        }
+        let data = self.imp.read_framebuffer().await?;
+        Ok(Framebuffer {
+            color: Texture::from_bytes(data.width, data.height, &data.color),
+            depth: data.depth_texture(),
+        })
+    }
 }
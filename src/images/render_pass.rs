@@ -8,7 +8,8 @@
 //!
 //! - [`PassDescriptor`]: Configures a complete render pass including shaders, resource bindings,
 //!   and drawing commands
-//! - [`DrawCommand`]: Specifies how vertices are assembled into primitives (triangles)
+//! - [`DrawCommand`]: Specifies how vertices are assembled into primitives (triangles,
+//!   lines or points), and which vertices to draw
 //!
 //! # Example
 //!
@@ -28,15 +29,20 @@
 //!     vertex_shader,
 //!     fragment_shader,
 //!     BindStyle::new(),
-//!     DrawCommand::TriangleList(3), // Draw 1 triangle (3 vertices)
+//!     DrawCommand::TriangleList(1), // Draw 1 triangle (3 vertices)
 //!     false, // no depth testing
 //!     false  // no alpha blending
 //! );
 //! ```
 
 use crate::bindings::BindStyle;
+use crate::bindings::forward::dynamic::buffer::CRepr;
+use crate::bindings::forward::dynamic::immediates::{ErasedImmediates, Immediates};
+use crate::images::shader::validation::ShaderError;
 use crate::images::shader::{FragmentShader, VertexShader};
+use crate::pixel_formats::Float4;
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Configuration for a complete render pass.
 ///
@@ -49,7 +55,7 @@ use std::fmt::Debug;
 /// - **Shaders**: Vertex and fragment shaders that process the geometry
 /// - **Bindings**: Resources (buffers, textures, etc.) made available to shaders
 /// - **Draw Command**: How vertices are assembled into primitives
-/// - **Render State**: Depth testing and alpha blending configuration
+/// - **Render State**: Depth testing, blending and rasterizer configuration
 #[derive(Debug, Clone, PartialEq)]
 pub struct PassDescriptor {
     #[allow(dead_code)] //nop implementation does not use
@@ -64,7 +70,40 @@ pub struct PassDescriptor {
     #[allow(dead_code)] //todo: mt2-496
     pub(crate) depth: bool,
     #[allow(dead_code)] //nop implementation does not use
-    pub(crate) alpha: bool,
+    pub(crate) blend: BlendMode,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) write_mask: ColorWriteMask,
+    /// The render texture this pass renders into, or `None` for the port's view.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) render_target: Option<crate::imp::RenderTexture>,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) rasterizer: RasterizerState,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) depth_stencil: DepthStencilState,
+    /// Overrides how the color attachment is loaded before this pass, or `None` to decide
+    /// from the passes before it.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) color_load: Option<LoadOp<Float4>>,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) depth_load: Option<LoadOp<f32>>,
+    /// Overrides how the color attachment is stored after this pass, or `None` to decide
+    /// from the passes after it.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) color_store: Option<StoreOp>,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) depth_store: Option<StoreOp>,
+    /// Where clip space maps to in the target, or `None` for the whole target.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) viewport: Option<TargetRect>,
+    /// The only pixels this pass may draw, or `None` for the whole target.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) scissor: Option<TargetRect>,
+    /// Small values set with each draw, or `None` if the shaders declare no `var<immediate>`.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) immediates: Option<ErasedImmediates>,
+    /// Values for the shaders' `override` declarations, by name or `@id`.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) constants: Vec<(String, f64)>,
 }
 impl PassDescriptor {
     /// Creates a new render pass descriptor.
@@ -77,7 +116,8 @@ impl PassDescriptor {
     /// * `bind_style` - Resource bindings configuration (buffers, textures, etc.)
     /// * `draw_command` - How to assemble vertices into primitives
     /// * `depth` - Whether to enable depth testing (requires a depth buffer)
-    /// * `alpha` - Whether to enable alpha blending
+    /// * `alpha` - Whether to enable alpha blending ([`BlendMode::Alpha`]); see
+    ///   [`with_blend_mode`](Self::with_blend_mode) for other modes
     ///
     /// # Design Note
     ///
@@ -99,9 +139,531 @@ impl PassDescriptor {
             fragment_shader,
             draw_command,
             depth,
-            alpha,
+            blend: if alpha {
+                BlendMode::Alpha
+            } else {
+                BlendMode::Replace
+            },
+            write_mask: ColorWriteMask::ALL,
+            render_target: None,
+            rasterizer: RasterizerState::new(),
+            depth_stencil: DepthStencilState::new(),
+            color_load: None,
+            depth_load: None,
+            color_store: None,
+            depth_store: None,
+            viewport: None,
+            scissor: None,
+            immediates: None,
+            constants: Vec::new(),
+        }
+    }
+
+    /// Renders this pass into `target` instead of the port's view.
+    ///
+    /// Later passes can then sample `target`, see
+    /// [`BindStyle::bind_render_texture`](crate::bindings::BindStyle::bind_render_texture).
+    /// The port runs this pass before any pass that samples `target`.
+    ///
+    /// Ports refuse the pass with [`PassError`] if it uses depth testing and `target` has
+    /// no depth attachment, or uses stencil, which render textures do not have.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # #[cfg(feature = "testing")]
+    /// # {
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::bindings::sideways::RenderTexture;
+    /// use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    /// use images_and_words::pixel_formats::RGBA8UNorm;
+    /// # use images_and_words::images::projection::WorldCoord;
+    /// # use images_and_words::images::view::View;
+    /// # test_executors::spawn_local(async {
+    /// # let engine = images_and_words::images::Engine::rendering_to(View::for_testing(), WorldCoord::new(0.0, 0.0, 0.0)).await.expect("can't get engine");
+    /// # let device = engine.bound_device();
+    /// let shadow_map = RenderTexture::<RGBA8UNorm>::new(device, 512, 512, true, "shadow_map")
+    ///     .await
+    ///     .expect("Failed to create render texture");
+    /// let vertex_shader = VertexShader::new("light_view",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("depth_only",
+    ///     "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }".to_string());
+    /// let pass = PassDescriptor::new(
+    ///     "shadows".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     true,
+    ///     false,
+    /// )
+    /// .with_render_target(&shadow_map);
+    /// # }, "with_render_target_doctest");
+    /// # }
+    /// ```
+    pub fn with_render_target<Format>(
+        mut self,
+        target: &crate::bindings::sideways::RenderTexture<Format>,
+    ) -> Self {
+        self.render_target = Some(target.imp.clone());
+        self
+    }
+
+    /// Sets how this pass rasterizes its primitives: culling, winding, fill mode and
+    /// depth bias.
+    ///
+    /// Passes use [`RasterizerState::new`] unless this is called.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::render_pass::{
+    ///     CullMode, DrawCommand, PassDescriptor, RasterizerState,
+    /// };
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    ///
+    /// let vertex_shader = VertexShader::new("vs",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("fs",
+    ///     "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }".to_string());
+    /// let double_sided = PassDescriptor::new(
+    ///     "foliage".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     true,
+    ///     false,
+    /// )
+    /// .with_rasterizer_state(RasterizerState::new().with_cull_mode(CullMode::None));
+    /// ```
+    pub fn with_rasterizer_state(mut self, rasterizer: RasterizerState) -> Self {
+        self.rasterizer = rasterizer;
+        self
+    }
+
+    /// Sets how this pass's output is combined with the color already in the target.
+    ///
+    /// This replaces the blending chosen by the `alpha` argument of [`new`](Self::new).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::render_pass::{BlendMode, DrawCommand, PassDescriptor};
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    ///
+    /// let vertex_shader = VertexShader::new("vs",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("fs",
+    ///     "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }".to_string());
+    /// let particles = PassDescriptor::new(
+    ///     "particles".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     false,
+    ///     false,
+    /// )
+    /// .with_blend_mode(BlendMode::Additive);
+    /// ```
+    pub fn with_blend_mode(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Enables depth testing with `depth_stencil`, instead of the default `LessEqual`
+    /// test into a [`DepthFormat::Depth16Unorm`] buffer.
+    ///
+    /// Every depth pass into the port's view must use the same [`DepthFormat`].  Passes
+    /// into a [`RenderTexture`](crate::bindings::sideways::RenderTexture) use its depth
+    /// attachment, which is [`DepthFormat::Depth32Float`], so they should leave the format
+    /// unset or set that one.  Ports refuse passes that break these rules, or use stencil
+    /// in a render texture, with [`PassError`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::render_pass::{
+    ///     CompareFunction, DepthStencilState, DrawCommand, PassDescriptor,
+    /// };
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    ///
+    /// let vertex_shader = VertexShader::new("vs",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("fs",
+    ///     "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }".to_string());
+    /// // Transparent geometry is hidden by opaque geometry, but does not hide itself
+    /// let transparent = PassDescriptor::new(
+    ///     "glass".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     true,
+    ///     true,
+    /// )
+    /// .with_depth_stencil_state(DepthStencilState::new().with_depth_write(false));
+    /// ```
+    pub fn with_depth_stencil_state(mut self, depth_stencil: DepthStencilState) -> Self {
+        self.depth = true;
+        self.depth_stencil = depth_stencil;
+        self
+    }
+
+    /// The depth format this pass needs in the port's view, or `None` if it does not
+    /// use the view's depth buffer.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn view_depth_format(&self) -> Option<DepthFormat> {
+        (self.depth && self.render_target.is_none())
+            .then(|| self.depth_stencil.format.unwrap_or_default())
+    }
+
+    /// Checks that the vertices or indices the pass draws can be counted in a `u32`.
+    fn check_draw_count(&self) -> Result<(), PassError> {
+        let indexed = self.bind_style.index_buffer.is_some();
+        match self.draw_command.checked_range(indexed) {
+            Some(_) => Ok(()),
+            None => Err(PassError::DrawCount {
+                pass: self.name.clone(),
+                draw_command: self.draw_command,
+            }),
+        }
+    }
+
+    /// Checks that an indirect draw has the buffers it reads, and that its arguments are in
+    /// its indirect buffer.
+    fn check_indirect_draw(&self) -> Result<(), PassError> {
+        let Some(draw) = self.draw_command.indirect_draw() else {
+            return Ok(());
+        };
+        let indexed = matches!(self.draw_command, DrawCommand::IndexedIndirect { .. });
+        let pass = self.name.clone();
+        let indirect = match &self.bind_style.indirect_buffer {
+            Some(indirect) if indirect.indexed == indexed => indirect,
+            _ => {
+                return Err(PassError::IndirectBuffer {
+                    pass,
+                    arguments: if indexed {
+                        "DrawIndexedIndirectArgs"
+                    } else {
+                        "DrawIndirectArgs"
+                    },
+                });
+            }
+        };
+        if indexed && self.bind_style.index_buffer.is_none() {
+            return Err(PassError::IndexBuffer { pass });
+        }
+        if draw as usize >= indirect.len {
+            return Err(PassError::IndirectDraw {
+                pass,
+                draw,
+                len: indirect.len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks this pass's depth and stencil state against its target.
+    fn check_depth_stencil(&self) -> Result<(), PassError> {
+        let depth_stencil = &self.depth_stencil;
+        let pass = self.name.clone();
+        if depth_stencil.uses_stencil() {
+            if self.render_target.is_some() {
+                return Err(PassError::RenderTargetStencil { pass });
+            }
+            let format = depth_stencil.format.unwrap_or_default();
+            if !format.has_stencil() {
+                return Err(PassError::StencilFormat { pass, format });
+            }
+        }
+        if let Some(target) = &self.render_target
+            && self.depth
+        {
+            let Some(expected) = target.depth_format() else {
+                return Err(PassError::NoDepthAttachment { pass });
+            };
+            if let Some(format) = depth_stencil.format
+                && format != expected
+            {
+                return Err(PassError::DepthFormat {
+                    pass,
+                    format,
+                    expected,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets which channels of the target this pass writes.  Defaults to [`ColorWriteMask::ALL`].
+    pub fn with_write_mask(mut self, write_mask: ColorWriteMask) -> Self {
+        self.write_mask = write_mask;
+        self
+    }
+
+    /// Sets what happens to the target's color before this pass draws.
+    ///
+    /// By default, the first pass into a target each frame clears it (for the port's view,
+    /// as [`Port::set_color_load`](crate::images::port::Port::set_color_load) says), and
+    /// later passes load what the passes before them drew.  [`LoadOp::Load`] on the first
+    /// pass keeps the previous frame's contents, so drawing accumulates across frames.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::render_pass::{DrawCommand, LoadOp, PassDescriptor};
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    /// use images_and_words::pixel_formats::Float4;
+    ///
+    /// let vertex_shader = VertexShader::new("overlay",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("overlay",
+    ///     "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }".to_string());
+    /// //draws over a white background, whatever the passes before it drew
+    /// let overlay = PassDescriptor::new(
+    ///     "overlay".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     false,
+    ///     false,
+    /// )
+    /// .with_color_load(LoadOp::Clear(Float4 { r: 1.0, g: 1.0, b: 1.0, a: 1.0 }));
+    /// ```
+    pub fn with_color_load(mut self, load: LoadOp<Float4>) -> Self {
+        self.color_load = Some(load);
+        self
+    }
+
+    /// Sets what happens to the target's depth (and stencil, which clears to `0`) before this
+    /// pass draws.
+    ///
+    /// By default, the first pass into a target each frame clears depth to the value its
+    /// [compare function](DepthStencilState::with_compare) needs, and later passes load it.
+    /// This has no effect on targets without depth.
+    pub fn with_depth_load(mut self, load: LoadOp<f32>) -> Self {
+        self.depth_load = Some(load);
+        self
+    }
+
+    /// Sets what happens to the target's color after this pass draws.  By default it is stored.
+    pub fn with_color_store(mut self, store: StoreOp) -> Self {
+        self.color_store = Some(store);
+        self
+    }
+
+    /// Sets what happens to the target's depth and stencil after this pass draws.
+    ///
+    /// By default they are stored while a later pass, a readback or the next frame may
+    /// use them, and discarded otherwise.
+    pub fn with_depth_store(mut self, store: StoreOp) -> Self {
+        self.depth_store = Some(store);
+        self
+    }
+
+    /// Draws into `viewport` of the target, instead of the whole target.
+    ///
+    /// Clip space from `-1` to `1` maps to the viewport, so the pass draws its whole picture
+    /// there, scaled to fit.  This is how split-screen views and minimaps draw several
+    /// cameras into one view.  The viewport may extend past the target; the part outside is
+    /// not drawn.
+    ///
+    /// Fractions are of the target's current size, so the viewport follows the view when it
+    /// is resized.  For the port's view, that is the size
+    /// [`PortReporter::drawable_size`](crate::images::port::PortReporter::drawable_size)
+    /// reports.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::render_pass::{DrawCommand, PassDescriptor, TargetRect};
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    ///
+    /// let vertex_shader = VertexShader::new("minimap",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("minimap",
+    ///     "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }".to_string());
+    /// //the top-right quarter of the view
+    /// let minimap = PassDescriptor::new(
+    ///     "minimap".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     false,
+    ///     false,
+    /// )
+    /// .with_viewport(TargetRect::Fraction { x: 0.75, y: 0.0, width: 0.25, height: 0.25 });
+    /// ```
+    pub fn with_viewport(mut self, viewport: TargetRect) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    /// Limits this pass to drawing the pixels in `scissor`, such as a clipped UI panel.
+    ///
+    /// Unlike [`with_viewport`](Self::with_viewport), this does not move or scale what the
+    /// pass draws; pixels outside the rectangle are left as they were.  Fractions are of the
+    /// target's current size, and the rectangle is rounded to whole pixels and clipped to
+    /// the target.
+    pub fn with_scissor(mut self, scissor: TargetRect) -> Self {
+        self.scissor = Some(scissor);
+        self
+    }
+
+    /// Sets the immediate data this pass's shaders read as a `var<immediate>`.
+    ///
+    /// Setting `immediates` renders a new frame with the new value; see the
+    /// [`immediates`](crate::bindings::forward::dynamic::immediates) module for the shader side
+    /// and for how backends without immediate data emulate it.
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::bindings::forward::dynamic::immediates::Immediates;
+    /// use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    ///
+    /// let vertex_shader = VertexShader::new("highlight",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("highlight",
+    ///     "var<immediate> highlighted: u32;
+    ///     @fragment fn main() -> @location(0) vec4<f32> { return vec4(f32(highlighted)); }".to_string());
+    /// let highlighted = Immediates::new(0u32);
+    /// let pass = PassDescriptor::new(
+    ///     "highlight".to_string(),
+    ///     vertex_shader,
+    ///     fragment_shader,
+    ///     BindStyle::new(),
+    ///     DrawCommand::TriangleList(1),
+    ///     false,
+    ///     false,
+    /// )
+    /// .with_immediates(&highlighted);
+    /// //draws the next frame highlighted
+    /// highlighted.set(1);
+    /// ```
+    pub fn with_immediates<T: CRepr>(mut self, immediates: &Immediates<T>) -> Self {
+        self.immediates = Some(immediates.erased());
+        self
+    }
+
+    /// Sets a pipeline-overridable constant, a WGSL `override`, for this pass's shaders.
+    ///
+    /// `name` is the override's name, or its number if it is declared with `@id`.  The
+    /// value converts to the override's type, so `bool`s are `0.0` or `1.0`.  Setting the
+    /// same constant again replaces its value.  Overrides without a default must be set
+    /// when the entry point uses them.
+    ///
+    /// This specializes one [`ShaderModule`](crate::images::shader::ShaderModule) per pass,
+    /// without composing new WGSL.
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    ///
+    /// let vertex_shader = VertexShader::new("vs",
+    ///     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+    /// let fragment_shader = FragmentShader::new("fs",
+    ///     "override brightness: f32;
+    ///     @fragment fn main() -> @location(0) vec4<f32> { return vec4(brightness); }".to_string());
+    /// let pass = PassDescriptor::new("dim".to_string(), vertex_shader, fragment_shader,
+    ///     BindStyle::new(), DrawCommand::TriangleList(1), false, false)
+    ///     .with_constant("brightness", 0.25);
+    /// assert_eq!(pass.validate(), Ok(()));
+    /// ```
+    pub fn with_constant(mut self, name: impl Into<String>, value: f64) -> Self {
+        let name = name.into();
+        self.constants.retain(|(set, _)| *set != name);
+        self.constants.push((name, value));
+        self
+    }
+
+    /// Checks the shaders against the pass's bindings, vertex layouts and immediate data.
+    ///
+    /// The WGSL is parsed and validated on the CPU, then each binding an entry point uses
+    /// is compared with the resource bound there: its group and binding, whether it is a
+    /// uniform, storage buffer, texture or sampler, the stage it is bound for, and the
+    /// size of the buffer's elements.  Vertex attributes are compared with the locations
+    /// and types of the vertex layouts, and constants with the shaders' `override`s.
+    ///
+    /// Ports on the wgpu backend call this when a pass is added or replaced, and refuse
+    /// the pass with [`PassError::Shader`] if it fails.  Passes for the software backend,
+    /// which does not run WGSL, need not have valid WGSL.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first mistake found; see [`ShaderError`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::bindings::bind_style::{BindSlot, Stage};
+    /// use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+    /// use images_and_words::images::shader::{FragmentShader, VertexShader};
+    ///
+    /// let vertex_shader = VertexShader::new("vs",
+    ///     "@group(0) @binding(0) var<uniform> camera: mat4x4<f32>;
+    ///      @vertex fn main(@location(0) x: f32) -> @builtin(position) vec4<f32> {
+    ///          return camera * vec4(x, 0.0, 0.0, 1.0);
+    ///      }".to_string());
+    /// let fragment_shader = FragmentShader::new("fs",
+    ///     "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }".to_string());
+    /// let mut bind_style = BindStyle::new();
+    /// bind_style.bind_camera_matrix(BindSlot::new(0), Stage::Vertex);
+    /// let pass = PassDescriptor::new("pass".to_string(), vertex_shader, fragment_shader,
+    ///     bind_style, DrawCommand::TriangleList(1), false, false);
+    ///
+    /// // @location(0) needs a vertex buffer
+    /// let error = pass.validate().unwrap_err();
+    /// assert_eq!(error.to_string(), "vs reads @location(0), but no vertex buffer provides it");
+    /// ```
+    pub fn validate(&self) -> Result<(), ShaderError> {
+        crate::images::shader::validation::validate_pass(self)
+    }
+
+    /// The viewport in a `width` by `height` target, as `[x, y, width, height]` in pixels.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn viewport_in(&self, width: u32, height: u32) -> [f32; 4] {
+        match self.viewport {
+            Some(viewport) => viewport.to_pixels(width, height),
+            None => [0.0, 0.0, width as f32, height as f32],
+        }
+    }
+
+    /// The scissor rectangle in a `width` by `height` target, as `[x, y, width, height]`
+    /// in whole pixels within the target.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn scissor_in(&self, width: u32, height: u32) -> [u32; 4] {
+        match self.scissor {
+            Some(scissor) => scissor.clip_to(width, height),
+            None => [0, 0, width, height],
         }
     }
+
+    /// Whether this pass loads its target its own way, so must begin a new render pass.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn overrides_load(&self) -> bool {
+        self.color_load.is_some() || self.depth_load.is_some()
+    }
+
+    /// Whether this pass stores its target its own way, so must end its render pass.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn overrides_store(&self) -> bool {
+        self.color_store.is_some() || self.depth_store.is_some()
+    }
     /// Returns the name of this render pass.
     #[allow(dead_code)] //nop implementation does not use
     pub(crate) fn name(&self) -> &str {
@@ -120,14 +682,297 @@ impl PassDescriptor {
     }
 }
 
-// Boilerplate
+/// The value to clear `target`'s depth to (`None` for the view), decided by the first
+/// depth pass into it.  See [`DepthStencilState`].
+#[allow(dead_code)] //nop implementation does not use
+pub(crate) fn clear_depth<'a>(
+    passes: impl IntoIterator<Item = &'a PassDescriptor>,
+    target: &Option<crate::imp::RenderTexture>,
+) -> f32 {
+    passes
+        .into_iter()
+        .find(|p| p.depth && p.render_target == *target)
+        .map_or(1.0, |p| p.depth_stencil.clear_depth())
+}
+
+/// Returns the order to run `passes` in, as indices into `passes`.
+///
+/// Every pass that renders into a render texture runs before the passes that sample it.
+/// Otherwise, passes keep the order they were added in.
+///
+/// # Errors
+///
+/// Returns [`PassError::Cycle`] if the passes depend on each other in a cycle, including
+/// a pass that samples its own render target.
+#[allow(dead_code)] //nop implementation does not use
+pub(crate) fn dependency_order(passes: &[PassDescriptor]) -> Result<Vec<usize>, PassError> {
+    let depends_on = |reader: &PassDescriptor, writer: &PassDescriptor| {
+        writer.render_target.as_ref().is_some_and(|target| {
+            reader
+                .bind_style
+                .binds
+                .values()
+                .any(|info| info.target.render_texture() == Some(target))
+        })
+    };
+    let mut order = Vec::with_capacity(passes.len());
+    let mut scheduled = vec![false; passes.len()];
+    while order.len() < passes.len() {
+        //the first pass whose writers have all run
+        let next = (0..passes.len()).find(|&reader| {
+            !scheduled[reader]
+                && (0..passes.len()).all(|writer| {
+                    scheduled[writer] || !depends_on(&passes[reader], &passes[writer])
+                })
+        });
+        match next {
+            Some(next) => {
+                scheduled[next] = true;
+                order.push(next);
+            }
+            None => {
+                let passes = (0..passes.len())
+                    .filter(|&p| !scheduled[p])
+                    .map(|p| passes[p].name.clone())
+                    .collect();
+                return Err(PassError::Cycle { passes });
+            }
+        }
+    }
+    Ok(order)
+}
 
-impl Eq for PassDescriptor {}
-/// Specifies how vertices are assembled into triangles for rendering.
+/// A pass that a [`Port`](crate::images::port::Port) refuses, because it could not run it.
+///
+/// Ports check passes as they are added, replaced or enabled, and leave their passes as
+/// they were when a pass is refused.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum PassError {
+    /// The pass's shaders don't compile, or don't match what the pass binds.
+    #[error("Pass {pass} does not match its shaders: {error}")]
+    Shader {
+        pass: String,
+        #[source]
+        error: ShaderError,
+    },
+    /// The passes sample each other's render targets in a cycle, so none can run first.
+    ///
+    /// This includes a pass that samples its own render target.  Disabled passes count, so
+    /// enabling a pass never makes a cycle.
+    #[error("Passes {passes:?} depend on each other's render textures in a cycle")]
+    Cycle { passes: Vec<String> },
+    /// The pass fills triangles in a mode the device does not support.
+    #[error("Pass {pass} uses {polygon_mode:?}, which this device does not support")]
+    PolygonMode {
+        pass: String,
+        polygon_mode: PolygonMode,
+    },
+    /// The pass uses unclipped depth, which the device does not support.
+    #[error("Pass {pass} uses unclipped depth, which this device does not support")]
+    UnclippedDepth { pass: String },
+    /// The pass's draw command counts vertices or indices past `u32::MAX`.
+    #[error("Pass {pass} draws {draw_command:?}, which counts past u32::MAX")]
+    DrawCount {
+        pass: String,
+        draw_command: DrawCommand,
+    },
+    /// The pass draws indirectly, but does not bind an indirect buffer of the arguments its
+    /// draw command reads.
+    #[error("Pass {pass} draws indirectly, but has no indirect buffer of {arguments}")]
+    IndirectBuffer {
+        pass: String,
+        arguments: &'static str,
+    },
+    /// The pass draws [`DrawCommand::IndexedIndirect`], but binds no index buffer.
+    #[error("Pass {pass} draws indexed indirect arguments, but has no index buffer")]
+    IndexBuffer { pass: String },
+    /// The pass draws with arguments past the end of its indirect buffer.
+    #[error("Pass {pass} draws indirect arguments {draw}, but its indirect buffer holds {len}")]
+    IndirectDraw { pass: String, draw: u32, len: usize },
+    /// The pass tests depth, but its render target has no depth attachment.
+    #[error("Pass {pass} uses depth testing, but its render target has no depth attachment")]
+    NoDepthAttachment { pass: String },
+    /// The pass uses stencil, but render textures have no stencil attachment.
+    #[error("Pass {pass} uses stencil, but render textures have no stencil attachment")]
+    RenderTargetStencil { pass: String },
+    /// The pass uses stencil with a depth format that has none.
+    #[error("Pass {pass} uses stencil, but its depth format {format:?} has no stencil")]
+    StencilFormat { pass: String, format: DepthFormat },
+    /// The pass asks for a different depth format from the buffer it tests against: the
+    /// render target's depth attachment, or the format earlier passes into the view use.
+    #[error("Pass {pass} uses depth format {format:?}, but its depth buffer is {expected:?}")]
+    DepthFormat {
+        pass: String,
+        format: DepthFormat,
+        expected: DepthFormat,
+    },
+    /// The software backend can't run WGSL, and the pass's shader has no software program.
+    #[error(
+        "Pass {pass} uses shader {shader} without a software program; attach one with with_software"
+    )]
+    NoSoftwareProgram { pass: String, shader: String },
+    /// The handle's pass was removed, or was added to another port.
+    #[error("{handle:?} was removed, or belongs to another port")]
+    StaleHandle { handle: PassHandle },
+}
+
+/// Identifies a pass added to a [`Port`](crate::images::port::Port), to remove, replace,
+/// move or toggle it later.
+///
+/// Compute passes have handles too, which only the port's compute pass methods accept.
+///
+/// Handles are unique across ports, and are never reused after the pass is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PassHandle(u64);
+
+impl PassHandle {
+    pub(crate) fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        PassHandle(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A pass in a [`PassList`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PassEntry {
+    pub(crate) handle: PassHandle,
+    pub(crate) descriptor: PassDescriptor,
+    /// Disabled passes keep their place in the list, but do not run.
+    pub(crate) enabled: bool,
+}
+
+/// A port's render passes, in the order they were added or moved to.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct PassList {
+    entries: Vec<PassEntry>,
+}
+
+impl PassList {
+    /// Adds a pass, unless the passes could not run with it.
+    pub(crate) fn add(&mut self, descriptor: PassDescriptor) -> Result<PassHandle, PassError> {
+        let handle = PassHandle::new();
+        self.entries.push(PassEntry {
+            handle,
+            descriptor,
+            enabled: true,
+        });
+        if let Err(error) = self.check() {
+            self.entries.pop();
+            return Err(error);
+        }
+        Ok(handle)
+    }
+
+    /// Checks that the passes can run together, whichever are enabled.
+    fn check(&self) -> Result<(), PassError> {
+        let mut view_format = None;
+        for descriptor in self.entries.iter().map(|entry| &entry.descriptor) {
+            descriptor.check_draw_count()?;
+            descriptor.check_indirect_draw()?;
+            descriptor.check_depth_stencil()?;
+            match (view_format, descriptor.view_depth_format()) {
+                (Some(expected), Some(format)) if format != expected => {
+                    return Err(PassError::DepthFormat {
+                        pass: descriptor.name.clone(),
+                        format,
+                        expected,
+                    });
+                }
+                (None, format) => view_format = format,
+                _ => {}
+            }
+        }
+        let descriptors: Vec<PassDescriptor> = self
+            .entries
+            .iter()
+            .map(|entry| entry.descriptor.clone())
+            .collect();
+        dependency_order(&descriptors).map(|_| ())
+    }
+
+    /// Returns where the pass is, or [`PassError::StaleHandle`] if it is not in the list.
+    fn index(&self, handle: PassHandle) -> Result<usize, PassError> {
+        self.entries
+            .iter()
+            .position(|entry| entry.handle == handle)
+            .ok_or(PassError::StaleHandle { handle })
+    }
+
+    /// Returns the pass, or `None` if it is not in the list.
+    pub(crate) fn get(&self, handle: PassHandle) -> Option<&PassEntry> {
+        self.entries.iter().find(|entry| entry.handle == handle)
+    }
+
+    pub(crate) fn remove(&mut self, handle: PassHandle) -> Result<(), PassError> {
+        let index = self.index(handle)?;
+        self.entries.remove(index);
+        Ok(())
+    }
+
+    /// Replaces a pass's descriptor, unless the passes could not run with it.
+    pub(crate) fn replace(
+        &mut self,
+        handle: PassHandle,
+        descriptor: PassDescriptor,
+    ) -> Result<(), PassError> {
+        let index = self.index(handle)?;
+        let previous = std::mem::replace(&mut self.entries[index].descriptor, descriptor);
+        if let Err(error) = self.check() {
+            self.entries[index].descriptor = previous;
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Moves the pass to `index`, or last if `index` is past the end.
+    pub(crate) fn move_to(&mut self, handle: PassHandle, index: usize) -> Result<(), PassError> {
+        let entry = self.entries.remove(self.index(handle)?);
+        let index = index.min(self.entries.len());
+        self.entries.insert(index, entry);
+        Ok(())
+    }
+
+    pub(crate) fn set_enabled(
+        &mut self,
+        handle: PassHandle,
+        enabled: bool,
+    ) -> Result<(), PassError> {
+        let index = self.index(handle)?;
+        self.entries[index].enabled = enabled;
+        Ok(())
+    }
+
+    /// Every pass, including disabled ones.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn entries(&self) -> &[PassEntry] {
+        &self.entries
+    }
+
+    /// The passes that run, in list order.
+    pub(crate) fn enabled(&self) -> impl Iterator<Item = &PassEntry> {
+        self.entries.iter().filter(|entry| entry.enabled)
+    }
+
+    /// The format of the view's depth buffer, or `None` if no enabled pass uses it.
+    ///
+    /// The list refuses passes into the view that need different formats, so the first
+    /// one decides.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn view_depth_format(&self) -> Option<DepthFormat> {
+        self.enabled()
+            .find_map(|entry| entry.descriptor.view_depth_format())
+    }
+}
+
+/// Specifies how vertices are assembled into primitives, and which vertices to draw.
 ///
 /// This enum controls the primitive topology - how the GPU interprets the stream
-/// of vertices to form triangles. The choice affects both how you organize your
-/// vertex data and how many vertices are needed.
+/// of vertices to form triangles, lines or points - along with how many vertices
+/// and instances are drawn.
+///
+/// When the pass binds an index buffer, "vertices" below means indices: each index
+/// selects the vertex that is drawn.
 ///
 /// # Examples
 ///
@@ -136,15 +981,59 @@ impl Eq for PassDescriptor {}
 /// use images_and_words::images::render_pass::DrawCommand;
 /// // Draw a quad using 4 vertices as a triangle strip
 /// // Vertices: [A, B, C, D] form triangles: [A,B,C] and [B,C,D]
-/// let draw_quad = DrawCommand::TriangleStrip(4);
+/// let draw_quad = DrawCommand::TriangleStrip(2);
 /// ```
 ///
-/// ## Triangle List  
+/// ## Triangle List
 /// ```
 /// use images_and_words::images::render_pass::DrawCommand;
 /// // Draw a quad using 6 vertices as a triangle list
 /// // Vertices: [A, B, C, D, E, F] form triangles: [A,B,C] and [D,E,F]
-/// let draw_quad = DrawCommand::TriangleList(6);
+/// let draw_quad = DrawCommand::TriangleList(2);
+/// ```
+///
+/// ## Instanced
+/// ```
+/// use images_and_words::images::render_pass::DrawCommand;
+/// // Draw 1000 copies of a 12-triangle mesh; bind per-instance data (such as
+/// // transforms) with `StepMode::Instance` to place each copy
+/// let draw_crowd = DrawCommand::TriangleListInstanced {
+///     triangles: 12,
+///     instances: 1000,
+/// };
+/// ```
+///
+/// ## Lines
+/// ```
+/// use images_and_words::images::render_pass::DrawCommand;
+/// // Plot a graph of 100 samples as 99 connected line segments
+/// let draw_plot = DrawCommand::LineStrip(99);
+/// ```
+///
+/// ## Sub-ranges
+/// ```
+/// use images_and_words::images::render_pass::{DrawCommand, Topology};
+/// // Draw the second mesh packed into a shared vertex and index buffer:
+/// // its 36 indices start at index 72, and count from vertex 24
+/// let draw_second_mesh = DrawCommand::Range {
+///     topology: Topology::TriangleList,
+///     first_vertex: 0,
+///     first_index: 72,
+///     base_vertex: 24,
+///     count: 36,
+///     instances: 1,
+/// };
+/// ```
+///
+/// ## Indirect
+/// ```
+/// use images_and_words::images::render_pass::{DrawCommand, Topology};
+/// // Draw with the arguments a culling compute pass wrote to the first element of
+/// // the buffer bound with `BindStyle::bind_indirect_buffer`
+/// let draw_visible = DrawCommand::Indirect {
+///     topology: Topology::TriangleList,
+///     draw: 0,
+/// };
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -152,7 +1041,7 @@ pub enum DrawCommand {
     /// Draws connected triangles where each vertex after the first two forms a
     /// triangle with the previous two vertices.
     ///
-    /// For `n` vertices, this produces `n-2` triangles. This is memory-efficient
+    /// For `n` triangles, this draws `n+2` vertices. This is memory-efficient
     /// for drawing connected surfaces like terrain meshes or quad strips.
     ///
     /// The payload is the number of triangles (not vertices).
@@ -160,9 +1049,1013 @@ pub enum DrawCommand {
 
     /// Draws independent triangles where each group of three vertices forms a triangle.
     ///
-    /// For `n` vertices, this produces `n/3` triangles. This is more flexible than
+    /// For `n` triangles, this draws `3n` vertices. This is more flexible than
     /// strips but requires more vertices for connected surfaces.
     ///
     /// The payload is the number of triangles (not vertices).
     TriangleList(u32),
+
+    /// Like [`TriangleStrip`](Self::TriangleStrip), drawn `instances` times.
+    ///
+    /// Each copy sees a different `@builtin(instance_index)`, and vertex buffers bound with
+    /// [`StepMode::Instance`](crate::images::vertex_layout::StepMode::Instance) advance
+    /// once per copy.
+    TriangleStripInstanced {
+        /// The number of triangles in each instance.
+        triangles: u32,
+        /// The number of instances to draw.
+        instances: u32,
+    },
+
+    /// Like [`TriangleList`](Self::TriangleList), drawn `instances` times.
+    ///
+    /// Each copy sees a different `@builtin(instance_index)`, and vertex buffers bound with
+    /// [`StepMode::Instance`](crate::images::vertex_layout::StepMode::Instance) advance
+    /// once per copy.
+    TriangleListInstanced {
+        /// The number of triangles in each instance.
+        triangles: u32,
+        /// The number of instances to draw.
+        instances: u32,
+    },
+
+    /// Draws independent line segments, each between a pair of vertices.
+    ///
+    /// For `n` lines, this draws `2n` vertices. Lines are one pixel wide.
+    ///
+    /// The payload is the number of lines (not vertices).
+    LineList(u32),
+
+    /// Draws connected line segments, each between a vertex and the one before it.
+    ///
+    /// For `n` lines, this draws `n+1` vertices. Lines are one pixel wide.
+    ///
+    /// The payload is the number of lines (not vertices).
+    LineStrip(u32),
+
+    /// Draws each vertex as a single pixel.
+    ///
+    /// The payload is the number of points.
+    PointList(u32),
+
+    /// Draws an explicit range of vertices, for drawing part of a shared buffer.
+    ///
+    /// Without an index buffer, this draws vertices `first_vertex..first_vertex + count`.
+    /// With one, it draws indices `first_index..first_index + count`, and `base_vertex` is
+    /// added to each index before it selects a vertex.
+    Range {
+        /// How the vertices are assembled into primitives.
+        topology: Topology,
+        /// The first vertex to draw, when the pass has no index buffer.
+        first_vertex: u32,
+        /// The first index to draw, when the pass has an index buffer.
+        first_index: u32,
+        /// Added to each index, when the pass has an index buffer.
+        base_vertex: i32,
+        /// The number of vertices (or indices) to draw.
+        count: u32,
+        /// The number of instances to draw.
+        instances: u32,
+    },
+
+    /// Draws with arguments read from the GPU, from the [`DrawIndirectArgs`] at index `draw`
+    /// of the pass's indirect buffer.
+    ///
+    /// Bind the buffer with
+    /// [`BindStyle::bind_indirect_buffer`](crate::bindings::BindStyle::bind_indirect_buffer).
+    /// The arguments are read when the pass draws, so a compute pass earlier in the frame
+    /// can decide how many vertices or instances to draw.  Ports refuse passes without the
+    /// buffer, or whose `draw` is past its end, with [`PassError`].
+    Indirect {
+        /// How the vertices are assembled into primitives.
+        topology: Topology,
+        /// The element of the indirect buffer holding the arguments.
+        draw: u32,
+    },
+
+    /// Like [`Indirect`](Self::Indirect), but draws from the pass's index buffer with the
+    /// [`DrawIndexedIndirectArgs`] at index `draw`.
+    IndexedIndirect {
+        /// How the vertices are assembled into primitives.
+        topology: Topology,
+        /// The element of the indirect buffer holding the arguments.
+        draw: u32,
+    },
+}
+
+/// The arguments of a [`DrawCommand::Indirect`], as the GPU reads them.
+///
+/// Equivalent to the arguments of a draw call with `vertex_count` vertices starting at
+/// `first_vertex`, drawn `instance_count` times, starting at instance `first_instance`.
+///
+/// A non-zero `first_instance` needs device support, see
+/// [`BoundDevice::supports_indirect_first_instance`](crate::images::BoundDevice::supports_indirect_first_instance).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(C)]
+pub struct DrawIndirectArgs {
+    /// The number of vertices to draw.
+    pub vertex_count: u32,
+    /// The number of instances to draw.
+    pub instance_count: u32,
+    /// The first vertex to draw.
+    pub first_vertex: u32,
+    /// The first instance to draw.
+    pub first_instance: u32,
+}
+
+/// The arguments of a [`DrawCommand::IndexedIndirect`], as the GPU reads them.
+///
+/// Equivalent to the arguments of an indexed draw call with `index_count` indices starting at
+/// `first_index`, each offset by `base_vertex`, drawn `instance_count` times, starting at
+/// instance `first_instance`.
+///
+/// A non-zero `first_instance` needs device support, see
+/// [`BoundDevice::supports_indirect_first_instance`](crate::images::BoundDevice::supports_indirect_first_instance).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(C)]
+pub struct DrawIndexedIndirectArgs {
+    /// The number of indices to draw.
+    pub index_count: u32,
+    /// The number of instances to draw.
+    pub instance_count: u32,
+    /// The first index to draw.
+    pub first_index: u32,
+    /// Added to each index before it selects a vertex.
+    pub base_vertex: i32,
+    /// The first instance to draw.
+    pub first_instance: u32,
+}
+
+// Safety: both are repr(C) and contain only 32-bit integers
+unsafe impl CRepr for DrawIndirectArgs {}
+unsafe impl CRepr for DrawIndexedIndirectArgs {}
+
+/// How a stream of vertices is assembled into primitives.
+///
+/// Used with [`DrawCommand::Range`]; the other draw commands imply their topology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Topology {
+    /// Each vertex is a point.
+    PointList,
+    /// Each pair of vertices is a line.
+    LineList,
+    /// Each vertex after the first continues a line from the previous one.
+    LineStrip,
+    /// Each group of three vertices is a triangle.
+    TriangleList,
+    /// Each vertex after the first two forms a triangle with the previous two.
+    TriangleStrip,
+}
+
+impl DrawCommand {
+    /// Returns how the vertices are assembled into primitives.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn topology(&self) -> Topology {
+        match *self {
+            DrawCommand::TriangleStrip(..) | DrawCommand::TriangleStripInstanced { .. } => {
+                Topology::TriangleStrip
+            }
+            DrawCommand::TriangleList(..) | DrawCommand::TriangleListInstanced { .. } => {
+                Topology::TriangleList
+            }
+            DrawCommand::LineList(..) => Topology::LineList,
+            DrawCommand::LineStrip(..) => Topology::LineStrip,
+            DrawCommand::PointList(..) => Topology::PointList,
+            DrawCommand::Range { topology, .. }
+            | DrawCommand::Indirect { topology, .. }
+            | DrawCommand::IndexedIndirect { topology, .. } => topology,
+        }
+    }
+
+    /// Returns the element of the indirect buffer holding the arguments, for indirect draws.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn indirect_draw(&self) -> Option<u32> {
+        match *self {
+            DrawCommand::Indirect { draw, .. } | DrawCommand::IndexedIndirect { draw, .. } => {
+                Some(draw)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the number of vertices (or indices) drawn for each instance, or `None` if
+    /// it does not fit in a `u32`.
+    const fn count(&self) -> Option<u32> {
+        match *self {
+            DrawCommand::TriangleStrip(triangles)
+            | DrawCommand::TriangleStripInstanced { triangles, .. } => strip_count(triangles, 2),
+            DrawCommand::TriangleList(triangles)
+            | DrawCommand::TriangleListInstanced { triangles, .. } => triangles.checked_mul(3),
+            DrawCommand::LineList(lines) => lines.checked_mul(2),
+            DrawCommand::LineStrip(lines) => strip_count(lines, 1),
+            DrawCommand::PointList(points) => Some(points),
+            DrawCommand::Range { count, .. } => Some(count),
+            //read from the indirect buffer instead
+            DrawCommand::Indirect { .. } | DrawCommand::IndexedIndirect { .. } => Some(0),
+        }
+    }
+
+    /// Returns the vertices drawn, or the indices if `indexed`, or `None` if they don't
+    /// all fit in a `u32`.
+    const fn checked_range(&self, indexed: bool) -> Option<std::ops::Range<u32>> {
+        let first = match *self {
+            DrawCommand::Range { first_index, .. } if indexed => first_index,
+            DrawCommand::Range { first_vertex, .. } => first_vertex,
+            _ => 0,
+        };
+        let Some(count) = self.count() else {
+            return None;
+        };
+        match first.checked_add(count) {
+            Some(end) => Some(first..end),
+            None => None,
+        }
+    }
+
+    /// Returns the vertices drawn, when the pass has no index buffer.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn vertices(&self) -> std::ops::Range<u32> {
+        //ports refuse draws that don't fit
+        self.checked_range(false)
+            .expect("vertices drawn fit in a u32")
+    }
+
+    /// Returns the indices drawn, when the pass has an index buffer.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn indices(&self) -> std::ops::Range<u32> {
+        self.checked_range(true)
+            .expect("indices drawn fit in a u32")
+    }
+
+    /// Returns the value added to each index, when the pass has an index buffer.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn base_vertex(&self) -> i32 {
+        match *self {
+            DrawCommand::Range { base_vertex, .. } => base_vertex,
+            _ => 0,
+        }
+    }
+
+    /// Returns the number of instances drawn.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn instance_count(&self) -> u32 {
+        match *self {
+            DrawCommand::TriangleStrip(..)
+            | DrawCommand::TriangleList(..)
+            | DrawCommand::LineList(..)
+            | DrawCommand::LineStrip(..)
+            | DrawCommand::PointList(..) => 1,
+            DrawCommand::TriangleStripInstanced { instances, .. }
+            | DrawCommand::TriangleListInstanced { instances, .. }
+            | DrawCommand::Range { instances, .. } => instances,
+            DrawCommand::Indirect { .. } | DrawCommand::IndexedIndirect { .. } => 0,
+        }
+    }
+}
+
+/// The number of vertices in a strip of `primitives`, where the first primitive
+/// needs `extra` more vertices than the rest.
+const fn strip_count(primitives: u32, extra: u32) -> Option<u32> {
+    if primitives == 0 {
+        Some(0)
+    } else {
+        primitives.checked_add(extra)
+    }
+}
+
+/// Fixed-function rasterizer settings for a pass: culling, winding, fill and depth bias.
+///
+/// The default culls back faces, treats counter-clockwise triangles as front faces, and
+/// fills them.
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::images::render_pass::{
+///     CullMode, FrontFace, PolygonMode, RasterizerState,
+/// };
+///
+/// // A mesh exported with clockwise winding
+/// let imported = RasterizerState::new().with_front_face(FrontFace::Clockwise);
+///
+/// // A wireframe debug view of both sides of every triangle
+/// let wireframe = RasterizerState::new()
+///     .with_cull_mode(CullMode::None)
+///     .with_polygon_mode(PolygonMode::Line);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RasterizerState {
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) cull_mode: CullMode,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) front_face: FrontFace,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) polygon_mode: PolygonMode,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) depth_bias: DepthBias,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) unclipped_depth: bool,
+}
+
+impl RasterizerState {
+    /// Creates the default rasterizer state.
+    pub const fn new() -> Self {
+        RasterizerState {
+            cull_mode: CullMode::Back,
+            front_face: FrontFace::CounterClockwise,
+            polygon_mode: PolygonMode::Fill,
+            depth_bias: DepthBias::NONE,
+            unclipped_depth: false,
+        }
+    }
+
+    /// Sets which faces are culled.  Defaults to [`CullMode::Back`].
+    pub const fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    /// Sets which winding is a front face.  Defaults to [`FrontFace::CounterClockwise`].
+    pub const fn with_front_face(mut self, front_face: FrontFace) -> Self {
+        self.front_face = front_face;
+        self
+    }
+
+    /// Sets how triangles are filled.  Defaults to [`PolygonMode::Fill`].
+    ///
+    /// Other modes need device support, see [`BoundDevice::supports_polygon_mode`](crate::images::BoundDevice::supports_polygon_mode).
+    /// Ports refuse passes that use a mode their device does not support, with
+    /// [`PassError::PolygonMode`], rather than drawing them filled.
+    pub const fn with_polygon_mode(mut self, polygon_mode: PolygonMode) -> Self {
+        self.polygon_mode = polygon_mode;
+        self
+    }
+
+    /// Sets the bias added to the depth of each triangle.  Defaults to [`DepthBias::NONE`].
+    pub const fn with_depth_bias(mut self, depth_bias: DepthBias) -> Self {
+        self.depth_bias = depth_bias;
+        self
+    }
+
+    /// Sets whether depth is clamped to the depth range instead of clipped.
+    ///
+    /// Normally, primitives are clipped where they cross the near and far planes.  With
+    /// unclipped depth they are drawn whole, with their depth clamped, which keeps shadow
+    /// casters behind the light's near plane from being cut off.  Needs device support,
+    /// see [`BoundDevice::supports_unclipped_depth`](crate::images::BoundDevice::supports_unclipped_depth).
+    /// Ports refuse passes that need it on devices without it, with
+    /// [`PassError::UnclippedDepth`], rather than clipping them.
+    pub const fn with_unclipped_depth(mut self, unclipped_depth: bool) -> Self {
+        self.unclipped_depth = unclipped_depth;
+        self
+    }
+}
+
+/// Which faces of triangles are culled (not drawn).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum CullMode {
+    /// Draws both faces.
+    None,
+    /// Culls front faces.
+    Front,
+    /// Culls back faces.
+    #[default]
+    Back,
+}
+
+/// Which winding, as seen on screen, makes a triangle face the viewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum FrontFace {
+    /// Triangles whose vertices run counter-clockwise are front faces.
+    #[default]
+    CounterClockwise,
+    /// Triangles whose vertices run clockwise are front faces.
+    Clockwise,
+}
+
+/// How triangles are filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum PolygonMode {
+    /// Triangles are filled.
+    #[default]
+    Fill,
+    /// Only the edges of triangles are drawn, for wireframes.
+    Line,
+    /// Only the vertices of triangles are drawn.
+    Point,
+}
+
+/// A bias added to the depth of triangles, to avoid z-fighting such as shadow acne.
+///
+/// The bias is `constant * r + slope_scale * slope`, where `r` is the smallest difference
+/// the depth format can represent and `slope` is the triangle's largest depth slope in
+/// screen space.  A nonzero `clamp` limits its magnitude.  Lines and points are not biased.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DepthBias {
+    /// A constant bias, in units of the depth format's precision.
+    pub constant: i32,
+    /// A bias proportional to the triangle's depth slope.
+    pub slope_scale: f32,
+    /// The largest magnitude of the bias, or `0.0` for no limit.
+    pub clamp: f32,
+}
+
+impl DepthBias {
+    /// No bias.
+    pub const NONE: DepthBias = DepthBias {
+        constant: 0,
+        slope_scale: 0.0,
+        clamp: 0.0,
+    };
+}
+
+/// How a pass's output color is combined with the color already in its target.
+///
+/// `src` is the color the fragment shader returns and `dst` is the color in the target.
+/// Blending happens in linear space, also for sRGB targets.
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::images::render_pass::{
+///     BlendComponent, BlendFactor, BlendMode, BlendOperation,
+/// };
+///
+/// // Subtracts the output from the target, keeping the target's alpha
+/// let subtract = BlendMode::Custom {
+///     color: BlendComponent {
+///         src_factor: BlendFactor::One,
+///         dst_factor: BlendFactor::One,
+///         operation: BlendOperation::ReverseSubtract,
+///     },
+///     alpha: BlendComponent {
+///         src_factor: BlendFactor::Zero,
+///         dst_factor: BlendFactor::One,
+///         operation: BlendOperation::Add,
+///     },
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum BlendMode {
+    /// The output replaces the target.
+    #[default]
+    Replace,
+    /// Straight alpha blending: `src * src.a + dst * (1 - src.a)`.
+    Alpha,
+    /// Blending for colors already multiplied by their alpha, such as composited UI:
+    /// `src + dst * (1 - src.a)`.
+    PremultipliedAlpha,
+    /// The output is added to the target, for particles and light accumulation: `src + dst`.
+    Additive,
+    /// The output is multiplied with the target, for tinting and darkening: `src * dst`.
+    Multiply,
+    /// Each channel keeps the smaller of the output and the target.
+    Min,
+    /// Each channel keeps the larger of the output and the target.
+    Max,
+    /// A custom blend equation for the color and alpha channels.
+    Custom {
+        /// How the red, green and blue channels are blended.
+        color: BlendComponent,
+        /// How the alpha channel is blended.
+        alpha: BlendComponent,
+    },
+}
+
+impl BlendMode {
+    /// The blend equations for the color and alpha channels, or `None` to replace.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn components(&self) -> Option<(BlendComponent, BlendComponent)> {
+        const fn both(
+            src_factor: BlendFactor,
+            dst_factor: BlendFactor,
+            operation: BlendOperation,
+        ) -> Option<(BlendComponent, BlendComponent)> {
+            let component = BlendComponent {
+                src_factor,
+                dst_factor,
+                operation,
+            };
+            Some((component, component))
+        }
+        match *self {
+            BlendMode::Replace => None,
+            BlendMode::Alpha => Some((
+                BlendComponent {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::OneMinusSrcAlpha,
+                    operation: BlendOperation::Add,
+                },
+                BlendComponent {
+                    src_factor: BlendFactor::One,
+                    dst_factor: BlendFactor::OneMinusSrcAlpha,
+                    operation: BlendOperation::Add,
+                },
+            )),
+            BlendMode::PremultipliedAlpha => both(
+                BlendFactor::One,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            ),
+            BlendMode::Additive => both(BlendFactor::One, BlendFactor::One, BlendOperation::Add),
+            BlendMode::Multiply => both(BlendFactor::Dst, BlendFactor::Zero, BlendOperation::Add),
+            BlendMode::Min => both(BlendFactor::One, BlendFactor::One, BlendOperation::Min),
+            BlendMode::Max => both(BlendFactor::One, BlendFactor::One, BlendOperation::Max),
+            BlendMode::Custom { color, alpha } => Some((color, alpha)),
+        }
+    }
+}
+
+/// One blend equation: `operation(src * src_factor, dst * dst_factor)`.
+///
+/// [`BlendOperation::Min`] and [`BlendOperation::Max`] ignore the factors, which must
+/// both be [`BlendFactor::One`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BlendComponent {
+    /// The factor the output is multiplied by.
+    pub src_factor: BlendFactor,
+    /// The factor the target is multiplied by.
+    pub dst_factor: BlendFactor,
+    /// How the two products are combined.
+    pub operation: BlendOperation,
+}
+
+/// A factor in a [`BlendComponent`].
+///
+/// Color factors such as [`Src`](Self::Src) apply channel by channel; in the alpha
+/// equation they use the alpha channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BlendFactor {
+    /// `0`
+    Zero,
+    /// `1`
+    One,
+    /// The output color.
+    Src,
+    /// `1 - ` the output color.
+    OneMinusSrc,
+    /// The output's alpha.
+    SrcAlpha,
+    /// `1 - ` the output's alpha.
+    OneMinusSrcAlpha,
+    /// The target color.
+    Dst,
+    /// `1 - ` the target color.
+    OneMinusDst,
+    /// The target's alpha.
+    DstAlpha,
+    /// `1 - ` the target's alpha.
+    OneMinusDstAlpha,
+    /// `min(src.a, 1 - dst.a)` for color, and `1` for alpha.
+    SrcAlphaSaturated,
+}
+
+/// How a [`BlendComponent`] combines the weighted output and target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BlendOperation {
+    /// `src + dst`
+    Add,
+    /// `src - dst`
+    Subtract,
+    /// `dst - src`
+    ReverseSubtract,
+    /// `min(src, dst)`
+    Min,
+    /// `max(src, dst)`
+    Max,
+}
+
+/// Which channels of the target a pass writes.
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::images::render_pass::ColorWriteMask;
+///
+/// // Keep the target's alpha, for example a mask rendered by an earlier pass
+/// let color_only = ColorWriteMask::COLOR;
+/// assert!(!color_only.alpha);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorWriteMask {
+    /// Whether red is written.
+    pub red: bool,
+    /// Whether green is written.
+    pub green: bool,
+    /// Whether blue is written.
+    pub blue: bool,
+    /// Whether alpha is written.
+    pub alpha: bool,
+}
+
+impl ColorWriteMask {
+    /// Writes every channel.
+    pub const ALL: ColorWriteMask = ColorWriteMask {
+        red: true,
+        green: true,
+        blue: true,
+        alpha: true,
+    };
+    /// Writes red, green and blue, but not alpha.
+    pub const COLOR: ColorWriteMask = ColorWriteMask {
+        alpha: false,
+        ..ColorWriteMask::ALL
+    };
+    /// Writes only alpha.
+    pub const ALPHA: ColorWriteMask = ColorWriteMask {
+        red: false,
+        green: false,
+        blue: false,
+        alpha: true,
+    };
+    /// Writes nothing, for passes that only write depth.
+    pub const NONE: ColorWriteMask = ColorWriteMask {
+        alpha: false,
+        ..ColorWriteMask::ALPHA
+    };
+}
+
+impl Default for ColorWriteMask {
+    fn default() -> Self {
+        ColorWriteMask::ALL
+    }
+}
+
+/// Depth and stencil testing for a pass.
+///
+/// The default tests with [`CompareFunction::LessEqual`] and writes depth into a
+/// [`DepthFormat::Depth16Unorm`] buffer, and ignores stencil.
+///
+/// The depth buffer is cleared to the far value of the first depth pass into it: `0.0`
+/// when that pass compares with [`CompareFunction::Greater`] or
+/// [`CompareFunction::GreaterEqual`] (reverse-Z), and `1.0` otherwise.  The stencil buffer
+/// is cleared to `0`.
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::images::render_pass::{
+///     CompareFunction, DepthFormat, DepthStencilState,
+/// };
+///
+/// // Reverse-Z keeps precision far from the camera in large scenes
+/// let reverse_z = DepthStencilState::new()
+///     .with_format(DepthFormat::Depth32Float)
+///     .with_compare(CompareFunction::Greater);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DepthStencilState {
+    /// `None` uses the target's format: [`DepthFormat::Depth16Unorm`] for the view.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) format: Option<DepthFormat>,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) compare: CompareFunction,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) depth_write: bool,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) stencil: StencilState,
+}
+
+impl DepthStencilState {
+    /// Creates the default depth-stencil state.
+    pub const fn new() -> Self {
+        DepthStencilState {
+            format: None,
+            compare: CompareFunction::LessEqual,
+            depth_write: true,
+            stencil: StencilState::IGNORE,
+        }
+    }
+
+    /// Sets the format of the depth buffer.  Defaults to [`DepthFormat::Depth16Unorm`] in
+    /// the port's view, and to the depth attachment's format in a render texture.
+    pub const fn with_format(mut self, format: DepthFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets how a fragment's depth is compared with the depth buffer; the fragment is
+    /// drawn if `compare(fragment, buffer)` passes.  Defaults to [`CompareFunction::LessEqual`].
+    pub const fn with_compare(mut self, compare: CompareFunction) -> Self {
+        self.compare = compare;
+        self
+    }
+
+    /// Sets whether drawn fragments write their depth.  Defaults to `true`.
+    pub const fn with_depth_write(mut self, depth_write: bool) -> Self {
+        self.depth_write = depth_write;
+        self
+    }
+
+    /// Sets the stencil test.  Defaults to [`StencilState::IGNORE`].
+    ///
+    /// Stencil needs a format with stencil, so also call [`with_format`](Self::with_format)
+    /// with [`DepthFormat::Depth24PlusStencil8`].  Ports refuse passes that use stencil
+    /// without one, with [`PassError::StencilFormat`].
+    pub const fn with_stencil(mut self, stencil: StencilState) -> Self {
+        self.stencil = stencil;
+        self
+    }
+
+    /// Whether this state reads or writes the stencil buffer.
+    pub(crate) const fn uses_stencil(&self) -> bool {
+        !self.stencil.is_ignore()
+    }
+
+    /// The value the depth buffer is cleared to, see the type docs.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) const fn clear_depth(&self) -> f32 {
+        match self.compare {
+            CompareFunction::Greater | CompareFunction::GreaterEqual => 0.0,
+            _ => 1.0,
+        }
+    }
+}
+
+/// The format of a depth buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum DepthFormat {
+    /// 16-bit normalized depth, enough for small scenes.
+    #[default]
+    Depth16Unorm,
+    /// At least 24 bits of depth.
+    Depth24Plus,
+    /// At least 24 bits of depth, and 8 bits of stencil.
+    Depth24PlusStencil8,
+    /// 32-bit float depth, for large scenes, best with reverse-Z.
+    Depth32Float,
+}
+
+impl DepthFormat {
+    /// Whether this format has a stencil buffer.
+    pub const fn has_stencil(&self) -> bool {
+        matches!(self, DepthFormat::Depth24PlusStencil8)
+    }
+}
+
+/// A comparison for depth and stencil tests, which passes if `compare(new, stored)` holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum CompareFunction {
+    /// Never passes.
+    Never,
+    /// Passes if the new value is less than the stored value.
+    Less,
+    /// Passes if the values are equal.
+    Equal,
+    /// Passes if the new value is less than or equal to the stored value.
+    #[default]
+    LessEqual,
+    /// Passes if the new value is greater than the stored value.
+    Greater,
+    /// Passes if the values differ.
+    NotEqual,
+    /// Passes if the new value is greater than or equal to the stored value.
+    GreaterEqual,
+    /// Always passes.
+    Always,
+}
+
+impl CompareFunction {
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn passes<T: PartialOrd>(&self, new: T, stored: T) -> bool {
+        match self {
+            CompareFunction::Never => false,
+            CompareFunction::Less => new < stored,
+            CompareFunction::Equal => new == stored,
+            CompareFunction::LessEqual => new <= stored,
+            CompareFunction::Greater => new > stored,
+            CompareFunction::NotEqual => new != stored,
+            CompareFunction::GreaterEqual => new >= stored,
+            CompareFunction::Always => true,
+        }
+    }
+}
+
+/// The stencil test for a pass.
+///
+/// For each fragment, `compare(reference & read_mask, stored & read_mask)` runs with the
+/// compare function of the face the fragment belongs to.  The face's operations then update
+/// the bits of the stored value in `write_mask`.
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::images::render_pass::{
+///     CompareFunction, StencilFaceState, StencilOperation, StencilState,
+/// };
+///
+/// // Marks every pixel the pass draws with 1...
+/// let mark = StencilState::new(
+///     StencilFaceState {
+///         compare: CompareFunction::Always,
+///         pass_op: StencilOperation::Replace,
+///         ..StencilFaceState::IGNORE
+///     },
+///     1,
+/// );
+/// // ...so a later pass can draw only inside the marked pixels, such as through a portal
+/// let inside = StencilState::new(
+///     StencilFaceState {
+///         compare: CompareFunction::Equal,
+///         ..StencilFaceState::IGNORE
+///     },
+///     1,
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StencilState {
+    /// The test for front-facing triangles, lines and points.
+    pub front: StencilFaceState,
+    /// The test for back-facing triangles.
+    pub back: StencilFaceState,
+    /// The bits of the reference and stored values that are compared.
+    pub read_mask: u32,
+    /// The bits of the stored value that operations may change.
+    pub write_mask: u32,
+    /// The reference value compared against, and written by [`StencilOperation::Replace`].
+    pub reference: u32,
+}
+
+impl StencilState {
+    /// Ignores the stencil buffer.
+    pub const IGNORE: StencilState = StencilState {
+        front: StencilFaceState::IGNORE,
+        back: StencilFaceState::IGNORE,
+        read_mask: 0,
+        write_mask: 0,
+        reference: 0,
+    };
+
+    /// Uses `face` for both faces, comparing against `reference` with all bits masked in.
+    pub const fn new(face: StencilFaceState, reference: u32) -> Self {
+        StencilState {
+            front: face,
+            back: face,
+            read_mask: !0,
+            write_mask: !0,
+            reference,
+        }
+    }
+
+    const fn is_ignore(&self) -> bool {
+        self.front.is_ignore() && self.back.is_ignore()
+    }
+}
+
+impl Default for StencilState {
+    fn default() -> Self {
+        StencilState::IGNORE
+    }
+}
+
+/// The stencil test and operations for one face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StencilFaceState {
+    /// How the reference value is compared with the stored value.
+    pub compare: CompareFunction,
+    /// What happens to the stored value when the stencil test fails.
+    pub fail_op: StencilOperation,
+    /// What happens to the stored value when the stencil test passes but the depth test fails.
+    pub depth_fail_op: StencilOperation,
+    /// What happens to the stored value when both tests pass.
+    pub pass_op: StencilOperation,
+}
+
+impl StencilFaceState {
+    /// Always passes, and keeps the stored value.
+    pub const IGNORE: StencilFaceState = StencilFaceState {
+        compare: CompareFunction::Always,
+        fail_op: StencilOperation::Keep,
+        depth_fail_op: StencilOperation::Keep,
+        pass_op: StencilOperation::Keep,
+    };
+
+    const fn is_ignore(&self) -> bool {
+        matches!(self.compare, CompareFunction::Always)
+            && matches!(self.fail_op, StencilOperation::Keep)
+            && matches!(self.depth_fail_op, StencilOperation::Keep)
+            && matches!(self.pass_op, StencilOperation::Keep)
+    }
+}
+
+/// How a stencil test updates the stored value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum StencilOperation {
+    /// Keeps the stored value.
+    #[default]
+    Keep,
+    /// Sets the stored value to `0`.
+    Zero,
+    /// Sets the stored value to the reference value.
+    Replace,
+    /// Inverts the bits of the stored value.
+    Invert,
+    /// Adds one, up to the largest value.
+    IncrementClamp,
+    /// Subtracts one, down to `0`.
+    DecrementClamp,
+    /// Adds one, wrapping to `0`.
+    IncrementWrap,
+    /// Subtracts one, wrapping to the largest value.
+    DecrementWrap,
+}
+
+/// What happens to an attachment's contents before a pass draws into it.
+///
+/// See [`PassDescriptor::with_color_load`] and
+/// [`Port::set_color_load`](crate::images::port::Port::set_color_load).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadOp<T> {
+    /// Fills the attachment with a value.  Colors are linear, like fragment shader output.
+    Clear(T),
+    /// Keeps the contents, as earlier passes or the previous frame left them.
+    Load,
+}
+
+/// What happens to an attachment's contents after a pass draws into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StoreOp {
+    /// Keeps the contents for later passes, readback and the next frame.
+    #[default]
+    Store,
+    /// Lets the device throw the contents away, which is cheaper on tiled GPUs.  Later
+    /// passes, readback and the next frame see undefined contents.
+    Discard,
+}
+
+/// A rectangle of a pass's target, measured from its top-left corner.
+///
+/// See [`PassDescriptor::with_viewport`] and [`PassDescriptor::with_scissor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetRect {
+    /// A rectangle in the target's pixels.
+    Pixels {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// A rectangle in fractions of the target's size, where `1.0` is its whole width or
+    /// height.
+    Fraction {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+impl TargetRect {
+    /// The rectangle in a `width` by `height` target, as `[x, y, width, height]` in pixels.
+    #[allow(dead_code)] //nop implementation does not use
+    fn to_pixels(self, target_width: u32, target_height: u32) -> [f32; 4] {
+        match self {
+            TargetRect::Pixels {
+                x,
+                y,
+                width,
+                height,
+            } => [x as f32, y as f32, width as f32, height as f32],
+            TargetRect::Fraction {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let (target_width, target_height) = (target_width as f32, target_height as f32);
+                [
+                    x * target_width,
+                    y * target_height,
+                    width * target_width,
+                    height * target_height,
+                ]
+            }
+        }
+    }
+
+    /// Like [`Self::to_pixels`], with each edge rounded to the nearest pixel and clipped to
+    /// the target.
+    #[allow(dead_code)] //nop implementation does not use
+    fn clip_to(self, target_width: u32, target_height: u32) -> [u32; 4] {
+        let [x, y, width, height] = self.to_pixels(target_width, target_height);
+        let clip = |edge: f32, size: u32| (edge.round().max(0.0) as u32).min(size);
+        let (left, right) = (clip(x, target_width), clip(x + width, target_width));
+        let (top, bottom) = (clip(y, target_height), clip(y + height, target_height));
+        [
+            left,
+            top,
+            right.saturating_sub(left),
+            bottom.saturating_sub(top),
+        ]
+    }
 }
@@ -1,17 +1,38 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
 //! Shader types for GPU programming.
 //!
-//! This module provides types for vertex and fragment shaders using WGSL (WebGPU Shading Language).
-//! Shaders are the programmable stages of the GPU pipeline that transform vertices and determine
-//! pixel colors.
+//! This module provides types for vertex, fragment, and compute shaders using WGSL (WebGPU
+//! Shading Language).  Shaders are the programmable stages of the GPU pipeline that transform
+//! vertices and determine pixel colors, or run general-purpose work.
 //!
 //! # Overview
 //!
 //! The shader types in this module are wrappers around WGSL code strings. They are used when
-//! creating render passes to define the programmable GPU behavior for rendering operations.
+//! creating render passes to define the programmable GPU behavior for rendering operations,
+//! and compute passes for work that doesn't draw anything.
 //!
 //! Currently, only WGSL is supported as the shading language, which provides good cross-platform
 //! compatibility through the wgpu backend.
+//!
+//! The `backend_software` feature cannot run WGSL; on that backend each shader also carries a
+//! Rust program, attached with `with_software`.  See the `software` module for details.
+//!
+//! The [`validation`] module checks a pass's WGSL against the resources it binds, without a
+//! GPU.  The [`reflection`] module reads the same WGSL to lay out vertices and bind resources
+//! by name, and the [`compose`] module builds WGSL from shared snippets.  Shaders read from
+//! files with `from_path` can be reloaded as the files change; see the [`file`] module.
+//!
+//! A [`ShaderModule`] holds WGSL with several entry points, such as a file of variants, and
+//! makes the vertex and fragment shaders of a pass from the entry points it names.
+
+pub mod compose;
+pub mod file;
+pub mod reflection;
+#[cfg(feature = "backend_software")]
+pub mod software;
+pub mod validation;
+
+use std::path::{Path, PathBuf};
 
 /// A fragment shader that runs for each pixel/fragment to determine its color.
 ///
@@ -66,6 +87,14 @@ pub struct FragmentShader {
     pub(crate) wgsl_code: String,
     #[allow(dead_code)] //nop implementation does not use
     pub(crate) label: &'static str,
+    /// Where the lines of `wgsl_code` were written, if it was composed.
+    pub(crate) source_map: Option<compose::SourceMap>,
+    /// The file `wgsl_code` was read from, which ports reload it from.
+    pub(crate) path: Option<PathBuf>,
+    /// The name of the entry point to run, or `None` for the only one of its stage.
+    pub(crate) entry_point: Option<String>,
+    #[cfg(feature = "backend_software")]
+    pub(crate) software: Option<software::FragmentProgram>,
 }
 
 /// A vertex shader that transforms vertex positions and prepares data for rasterization.
@@ -133,6 +162,58 @@ pub struct VertexShader {
     pub(crate) wgsl_code: String,
     #[allow(dead_code)] //nop implementation does not use
     pub(crate) label: &'static str,
+    /// Where the lines of `wgsl_code` were written, if it was composed.
+    pub(crate) source_map: Option<compose::SourceMap>,
+    /// The file `wgsl_code` was read from, which ports reload it from.
+    pub(crate) path: Option<PathBuf>,
+    /// The name of the entry point to run, or `None` for the only one of its stage.
+    pub(crate) entry_point: Option<String>,
+    #[cfg(feature = "backend_software")]
+    pub(crate) software: Option<software::VertexProgram>,
+}
+
+/// A compute shader that runs general-purpose work on the GPU.
+///
+/// Compute shaders are run by a
+/// [`ComputePassDescriptor`](crate::images::compute_pass::ComputePassDescriptor), which
+/// dispatches some number of workgroups.  They typically read and write storage buffers.
+///
+/// # Examples
+///
+/// A shader that doubles every element of a buffer:
+///
+/// ```
+/// use images_and_words::images::shader::ComputeShader;
+///
+/// let shader = ComputeShader::new(
+///     "double",
+///     r#"
+///     @group(0) @binding(0)
+///     var<storage, read_write> values: array<f32>;
+///
+///     @compute @workgroup_size(64)
+///     fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+///         if (id.x < arrayLength(&values)) {
+///             values[id.x] = values[id.x] * 2.0;
+///         }
+///     }
+///     "#.to_string()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputeShader {
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) wgsl_code: String,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) label: &'static str,
+    /// The @compute function to run, or `None` if the WGSL has exactly one.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) entry_point: Option<String>,
+    /// Values for the shader's `override` declarations, by name or `@id`.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) constants: Vec<(String, f64)>,
+    #[cfg(feature = "backend_software")]
+    pub(crate) software: Option<software::ComputeProgram>,
 }
 
 impl FragmentShader {
@@ -157,7 +238,68 @@ impl FragmentShader {
     /// );
     /// ```
     pub fn new(label: &'static str, wgsl_code: String) -> Self {
-        Self { label, wgsl_code }
+        Self {
+            label,
+            wgsl_code,
+            source_map: None,
+            path: None,
+            entry_point: None,
+            #[cfg(feature = "backend_software")]
+            software: None,
+        }
+    }
+
+    /// Attaches a Rust program that the software backend runs in place of the WGSL.
+    ///
+    /// The program returns a linear RGBA color for the fragment, or `None` to discard it.
+    /// Other backends ignore the program.
+    #[cfg(feature = "backend_software")]
+    pub fn with_software(
+        mut self,
+        program: impl Fn(&software::FragmentInput) -> Option<[f32; 4]> + Send + Sync + 'static,
+    ) -> Self {
+        self.software = Some(software::FragmentProgram::new(program));
+        self
+    }
+
+    /// Reads a fragment shader from the WGSL file at `path`.
+    ///
+    /// The shader remembers `path`, so ports can reload it with
+    /// [`Port::reload_shaders`](crate::images::port::Port::reload_shaders) as the file
+    /// changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or is not UTF-8.
+    pub async fn from_path(
+        label: &'static str,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, file::FileError> {
+        let path = path.as_ref();
+        let wgsl_code = file::read_wgsl(path, crate::Priority::UserInitiated).await?;
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            ..Self::new(label, wgsl_code)
+        })
+    }
+
+    /// Creates a shader from WGSL composed by a [`Composer`](compose::Composer).
+    ///
+    /// Compile errors are reported at the file and line each composed line was written on.
+    pub fn from_composed(label: &'static str, composed: compose::Composed) -> Self {
+        let (wgsl_code, source_map) = composed.into_parts();
+        Self {
+            source_map: Some(source_map),
+            ..Self::new(label, wgsl_code)
+        }
+    }
+
+    /// Runs the @fragment function named `entry_point`, for WGSL that has several.
+    ///
+    /// Without an entry point, the WGSL must have exactly one @fragment function.
+    pub fn with_entry_point(mut self, entry_point: impl Into<String>) -> Self {
+        self.entry_point = Some(entry_point.into());
+        self
     }
 }
 
@@ -196,7 +338,68 @@ impl VertexShader {
     /// );
     /// ```
     pub fn new(label: &'static str, wgsl_code: String) -> Self {
-        Self { label, wgsl_code }
+        Self {
+            label,
+            wgsl_code,
+            source_map: None,
+            path: None,
+            entry_point: None,
+            #[cfg(feature = "backend_software")]
+            software: None,
+        }
+    }
+
+    /// Attaches a Rust program that the software backend runs in place of the WGSL.
+    ///
+    /// The program returns the clip-space position of the vertex and any varyings.
+    /// Other backends ignore the program.
+    #[cfg(feature = "backend_software")]
+    pub fn with_software(
+        mut self,
+        program: impl Fn(&software::VertexInput) -> software::VertexOutput + Send + Sync + 'static,
+    ) -> Self {
+        self.software = Some(software::VertexProgram::new(program));
+        self
+    }
+
+    /// Reads a vertex shader from the WGSL file at `path`.
+    ///
+    /// The shader remembers `path`, so ports can reload it with
+    /// [`Port::reload_shaders`](crate::images::port::Port::reload_shaders) as the file
+    /// changes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or is not UTF-8.
+    pub async fn from_path(
+        label: &'static str,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, file::FileError> {
+        let path = path.as_ref();
+        let wgsl_code = file::read_wgsl(path, crate::Priority::UserInitiated).await?;
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            ..Self::new(label, wgsl_code)
+        })
+    }
+
+    /// Creates a shader from WGSL composed by a [`Composer`](compose::Composer).
+    ///
+    /// Compile errors are reported at the file and line each composed line was written on.
+    pub fn from_composed(label: &'static str, composed: compose::Composed) -> Self {
+        let (wgsl_code, source_map) = composed.into_parts();
+        Self {
+            source_map: Some(source_map),
+            ..Self::new(label, wgsl_code)
+        }
+    }
+
+    /// Runs the @vertex function named `entry_point`, for WGSL that has several.
+    ///
+    /// Without an entry point, the WGSL must have exactly one @vertex function.
+    pub fn with_entry_point(mut self, entry_point: impl Into<String>) -> Self {
+        self.entry_point = Some(entry_point.into());
+        self
     }
 }
 
@@ -212,3 +415,207 @@ impl AsRef<str> for VertexShader {
         &self.wgsl_code
     }
 }
+
+impl ComputeShader {
+    /// Creates a new compute shader with the given label and WGSL code.
+    ///
+    /// The label is used for debugging and error messages. The WGSL code should contain
+    /// a compute shader entry point function (typically named `cs_main` or similar).
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - A static string label for debugging purposes
+    /// * `wgsl_code` - The WGSL shader code as a string
+    pub fn new(label: &'static str, wgsl_code: String) -> Self {
+        Self {
+            label,
+            wgsl_code,
+            entry_point: None,
+            constants: Vec::new(),
+            #[cfg(feature = "backend_software")]
+            software: None,
+        }
+    }
+
+    /// Runs the @compute function named `entry_point`, for WGSL that has several.
+    ///
+    /// Without an entry point, the WGSL must have exactly one @compute function.
+    pub fn with_entry_point(mut self, entry_point: impl Into<String>) -> Self {
+        self.entry_point = Some(entry_point.into());
+        self
+    }
+
+    /// Sets the WGSL `override` named `name`, or with `@id(name)`, to `value`.
+    ///
+    /// Setting the same constant again replaces its value.  Overrides without a default
+    /// must be set when the entry point uses them.  The software backend ignores
+    /// constants.
+    ///
+    /// ```
+    /// use images_and_words::bindings::BindStyle;
+    /// use images_and_words::images::compute_pass::{ComputePassDescriptor, Dispatch};
+    /// use images_and_words::images::shader::ComputeShader;
+    ///
+    /// let shader = ComputeShader::new("scale",
+    ///     "override factor: f32;
+    ///     @compute @workgroup_size(1) fn main() { let scaled = 2.0 * factor; }".to_string())
+    ///     .with_constant("factor", 0.5);
+    /// let pass = ComputePassDescriptor::new("scale".to_string(), shader, BindStyle::new(),
+    ///     Dispatch::Workgroups(1, 1, 1));
+    /// assert_eq!(pass.validate(), Ok(()));
+    /// ```
+    pub fn with_constant(mut self, name: impl Into<String>, value: f64) -> Self {
+        let name = name.into();
+        self.constants.retain(|(set, _)| *set != name);
+        self.constants.push((name, value));
+        self
+    }
+
+    /// Attaches a Rust program that the software backend runs in place of the WGSL.
+    ///
+    /// The program runs once per workgroup, so it should loop over the invocations of
+    /// its workgroup.  Other backends ignore the program.
+    #[cfg(feature = "backend_software")]
+    pub fn with_software(
+        mut self,
+        program: impl Fn(&software::ComputeInput) + Send + Sync + 'static,
+    ) -> Self {
+        self.software = Some(software::ComputeProgram::new(program));
+        self
+    }
+}
+
+// Boilerplate for ComputeShader
+impl std::fmt::Display for ComputeShader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ComputeShader({})", self.label)
+    }
+}
+
+impl AsRef<str> for ComputeShader {
+    fn as_ref(&self) -> &str {
+        &self.wgsl_code
+    }
+}
+
+/// WGSL with several entry points, from which passes take their vertex and fragment shaders.
+///
+/// The shaders a module makes share its WGSL, so a pass whose shaders both come from one
+/// module compiles it once.  WGSL `override` declarations let passes specialize the module
+/// with [`PassDescriptor::with_constant`](crate::images::render_pass::PassDescriptor::with_constant).
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::bindings::BindStyle;
+/// use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+/// use images_and_words::images::shader::ShaderModule;
+///
+/// let module = ShaderModule::new(
+///     "scene",
+///     r#"
+///     override debug_scale: f32 = 1.0;
+///
+///     @vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+///         return vec4<f32>(f32(i), 0.0, 0.0, 1.0);
+///     }
+///     @vertex fn vs_shadow(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+///         return vec4<f32>(0.0, f32(i), 0.0, 1.0);
+///     }
+///     @fragment fn fs_main() -> @location(0) vec4<f32> { return vec4<f32>(1.0); }
+///     @fragment fn fs_debug() -> @location(0) vec4<f32> { return vec4<f32>(debug_scale); }
+///     "#.to_string(),
+/// );
+/// let debug = PassDescriptor::new(
+///     "debug".to_string(),
+///     module.vertex("vs_main"),
+///     module.fragment("fs_debug"),
+///     BindStyle::new(),
+///     DrawCommand::TriangleList(1),
+///     false,
+///     false,
+/// )
+/// .with_constant("debug_scale", 0.5);
+/// assert_eq!(debug.validate(), Ok(()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderModule {
+    wgsl_code: String,
+    label: &'static str,
+    source_map: Option<compose::SourceMap>,
+    path: Option<PathBuf>,
+}
+
+impl ShaderModule {
+    /// Creates a module with the given label and WGSL code.
+    pub fn new(label: &'static str, wgsl_code: String) -> Self {
+        Self {
+            wgsl_code,
+            label,
+            source_map: None,
+            path: None,
+        }
+    }
+
+    /// Reads a module from the WGSL file at `path`.
+    ///
+    /// The shaders the module makes remember `path`, so ports reload them as the file
+    /// changes, like shaders read with [`VertexShader::from_path`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or is not UTF-8.
+    pub async fn from_path(
+        label: &'static str,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, file::FileError> {
+        let path = path.as_ref();
+        let wgsl_code = file::read_wgsl(path, crate::Priority::UserInitiated).await?;
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            ..Self::new(label, wgsl_code)
+        })
+    }
+
+    /// Creates a module from WGSL composed by a [`Composer`](compose::Composer).
+    pub fn from_composed(label: &'static str, composed: compose::Composed) -> Self {
+        let (wgsl_code, source_map) = composed.into_parts();
+        Self {
+            source_map: Some(source_map),
+            ..Self::new(label, wgsl_code)
+        }
+    }
+
+    /// Makes a vertex shader that runs the @vertex function named `entry_point`.
+    pub fn vertex(&self, entry_point: &str) -> VertexShader {
+        VertexShader {
+            source_map: self.source_map.clone(),
+            path: self.path.clone(),
+            ..VertexShader::new(self.label, self.wgsl_code.clone())
+        }
+        .with_entry_point(entry_point)
+    }
+
+    /// Makes a fragment shader that runs the @fragment function named `entry_point`.
+    pub fn fragment(&self, entry_point: &str) -> FragmentShader {
+        FragmentShader {
+            source_map: self.source_map.clone(),
+            path: self.path.clone(),
+            ..FragmentShader::new(self.label, self.wgsl_code.clone())
+        }
+        .with_entry_point(entry_point)
+    }
+}
+
+// Boilerplate for ShaderModule
+impl std::fmt::Display for ShaderModule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ShaderModule({})", self.label)
+    }
+}
+
+impl AsRef<str> for ShaderModule {
+    fn as_ref(&self) -> &str {
+        &self.wgsl_code
+    }
+}
@@ -0,0 +1,490 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Composes WGSL from shared snippets.
+//!
+//! WGSL has no way to share code between shaders.  A [`Composer`] holds named snippets and
+//! defines, and expands a few line directives before the WGSL is compiled:
+//!
+//! - `#include "name"` inserts the snippet named `name`.  Each snippet is included once per
+//!   shader; later includes of it are ignored, so snippets may include each other.
+//! - `#define NAME` defines `NAME`, and `#define NAME value` also replaces `NAME` with
+//!   `value` in the lines that follow.  `#undef NAME` removes it.
+//! - `#ifdef NAME`, `#ifndef NAME`, `#else` and `#endif` keep or drop the lines between
+//!   them.
+//!
+//! Directives must be alone on their line.  Composing is pure text, so it needs no GPU.
+//!
+//! The [`Composed`] result remembers where each of its lines came from.  Shaders created
+//! with [`VertexShader::from_composed`](super::VertexShader::from_composed) and
+//! [`FragmentShader::from_composed`](super::FragmentShader::from_composed) report compile
+//! errors at the file and line they were written on, rather than in the composed WGSL.
+//!
+//! # Built-in snippets
+//!
+//! - `images_and_words/camera` declares `struct Camera { view_projection: mat4x4<f32> }`,
+//!   laid out as the camera the port binds.
+//! - `images_and_words/color` declares `srgb_to_linear` and `linear_to_srgb`, which convert
+//!   `vec3<f32>` colors.
+//!
+//! # Example
+//!
+//! ```
+//! use images_and_words::images::shader::VertexShader;
+//! use images_and_words::images::shader::compose::Composer;
+//!
+//! let composer = Composer::new()
+//!     .with_snippet("offset", "fn offset(p: vec4<f32>) -> vec4<f32> { return p + OFFSET; }")
+//!     .with_define("OFFSET", "vec4(0.5, 0.0, 0.0, 0.0)");
+//! let composed = composer.compose("sprites.wgsl", r#"
+//! #include "images_and_words/camera"
+//! #include "offset"
+//! @group(0) @binding(0) var<uniform> camera: Camera;
+//! @vertex fn vs_main(@location(0) position: vec4<f32>) -> @builtin(position) vec4<f32> {
+//! #ifdef FLAT
+//!     return position;
+//! #else
+//!     return offset(camera.view_projection * position);
+//! #endif
+//! }
+//! "#).unwrap();
+//! assert!(composed.wgsl().contains("struct Camera"));
+//! assert!(!composed.wgsl().contains("return position;"));
+//! let shader = VertexShader::from_composed("sprites", composed);
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+/// The snippets every [`Composer`] starts with.
+const BUILT_IN_SNIPPETS: [(&str, &str); 2] = [
+    (
+        "images_and_words/camera",
+        "// The camera the port binds: its view and projection, combined.
+struct Camera {
+    view_projection: mat4x4<f32>,
+}
+",
+    ),
+    (
+        "images_and_words/color",
+        "// Converts an sRGB-encoded color to linear.
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+// Converts a linear color to sRGB encoding.
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+",
+    ),
+];
+
+/// A mistake in the directives of a shader being composed.
+///
+/// Each variant names the file, as passed to [`Composer::compose`] or the snippet's name,
+/// and the 1-based line of the directive.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ComposeError {
+    /// An `#include` names a snippet the composer does not have.
+    #[error("{file}:{line}: there is no snippet named `{name}` to include")]
+    UnknownSnippet {
+        file: String,
+        line: u32,
+        name: String,
+    },
+    /// A line starting with `#` is not a directive the composer understands.
+    #[error("{file}:{line}: `{directive}` is not a directive")]
+    Directive {
+        file: String,
+        line: u32,
+        directive: String,
+    },
+    /// An `#else` or `#endif` has no `#ifdef` or `#ifndef` before it in its file.
+    #[error("{file}:{line}: `{directive}` has no #ifdef or #ifndef before it")]
+    Unmatched {
+        file: String,
+        line: u32,
+        directive: String,
+    },
+    /// An `#ifdef` or `#ifndef` is not closed by an `#endif` in its file.
+    #[error("{file}:{line}: this conditional has no #endif")]
+    Unterminated { file: String, line: u32 },
+}
+
+/// Where each line of composed WGSL was written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceMap {
+    /// The file and 1-based line of each composed line.
+    lines: Vec<(String, u32)>,
+}
+
+impl SourceMap {
+    /// Returns the file and line that `line` of the composed WGSL was written on.
+    ///
+    /// Lines are numbered from 1, as compilers report them.
+    pub fn locate(&self, line: u32) -> Option<(&str, u32)> {
+        let (file, line) = self.lines.get(line.checked_sub(1)? as usize)?;
+        Some((file, *line))
+    }
+}
+
+/// WGSL composed by a [`Composer`], with where each of its lines came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Composed {
+    wgsl: String,
+    source_map: SourceMap,
+}
+
+impl Composed {
+    /// The composed WGSL.
+    pub fn wgsl(&self) -> &str {
+        &self.wgsl
+    }
+
+    /// Where each line of [`wgsl`](Self::wgsl) was written.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    pub(crate) fn into_parts(self) -> (String, SourceMap) {
+        (self.wgsl, self.source_map)
+    }
+}
+
+/// Named snippets and defines that shaders are composed from.
+///
+/// See the [module documentation](self) for the directives it understands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Composer {
+    snippets: HashMap<String, String>,
+    defines: HashMap<String, String>,
+}
+
+impl Default for Composer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Composer {
+    /// Creates a composer with the built-in snippets and no defines.
+    pub fn new() -> Self {
+        Composer {
+            snippets: BUILT_IN_SNIPPETS
+                .iter()
+                .map(|(name, wgsl)| (name.to_string(), wgsl.to_string()))
+                .collect(),
+            defines: HashMap::new(),
+        }
+    }
+
+    /// Adds a snippet that `#include "name"` inserts, replacing any snippet of that name.
+    pub fn with_snippet(mut self, name: impl Into<String>, wgsl: impl Into<String>) -> Self {
+        self.snippets.insert(name.into(), wgsl.into());
+        self
+    }
+
+    /// Defines `name` for every shader composed, as `#define name value` would.
+    ///
+    /// If `value` is empty, `name` is only defined for `#ifdef`, and not replaced.
+    pub fn with_define(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.defines.insert(name.into(), value.into());
+        self
+    }
+
+    /// Expands the directives of `wgsl`, which was read from `file`.
+    ///
+    /// `file` only names the source in errors and the [`SourceMap`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the first directive that can't be expanded.
+    pub fn compose(&self, file: &str, wgsl: &str) -> Result<Composed, ComposeError> {
+        let mut expansion = Expansion {
+            composer: self,
+            defines: self.defines.clone(),
+            included: HashSet::new(),
+            wgsl: String::new(),
+            lines: Vec::new(),
+        };
+        expansion.expand(file, wgsl)?;
+        Ok(Composed {
+            wgsl: expansion.wgsl,
+            source_map: SourceMap {
+                lines: expansion.lines,
+            },
+        })
+    }
+}
+
+/// An `#ifdef` or `#ifndef` whose `#endif` has not been reached.
+struct Conditional {
+    /// The line of the `#ifdef` or `#ifndef`.
+    line: u32,
+    /// Whether the lines of the current branch are kept.
+    active: bool,
+    /// Whether the lines around the conditional are kept.
+    enclosing_active: bool,
+    seen_else: bool,
+}
+
+/// The state of one [`Composer::compose`].
+struct Expansion<'a> {
+    composer: &'a Composer,
+    defines: HashMap<String, String>,
+    /// The snippets included so far.
+    included: HashSet<String>,
+    wgsl: String,
+    lines: Vec<(String, u32)>,
+}
+
+impl Expansion<'_> {
+    fn expand(&mut self, file: &str, wgsl: &str) -> Result<(), ComposeError> {
+        let mut conditionals: Vec<Conditional> = Vec::new();
+        for (index, text) in wgsl.lines().enumerate() {
+            let line = index as u32 + 1;
+            let active = conditionals.last().is_none_or(|c| c.active);
+            let Some(directive) = text.trim_start().strip_prefix('#') else {
+                if active {
+                    let substituted = self.substitute(text);
+                    self.wgsl.push_str(&substituted);
+                    self.wgsl.push('\n');
+                    self.lines.push((file.to_string(), line));
+                }
+                continue;
+            };
+            let directive = directive.trim();
+            let (keyword, argument) = directive
+                .split_once(char::is_whitespace)
+                .map_or((directive, ""), |(keyword, argument)| {
+                    (keyword, argument.trim())
+                });
+            let malformed = || ComposeError::Directive {
+                file: file.to_string(),
+                line,
+                directive: format!("#{directive}"),
+            };
+            let unmatched = || ComposeError::Unmatched {
+                file: file.to_string(),
+                line,
+                directive: format!("#{keyword}"),
+            };
+            let name = || match argument {
+                "" => Err(malformed()),
+                name if name.contains(char::is_whitespace) => Err(malformed()),
+                name => Ok(name.to_string()),
+            };
+            match keyword {
+                "ifdef" | "ifndef" => {
+                    let defined = self.defines.contains_key(&name()?);
+                    conditionals.push(Conditional {
+                        line,
+                        active: active && defined == (keyword == "ifdef"),
+                        enclosing_active: active,
+                        seen_else: false,
+                    });
+                }
+                "else" if argument.is_empty() => {
+                    let conditional = conditionals.last_mut().ok_or_else(unmatched)?;
+                    if conditional.seen_else {
+                        return Err(unmatched());
+                    }
+                    conditional.seen_else = true;
+                    conditional.active = conditional.enclosing_active && !conditional.active;
+                }
+                "endif" if argument.is_empty() => {
+                    conditionals.pop().ok_or_else(unmatched)?;
+                }
+                _ if !active => {}
+                "define" => {
+                    let (name, value) = argument
+                        .split_once(char::is_whitespace)
+                        .map_or((argument, ""), |(name, value)| (name, value.trim()));
+                    if name.is_empty() {
+                        return Err(malformed());
+                    }
+                    self.defines.insert(name.to_string(), value.to_string());
+                }
+                "undef" => {
+                    self.defines.remove(&name()?);
+                }
+                "include" => {
+                    let snippet = argument
+                        .strip_prefix('"')
+                        .and_then(|argument| argument.strip_suffix('"'))
+                        .ok_or_else(malformed)?;
+                    let Some(wgsl) = self.composer.snippets.get(snippet) else {
+                        return Err(ComposeError::UnknownSnippet {
+                            file: file.to_string(),
+                            line,
+                            name: snippet.to_string(),
+                        });
+                    };
+                    if self.included.insert(snippet.to_string()) {
+                        self.expand(snippet, wgsl)?;
+                    }
+                }
+                _ => return Err(malformed()),
+            }
+        }
+        match conditionals.first() {
+            Some(conditional) => Err(ComposeError::Unterminated {
+                file: file.to_string(),
+                line: conditional.line,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Replaces the defined identifiers in `text` with their values.
+    fn substitute(&self, text: &str) -> String {
+        let mut substituted = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == '_') {
+            //identifiers can't start inside a number or another identifier
+            let preceding = rest[..start].chars().next_back();
+            let end = rest[start..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map_or(rest.len(), |end| start + end);
+            let identifier = &rest[start..end];
+            substituted.push_str(&rest[..start]);
+            match self.defines.get(identifier) {
+                Some(value)
+                    if !value.is_empty()
+                        && !preceding.is_some_and(|c| c.is_alphanumeric() || c == '.') =>
+                {
+                    substituted.push_str(value)
+                }
+                _ => substituted.push_str(identifier),
+            }
+            rest = &rest[end..];
+        }
+        substituted.push_str(rest);
+        substituted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComposeError, Composer};
+
+    #[test]
+    fn includes_snippets_once() {
+        let composer = Composer::new()
+            .with_snippet("a", "#include \"b\"\nfn a() {}")
+            .with_snippet("b", "#include \"a\"\nfn b() {}");
+        let composed = composer
+            .compose("main.wgsl", "#include \"a\"\n#include \"b\"\nfn main() {}")
+            .unwrap();
+        assert_eq!(composed.wgsl(), "fn b() {}\nfn a() {}\nfn main() {}\n");
+    }
+
+    #[test]
+    fn maps_lines_to_their_files() {
+        let composer = Composer::new().with_snippet("helpers", "// helpers\nfn helper() {}");
+        let composed = composer
+            .compose(
+                "main.wgsl",
+                "fn first() {}\n#include \"helpers\"\nfn last() {}",
+            )
+            .unwrap();
+        let source_map = composed.source_map();
+        assert_eq!(source_map.locate(1), Some(("main.wgsl", 1)));
+        assert_eq!(source_map.locate(2), Some(("helpers", 1)));
+        assert_eq!(source_map.locate(3), Some(("helpers", 2)));
+        assert_eq!(source_map.locate(4), Some(("main.wgsl", 3)));
+        assert_eq!(source_map.locate(0), None);
+        assert_eq!(source_map.locate(5), None);
+    }
+
+    #[test]
+    fn conditionals_follow_defines() {
+        let wgsl =
+            "#ifdef SHADOWS\nshadows\n#ifndef SOFT\nhard\n#else\nsoft\n#endif\n#else\nnone\n#endif";
+        let compose = |composer: Composer| composer.compose("main.wgsl", wgsl).unwrap();
+        assert_eq!(compose(Composer::new()).wgsl(), "none\n");
+        assert_eq!(
+            compose(Composer::new().with_define("SHADOWS", "")).wgsl(),
+            "shadows\nhard\n"
+        );
+        assert_eq!(
+            compose(
+                Composer::new()
+                    .with_define("SHADOWS", "")
+                    .with_define("SOFT", "")
+            )
+            .wgsl(),
+            "shadows\nsoft\n"
+        );
+    }
+
+    #[test]
+    fn defines_replace_identifiers() {
+        let wgsl = "#define COUNT 4u\nvar<private> counts: array<u32, COUNT>;\nlet x = COUNTS + s.COUNT + 2COUNT;\n#undef COUNT\nCOUNT";
+        let composed = Composer::new().compose("main.wgsl", wgsl).unwrap();
+        assert_eq!(
+            composed.wgsl(),
+            "var<private> counts: array<u32, 4u>;\nlet x = COUNTS + s.COUNT + 2COUNT;\nCOUNT\n"
+        );
+    }
+
+    #[test]
+    fn dropped_lines_do_not_define_or_include() {
+        let wgsl =
+            "#ifdef MISSING\n#include \"missing\"\n#define FLAG\n#endif\n#ifdef FLAG\nflag\n#endif";
+        let composed = Composer::new().compose("main.wgsl", wgsl).unwrap();
+        assert_eq!(composed.wgsl(), "");
+    }
+
+    #[test]
+    fn reports_mistakes_where_they_are() {
+        let composer = Composer::new().with_snippet("broken", "fn ok() {}\n#ifdef X");
+        let error = |wgsl: &str| composer.compose("main.wgsl", wgsl).unwrap_err();
+        assert_eq!(
+            error("\n#include \"missing\""),
+            ComposeError::UnknownSnippet {
+                file: "main.wgsl".to_string(),
+                line: 2,
+                name: "missing".to_string()
+            }
+        );
+        assert_eq!(
+            error("#include \"broken\"\n#endif"),
+            ComposeError::Unterminated {
+                file: "broken".to_string(),
+                line: 2
+            }
+        );
+        assert_eq!(
+            error("#endif"),
+            ComposeError::Unmatched {
+                file: "main.wgsl".to_string(),
+                line: 1,
+                directive: "#endif".to_string()
+            }
+        );
+        assert!(matches!(
+            error("#include missing"),
+            ComposeError::Directive { line: 1, .. }
+        ));
+        assert!(matches!(
+            error("#pragma once"),
+            ComposeError::Directive { line: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn built_in_snippets_are_valid() {
+        let composed = Composer::new()
+            .compose(
+                "main.wgsl",
+                "#include \"images_and_words/camera\"\n#include \"images_and_words/color\"",
+            )
+            .unwrap();
+        naga::front::wgsl::parse_str(composed.wgsl()).unwrap();
+    }
+}
@@ -0,0 +1,37 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Shaders read from WGSL files, which ports reload as the files change.
+//!
+//! [`VertexShader::from_path`](super::VertexShader::from_path) and
+//! [`FragmentShader::from_path`](super::FragmentShader::from_path) remember the file they
+//! read.  [`Port::reload_shaders`](crate::images::port::Port::reload_shaders) reads the
+//! files of a port's passes again and prepares the passes whose files changed, and
+//! [`Port::watch_shaders`](crate::images::port::Port::watch_shaders) does so periodically.
+//! Files are polled with `async_file`, so this works on every platform the crate supports.
+
+use crate::Priority;
+use std::path::{Path, PathBuf};
+
+/// A shader file that couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum FileError {
+    /// The file couldn't be opened or read.
+    #[error("Can't read {}: {message}", path.display())]
+    Read { path: PathBuf, message: String },
+    /// The file is not UTF-8, as WGSL must be.
+    #[error("{} is not UTF-8", path.display())]
+    NotUtf8 { path: PathBuf },
+}
+
+/// Reads the WGSL in the file at `path`.
+pub(crate) async fn read_wgsl(path: &Path, priority: Priority) -> Result<String, FileError> {
+    let read = |e: async_file::Error| FileError::Read {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    };
+    let file = async_file::File::open(path, priority).await.map_err(read)?;
+    let data = file.read_all(priority).await.map_err(read)?;
+    String::from_utf8(data.into_boxed_slice().into_vec()).map_err(|_| FileError::NotUtf8 {
+        path: path.to_path_buf(),
+    })
+}
@@ -0,0 +1,662 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Derives vertex layouts and bindings from what a pass's shaders declare.
+//!
+//! Rather than numbering each binding by hand, [`vertex_layout`] reads the `@location`
+//! inputs of a vertex shader, and a [`BindingPlan`] reads the `@group`/`@binding`
+//! declarations of a pass's shaders, so resources can be bound by the names the WGSL gives
+//! them:
+//!
+//! ```
+//! use images_and_words::images::shader::{FragmentShader, VertexShader};
+//! use images_and_words::images::shader::reflection::{BindingKind, BindingPlan, PortValue};
+//! use images_and_words::images::shader::validation::ShaderError;
+//!
+//! let vertex_shader = VertexShader::new("vs",
+//!     "@group(0) @binding(0) var<uniform> camera: mat4x4<f32>;
+//!      @vertex fn main(@location(0) position: vec3<f32>) -> @builtin(position) vec4<f32> {
+//!          return camera * vec4(position, 1.0);
+//!      }".to_string());
+//! let fragment_shader = FragmentShader::new("fs",
+//!     "@group(0) @binding(1) var<uniform> time: f32;
+//!      @fragment fn main() -> @location(0) vec4<f32> { return vec4(time); }".to_string());
+//!
+//! let mut plan = BindingPlan::new(&vertex_shader, &fragment_shader).unwrap();
+//! assert_eq!(plan.slots()[0].name(), "camera");
+//! assert_eq!(plan.slots()[0].kind(), BindingKind::Uniform);
+//!
+//! plan.bind("camera", PortValue::Camera).unwrap();
+//! // The shaders have no `sprites`
+//! assert!(matches!(plan.bind("sprites", PortValue::Time), Err(ShaderError::UnknownBinding { .. })));
+//! // `time` is not bound yet
+//! assert!(matches!(plan.clone().into_bind_style(), Err(ShaderError::Unbound { .. })));
+//! plan.bind("time", PortValue::Time).unwrap();
+//! let bind_style = plan.into_bind_style().unwrap();
+//! ```
+//!
+//! Vertex buffers have no names in WGSL, so they are bound on the resulting
+//! [`BindStyle`] with the layout [`vertex_layout`] returns.
+
+use crate::bindings::BindStyle;
+use crate::bindings::bind_style::{BindSlot, BindTarget, Stage};
+use crate::bindings::sampler::SamplerType;
+use crate::images::shader::validation::{Expected, Parsed, ShaderError};
+use crate::images::shader::{FragmentShader, VertexShader};
+use crate::images::vertex_layout::{VertexField, VertexFieldType, VertexLayout};
+use naga::common::wgsl::TypeContext;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Returns a layout providing each `@location` input of `shader`, in location order.
+///
+/// Each input is one field named after it, so the layout describes a single vertex buffer
+/// whose vertices hold every input.  To read the inputs from several buffers, split the
+/// fields into one layout per buffer, keeping their order.
+///
+/// # Errors
+///
+/// Returns an error if `shader` does not compile, skips a location, or reads an input as
+/// something other than `f32` or a vector of them.
+pub fn vertex_layout(shader: &VertexShader) -> Result<VertexLayout, ShaderError> {
+    let parsed = Parsed::vertex(shader)?;
+    let mut inputs = parsed.vertex_inputs();
+    inputs.sort_by_key(|(location, ..)| *location);
+    let mut layout = VertexLayout::new();
+    for (index, (location, name, ty)) in inputs.into_iter().enumerate() {
+        if location != index as u32 {
+            return Err(ShaderError::LocationGap {
+                shader: parsed.label,
+                location: index as u32,
+            });
+        }
+        let r#type = match parsed.module.types[ty].inner {
+            naga::TypeInner::Scalar(naga::Scalar::F32) => VertexFieldType::F32,
+            naga::TypeInner::Vector {
+                size,
+                scalar: naga::Scalar::F32,
+            } => match size {
+                naga::VectorSize::Bi => VertexFieldType::F32x2,
+                naga::VectorSize::Tri => VertexFieldType::F32x3,
+                naga::VectorSize::Quad => VertexFieldType::F32x4,
+            },
+            _ => {
+                return Err(ShaderError::UnsupportedAttribute {
+                    shader: parsed.label,
+                    location,
+                    declared: parsed.module.to_ctx().type_to_string(ty),
+                });
+            }
+        };
+        layout.fields.push(VertexField {
+            name: Cow::Owned(name),
+            r#type,
+        });
+    }
+    Ok(layout)
+}
+
+/// The kind of resource a shader declares a binding as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BindingKind {
+    /// A `var<uniform>`.
+    Uniform,
+    /// A `var<storage>`, which the shader writes if `writable`.
+    Storage { writable: bool },
+    /// A `texture_2d<f32>`.
+    Texture,
+    /// A `texture_depth_2d`.
+    DepthTexture,
+    /// A filtering `sampler`.
+    Sampler,
+    /// A declaration that nothing can be bound to, such as a `texture_3d`.
+    Other,
+}
+
+impl BindingKind {
+    /// Returns how `variable` is declared in `module`.
+    pub(crate) fn of(module: &naga::Module, variable: &naga::GlobalVariable) -> Self {
+        use naga::{AddressSpace, ImageClass, ImageDimension, ScalarKind, TypeInner};
+        match variable.space {
+            AddressSpace::Uniform => return BindingKind::Uniform,
+            AddressSpace::Storage { access } => {
+                return BindingKind::Storage {
+                    writable: access.contains(naga::StorageAccess::STORE),
+                };
+            }
+            AddressSpace::Handle => {}
+            _ => return BindingKind::Other,
+        }
+        match module.types[variable.ty].inner {
+            TypeInner::Image {
+                dim: ImageDimension::D2,
+                arrayed: false,
+                class:
+                    ImageClass::Sampled {
+                        kind: ScalarKind::Float,
+                        multi: false,
+                    },
+            } => BindingKind::Texture,
+            TypeInner::Image {
+                dim: ImageDimension::D2,
+                arrayed: false,
+                class: ImageClass::Depth { multi: false },
+            } => BindingKind::DepthTexture,
+            TypeInner::Sampler { comparison: false } => BindingKind::Sampler,
+            _ => BindingKind::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for BindingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BindingKind::Uniform => write!(f, "a uniform buffer"),
+            BindingKind::Storage { writable: true } => write!(f, "a read-write storage buffer"),
+            BindingKind::Storage { writable: false } => write!(f, "a read-only storage buffer"),
+            BindingKind::Texture => write!(f, "a texture_2d<f32>"),
+            BindingKind::DepthTexture => write!(f, "a texture_depth_2d"),
+            BindingKind::Sampler => write!(f, "a sampler"),
+            BindingKind::Other => write!(f, "an unsupported resource"),
+        }
+    }
+}
+
+/// A binding a shader of the pass uses, as listed by [`BindingPlan::slots`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedSlot {
+    pub(crate) name: String,
+    pub(crate) slot: BindSlot,
+    pub(crate) kind: BindingKind,
+    pub(crate) stage: Stage,
+    /// The label of the shader that uses the binding.
+    pub(crate) shader: &'static str,
+    /// How the binding is declared, as it would be written in WGSL.
+    pub(crate) declaration: String,
+}
+
+impl PlannedSlot {
+    /// The name the shader gives the binding.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The slot the shader declares the binding at.
+    pub fn slot(&self) -> BindSlot {
+        self.slot
+    }
+
+    /// The kind of resource the shader declares.
+    pub fn kind(&self) -> BindingKind {
+        self.kind
+    }
+
+    /// The stage of the shader that uses the binding.
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+}
+
+/// A value the port provides, bound by [`BindingPlan::bind`].
+///
+/// These are the values of [`BindStyle::bind_camera_matrix`], [`BindStyle::bind_frame_counter`]
+/// and [`BindStyle::bind_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PortValue {
+    /// The camera's `mat4x4<f32>`.
+    Camera,
+    /// The `u32` frame counter.
+    FrameCounter,
+    /// The `f32` seconds since the port was created.
+    Time,
+}
+
+mod sealed {
+    use super::BindingKind;
+    use crate::bindings::BindStyle;
+    use crate::bindings::bind_style::{BindSlot, Stage};
+
+    pub trait Resource {
+        /// Binds the resource at `slot`, which the shader declares as `kind`.
+        fn bind_to(
+            self,
+            bind_style: &mut BindStyle,
+            slot: BindSlot,
+            stage: Stage,
+            kind: BindingKind,
+        );
+    }
+}
+
+/// Something a [`BindingPlan`] can bind by name.
+///
+/// This is implemented for references to static, dynamic and reverse buffers, to static,
+/// frame and render textures, for [`SamplerType`], and for [`PortValue`].  A render texture
+/// binds its depth attachment where the shader declares a `texture_depth_2d`.
+pub trait Resource: sealed::Resource {}
+
+impl<T: sealed::Resource> Resource for T {}
+
+impl<Element> sealed::Resource for &crate::bindings::forward::r#static::buffer::Buffer<Element> {
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, _: BindingKind) {
+        bind_style.bind_static_buffer(slot, stage, self);
+    }
+}
+
+impl<Element> sealed::Resource for &crate::bindings::forward::dynamic::buffer::Buffer<Element>
+where
+    Element: Send + Sync + 'static,
+{
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, _: BindingKind) {
+        bind_style.bind_dynamic_buffer(slot, stage, self);
+    }
+}
+
+impl<Element> sealed::Resource for &crate::bindings::reverse::buffer::Buffer<Element> {
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, _: BindingKind) {
+        bind_style.bind_reverse_buffer(slot, stage, self);
+    }
+}
+
+impl<Format> sealed::Resource for &crate::bindings::forward::r#static::texture::Texture<Format>
+where
+    Format: crate::pixel_formats::sealed::PixelFormat,
+{
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, _: BindingKind) {
+        bind_style.bind_static_texture(slot, stage, self, None);
+    }
+}
+
+impl<Format> sealed::Resource
+    for &crate::bindings::forward::dynamic::frame_texture::FrameTexture<Format>
+where
+    Format: crate::pixel_formats::sealed::PixelFormat + 'static,
+{
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, _: BindingKind) {
+        bind_style.bind_dynamic_texture(slot, stage, self);
+    }
+}
+
+impl<Format> sealed::Resource for &crate::bindings::sideways::RenderTexture<Format> {
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, kind: BindingKind) {
+        if kind == BindingKind::DepthTexture {
+            bind_style.bind_render_texture_depth(slot, stage, self);
+        } else {
+            bind_style.bind_render_texture(slot, stage, self, None);
+        }
+    }
+}
+
+impl sealed::Resource for SamplerType {
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, _: BindingKind) {
+        bind_style.bind_sampler(slot, stage, self);
+    }
+}
+
+impl sealed::Resource for PortValue {
+    fn bind_to(self, bind_style: &mut BindStyle, slot: BindSlot, stage: Stage, _: BindingKind) {
+        match self {
+            PortValue::Camera => bind_style.bind_camera_matrix(slot, stage),
+            PortValue::FrameCounter => bind_style.bind_frame_counter(slot, stage),
+            PortValue::Time => bind_style.bind_time(slot, stage),
+        }
+    }
+}
+
+/// The bindings a pass's shaders use, filled in by name.
+///
+/// A plan lists each `@group`/`@binding` declaration the entry points of a vertex and a
+/// fragment shader use.  Binding a name checks that the shader declares it, and as a
+/// resource of the kind bound; [`into_bind_style`](Self::into_bind_style) checks that every
+/// name was bound.  Each mistake is reported as a [`ShaderError`] before a pipeline is
+/// built.
+///
+/// When the plan binds a sampler, the textures it binds in the same group are bound
+/// filterable, so they can be sampled with it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingPlan {
+    pub(crate) slots: Vec<PlannedSlot>,
+    pub(crate) bind_style: BindStyle,
+}
+
+impl BindingPlan {
+    /// Reads the bindings that `vertex` and `fragment` use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either shader does not compile, or if both use a binding of
+    /// the same name or slot, as a binding is bound for only one stage.
+    pub fn new(vertex: &VertexShader, fragment: &FragmentShader) -> Result<Self, ShaderError> {
+        let vertex = Parsed::vertex(vertex)?;
+        let fragment = Parsed::fragment(fragment)?;
+        let mut slots: Vec<PlannedSlot> = Vec::new();
+        for shader in [&vertex, &fragment] {
+            for variable in shader.used_globals() {
+                let Some(binding) = &variable.binding else {
+                    continue;
+                };
+                let planned = PlannedSlot {
+                    name: shader.name(variable),
+                    slot: BindSlot::in_group(binding.group, binding.binding),
+                    kind: BindingKind::of(&shader.module, variable),
+                    stage: shader.stage,
+                    shader: shader.label,
+                    declaration: shader.declaration(variable),
+                };
+                if slots
+                    .iter()
+                    .any(|other| other.name == planned.name || other.slot == planned.slot)
+                {
+                    return Err(ShaderError::SharedBinding {
+                        variable: planned.name,
+                    });
+                }
+                slots.push(planned);
+            }
+        }
+        slots.sort_by_key(|planned| planned.slot);
+        Ok(BindingPlan {
+            slots,
+            bind_style: BindStyle::new(),
+        })
+    }
+
+    /// The bindings the shaders use, in slot order.
+    pub fn slots(&self) -> &[PlannedSlot] {
+        &self.slots
+    }
+
+    /// Binds `resource` to the binding named `name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no shader uses a binding named `name`, or if the shader declares
+    /// it as another kind of resource.  The binding is left unbound.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is already bound.
+    pub fn bind(&mut self, name: &str, resource: impl Resource) -> Result<(), ShaderError> {
+        let Some(planned) = self.slots.iter().find(|planned| planned.name == name) else {
+            return Err(ShaderError::UnknownBinding {
+                name: name.to_string(),
+            });
+        };
+        resource.bind_to(
+            &mut self.bind_style,
+            planned.slot,
+            planned.stage,
+            planned.kind,
+        );
+        let expected = Expected::for_target(&self.bind_style.binds[&planned.slot]);
+        if !expected.accepts(planned.kind) {
+            self.bind_style.binds.remove(&planned.slot);
+            return Err(ShaderError::Mismatch {
+                shader: planned.shader,
+                variable: planned.name.clone(),
+                slot: planned.slot,
+                declared: planned.declaration.clone(),
+                bound: expected.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Binds the port's global group to `group`, as [`BindStyle::bind_global_group`].
+    ///
+    /// The bindings the shaders use in `group` are then bound by the port.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the global group is already bound, or if a name in `group` is bound.
+    pub fn bind_global_group(&mut self, group: u32) {
+        self.bind_style.bind_global_group(group);
+    }
+
+    /// Returns the plan's bindings, once every name is bound.
+    ///
+    /// Vertex, index and indirect buffers, which have no names, may be bound on the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ShaderError::Unbound`] for the first binding, in slot order, that nothing
+    /// is bound to.
+    pub fn into_bind_style(mut self) -> Result<BindStyle, ShaderError> {
+        for planned in &self.slots {
+            let global = self.bind_style.global_group == Some(planned.slot.group);
+            if !global && !self.bind_style.binds.contains_key(&planned.slot) {
+                return Err(ShaderError::Unbound {
+                    shader: planned.shader,
+                    variable: planned.name.clone(),
+                    slot: planned.slot,
+                });
+            }
+        }
+        let samplers: HashMap<u32, SamplerType> = self
+            .bind_style
+            .binds
+            .iter()
+            .filter_map(|(slot, info)| match info.target {
+                BindTarget::Sampler(sampler) => Some((slot.group, sampler)),
+                _ => None,
+            })
+            .collect();
+        for (slot, info) in &mut self.bind_style.binds {
+            if let BindTarget::StaticTexture(_, filter) | BindTarget::RenderTexture(_, filter) =
+                &mut info.target
+                && filter.is_none()
+            {
+                *filter = samplers.get(&slot.group).copied();
+            }
+        }
+        Ok(self.bind_style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BindingKind, BindingPlan, PortValue, vertex_layout};
+    use crate::bindings::bind_style::{BindSlot, Stage};
+    use crate::images::shader::validation::ShaderError;
+    use crate::images::shader::{FragmentShader, VertexShader};
+    use crate::images::vertex_layout::VertexFieldType;
+
+    const FRAGMENT: &str = "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }";
+
+    fn vertex(wgsl: &str) -> VertexShader {
+        VertexShader::new("vs", wgsl.to_string())
+    }
+
+    fn fragment(wgsl: &str) -> FragmentShader {
+        FragmentShader::new("fs", wgsl.to_string())
+    }
+
+    #[test]
+    fn vertex_layout_follows_locations() {
+        let shader = vertex(
+            "struct Vertex { @location(1) color: vec4<f32>, @location(0) position: vec3<f32> }
+             @vertex fn main(vertex: Vertex, @location(2) size: f32) -> @builtin(position) vec4<f32> {
+                 return vec4(vertex.position * size, 1.0) + vertex.color;
+             }",
+        );
+        let layout = vertex_layout(&shader).unwrap();
+        let fields: Vec<_> = layout
+            .fields
+            .iter()
+            .map(|field| (field.name.as_ref(), field.r#type))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("position", VertexFieldType::F32x3),
+                ("color", VertexFieldType::F32x4),
+                ("size", VertexFieldType::F32),
+            ]
+        );
+        assert_eq!(layout.element_stride(), 32);
+    }
+
+    #[test]
+    fn vertex_layout_reports_gaps_and_unsupported_types() {
+        let gap = vertex(
+            "@vertex fn main(@location(1) x: f32) -> @builtin(position) vec4<f32> { return vec4(x); }",
+        );
+        assert!(matches!(
+            vertex_layout(&gap),
+            Err(ShaderError::LocationGap { location: 0, .. })
+        ));
+        let integer = vertex(
+            "@vertex fn main(@location(0) x: i32) -> @builtin(position) vec4<f32> { return vec4(f32(x)); }",
+        );
+        assert!(matches!(
+            vertex_layout(&integer),
+            Err(ShaderError::UnsupportedAttribute { location: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn lists_slots_with_their_kind_and_stage() {
+        let plan = BindingPlan::new(
+            &vertex(
+                "@group(0) @binding(0) var<uniform> camera: mat4x4<f32>;
+                 @group(0) @binding(5) var<uniform> unused: f32;
+                 @vertex fn main() -> @builtin(position) vec4<f32> { return camera[0]; }",
+            ),
+            &fragment(
+                "@group(1) @binding(0) var sprites: texture_2d<f32>;
+                 @group(1) @binding(1) var sprite_sampler: sampler;
+                 @group(0) @binding(1) var<storage, read_write> hits: array<u32>;
+                 @fragment fn main() -> @location(0) vec4<f32> {
+                     hits[0] = 1u;
+                     return textureSample(sprites, sprite_sampler, vec2(0.5));
+                 }",
+            ),
+        )
+        .unwrap();
+        let slots: Vec<_> = plan
+            .slots()
+            .iter()
+            .map(|planned| {
+                (
+                    planned.name(),
+                    planned.slot(),
+                    planned.kind(),
+                    planned.stage(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            slots,
+            [
+                (
+                    "camera",
+                    BindSlot::new(0),
+                    BindingKind::Uniform,
+                    Stage::Vertex
+                ),
+                (
+                    "hits",
+                    BindSlot::new(1),
+                    BindingKind::Storage { writable: true },
+                    Stage::Fragment
+                ),
+                (
+                    "sprites",
+                    BindSlot::in_group(1, 0),
+                    BindingKind::Texture,
+                    Stage::Fragment
+                ),
+                (
+                    "sprite_sampler",
+                    BindSlot::in_group(1, 1),
+                    BindingKind::Sampler,
+                    Stage::Fragment
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn binds_by_name() {
+        let mut plan = BindingPlan::new(
+            &vertex(
+                "@group(0) @binding(3) var<uniform> view: mat4x4<f32>;
+                 @vertex fn main() -> @builtin(position) vec4<f32> { return view[0]; }",
+            ),
+            &fragment(
+                "@group(0) @binding(0) var<uniform> frame: u32;
+                 @fragment fn main() -> @location(0) vec4<f32> { return vec4(f32(frame)); }",
+            ),
+        )
+        .unwrap();
+        plan.bind("view", PortValue::Camera).unwrap();
+        plan.bind("frame", PortValue::FrameCounter).unwrap();
+        let bind_style = plan.into_bind_style().unwrap();
+        assert_eq!(bind_style.binds[&BindSlot::new(3)].stage, Stage::Vertex);
+        assert_eq!(bind_style.binds[&BindSlot::new(0)].stage, Stage::Fragment);
+    }
+
+    #[test]
+    fn reports_unknown_missing_and_mismatched_names() {
+        let mut plan = BindingPlan::new(
+            &vertex("@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }"),
+            &fragment(
+                "@group(0) @binding(0) var sprites: texture_2d<f32>;
+                 @fragment fn main() -> @location(0) vec4<f32> {
+                     return textureLoad(sprites, vec2(0), 0);
+                 }",
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            plan.bind("sprite", PortValue::Time),
+            Err(ShaderError::UnknownBinding {
+                name: "sprite".to_string()
+            })
+        );
+        assert!(matches!(
+            plan.bind("sprites", PortValue::Time),
+            Err(ShaderError::Mismatch { .. })
+        ));
+        assert_eq!(
+            plan.into_bind_style(),
+            Err(ShaderError::Unbound {
+                shader: "fs",
+                variable: "sprites".to_string(),
+                slot: BindSlot::new(0)
+            })
+        );
+    }
+
+    #[test]
+    fn reports_bindings_both_stages_use() {
+        let shared = "@group(0) @binding(0) var<uniform> time: f32;";
+        let result = BindingPlan::new(
+            &vertex(&format!(
+                "{shared} @vertex fn main() -> @builtin(position) vec4<f32> {{ return vec4(time); }}"
+            )),
+            &fragment(&format!(
+                "{shared} @fragment fn main() -> @location(0) vec4<f32> {{ return vec4(time); }}"
+            )),
+        );
+        assert_eq!(
+            result,
+            Err(ShaderError::SharedBinding {
+                variable: "time".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn global_group_binds_its_names() {
+        let mut plan = BindingPlan::new(
+            &vertex(
+                "@group(1) @binding(0) var<uniform> camera: mat4x4<f32>;
+                 @vertex fn main() -> @builtin(position) vec4<f32> { return camera[0]; }",
+            ),
+            &fragment(FRAGMENT),
+        )
+        .unwrap();
+        plan.bind_global_group(1);
+        assert!(plan.into_bind_style().is_ok());
+    }
+}
@@ -0,0 +1,388 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Rust programs that stand in for WGSL on the software backend.
+//!
+//! The `backend_software` feature rasterizes on the CPU and cannot run WGSL.  Instead, each
+//! shader carries a Rust closure attached with [`VertexShader::with_software`](super::VertexShader::with_software),
+//! [`FragmentShader::with_software`](super::FragmentShader::with_software)
+//! or [`ComputeShader::with_software`](super::ComputeShader::with_software).  The closures
+//! follow the same conventions as the WGSL entry points they replace:
+//!
+//! * the vertex program returns a clip-space position, plus any number of `f32` varyings
+//! * varyings are interpolated perspective-correctly across the primitive
+//! * the fragment program returns a linear RGBA color, or `None` to discard the fragment
+//! * the compute program runs once per workgroup, and writes its results with [`Resources::store`]
+//!
+//! Resources bound through [`BindStyle`](crate::bindings::BindStyle) are available to every
+//! program through [`Resources`], addressed by the same binding slot as in WGSL.
+//!
+//! # Example
+//!
+//! ```
+//! use images_and_words::images::shader::{FragmentShader, VertexShader};
+//! use images_and_words::images::shader::software::VertexOutput;
+//!
+//! // A full-screen triangle, equivalent to the usual `vertex_index` trick in WGSL
+//! let vertex_shader = VertexShader::new("fullscreen", String::new()).with_software(|input| {
+//!     let positions = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+//!     let [x, y] = positions[input.vertex_index as usize];
+//!     VertexOutput::new([x, y, 0.0, 1.0])
+//! });
+//! let fragment_shader = FragmentShader::new("red", String::new())
+//!     .with_software(|_input| Some([1.0, 0.0, 0.0, 1.0]));
+//! ```
+
+use crate::bindings::bind_style::BindSlot;
+use crate::bindings::forward::dynamic::buffer::CRepr;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Inputs to a software vertex program.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct VertexInput<'a> {
+    /// Equivalent to `@builtin(vertex_index)`.
+    pub vertex_index: u32,
+    /// Equivalent to `@builtin(instance_index)`.
+    pub instance_index: u32,
+    /// The fields of every bound vertex buffer for this vertex, in binding slot order.
+    ///
+    /// Within one buffer, fields appear in [`VertexLayout`](crate::images::vertex_layout::VertexLayout) order.
+    /// Buffers bound with [`StepMode::Instance`](crate::images::vertex_layout::StepMode::Instance)
+    /// contribute the fields of this instance instead.
+    pub attributes: &'a [f32],
+    /// Resources bound to the pass.
+    pub resources: &'a Resources,
+}
+
+/// The result of a software vertex program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VertexOutput {
+    /// Clip-space position, equivalent to `@builtin(position)`.
+    pub position: [f32; 4],
+    /// Values interpolated across the primitive and passed to the fragment program.
+    pub varyings: Vec<f32>,
+}
+
+impl VertexOutput {
+    /// Creates an output with the given clip-space position and no varyings.
+    pub fn new(position: [f32; 4]) -> Self {
+        Self {
+            position,
+            varyings: Vec::new(),
+        }
+    }
+
+    /// Creates an output with the given clip-space position and varyings.
+    pub fn with_varyings(position: [f32; 4], varyings: Vec<f32>) -> Self {
+        Self { position, varyings }
+    }
+}
+
+/// Inputs to a software fragment program.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct FragmentInput<'a> {
+    /// Equivalent to `@builtin(position)`: the pixel centre in framebuffer coordinates,
+    /// the fragment depth, and the reciprocal of clip-space w.
+    pub position: [f32; 4],
+    /// Equivalent to `@builtin(front_facing)`.
+    pub front_facing: bool,
+    /// The interpolated varyings written by the vertex program.
+    pub varyings: &'a [f32],
+    /// Resources bound to the pass.
+    pub resources: &'a Resources,
+}
+
+/// Inputs to a software compute program.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ComputeInput<'a> {
+    /// Equivalent to `@builtin(workgroup_id)`.
+    pub workgroup_id: [u32; 3],
+    /// Equivalent to `@builtin(num_workgroups)`.
+    pub num_workgroups: [u32; 3],
+    /// Resources bound to the pass.
+    pub resources: &'a Resources,
+}
+
+/// Resources bound to a pass, as seen by software programs.
+///
+/// Bindings are addressed by the slot passed to [`BindStyle`](crate::bindings::BindStyle);
+/// a plain `u32` addresses that binding in group 0.  The camera binds a buffer of 16 `f32`s
+/// holding the column-major projection matrix, the frame counter a `u32`, and the time an
+/// `f32`.  When the pass binds the global group, these appear at bindings 0, 1 and 2 of
+/// that group.
+/// Reverse buffers and [`ShaderReadWrite`](crate::bindings::visible_to::GPUBufferUsage::ShaderReadWrite)
+/// buffers can be read like any other buffer, and written with [`Resources::store`].
+/// A pass's [`Immediates`](crate::bindings::forward::dynamic::immediates::Immediates) are read
+/// with [`Resources::immediates`].
+///
+/// # Panics
+///
+/// Accessors panic if the slot is unbound or holds a different kind of resource,
+/// much as the WGSL would fail validation.
+#[derive(Debug, Default)]
+pub struct Resources {
+    pub(crate) bindings: HashMap<BindSlot, Resource>,
+    /// The bytes of the pass's immediate data, if it has any.
+    pub(crate) immediates: Option<Vec<u8>>,
+}
+
+#[derive(Debug)]
+#[allow(dead_code)] //wgpu backend does not use
+pub(crate) enum Resource {
+    Buffer(Arc<Vec<u8>>),
+    /// A buffer that programs write, like `var<storage, read_write>`.
+    Storage(Mutex<Vec<u8>>),
+    Texture(TextureResource),
+    Sampler,
+}
+
+#[derive(Debug)]
+pub(crate) struct TextureResource {
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    pub(crate) bytes_per_pixel: usize,
+    /// Converts one texel's bytes to linear RGBA.
+    pub(crate) decode: fn(&[u8]) -> [f32; 4],
+    pub(crate) data: Arc<Vec<u8>>,
+}
+
+impl TextureResource {
+    fn load(&self, x: u32, y: u32) -> [f32; 4] {
+        let x = x.min(self.width as u32 - 1) as usize;
+        let y = y.min(self.height as u32 - 1) as usize;
+        let offset = (y * self.width as usize + x) * self.bytes_per_pixel;
+        (self.decode)(&self.data[offset..offset + self.bytes_per_pixel])
+    }
+}
+
+impl Resources {
+    fn with_buffer<R>(&self, binding: impl Into<BindSlot>, f: impl FnOnce(&[u8]) -> R) -> R {
+        let slot = binding.into();
+        match self.bindings.get(&slot) {
+            Some(Resource::Buffer(bytes)) => f(bytes),
+            Some(Resource::Storage(bytes)) => f(&bytes.lock().unwrap()),
+            other => panic!("{slot} is not a buffer (found {other:?})"),
+        }
+    }
+
+    fn texture(&self, binding: impl Into<BindSlot>) -> &TextureResource {
+        let slot = binding.into();
+        match self.bindings.get(&slot) {
+            Some(Resource::Texture(texture)) => texture,
+            other => panic!("{slot} is not a texture (found {other:?})"),
+        }
+    }
+
+    /// Returns whether the binding slot holds a sampler.
+    pub fn is_sampler(&self, binding: impl Into<BindSlot>) -> bool {
+        let slot = binding.into();
+        matches!(self.bindings.get(&slot), Some(Resource::Sampler))
+    }
+
+    /// Reads the element at `index` of the buffer bound at `binding`.
+    pub fn element<T: CRepr>(&self, binding: impl Into<BindSlot>, index: usize) -> T {
+        let slot = binding.into();
+        self.with_buffer(slot, |bytes| {
+            let size = std::mem::size_of::<T>();
+            let offset = index * size;
+            assert!(
+                offset + size <= bytes.len(),
+                "element {index} is out of bounds for {slot}"
+            );
+            //safety: CRepr types are plain data and the range was checked above
+            unsafe { std::ptr::read_unaligned(bytes.as_ptr().add(offset) as *const T) }
+        })
+    }
+
+    /// Returns the number of whole elements of type `T` in the buffer bound at `binding`.
+    pub fn element_count<T: CRepr>(&self, binding: impl Into<BindSlot>) -> usize {
+        self.with_buffer(binding, |bytes| bytes.len() / std::mem::size_of::<T>())
+    }
+
+    /// Writes the element at `index` of the writable buffer bound at `binding`.
+    ///
+    /// This is the equivalent of assigning to a `var<storage, read_write>` in WGSL.
+    /// Fragments and workgroups run one at a time, so there are no data races to worry about.
+    pub fn store<T: CRepr>(&self, binding: impl Into<BindSlot>, index: usize, value: T) {
+        let slot = binding.into();
+        let mut bytes = match self.bindings.get(&slot) {
+            Some(Resource::Storage(bytes)) => bytes.lock().unwrap(),
+            other => panic!("{slot} is not a writable buffer (found {other:?})"),
+        };
+        let size = std::mem::size_of::<T>();
+        let offset = index * size;
+        assert!(
+            offset + size <= bytes.len(),
+            "element {index} is out of bounds for {slot}"
+        );
+        //safety: CRepr types are plain data and the range was checked above
+        unsafe { std::ptr::write_unaligned(bytes.as_mut_ptr().add(offset) as *mut T, value) }
+    }
+
+    /// Reads the pass's immediate data, like a `var<immediate>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pass has no immediate data, or it is smaller than `T`.
+    pub fn immediates<T: CRepr>(&self) -> T {
+        let bytes = self
+            .immediates
+            .as_ref()
+            .expect("the pass has no immediate data");
+        assert!(
+            std::mem::size_of::<T>() <= bytes.len(),
+            "the immediate data is smaller than {}",
+            std::any::type_name::<T>()
+        );
+        //safety: CRepr types are plain data and the size was checked above
+        unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) }
+    }
+
+    /// Returns the size of the texture bound at `binding`, like `textureDimensions`.
+    pub fn texture_dimensions(&self, binding: impl Into<BindSlot>) -> (u32, u32) {
+        let texture = self.texture(binding);
+        (texture.width as u32, texture.height as u32)
+    }
+
+    /// Reads one texel of the texture bound at `binding`, like `textureLoad`.
+    ///
+    /// Coordinates outside the texture are clamped to the edge.
+    pub fn texture_load(&self, binding: impl Into<BindSlot>, x: u32, y: u32) -> [f32; 4] {
+        self.texture(binding).load(x, y)
+    }
+
+    /// Samples the texture bound at `binding` with bilinear filtering, like `textureSample`.
+    ///
+    /// `u` and `v` are normalized coordinates; sampling clamps to the edge.
+    pub fn texture_sample(&self, binding: impl Into<BindSlot>, u: f32, v: f32) -> [f32; 4] {
+        let texture = self.texture(binding);
+        let x = (u * texture.width as f32 - 0.5).max(0.0);
+        let y = (v * texture.height as f32 - 0.5).max(0.0);
+        let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+        let (fx, fy) = (x.fract(), y.fract());
+        let c00 = texture.load(x0, y0);
+        let c10 = texture.load(x0 + 1, y0);
+        let c01 = texture.load(x0, y0 + 1);
+        let c11 = texture.load(x0 + 1, y0 + 1);
+        std::array::from_fn(|c| {
+            let top = c00[c] * (1.0 - fx) + c10[c] * fx;
+            let bottom = c01[c] * (1.0 - fx) + c11[c] * fx;
+            top * (1.0 - fy) + bottom * fy
+        })
+    }
+}
+
+type VertexFn = dyn Fn(&VertexInput) -> VertexOutput + Send + Sync;
+type FragmentFn = dyn Fn(&FragmentInput) -> Option<[f32; 4]> + Send + Sync;
+type ComputeFn = dyn Fn(&ComputeInput) + Send + Sync;
+
+/// A vertex program attached to a [`VertexShader`](super::VertexShader).
+#[derive(Clone)]
+pub(crate) struct VertexProgram(Arc<VertexFn>);
+
+/// A fragment program attached to a [`FragmentShader`](super::FragmentShader).
+#[derive(Clone)]
+pub(crate) struct FragmentProgram(Arc<FragmentFn>);
+
+/// A compute program attached to a [`ComputeShader`](super::ComputeShader).
+#[derive(Clone)]
+pub(crate) struct ComputeProgram(Arc<ComputeFn>);
+
+impl VertexProgram {
+    pub(crate) fn new(
+        program: impl Fn(&VertexInput) -> VertexOutput + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(program))
+    }
+    #[allow(dead_code)] //wgpu backend does not use
+    pub(crate) fn run(&self, input: &VertexInput) -> VertexOutput {
+        (self.0)(input)
+    }
+}
+
+impl FragmentProgram {
+    pub(crate) fn new(
+        program: impl Fn(&FragmentInput) -> Option<[f32; 4]> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(program))
+    }
+    #[allow(dead_code)] //wgpu backend does not use
+    pub(crate) fn run(&self, input: &FragmentInput) -> Option<[f32; 4]> {
+        (self.0)(input)
+    }
+}
+
+impl ComputeProgram {
+    pub(crate) fn new(program: impl Fn(&ComputeInput) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(program))
+    }
+    #[allow(dead_code)] //wgpu backend does not use
+    pub(crate) fn run(&self, input: &ComputeInput) {
+        (self.0)(input)
+    }
+}
+
+// Boilerplate
+//
+// Programs are compared by identity, so that descriptors sharing a closure compare equal.
+
+impl Debug for VertexProgram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("VertexProgram")
+            .field(&Arc::as_ptr(&self.0))
+            .finish()
+    }
+}
+impl PartialEq for VertexProgram {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for VertexProgram {}
+impl Hash for VertexProgram {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.0) as *const ()).hash(state);
+    }
+}
+
+impl Debug for FragmentProgram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FragmentProgram")
+            .field(&Arc::as_ptr(&self.0))
+            .finish()
+    }
+}
+impl PartialEq for FragmentProgram {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for FragmentProgram {}
+impl Hash for FragmentProgram {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.0) as *const ()).hash(state);
+    }
+}
+
+impl Debug for ComputeProgram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ComputeProgram")
+            .field(&Arc::as_ptr(&self.0))
+            .finish()
+    }
+}
+impl PartialEq for ComputeProgram {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for ComputeProgram {}
+impl Hash for ComputeProgram {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.0) as *const ()).hash(state);
+    }
+}
@@ -0,0 +1,992 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Checks a pass's shaders against the resources it binds.
+//!
+//! [`PassDescriptor::validate`] parses the WGSL of a pass with naga, validates it, and
+//! compares what its entry points use with the pass's [`BindStyle`](crate::bindings::BindStyle),
+//! vertex layouts, immediate data and constants.  This runs on the CPU, so mistakes are reported with
+//! the shader and binding they concern, before a backend compiles the pass.
+//!
+//! Only the resources an entry point uses are checked; a pass may bind more than its
+//! shaders use.
+//!
+//! # Example
+//!
+//! ```
+//! use images_and_words::bindings::BindStyle;
+//! use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+//! use images_and_words::images::shader::{FragmentShader, VertexShader};
+//! use images_and_words::images::shader::validation::ShaderError;
+//!
+//! let vertex_shader = VertexShader::new("vs",
+//!     "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }".to_string());
+//! let fragment_shader = FragmentShader::new("fs",
+//!     "@group(0) @binding(0) var<uniform> tint: vec4<f32>;
+//!      @fragment fn main() -> @location(0) vec4<f32> { return tint; }".to_string());
+//! let pass = PassDescriptor::new("pass".to_string(), vertex_shader, fragment_shader,
+//!     BindStyle::new(), DrawCommand::TriangleList(1), false, false);
+//!
+//! // The pass forgot to bind the tint
+//! assert!(matches!(pass.validate(), Err(ShaderError::Unbound { .. })));
+//! ```
+
+use crate::bindings::BindStyle;
+use crate::bindings::bind_style::{BindInfo, BindSlot, BindTarget, Stage};
+use crate::bindings::visible_to::GPUBufferUsage;
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::images::render_pass::PassDescriptor;
+use crate::images::shader::compose::SourceMap;
+use crate::images::shader::reflection::BindingKind;
+use crate::images::shader::{ComputeShader, FragmentShader, VertexShader};
+use crate::images::vertex_layout::VertexFieldType;
+use naga::common::wgsl::TypeContext;
+use std::collections::HashMap;
+
+/// The sizes of the global group's camera, frame counter and time, by binding.
+const GLOBAL_SIZES: [usize; 3] = [64, 4, 4];
+
+/// A mistake found by checking a pass's shaders.
+///
+/// Each variant names the shader by its label.  The messages are meant to be read, and
+/// for WGSL that doesn't compile, include the source lines the error points at.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ShaderError {
+    /// The shader is not valid WGSL.
+    #[error("{shader} is not valid WGSL:\n{message}")]
+    Invalid {
+        shader: &'static str,
+        message: String,
+    },
+    /// The shader does not name an entry point, and does not have exactly one for its stage.
+    #[error("{shader} has {count} @{stage} entry points, but needs exactly one")]
+    EntryPoint {
+        shader: &'static str,
+        stage: Stage,
+        count: usize,
+    },
+    /// The shader names an entry point that its WGSL does not have for its stage.
+    #[error("{shader} has no @{stage} entry point named `{name}`")]
+    MissingEntryPoint {
+        shader: &'static str,
+        stage: Stage,
+        name: String,
+    },
+    /// The shader uses a binding that the pass does not bind.
+    #[error("{shader} uses `{variable}` at {slot}, but nothing is bound there")]
+    Unbound {
+        shader: &'static str,
+        variable: String,
+        slot: BindSlot,
+    },
+    /// The shader declares a binding as a different kind of resource than the one bound.
+    #[error("{shader} declares `{variable}` at {slot} as {declared}, but {bound} is bound there")]
+    Mismatch {
+        shader: &'static str,
+        variable: String,
+        slot: BindSlot,
+        declared: String,
+        bound: String,
+    },
+    /// The shader uses a binding that is bound for another stage.
+    #[error("{shader} uses `{variable}` at {slot}, which is bound for the {bound} stage")]
+    Stage {
+        shader: &'static str,
+        variable: String,
+        slot: BindSlot,
+        bound: Stage,
+    },
+    /// The shader reads a buffer in elements of another size than the buffer's elements.
+    ///
+    /// A shader may read a buffer as one element, as the whole buffer, or as an array of
+    /// elements.
+    #[error(
+        "{shader} reads `{variable}` at {slot} in {declared_size}-byte elements, but the buffer bound there has {element_size}-byte elements"
+    )]
+    Size {
+        shader: &'static str,
+        variable: String,
+        slot: BindSlot,
+        declared_size: usize,
+        element_size: usize,
+    },
+    /// The vertex shader reads an attribute that no vertex buffer provides.
+    #[error("{shader} reads @location({location}), but no vertex buffer provides it")]
+    MissingAttribute { shader: &'static str, location: u32 },
+    /// The vertex shader reads an attribute as another type than its vertex layout.
+    #[error(
+        "{shader} reads @location({location}) as {declared}, but the vertex layout provides {provided:?}"
+    )]
+    AttributeType {
+        shader: &'static str,
+        location: u32,
+        declared: String,
+        provided: VertexFieldType,
+    },
+    /// A vertex layout does not span whole elements of its buffer.
+    #[error(
+        "The vertex layout at {slot} is {layout_size} bytes, which is not a whole number of the {element_size}-byte elements of the buffer bound there"
+    )]
+    Stride {
+        slot: BindSlot,
+        layout_size: usize,
+        element_size: usize,
+    },
+    /// The shader's immediate data is another size than the pass's.
+    #[error(
+        "{shader} declares `{variable}` as {declared_size} bytes of immediate data, but the pass has {provided}"
+    )]
+    Immediates {
+        shader: &'static str,
+        variable: String,
+        declared_size: usize,
+        provided: usize,
+    },
+    /// A [`BindingPlan`](super::reflection::BindingPlan) was asked to bind a name that no
+    /// shader of the pass uses.
+    #[error("No shader of the pass uses a binding named `{name}`")]
+    UnknownBinding { name: String },
+    /// Both shaders of a pass use a binding, which is bound for only one stage.
+    #[error("Both shaders use `{variable}`, but a binding is bound for only one stage")]
+    SharedBinding { variable: String },
+    /// The vertex shader skips a location, which vertex layouts can't express.
+    #[error(
+        "{shader} reads a later location but not @location({location}); vertex layouts provide consecutive locations"
+    )]
+    LocationGap { shader: &'static str, location: u32 },
+    /// The vertex shader reads an attribute as a type that no vertex field provides.
+    #[error("{shader} reads @location({location}) as {declared}, which no vertex field provides")]
+    UnsupportedAttribute {
+        shader: &'static str,
+        location: u32,
+        declared: String,
+    },
+    /// The pass sets a constant that no shader of the pass declares as an `override`.
+    ///
+    /// Overrides declared with `@id` are set by their number.
+    #[error("The pass sets the constant `{name}`, but no shader declares an override by that key")]
+    UnknownConstant { name: String },
+    /// The entry point uses an `override` without a default, which the pass does not set.
+    #[error(
+        "{shader} uses the override `{name}`, which has no default, but the pass does not set it"
+    )]
+    MissingConstant { shader: &'static str, name: String },
+    /// The device could not build the pass's pipeline, though its shaders passed these
+    /// checks, for example because a fragment output doesn't suit the target's format.
+    #[error("The device could not build a pipeline for pass {pass}:\n{message}")]
+    Pipeline { pass: String, message: String },
+}
+
+impl ShaderError {
+    /// Whether the mistake is in the shader's own WGSL, such as its syntax, entry points or
+    /// overrides, rather than in how it matches the pass's bindings.
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) fn is_in_shader(&self) -> bool {
+        match self {
+            ShaderError::Invalid { .. }
+            | ShaderError::EntryPoint { .. }
+            | ShaderError::MissingEntryPoint { .. }
+            | ShaderError::UnknownConstant { .. }
+            | ShaderError::MissingConstant { .. }
+            | ShaderError::Pipeline { .. }
+            | ShaderError::LocationGap { .. }
+            | ShaderError::UnsupportedAttribute { .. } => true,
+            ShaderError::Unbound { .. }
+            | ShaderError::Mismatch { .. }
+            | ShaderError::Stage { .. }
+            | ShaderError::Size { .. }
+            | ShaderError::MissingAttribute { .. }
+            | ShaderError::AttributeType { .. }
+            | ShaderError::Stride { .. }
+            | ShaderError::Immediates { .. }
+            | ShaderError::UnknownBinding { .. }
+            | ShaderError::SharedBinding { .. } => false,
+        }
+    }
+}
+
+/// What a shader may declare for a bound resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Expected {
+    /// A `var<uniform>`.
+    Uniform,
+    /// A `var<uniform>` or `var<storage, read>`, as backends choose by the buffer's size.
+    UniformOrStorage,
+    /// A `var<storage>`, which may be `read_write` if `writable`.
+    Storage { writable: bool },
+    /// A `texture_2d<f32>`.
+    Texture,
+    /// A `texture_depth_2d`.
+    DepthTexture,
+    /// A filtering `sampler`.
+    Sampler,
+}
+
+impl Expected {
+    fn for_buffer(usage: GPUBufferUsage, stage: Stage) -> Self {
+        match usage {
+            GPUBufferUsage::VertexShaderRead
+            | GPUBufferUsage::FragmentShaderRead
+            | GPUBufferUsage::ComputeShaderRead => Expected::UniformOrStorage,
+            //vertex shaders cannot write storage buffers
+            GPUBufferUsage::ShaderReadWrite | GPUBufferUsage::Indirect => Expected::Storage {
+                writable: stage != Stage::Vertex,
+            },
+            //meant for vertex and index buffers, which shaders can only read
+            GPUBufferUsage::VertexBuffer | GPUBufferUsage::Index => {
+                Expected::Storage { writable: false }
+            }
+        }
+    }
+
+    pub(super) fn for_target(info: &BindInfo) -> Self {
+        if let Some(buffer) = &info.buffer {
+            return Self::for_buffer(buffer.usage, info.stage);
+        }
+        match info.target {
+            BindTarget::Camera | BindTarget::FrameCounter | BindTarget::Time => Expected::Uniform,
+            BindTarget::StaticTexture(..)
+            | BindTarget::DynamicTexture(_)
+            | BindTarget::RenderTexture(..) => Expected::Texture,
+            BindTarget::RenderTextureDepth(_) => Expected::DepthTexture,
+            BindTarget::Sampler(_) => Expected::Sampler,
+            //buffers carry their usage
+            BindTarget::StaticBuffer(_)
+            | BindTarget::DynamicBuffer(_)
+            | BindTarget::ReverseBuffer(_)
+            | BindTarget::VB(..)
+            | BindTarget::DynamicVB(..) => unreachable!("buffers are bound with their usage"),
+        }
+    }
+
+    /// Returns whether a binding declared as `kind` can read or write what is bound.
+    pub(super) fn accepts(self, kind: BindingKind) -> bool {
+        match (self, kind) {
+            (Expected::Uniform | Expected::UniformOrStorage, BindingKind::Uniform) => true,
+            (Expected::UniformOrStorage, BindingKind::Storage { writable }) => !writable,
+            (Expected::Storage { writable }, BindingKind::Storage { writable: writes }) => {
+                writable || !writes
+            }
+            (Expected::Texture, BindingKind::Texture)
+            | (Expected::DepthTexture, BindingKind::DepthTexture)
+            | (Expected::Sampler, BindingKind::Sampler) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expected::Uniform => write!(f, "a uniform buffer"),
+            Expected::UniformOrStorage => write!(f, "a uniform or read-only storage buffer"),
+            Expected::Storage { writable: true } => write!(f, "a read-write storage buffer"),
+            Expected::Storage { writable: false } => write!(f, "a read-only storage buffer"),
+            Expected::Texture => write!(f, "a texture_2d<f32>"),
+            Expected::DepthTexture => write!(f, "a texture_depth_2d"),
+            Expected::Sampler => write!(f, "a sampler"),
+        }
+    }
+}
+
+/// A shader parsed and validated by naga.
+pub(super) struct Parsed {
+    pub(super) label: &'static str,
+    pub(super) module: naga::Module,
+    info: naga::valid::ModuleInfo,
+    layouter: naga::proc::Layouter,
+    /// The index of the entry point in `module.entry_points`.
+    entry_point: usize,
+    pub(super) stage: Stage,
+}
+
+impl Parsed {
+    pub(super) fn vertex(shader: &VertexShader) -> Result<Self, ShaderError> {
+        Self::new(
+            shader.label,
+            &shader.wgsl_code,
+            shader.source_map.as_ref(),
+            Stage::Vertex,
+            shader.entry_point.as_deref(),
+        )
+    }
+
+    pub(super) fn fragment(shader: &FragmentShader) -> Result<Self, ShaderError> {
+        Self::new(
+            shader.label,
+            &shader.wgsl_code,
+            shader.source_map.as_ref(),
+            Stage::Fragment,
+            shader.entry_point.as_deref(),
+        )
+    }
+
+    pub(super) fn compute(shader: &ComputeShader) -> Result<Self, ShaderError> {
+        Self::new(
+            shader.label,
+            &shader.wgsl_code,
+            None,
+            Stage::Compute,
+            shader.entry_point.as_deref(),
+        )
+    }
+
+    fn new(
+        label: &'static str,
+        wgsl: &str,
+        source_map: Option<&SourceMap>,
+        stage: Stage,
+        entry_point: Option<&str>,
+    ) -> Result<Self, ShaderError> {
+        //composed shaders are reported where the failing line was written
+        let invalid = |message: String, location: Option<naga::SourceLocation>| {
+            let written = location.and_then(|location| {
+                let (file, line) = source_map?.locate(location.line_number)?;
+                Some((file, line, location.line_position))
+            });
+            ShaderError::Invalid {
+                shader: label,
+                message: match written {
+                    Some((file, line, column)) => format!("{file}:{line}:{column}\n{message}"),
+                    None => message,
+                },
+            }
+        };
+        let module = naga::front::wgsl::parse_str(wgsl)
+            .map_err(|e| invalid(e.emit_to_string_with_path(wgsl, label), e.location(wgsl)))?;
+        //devices that lack a capability report it when the pass is prepared
+        let info = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        )
+        .validate(&module)
+        .map_err(|e| invalid(e.emit_to_string_with_path(wgsl, label), e.location(wgsl)))?;
+        let naga_stage = match stage {
+            Stage::Vertex => naga::ShaderStage::Vertex,
+            Stage::Fragment => naga::ShaderStage::Fragment,
+            Stage::Compute => naga::ShaderStage::Compute,
+        };
+        let entry_points: Vec<usize> = (0..module.entry_points.len())
+            .filter(|&i| module.entry_points[i].stage == naga_stage)
+            .collect();
+        let entry_point = match entry_point {
+            Some(name) => entry_points
+                .into_iter()
+                .find(|&i| module.entry_points[i].name == name)
+                .ok_or_else(|| ShaderError::MissingEntryPoint {
+                    shader: label,
+                    stage,
+                    name: name.to_string(),
+                })?,
+            None => match entry_points[..] {
+                [entry_point] => entry_point,
+                _ => {
+                    return Err(ShaderError::EntryPoint {
+                        shader: label,
+                        stage,
+                        count: entry_points.len(),
+                    });
+                }
+            },
+        };
+        let mut layouter = naga::proc::Layouter::default();
+        layouter
+            .update(module.to_ctx())
+            .expect("validated modules have layouts");
+        Ok(Parsed {
+            label,
+            module,
+            info,
+            layouter,
+            entry_point,
+            stage,
+        })
+    }
+
+    /// Returns the global variables the entry point uses.
+    pub(super) fn used_globals(&self) -> impl Iterator<Item = &naga::GlobalVariable> {
+        let function = self.info.get_entry_point(self.entry_point);
+        self.module
+            .global_variables
+            .iter()
+            .filter(move |(handle, _)| !function[*handle].is_empty())
+            .map(|(_, variable)| variable)
+    }
+
+    /// Returns the keys the module's overrides are set by: their `@id`, or their name.
+    fn override_keys(module: &naga::Module) -> impl Iterator<Item = String> {
+        module
+            .overrides
+            .iter()
+            .map(|(_, r#override)| match r#override.id {
+                Some(id) => id.to_string(),
+                None => r#override.name.clone().unwrap_or_default(),
+            })
+    }
+
+    /// Returns the keys of the overrides without a default that the entry point uses.
+    fn required_overrides(&self) -> Vec<String> {
+        if self.module.overrides.is_empty() {
+            return Vec::new();
+        }
+        //compacting the module to the entry point drops the overrides it doesn't use
+        let mut module = self.module.clone();
+        let entry_point = module.entry_points.swap_remove(self.entry_point);
+        module.entry_points = vec![entry_point];
+        naga::compact::compact(&mut module, naga::compact::KeepUnused::No);
+        let required: Vec<bool> = module
+            .overrides
+            .iter()
+            .map(|(_, r#override)| r#override.init.is_none())
+            .collect();
+        Self::override_keys(&module)
+            .zip(required)
+            .filter_map(|(key, required)| required.then_some(key))
+            .collect()
+    }
+
+    pub(super) fn name(&self, variable: &naga::GlobalVariable) -> String {
+        variable.name.clone().unwrap_or_default()
+    }
+
+    /// Describes how `variable` is declared, as it would be written in WGSL.
+    pub(super) fn declaration(&self, variable: &naga::GlobalVariable) -> String {
+        let ty = self.module.to_ctx().type_to_string(variable.ty);
+        match variable.space {
+            naga::AddressSpace::Uniform => format!("var<uniform> {ty}"),
+            naga::AddressSpace::Storage { access }
+                if access.contains(naga::StorageAccess::STORE) =>
+            {
+                format!("var<storage, read_write> {ty}")
+            }
+            naga::AddressSpace::Storage { .. } => format!("var<storage, read> {ty}"),
+            _ => ty,
+        }
+    }
+
+    /// Returns the entry point's `@location` inputs as their location, name and type.
+    ///
+    /// Inputs may be arguments, or members of a struct argument.
+    pub(super) fn vertex_inputs(&self) -> Vec<(u32, String, naga::Handle<naga::Type>)> {
+        let function = &self.module.entry_points[self.entry_point].function;
+        let mut inputs = Vec::new();
+        for argument in &function.arguments {
+            match (&argument.binding, &self.module.types[argument.ty].inner) {
+                (Some(naga::Binding::Location { location, .. }), _) => {
+                    inputs.push((
+                        *location,
+                        argument.name.clone().unwrap_or_default(),
+                        argument.ty,
+                    ));
+                }
+                (None, naga::TypeInner::Struct { members, .. }) => {
+                    for member in members {
+                        if let Some(naga::Binding::Location { location, .. }) = member.binding {
+                            inputs.push((
+                                location,
+                                member.name.clone().unwrap_or_default(),
+                                member.ty,
+                            ));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        inputs
+    }
+
+    /// Returns the size of the elements `variable` reads a buffer in.
+    ///
+    /// Arrays, and structs ending in a runtime-sized array, are read in elements of the
+    /// array's stride.  Anything else is read as one element of its size.
+    fn element_size(&self, variable: &naga::GlobalVariable) -> (usize, bool) {
+        use naga::TypeInner;
+        let stride = |ty: naga::Handle<naga::Type>| match self.module.types[ty].inner {
+            TypeInner::Array { stride, .. } => Some(stride as usize),
+            _ => None,
+        };
+        if let Some(stride) = stride(variable.ty) {
+            return (stride, true);
+        }
+        if let TypeInner::Struct { members, .. } = &self.module.types[variable.ty].inner
+            && let Some(last) = members.last()
+            && let TypeInner::Array {
+                size: naga::ArraySize::Dynamic,
+                stride,
+                ..
+            } = self.module.types[last.ty].inner
+        {
+            return (stride as usize, true);
+        }
+        (self.layouter[variable.ty].size as usize, false)
+    }
+}
+
+/// Checks `descriptor`'s shaders against its bindings, vertex layouts, immediate data and
+/// constants.
+pub(crate) fn validate_pass(descriptor: &PassDescriptor) -> Result<(), ShaderError> {
+    let vertex = Parsed::vertex(&descriptor.vertex_shader)?;
+    let fragment = Parsed::fragment(&descriptor.fragment_shader)?;
+    let immediates = descriptor
+        .immediates
+        .as_ref()
+        .map_or(0, |immediates| immediates.size() as usize);
+    for shader in [&vertex, &fragment] {
+        check_bindings(shader, descriptor.bind_style())?;
+        check_immediates(shader, immediates)?;
+    }
+    check_constants(&[&vertex, &fragment], &descriptor.constants)?;
+    check_vertex_inputs(&vertex, descriptor)
+}
+
+/// Checks `descriptor`'s compute shader against its bindings and constants.
+pub(crate) fn validate_compute_pass(descriptor: &ComputePassDescriptor) -> Result<(), ShaderError> {
+    let shader = Parsed::compute(&descriptor.shader)?;
+    check_bindings(&shader, descriptor.bind_style())?;
+    //compute passes have no immediate data
+    check_immediates(&shader, 0)?;
+    check_constants(&[&shader], &descriptor.shader.constants)
+}
+
+fn check_bindings(shader: &Parsed, bind_style: &BindStyle) -> Result<(), ShaderError> {
+    for variable in shader.used_globals() {
+        let Some(binding) = &variable.binding else {
+            continue;
+        };
+        let slot = BindSlot::in_group(binding.group, binding.binding);
+        let (expected, stage, sizes) = if bind_style.global_group == Some(binding.group) {
+            match GLOBAL_SIZES.get(binding.binding as usize) {
+                Some(&size) => (Expected::Uniform, shader.stage, Some((size, size))),
+                None => return Err(unbound(shader, variable, slot)),
+            }
+        } else {
+            let Some(info) = bind_style.binds.get(&slot) else {
+                return Err(unbound(shader, variable, slot));
+            };
+            let sizes = match (&info.buffer, &info.target) {
+                (Some(buffer), _) => Some((buffer.element_size, buffer.byte_size)),
+                (None, BindTarget::Camera) => Some((64, 64)),
+                (None, BindTarget::FrameCounter | BindTarget::Time) => Some((4, 4)),
+                (None, _) => None,
+            };
+            (Expected::for_target(info), info.stage, sizes)
+        };
+        if !expected.accepts(BindingKind::of(&shader.module, variable)) {
+            return Err(ShaderError::Mismatch {
+                shader: shader.label,
+                variable: shader.name(variable),
+                slot,
+                declared: shader.declaration(variable),
+                bound: expected.to_string(),
+            });
+        }
+        if stage != shader.stage {
+            return Err(ShaderError::Stage {
+                shader: shader.label,
+                variable: shader.name(variable),
+                slot,
+                bound: stage,
+            });
+        }
+        if let Some((element_size, byte_size)) = sizes {
+            let (declared_size, is_array) = shader.element_size(variable);
+            if declared_size != element_size && (is_array || declared_size != byte_size) {
+                return Err(ShaderError::Size {
+                    shader: shader.label,
+                    variable: shader.name(variable),
+                    slot,
+                    declared_size,
+                    element_size,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn unbound(shader: &Parsed, variable: &naga::GlobalVariable, slot: BindSlot) -> ShaderError {
+    ShaderError::Unbound {
+        shader: shader.label,
+        variable: shader.name(variable),
+        slot,
+    }
+}
+
+/// Checks that the shader's immediate data, if it uses any, is `provided` bytes.
+fn check_immediates(shader: &Parsed, provided: usize) -> Result<(), ShaderError> {
+    for variable in shader.used_globals() {
+        if variable.space != naga::AddressSpace::Immediate {
+            continue;
+        }
+        let declared_size = shader.layouter[variable.ty].size as usize;
+        if declared_size != provided {
+            return Err(ShaderError::Immediates {
+                shader: shader.label,
+                variable: shader.name(variable),
+                declared_size,
+                provided,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_constants(shaders: &[&Parsed], constants: &[(String, f64)]) -> Result<(), ShaderError> {
+    let is_set = |key: &str| constants.iter().any(|(name, _)| name == key);
+    //every shader gets every constant, so one need only be declared by any
+    for (name, _) in constants {
+        if !shaders
+            .iter()
+            .flat_map(|shader| Parsed::override_keys(&shader.module))
+            .any(|key| key == *name)
+        {
+            return Err(ShaderError::UnknownConstant { name: name.clone() });
+        }
+    }
+    for shader in shaders {
+        if let Some(name) = shader
+            .required_overrides()
+            .into_iter()
+            .find(|key| !is_set(key))
+        {
+            return Err(ShaderError::MissingConstant {
+                shader: shader.label,
+                name,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_vertex_inputs(vertex: &Parsed, descriptor: &PassDescriptor) -> Result<(), ShaderError> {
+    let bind_style = descriptor.bind_style();
+    //fields take consecutive locations across the vertex buffers, in slot order
+    let mut provided = HashMap::new();
+    for slot in bind_style.vertex_buffer_slots() {
+        let info = &bind_style.binds[&slot];
+        let layout = match &info.target {
+            BindTarget::VB(layout, ..) | BindTarget::DynamicVB(layout, ..) => layout,
+            _ => unreachable!("vertex_buffer_slots only returns vertex buffers"),
+        };
+        //a vertex may span several elements, as when vertices are flat arrays of floats
+        if let Some(buffer) = &info.buffer
+            && layout.element_stride() % buffer.element_size != 0
+        {
+            return Err(ShaderError::Stride {
+                slot,
+                layout_size: layout.element_stride(),
+                element_size: buffer.element_size,
+            });
+        }
+        for field in &layout.fields {
+            provided.insert(provided.len() as u32, field.r#type);
+        }
+    }
+
+    for (location, _, ty) in vertex.vertex_inputs() {
+        let Some(&field_type) = provided.get(&location) else {
+            return Err(ShaderError::MissingAttribute {
+                shader: vertex.label,
+                location,
+            });
+        };
+        //vertex formats may have more or fewer components than the shader reads
+        let scalar = match vertex.module.types[ty].inner {
+            naga::TypeInner::Scalar(scalar) | naga::TypeInner::Vector { scalar, .. } => {
+                Some(scalar)
+            }
+            _ => None,
+        };
+        let matches = match field_type {
+            VertexFieldType::F32
+            | VertexFieldType::F32x2
+            | VertexFieldType::F32x3
+            | VertexFieldType::F32x4 => scalar == Some(naga::Scalar::F32),
+        };
+        if !matches {
+            return Err(ShaderError::AttributeType {
+                shader: vertex.label,
+                location,
+                declared: vertex.module.to_ctx().type_to_string(ty),
+                provided: field_type,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShaderError;
+    use crate::bindings::BindStyle;
+    use crate::bindings::bind_style::{BindSlot, Stage};
+    use crate::bindings::forward::dynamic::immediates::Immediates;
+    use crate::images::render_pass::{DrawCommand, PassDescriptor};
+    use crate::images::shader::compose::Composer;
+    use crate::images::shader::{FragmentShader, ShaderModule, VertexShader};
+
+    const VERTEX: &str = "@vertex fn main() -> @builtin(position) vec4<f32> { return vec4(0.0); }";
+    const FRAGMENT: &str = "@fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }";
+
+    fn pass(vertex: &str, fragment: &str, bind_style: BindStyle) -> PassDescriptor {
+        PassDescriptor::new(
+            "pass".to_string(),
+            VertexShader::new("vs", vertex.to_string()),
+            FragmentShader::new("fs", fragment.to_string()),
+            bind_style,
+            DrawCommand::TriangleList(1),
+            false,
+            false,
+        )
+    }
+
+    fn camera_at(slot: BindSlot, stage: Stage) -> BindStyle {
+        let mut bind_style = BindStyle::new();
+        bind_style.bind_camera_matrix(slot, stage);
+        bind_style
+    }
+
+    #[test]
+    fn matching_pass_is_valid() {
+        let vertex = "@group(1) @binding(0) var<uniform> camera: mat4x4<f32>;
+            @vertex fn main() -> @builtin(position) vec4<f32> { return camera[0]; }";
+        let bind_style = camera_at(BindSlot::in_group(1, 0), Stage::Vertex);
+        assert_eq!(pass(vertex, FRAGMENT, bind_style).validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_wgsl_points_at_the_line() {
+        let error = pass(
+            VERTEX,
+            "@fragment fn main() {\n  return nope;\n}",
+            BindStyle::new(),
+        )
+        .validate()
+        .unwrap_err();
+        let ShaderError::Invalid { shader, message } = &error else {
+            panic!("{error:?}");
+        };
+        assert_eq!(*shader, "fs");
+        assert!(message.contains("nope"), "{message}");
+        assert!(message.contains("fs:2:"), "{message}");
+    }
+
+    #[test]
+    fn needs_one_entry_point() {
+        assert_eq!(
+            pass(FRAGMENT, FRAGMENT, BindStyle::new()).validate(),
+            Err(ShaderError::EntryPoint {
+                shader: "vs",
+                stage: Stage::Vertex,
+                count: 0
+            })
+        );
+    }
+
+    #[test]
+    fn unused_bindings_are_not_checked() {
+        let fragment = "@group(0) @binding(3) var<uniform> tint: vec4<f32>;
+            @fragment fn main() -> @location(0) vec4<f32> { return vec4(1.0); }";
+        //the bind style may bind more than the shaders use, too
+        let bind_style = camera_at(BindSlot::new(0), Stage::Vertex);
+        assert_eq!(pass(VERTEX, fragment, bind_style).validate(), Ok(()));
+    }
+
+    #[test]
+    fn reports_unbound_binding() {
+        let fragment = "@group(2) @binding(3) var<uniform> tint: vec4<f32>;
+            @fragment fn main() -> @location(0) vec4<f32> { return tint; }";
+        let error = pass(VERTEX, fragment, BindStyle::new()).validate();
+        assert_eq!(
+            error,
+            Err(ShaderError::Unbound {
+                shader: "fs",
+                variable: "tint".to_string(),
+                slot: BindSlot::in_group(2, 3)
+            })
+        );
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "fs uses `tint` at group 2 slot 3, but nothing is bound there"
+        );
+    }
+
+    #[test]
+    fn reports_another_kind_of_resource() {
+        let fragment = "@group(0) @binding(0) var image: texture_2d<f32>;
+            @fragment fn main() -> @location(0) vec4<f32> { return textureLoad(image, vec2(0), 0); }";
+        let bind_style = camera_at(BindSlot::new(0), Stage::Fragment);
+        assert_eq!(
+            pass(VERTEX, fragment, bind_style)
+                .validate()
+                .unwrap_err()
+                .to_string(),
+            "fs declares `image` at slot 0 as texture_2d<f32>, but a uniform buffer is bound there"
+        );
+    }
+
+    #[test]
+    fn reports_binding_for_another_stage() {
+        let fragment = "@group(0) @binding(0) var<uniform> camera: mat4x4<f32>;
+            @fragment fn main() -> @location(0) vec4<f32> { return camera[0]; }";
+        let bind_style = camera_at(BindSlot::new(0), Stage::Vertex);
+        assert_eq!(
+            pass(VERTEX, fragment, bind_style).validate(),
+            Err(ShaderError::Stage {
+                shader: "fs",
+                variable: "camera".to_string(),
+                slot: BindSlot::new(0),
+                bound: Stage::Vertex
+            })
+        );
+    }
+
+    #[test]
+    fn reports_wrong_size_of_special_binding() {
+        let vertex = "@group(0) @binding(0) var<uniform> camera: mat3x3<f32>;
+            @vertex fn main() -> @builtin(position) vec4<f32> { return vec4(camera[0], 1.0); }";
+        let bind_style = camera_at(BindSlot::new(0), Stage::Vertex);
+        assert_eq!(
+            pass(vertex, FRAGMENT, bind_style).validate(),
+            Err(ShaderError::Size {
+                shader: "vs",
+                variable: "camera".to_string(),
+                slot: BindSlot::new(0),
+                declared_size: 48,
+                element_size: 64
+            })
+        );
+    }
+
+    #[test]
+    fn checks_the_global_group() {
+        let vertex = "@group(1) @binding(2) var<uniform> time: f32;
+            @group(1) @binding(3) var<uniform> extra: f32;
+            @vertex fn main() -> @builtin(position) vec4<f32> { return vec4(time + extra); }";
+        let mut bind_style = BindStyle::new();
+        bind_style.bind_global_group(1);
+        assert_eq!(
+            pass(vertex, FRAGMENT, bind_style).validate(),
+            Err(ShaderError::Unbound {
+                shader: "vs",
+                variable: "extra".to_string(),
+                slot: BindSlot::in_group(1, 3)
+            })
+        );
+    }
+
+    #[test]
+    fn reports_missing_vertex_attribute() {
+        let vertex = "struct Input { @location(0) position: vec2<f32> }
+            @vertex fn main(input: Input) -> @builtin(position) vec4<f32> {
+                return vec4(input.position, 0.0, 1.0);
+            }";
+        assert_eq!(
+            pass(vertex, FRAGMENT, BindStyle::new()).validate(),
+            Err(ShaderError::MissingAttribute {
+                shader: "vs",
+                location: 0
+            })
+        );
+    }
+
+    #[test]
+    fn checks_immediate_size() {
+        let fragment = "var<immediate> tint: vec4<f32>;
+            @fragment fn main() -> @location(0) vec4<f32> { return tint; }";
+        let missing = pass(VERTEX, fragment, BindStyle::new());
+        assert_eq!(
+            missing.validate(),
+            Err(ShaderError::Immediates {
+                shader: "fs",
+                variable: "tint".to_string(),
+                declared_size: 16,
+                provided: 0
+            })
+        );
+        #[repr(C)]
+        struct Tint([f32; 4]);
+        unsafe impl crate::bindings::forward::dynamic::buffer::CRepr for Tint {}
+        let tint = Immediates::new(Tint([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(missing.with_immediates(&tint).validate(), Ok(()));
+    }
+
+    #[test]
+    fn composed_errors_point_at_their_file() {
+        let composed = Composer::new()
+            .with_snippet("tint", "// the tint\nfn tint() -> vec4<f32> { return vec4(undefined); }")
+            .compose(
+                "main.wgsl",
+                "#include \"tint\"\n@fragment fn main() -> @location(0) vec4<f32> { return tint(); }",
+            )
+            .unwrap();
+        let descriptor = PassDescriptor::new(
+            "pass".to_string(),
+            VertexShader::new("vs", VERTEX.to_string()),
+            FragmentShader::from_composed("fs", composed),
+            BindStyle::new(),
+            DrawCommand::TriangleList(1),
+            false,
+            false,
+        );
+        let Err(ShaderError::Invalid { message, .. }) = descriptor.validate() else {
+            panic!("expected an invalid shader");
+        };
+        assert!(message.starts_with("tint:2:"), "{message}");
+    }
+
+    const VARIANTS: &str = "override shadow_bias: f32;
+        @id(7) override exposure: f32 = 1.0;
+        @vertex fn vs_main() -> @builtin(position) vec4<f32> { return vec4(0.0); }
+        @vertex fn vs_shadow() -> @builtin(position) vec4<f32> { return vec4(shadow_bias); }
+        @fragment fn fs_main() -> @location(0) vec4<f32> { return vec4(1.0); }
+        @fragment fn fs_debug() -> @location(0) vec4<f32> { return vec4(exposure); }";
+
+    fn variant(vertex: &str, fragment: &str) -> PassDescriptor {
+        let module = ShaderModule::new("variants", VARIANTS.to_string());
+        PassDescriptor::new(
+            "pass".to_string(),
+            module.vertex(vertex),
+            module.fragment(fragment),
+            BindStyle::new(),
+            DrawCommand::TriangleList(1),
+            false,
+            false,
+        )
+    }
+
+    #[test]
+    fn finds_entry_points_by_name() {
+        assert_eq!(variant("vs_main", "fs_debug").validate(), Ok(()));
+        //fs_main is a fragment entry point
+        assert_eq!(
+            variant("fs_main", "fs_main").validate(),
+            Err(ShaderError::MissingEntryPoint {
+                shader: "variants",
+                stage: Stage::Vertex,
+                name: "fs_main".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn checks_constants() {
+        //only vs_shadow uses the override without a default
+        assert_eq!(
+            variant("vs_shadow", "fs_main").validate(),
+            Err(ShaderError::MissingConstant {
+                shader: "variants",
+                name: "shadow_bias".to_string()
+            })
+        );
+        let shadow = variant("vs_shadow", "fs_debug").with_constant("shadow_bias", 0.5);
+        assert_eq!(shadow.clone().with_constant("7", 2.0).validate(), Ok(()));
+        //overrides with an id are set by their id
+        assert_eq!(
+            shadow.with_constant("exposure", 2.0).validate(),
+            Err(ShaderError::UnknownConstant {
+                name: "exposure".to_string()
+            })
+        );
+    }
+}
@@ -25,6 +25,8 @@
 //! layout.add_field("color", VertexFieldType::F32); // r, g, b
 //! ```
 
+use std::borrow::Cow;
+
 /// Describes the layout of a vertex buffer.
 ///
 /// This type specifies how vertex data is structured in memory, including what
@@ -59,10 +61,10 @@ pub struct VertexLayout {
     pub(crate) fields: Vec<VertexField>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct VertexField {
     #[allow(dead_code)] //nop implementation does not use
-    pub(crate) name: &'static str,
+    pub(crate) name: Cow<'static, str>,
     #[allow(dead_code)] //nop implementation does not use
     pub(crate) r#type: VertexFieldType,
 }
@@ -70,16 +72,21 @@ pub(crate) struct VertexField {
 /// Specifies the data type of a vertex attribute field.
 ///
 /// This enum defines the possible types for individual fields within a vertex.
-/// Currently only 32-bit floating point values are supported, but this may be
-/// extended in the future to support other common vertex data types.
+/// Currently only 32-bit floating point values and vectors of them are supported, but
+/// this may be extended in the future to support other common vertex data types.
+///
+/// Each field is read at its own `@location`, so a `vec3<f32>` position is one
+/// [`F32x3`](Self::F32x3) field, or three [`F32`](Self::F32) fields read as three `f32`s.
 ///
 /// # Example
 ///
 /// ```
 /// use images_and_words::images::vertex_layout::VertexFieldType;
 ///
-/// // Currently F32 is the only supported type
+/// // A single float, such as a brightness
 /// let field_type = VertexFieldType::F32;
+/// // A vec3<f32>, such as a position
+/// let position_type = VertexFieldType::F32x3;
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -89,6 +96,38 @@ pub enum VertexFieldType {
     /// This is the most common type for vertex attributes like positions,
     /// normals, texture coordinates, and colors.
     F32,
+    /// Two 32-bit floating point values, read as a `vec2<f32>`.
+    F32x2,
+    /// Three 32-bit floating point values, read as a `vec3<f32>`.
+    F32x3,
+    /// Four 32-bit floating point values, read as a `vec4<f32>`.
+    F32x4,
+}
+
+/// Specifies how often the GPU advances to the next element of a vertex buffer.
+///
+/// Most vertex buffers hold one element per vertex.  An instance buffer holds one
+/// element per instance instead, such as a transform for each copy of a mesh drawn with
+/// [`DrawCommand::TriangleListInstanced`](crate::images::render_pass::DrawCommand::TriangleListInstanced).
+///
+/// # Example
+///
+/// ```
+/// use images_and_words::images::vertex_layout::StepMode;
+///
+/// // Positions of the mesh advance per vertex...
+/// let mesh = StepMode::Vertex;
+/// // ...while each copy's offset advances per instance.
+/// let offsets = StepMode::Instance;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum StepMode {
+    /// Advances to the next element for each vertex.
+    #[default]
+    Vertex,
+    /// Advances to the next element for each instance.
+    Instance,
 }
 
 impl VertexFieldType {
@@ -96,6 +135,9 @@ impl VertexFieldType {
     pub(crate) fn stride(&self) -> usize {
         match self {
             VertexFieldType::F32 => 4,
+            VertexFieldType::F32x2 => 8,
+            VertexFieldType::F32x3 => 12,
+            VertexFieldType::F32x4 => 16,
         }
     }
 }
@@ -149,7 +191,10 @@ impl VertexLayout {
     /// layout.add_field("color_b", VertexFieldType::F32);
     /// ```
     pub fn add_field(&mut self, name: &'static str, r#type: VertexFieldType) {
-        self.fields.push(VertexField { name, r#type });
+        self.fields.push(VertexField {
+            name: Cow::Borrowed(name),
+            r#type,
+        });
     }
 
     #[allow(dead_code)] //nop implementation does not use
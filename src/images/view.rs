@@ -3,6 +3,7 @@
 //!
 //! A [`View`] represents a rendering surface that can be either:
 //! - A window surface (when using the `app_window` feature)
+//! - An offscreen render target, whose contents can be read back (always available)
 //! - A test surface (always available)
 //!
 //! Views are the primary way to create rendering targets for the [`Engine`](crate::images::Engine).
@@ -47,6 +48,17 @@
 //! # }
 //! ```
 //!
+//! ## Creating an offscreen view
+//!
+//! ```
+//! # if cfg!(not(feature="backend_wgpu")) { return; }
+//! use images_and_words::images::view::View;
+//! use images_and_words::pixel_formats::RGBA8UnormSRGB;
+//!
+//! // Renders into a 256x256 texture instead of a window
+//! let view = View::offscreen(256, 256, RGBA8UnormSRGB);
+//! ```
+//!
 //! ## Using a view with an engine
 //!
 //! ```
@@ -64,9 +76,11 @@
 //! ```
 
 use crate::entry_point::EntryPoint;
+use crate::pixel_formats::sealed::PixelFormat;
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
 };
+use std::any::TypeId;
 
 /// Error type for view operations.
 ///
@@ -83,8 +97,8 @@ impl std::fmt::Display for Error {
 
 /// A rendering surface that can display graphics content.
 ///
-/// A `View` represents either an OS window surface or a test surface,
-/// depending on the enabled features. It serves as the connection point
+/// A `View` represents an OS window surface, an offscreen render target or a test
+/// surface, depending on how it was created and the enabled features. It serves as the connection point
 /// between the graphics system and the display surface.
 ///
 /// Views are typically created and then passed to [`Engine::rendering_to`](crate::images::Engine::rendering_to)
@@ -93,6 +107,7 @@ impl std::fmt::Display for Error {
 /// # Platform Support
 ///
 /// - **Window surfaces**: Requires the `app_window` feature
+/// - **Offscreen render targets**: Always available
 /// - **Test surfaces**: Always available
 ///
 /// # Thread Safety
@@ -111,6 +126,7 @@ pub struct View {
 #[derive(Debug, Clone)]
 enum WindowingImpl {
     Testing,
+    Offscreen(Offscreen),
     #[cfg(feature = "app_window")]
     AppWindow(std::sync::Arc<app_window::surface::Surface>),
 }
@@ -118,7 +134,7 @@ enum WindowingImpl {
 impl WindowingImpl {
     fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
         match self {
-            WindowingImpl::Testing => Err(HandleError::NotSupported),
+            WindowingImpl::Testing | WindowingImpl::Offscreen(_) => Err(HandleError::NotSupported),
             #[cfg(feature = "app_window")]
             WindowingImpl::AppWindow(surface) => Ok(surface.window_handle()),
         }
@@ -126,7 +142,7 @@ impl WindowingImpl {
 
     fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
         match self {
-            WindowingImpl::Testing => Err(HandleError::NotSupported),
+            WindowingImpl::Testing | WindowingImpl::Offscreen(_) => Err(HandleError::NotSupported),
             #[cfg(feature = "app_window")]
             WindowingImpl::AppWindow(surface) => Ok(surface.display_handle()),
         }
@@ -137,6 +153,7 @@ impl WindowingImpl {
     async fn size_scale(&self) -> (u16, u16, f64) {
         match self {
             WindowingImpl::Testing => (800, 600, 1.0),
+            WindowingImpl::Offscreen(offscreen) => (offscreen.width, offscreen.height, 1.0),
             #[cfg(feature = "app_window")]
             WindowingImpl::AppWindow(surface) => {
                 let (size, scale) = surface.size_scale().await;
@@ -146,6 +163,22 @@ impl WindowingImpl {
     }
 }
 
+/**
+Describes the render target of an offscreen view.
+
+Unlike a window, the size and format are fixed when the view is created.
+*/
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Offscreen {
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    /// The crate-level pixel format, so readback can check the format it was asked for.
+    pub(crate) format: TypeId,
+    pub(crate) format_name: &'static str,
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) imp_format: crate::imp::OffscreenFormat,
+}
+
 //we need this to port across to render thread
 unsafe impl Send for View {}
 
@@ -216,6 +249,14 @@ impl View {
         self.windowing_impl.size_scale().await
     }
 
+    /// Returns the offscreen configuration, or `None` for views that are not offscreen.
+    pub(crate) fn offscreen_config(&self) -> Option<&Offscreen> {
+        match &self.windowing_impl {
+            WindowingImpl::Offscreen(offscreen) => Some(offscreen),
+            _ => None,
+        }
+    }
+
     /// Creates a view from an OS window surface.
     ///
     /// This method creates a `View` that renders to an actual window managed
@@ -270,6 +311,64 @@ impl View {
         })
     }
 
+    /// Creates a view that renders into an offscreen texture.
+    ///
+    /// Unlike [`for_testing`](Self::for_testing), whose frames are discarded, an offscreen
+    /// view keeps the most recent frame in a persistent texture.  Read it back with
+    /// [`Port::read_framebuffer`](crate::images::port::Port::read_framebuffer).  This is useful
+    /// for thumbnails, server-side image generation and golden-image tests.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the render target in pixels
+    /// * `height` - The height of the render target in pixels
+    /// * `_format` - The pixel format of the render target.  Fragment shaders write to it
+    ///   as they would to a window surface, so pick a format the fragment output can be
+    ///   stored in (e.g. a float or unorm format for `vec4<f32>` output).
+    ///
+    /// The view has a scale factor of 1.0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # if cfg!(not(feature="backend_wgpu")) { return; }
+    /// # test_executors::spawn_local(async {
+    /// use images_and_words::images::{Engine, view::View};
+    /// use images_and_words::images::projection::WorldCoord;
+    /// use images_and_words::pixel_formats::RGBA8UNorm;
+    ///
+    /// let view = View::offscreen(64, 32, RGBA8UNorm);
+    /// let engine = Engine::rendering_to(view, WorldCoord::new(0.0, 0.0, 10.0))
+    ///     .await
+    ///     .expect("Failed to create engine");
+    /// let port = engine.main_port();
+    /// port.force_render().await;
+    ///
+    /// let framebuffer = port
+    ///     .read_framebuffer::<RGBA8UNorm>()
+    ///     .await
+    ///     .expect("Failed to read framebuffer");
+    /// assert_eq!(framebuffer.color.width(), 64);
+    /// assert_eq!(framebuffer.color.height(), 32);
+    /// # }, "offscreen_doctest");
+    /// ```
+    pub fn offscreen<Format: PixelFormat>(width: u16, height: u16, _format: Format) -> Self {
+        assert!(
+            width > 0 && height > 0,
+            "Offscreen view must not be empty ({width}x{height})"
+        );
+        View {
+            gpu_impl: None,
+            windowing_impl: WindowingImpl::Offscreen(Offscreen {
+                width,
+                height,
+                format: TypeId::of::<Format>(),
+                format_name: std::any::type_name::<Format>(),
+                imp_format: crate::imp::OffscreenFormat::new::<Format>(),
+            }),
+        }
+    }
+
     /// Creates a view suitable for testing.
     ///
     /// This method creates a `View` that doesn't require an actual window surface,
@@ -37,8 +37,6 @@ impl DirtyRect {
         }
     }
 }
-#[cfg(feature = "exfiltrate")]
-use exfiltrate::command::ImageInfo;
 use std::pin::Pin;
 
 pub trait GPUableTextureWrapper: Send + Sync {}
@@ -74,26 +72,19 @@ pub(crate) trait MappableTextureWrapped: MappableTextureWrapper + std::any::Any
     fn take_dirty_rect(&mut self) -> Option<crate::imp::DirtyRect>;
 }
 
-#[cfg(not(feature = "backend_wgpu"))]
+#[cfg(not(any(feature = "backend_wgpu", feature = "backend_software")))]
 mod nop;
-#[cfg(not(feature = "backend_wgpu"))]
+#[cfg(not(any(feature = "backend_wgpu", feature = "backend_software")))]
 pub(crate) use nop::*;
 
+//wgpu takes precedence if both backends are enabled
+#[cfg(all(feature = "backend_software", not(feature = "backend_wgpu")))]
+mod software;
+#[cfg(all(feature = "backend_software", not(feature = "backend_wgpu")))]
+pub(crate) use software::*;
+
 #[cfg(feature = "backend_wgpu")]
 mod wgpu;
 
 #[cfg(feature = "backend_wgpu")]
 pub(crate) use wgpu::*;
-
-#[cfg(feature = "exfiltrate")]
-use wasm_safe_mutex::Mutex;
-
-#[cfg(feature = "exfiltrate")]
-pub(crate) enum DumpMessage {
-    Image(ImageInfo),
-    Expect(usize),
-}
-
-#[cfg(feature = "exfiltrate")]
-pub(crate) static DUMP_NEXT_FRAME: Mutex<Option<wasm_safe_mutex::mpsc::Sender<DumpMessage>>> =
-    Mutex::new(None);
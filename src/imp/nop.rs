@@ -8,7 +8,7 @@ use crate::bindings::sampler::SamplerType;
 use crate::bindings::visible_to::{CPUStrategy, GPUBufferUsage, TextureConfig, TextureUsage};
 use crate::images::camera::Camera;
 use crate::images::port::PortReporterSend;
-use crate::images::render_pass::PassDescriptor;
+use crate::images::render_pass::PassList;
 use crate::imp::{GPUableTextureWrapper, MappableTextureWrapper};
 use crate::pixel_formats::sealed::PixelFormat as CratePixelFormat;
 use crate::send_phantom::SendPhantom;
@@ -72,13 +72,67 @@ impl Port {
         Ok(Port {})
     }
 
-    pub async fn add_fixed_pass(&mut self, _descriptor: PassDescriptor) {
+    pub async fn set_passes(&self, _passes: PassList) {
+        todo!()
+    }
+    pub async fn set_compute_passes(
+        &self,
+        _descriptors: Vec<crate::images::compute_pass::ComputePassDescriptor>,
+    ) {
         todo!()
     }
     pub async fn start(&mut self) -> Result<(), Error> {
         todo!()
     }
-    pub async fn render_frame(&mut self) {
+    pub async fn render_frame(
+        &mut self,
+        _capture: bool,
+    ) -> Option<Result<crate::images::port::FramebufferData, Error>> {
+        todo!()
+    }
+    pub async fn set_sample_count(&self, _sample_count: u32) -> u32 {
+        todo!()
+    }
+    pub async fn set_color_load(
+        &self,
+        _load: crate::images::render_pass::LoadOp<crate::pixel_formats::Float4>,
+    ) {
+        todo!()
+    }
+    pub async fn set_depth_load(&self, _load: crate::images::render_pass::LoadOp<f32>) {
+        todo!()
+    }
+    pub async fn read_framebuffer(&self) -> Result<crate::images::port::FramebufferData, Error> {
+        todo!()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OffscreenFormat;
+impl OffscreenFormat {
+    pub fn new<Format>() -> Self {
+        OffscreenFormat
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderTexture;
+impl RenderTexture {
+    pub async fn new<Format>(
+        _bound_device: &Arc<crate::images::BoundDevice>,
+        _width: u16,
+        _height: u16,
+        _depth: bool,
+        _debug_name: &str,
+    ) -> Result<Self, Error> {
+        todo!()
+    }
+
+    pub fn has_depth(&self) -> bool {
+        todo!()
+    }
+
+    pub fn depth_format(&self) -> Option<crate::images::render_pass::DepthFormat> {
         todo!()
     }
 }
@@ -116,6 +170,43 @@ impl BoundDevice {
     ) -> Result<Self, Error> {
         todo!()
     }
+
+    pub(crate) fn supports_polygon_mode(
+        &self,
+        _polygon_mode: crate::images::render_pass::PolygonMode,
+    ) -> bool {
+        todo!()
+    }
+
+    pub(crate) fn supports_unclipped_depth(&self) -> bool {
+        todo!()
+    }
+
+    pub(crate) fn supports_indirect_first_instance(&self) -> bool {
+        todo!()
+    }
+
+    pub(crate) fn check_pass(
+        &self,
+        _descriptor: &crate::images::render_pass::PassDescriptor,
+    ) -> Result<(), crate::images::render_pass::PassError> {
+        todo!()
+    }
+
+    pub(crate) fn check_compute_pass(
+        &self,
+        _descriptor: &crate::images::compute_pass::ComputePassDescriptor,
+    ) -> Result<(), crate::images::render_pass::PassError> {
+        todo!()
+    }
+
+    pub(crate) fn load_pipeline_cache(&self, _directory: &std::path::Path) {
+        todo!()
+    }
+
+    pub(crate) fn save_pipeline_cache(&self) -> std::io::Result<()> {
+        todo!()
+    }
 }
 
 #[derive(Debug)]
@@ -492,6 +583,42 @@ impl AsRef<MappableBuffer2> for MappableBuffer2 {
     }
 }
 
+#[derive(Debug)]
+pub struct MappableReadBuffer;
+
+impl MappableReadBuffer {
+    pub async fn new(
+        _bound_device: Arc<crate::images::BoundDevice>,
+        _byte_size: usize,
+        _debug_name: &str,
+        _initial: Vec<u8>,
+    ) -> Result<Self, Error> {
+        todo!()
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        todo!()
+    }
+}
+
+impl crate::bindings::resource_tracking::sealed::Mappable for MappableReadBuffer {
+    async fn map_read(&mut self) {
+        todo!()
+    }
+
+    async fn map_write(&mut self) {
+        todo!()
+    }
+
+    fn byte_len(&self) -> usize {
+        todo!()
+    }
+
+    async fn unmap(&mut self) {
+        // No-op as requested
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct GPUableBuffer;
 
@@ -524,4 +651,13 @@ impl GPUableBuffer {
     ) -> Result<Self, Error> {
         todo!()
     }
+
+    pub async fn new_gpu_writable<I: FnOnce(&mut [std::mem::MaybeUninit<u8>]) -> &[u8]>(
+        _bound_device: Arc<crate::images::BoundDevice>,
+        _byte_size: usize,
+        _debug_name: &str,
+        _initializer: I,
+    ) -> Self {
+        todo!()
+    }
 }
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::images::render_pass::{PassDescriptor, PassError, PolygonMode};
+use crate::imp::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The software device.
+///
+/// All resources live in CPU memory, so there is nothing to bind.
+#[derive(Debug, Clone)]
+pub struct BoundDevice {
+    #[allow(dead_code)]
+    entry_point: Arc<crate::entry_point::EntryPoint>,
+}
+
+impl BoundDevice {
+    pub(crate) async fn bind(
+        _unbound_device: crate::images::device::UnboundDevice,
+        entry_point: Arc<crate::entry_point::EntryPoint>,
+    ) -> Result<Self, Error> {
+        Ok(BoundDevice { entry_point })
+    }
+
+    /// The rasterizer implements every polygon mode.
+    pub(crate) fn supports_polygon_mode(&self, _polygon_mode: PolygonMode) -> bool {
+        true
+    }
+
+    pub(crate) fn supports_unclipped_depth(&self) -> bool {
+        true
+    }
+
+    pub(crate) fn supports_indirect_first_instance(&self) -> bool {
+        true
+    }
+
+    /// WGSL is not run, so it is not checked, but the shader needs a program.
+    pub(crate) fn check_compute_pass(
+        &self,
+        descriptor: &ComputePassDescriptor,
+    ) -> Result<(), PassError> {
+        if descriptor.shader.software.is_none() {
+            return Err(PassError::NoSoftwareProgram {
+                pass: descriptor.name().to_string(),
+                shader: descriptor.shader.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Programs are plain Rust, so there is no WGSL to check, but each shader needs one.
+    pub(crate) fn check_pass(&self, descriptor: &PassDescriptor) -> Result<(), PassError> {
+        let missing = if descriptor.vertex_shader.software.is_none() {
+            Some(descriptor.vertex_shader.to_string())
+        } else if descriptor.fragment_shader.software.is_none() {
+            Some(descriptor.fragment_shader.to_string())
+        } else {
+            None
+        };
+        match missing {
+            Some(shader) => Err(PassError::NoSoftwareProgram {
+                pass: descriptor.name().to_string(),
+                shader,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Programs are plain Rust, so there are no pipelines to cache.
+    pub(crate) fn load_pipeline_cache(&self, _directory: &Path) {}
+
+    pub(crate) fn save_pipeline_cache(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
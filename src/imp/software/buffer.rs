@@ -0,0 +1,323 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Buffers for the software backend.
+//!
+//! "Device" memory is an ordinary byte vector shared between the GPU side and the CPU side of a
+//! buffer.  Writes are copy-on-write, so a frame that has snapshotted the contents keeps a
+//! consistent view even if the buffer is written while it renders.
+
+use crate::bindings::visible_to::GPUBufferUsage;
+use crate::images::BoundDevice;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+/**
+Backend-specific information for copying between buffers.
+
+The software backend copies immediately, so there is no encoder to thread through.
+*/
+#[derive(Debug)]
+pub struct CopyInfo<'a> {
+    _marker: PhantomData<&'a mut ()>,
+}
+
+impl CopyInfo<'_> {
+    pub(super) fn new() -> Self {
+        CopyInfo {
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Shared storage standing in for device memory.
+#[derive(Debug)]
+pub(crate) struct DeviceBuffer {
+    bytes: Mutex<Arc<Vec<u8>>>,
+}
+
+impl DeviceBuffer {
+    fn new(bytes: Vec<u8>) -> Self {
+        DeviceBuffer {
+            bytes: Mutex::new(Arc::new(bytes)),
+        }
+    }
+
+    /// Returns the current contents without copying.
+    pub(super) fn snapshot(&self) -> Arc<Vec<u8>> {
+        self.bytes.lock().unwrap().clone()
+    }
+
+    /// Replaces the contents, as a shader writing the whole buffer would.
+    pub(super) fn replace(&self, bytes: Vec<u8>) {
+        *self.bytes.lock().unwrap() = Arc::new(bytes);
+    }
+
+    fn write(&self, data: &[u8], dst_offset: usize) {
+        let mut bytes = self.bytes.lock().unwrap();
+        Arc::make_mut(&mut bytes)[dst_offset..dst_offset + data.len()].copy_from_slice(data);
+    }
+}
+
+/// Runs a buffer initializer into a fresh byte vector.
+fn initialize<I: FnOnce(&mut [std::mem::MaybeUninit<u8>]) -> &[u8]>(
+    size: usize,
+    initializer: I,
+) -> Vec<u8> {
+    let mut data = vec![std::mem::MaybeUninit::uninit(); size];
+    let data_ptr = data.as_ptr();
+    let initialized = initializer(&mut data);
+
+    // Safety: we ensure that the data is initialized and has the correct length
+    assert_eq!(initialized.as_ptr(), data_ptr as *const u8);
+    assert_eq!(initialized.len(), size);
+    unsafe { std::mem::transmute::<Vec<std::mem::MaybeUninit<u8>>, Vec<u8>>(data) }
+}
+
+/**
+The CPU side of a dynamic buffer.
+
+Writes go straight to the shared device storage, like `write_buffer_with` on wgpu.
+*/
+#[derive(Debug)]
+pub struct MappableBuffer2 {
+    device_buffer: Arc<DeviceBuffer>,
+    size: usize,
+    _debug_label: String,
+}
+
+impl MappableBuffer2 {
+    pub async fn new_for_gpu_buffer(
+        _bound_device: Arc<crate::images::BoundDevice>,
+        device_buffer: Arc<DeviceBuffer>,
+        size: usize,
+        debug_name: &str,
+    ) -> Result<Self, crate::imp::Error> {
+        Ok(MappableBuffer2 {
+            device_buffer,
+            size,
+            _debug_label: debug_name.to_string(),
+        })
+    }
+
+    pub async fn write(&mut self, data: &[u8], dst_offset: usize) {
+        assert!(
+            dst_offset + data.len() <= self.size,
+            "Write out of bounds: offset {} + len {} > size {}",
+            dst_offset,
+            data.len(),
+            self.size
+        );
+        self.device_buffer.write(data, dst_offset);
+    }
+
+    pub async fn map_write(&mut self) {
+        // No-op: CPU memory doesn't require mapping
+    }
+
+    pub fn unmap(&mut self) {
+        // No-op: CPU memory doesn't require mapping
+    }
+}
+
+impl crate::bindings::resource_tracking::sealed::Mappable for MappableBuffer2 {
+    async fn map_read(&mut self) {
+        unreachable!("MappableBuffer2 writes straight to the GPU and is never read back")
+    }
+
+    async fn map_write(&mut self) {
+        self.map_write().await
+    }
+
+    fn unmap(&mut self) {
+        self.unmap();
+    }
+}
+
+impl AsRef<MappableBuffer2> for MappableBuffer2 {
+    fn as_ref(&self) -> &MappableBuffer2 {
+        self
+    }
+}
+
+/**
+The CPU side of a reverse buffer.
+
+Holds the contents of the GPU side as of the last copy.
+*/
+#[derive(Debug)]
+pub struct MappableReadBuffer {
+    data: Arc<Vec<u8>>,
+}
+
+impl MappableReadBuffer {
+    /// Creates a buffer that reads as `initial` until the GPU writes.
+    pub async fn new(
+        _bound_device: Arc<BoundDevice>,
+        _size: usize,
+        _debug_name: &str,
+        initial: Vec<u8>,
+    ) -> Result<Self, crate::imp::Error> {
+        Ok(MappableReadBuffer {
+            data: Arc::new(initial),
+        })
+    }
+
+    /// The results most recently read back.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub async fn map_read(&mut self) {
+        // No-op: CPU memory doesn't require mapping
+    }
+
+    pub fn unmap(&mut self) {
+        // No-op: CPU memory doesn't require mapping
+    }
+}
+
+impl crate::bindings::resource_tracking::sealed::Mappable for MappableReadBuffer {
+    async fn map_read(&mut self) {
+        self.map_read().await
+    }
+
+    async fn map_write(&mut self) {
+        unreachable!("MappableReadBuffer is only written by the GPU")
+    }
+
+    fn unmap(&mut self) {
+        self.unmap();
+    }
+}
+
+/**
+The GPU side of a dynamic buffer.
+*/
+#[derive(Debug, Clone)]
+pub struct GPUableBuffer {
+    device_buffer: Arc<DeviceBuffer>,
+    bound_device: Arc<BoundDevice>,
+    /// Whether shaders may write the buffer, see [`GPUBufferUsage::ShaderReadWrite`] and
+    /// [`GPUBufferUsage::Indirect`].
+    writable: bool,
+}
+
+impl PartialEq for GPUableBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.device_buffer, &other.device_buffer)
+    }
+}
+
+impl GPUableBuffer {
+    /// Returns the current contents of the buffer.
+    pub(super) fn snapshot(&self) -> Arc<Vec<u8>> {
+        self.device_buffer.snapshot()
+    }
+
+    /// Copies the current contents into `destination`.
+    pub(super) fn copy_to_mappable_read_buffer(&self, destination: &mut MappableReadBuffer) {
+        destination.data = self.snapshot();
+    }
+
+    /// Get a clone of the device buffer, for the CPU side to write into.
+    pub(crate) fn device_buffer_clone(&self) -> Arc<DeviceBuffer> {
+        self.device_buffer.clone()
+    }
+
+    /// Returns whether shaders may write the buffer.
+    pub(super) fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    pub(crate) fn bound_device(&self) -> Arc<BoundDevice> {
+        self.bound_device.clone()
+    }
+
+    pub(crate) async fn new_with_data<I: FnOnce(&mut [std::mem::MaybeUninit<u8>]) -> &[u8]>(
+        bound_device: Arc<crate::images::BoundDevice>,
+        size: usize,
+        usage: GPUBufferUsage,
+        _debug_name: &str,
+        initializer: I,
+    ) -> Self {
+        GPUableBuffer {
+            device_buffer: Arc::new(DeviceBuffer::new(initialize(size, initializer))),
+            bound_device,
+            writable: matches!(
+                usage,
+                GPUBufferUsage::ShaderReadWrite | GPUBufferUsage::Indirect
+            ),
+        }
+    }
+
+    /// Creates a buffer that shaders write into, with initial data.
+    pub(crate) async fn new_gpu_writable<I: FnOnce(&mut [std::mem::MaybeUninit<u8>]) -> &[u8]>(
+        bound_device: Arc<crate::images::BoundDevice>,
+        size: usize,
+        _debug_name: &str,
+        initializer: I,
+    ) -> Self {
+        GPUableBuffer {
+            device_buffer: Arc::new(DeviceBuffer::new(initialize(size, initializer))),
+            bound_device,
+            writable: true,
+        }
+    }
+}
+
+/**
+A static buffer, which the CPU never writes after creation.
+
+Shaders may still write it, if it was created with [`GPUBufferUsage::ShaderReadWrite`].
+*/
+#[derive(Debug, Clone)]
+pub struct GPUableBufferStatic {
+    device_buffer: Arc<DeviceBuffer>,
+    writable: bool,
+}
+
+impl PartialEq for GPUableBufferStatic {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.device_buffer, &other.device_buffer)
+    }
+}
+
+impl Eq for GPUableBufferStatic {}
+
+impl std::hash::Hash for GPUableBufferStatic {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.device_buffer).hash(state);
+    }
+}
+
+impl GPUableBufferStatic {
+    /// Returns the contents of the buffer.
+    pub(super) fn snapshot(&self) -> Arc<Vec<u8>> {
+        self.device_buffer.snapshot()
+    }
+
+    /// Get a clone of the device buffer, for shaders to write into.
+    pub(super) fn device_buffer_clone(&self) -> Arc<DeviceBuffer> {
+        self.device_buffer.clone()
+    }
+
+    /// Returns whether shaders may write the buffer.
+    pub(super) fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    pub(crate) async fn new_with_data<I: FnOnce(&mut [std::mem::MaybeUninit<u8>]) -> &[u8]>(
+        _bound_device: Arc<crate::images::BoundDevice>,
+        size: usize,
+        usage: GPUBufferUsage,
+        _debug_name: &str,
+        initializer: I,
+    ) -> Result<Self, crate::imp::Error> {
+        Ok(GPUableBufferStatic {
+            device_buffer: Arc::new(DeviceBuffer::new(initialize(size, initializer))),
+            writable: matches!(
+                usage,
+                GPUBufferUsage::ShaderReadWrite | GPUBufferUsage::Indirect
+            ),
+        })
+    }
+}
@@ -0,0 +1,9 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+
+#[derive(Debug, Clone)]
+pub struct EntryPoint;
+impl EntryPoint {
+    pub async fn new() -> Result<Self, crate::imp::Error> {
+        Ok(EntryPoint)
+    }
+}
@@ -0,0 +1,8 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+
+/// Errors from the software backend.
+///
+/// The software backend has no devices or surfaces to fail, so this is currently uninhabited.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {}
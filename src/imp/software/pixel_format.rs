@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::bindings::software::texture::linear_to_srgb;
+use crate::pixel_formats::{
+    BGRA8UNormSRGB, R8UNorm, R16Float, R32Float, R32SInt, RGBA8UNorm, RGBA8UnormSRGB, RGBA16Unorm,
+    RGBA32Float, RGFloat,
+};
+
+/// How the software backend reads and writes texels.
+///
+/// Shaders see every format as four linear floats, like the WGSL `vec4<f32>` returned by
+/// `textureLoad`.  Missing channels read as 0, except alpha, which reads as 1.
+pub trait PixelFormat {
+    /// Converts the bytes of one texel to linear RGBA.
+    fn decode(bytes: &[u8]) -> [f32; 4];
+    /// Stores linear RGBA into the bytes of one texel, as a render target would.
+    ///
+    /// Normalized formats clamp, and sRGB formats encode.  Extra channels are dropped.
+    fn encode(color: [f32; 4], bytes: &mut [u8]);
+    /// Converts the bytes of one texel to 8-bit sRGB, for frame captures.
+    ///
+    /// Float formats hold linear values, which are encoded.  Normalized formats are assumed
+    /// to hold display values already, so they override this to copy them.
+    fn to_srgba8(bytes: &[u8]) -> [u8; 4] {
+        let [r, g, b, a] = Self::decode(bytes);
+        [srgb8(r), srgb8(g), srgb8(b), unorm8(a)]
+    }
+}
+
+/**
+The format of an offscreen render target.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct OffscreenFormat {
+    pub(super) bytes_per_pixel: usize,
+    pub(super) decode: fn(&[u8]) -> [f32; 4],
+    pub(super) encode: fn([f32; 4], &mut [u8]),
+    pub(super) to_srgba8: fn(&[u8]) -> [u8; 4],
+}
+
+impl OffscreenFormat {
+    pub fn new<Format: crate::pixel_formats::sealed::PixelFormat>() -> Self {
+        OffscreenFormat {
+            bytes_per_pixel: std::mem::size_of::<Format::CPixel>(),
+            decode: <Format as PixelFormat>::decode,
+            encode: <Format as PixelFormat>::encode,
+            to_srgba8: <Format as PixelFormat>::to_srgba8,
+        }
+    }
+}
+
+/// Converts an sRGB-encoded channel to linear.
+pub(super) fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn f32_at(bytes: &[u8], index: usize) -> f32 {
+    f32::from_ne_bytes(bytes[index * 4..index * 4 + 4].try_into().unwrap())
+}
+
+fn put_f32(bytes: &mut [u8], index: usize, value: f32) {
+    bytes[index * 4..index * 4 + 4].copy_from_slice(&value.to_ne_bytes());
+}
+
+fn unorm8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn srgb8(c: f32) -> u8 {
+    unorm8(linear_to_srgb(c.clamp(0.0, 1.0)))
+}
+
+impl PixelFormat for R8UNorm {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        [bytes[0] as f32 / 255.0, 0.0, 0.0, 1.0]
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        bytes[0] = unorm8(color[0]);
+    }
+    fn to_srgba8(bytes: &[u8]) -> [u8; 4] {
+        [bytes[0], 0, 0, 255]
+    }
+}
+impl PixelFormat for RGBA16Unorm {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        std::array::from_fn(|c| {
+            u16::from_ne_bytes([bytes[c * 2], bytes[c * 2 + 1]]) as f32 / 65535.0
+        })
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        for (c, value) in color.into_iter().enumerate() {
+            let value = (value.clamp(0.0, 1.0) * 65535.0).round() as u16;
+            bytes[c * 2..c * 2 + 2].copy_from_slice(&value.to_ne_bytes());
+        }
+    }
+    fn to_srgba8(bytes: &[u8]) -> [u8; 4] {
+        Self::decode(bytes).map(unorm8)
+    }
+}
+impl PixelFormat for RGFloat {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        [f32_at(bytes, 0), f32_at(bytes, 1), 0.0, 1.0]
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        put_f32(bytes, 0, color[0]);
+        put_f32(bytes, 1, color[1]);
+    }
+}
+impl PixelFormat for R32SInt {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        let value = i32::from_ne_bytes(bytes[0..4].try_into().unwrap());
+        [value as f32, 0.0, 0.0, 1.0]
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        bytes[0..4].copy_from_slice(&(color[0] as i32).to_ne_bytes());
+    }
+}
+impl PixelFormat for R32Float {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        [f32_at(bytes, 0), 0.0, 0.0, 1.0]
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        put_f32(bytes, 0, color[0]);
+    }
+}
+impl PixelFormat for RGBA8UNorm {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        std::array::from_fn(|c| bytes[c] as f32 / 255.0)
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        for c in 0..4 {
+            bytes[c] = unorm8(color[c]);
+        }
+    }
+    fn to_srgba8(bytes: &[u8]) -> [u8; 4] {
+        [bytes[0], bytes[1], bytes[2], bytes[3]]
+    }
+}
+impl PixelFormat for BGRA8UNormSRGB {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        [
+            srgb_to_linear(bytes[2] as f32 / 255.0),
+            srgb_to_linear(bytes[1] as f32 / 255.0),
+            srgb_to_linear(bytes[0] as f32 / 255.0),
+            bytes[3] as f32 / 255.0,
+        ]
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        bytes[0] = srgb8(color[2]);
+        bytes[1] = srgb8(color[1]);
+        bytes[2] = srgb8(color[0]);
+        bytes[3] = unorm8(color[3]);
+    }
+    fn to_srgba8(bytes: &[u8]) -> [u8; 4] {
+        [bytes[2], bytes[1], bytes[0], bytes[3]]
+    }
+}
+impl PixelFormat for RGBA32Float {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        std::array::from_fn(|c| f32_at(bytes, c))
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        for (c, value) in color.into_iter().enumerate() {
+            put_f32(bytes, c, value);
+        }
+    }
+}
+impl PixelFormat for RGBA8UnormSRGB {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        [
+            srgb_to_linear(bytes[0] as f32 / 255.0),
+            srgb_to_linear(bytes[1] as f32 / 255.0),
+            srgb_to_linear(bytes[2] as f32 / 255.0),
+            bytes[3] as f32 / 255.0,
+        ]
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        bytes[0] = srgb8(color[0]);
+        bytes[1] = srgb8(color[1]);
+        bytes[2] = srgb8(color[2]);
+        bytes[3] = unorm8(color[3]);
+    }
+    fn to_srgba8(bytes: &[u8]) -> [u8; 4] {
+        [bytes[0], bytes[1], bytes[2], bytes[3]]
+    }
+}
+impl PixelFormat for R16Float {
+    fn decode(bytes: &[u8]) -> [f32; 4] {
+        let value = half::f16::from_ne_bytes([bytes[0], bytes[1]]);
+        [value.to_f32(), 0.0, 0.0, 1.0]
+    }
+    fn encode(color: [f32; 4], bytes: &mut [u8]) {
+        bytes[0..2].copy_from_slice(&half::f16::from_f32(color[0]).to_ne_bytes());
+    }
+}
@@ -0,0 +1,721 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::bindings::bind_style::{BindSlot, BindStyle, BindTarget, IndexFormat, IndexTarget};
+use crate::bindings::software::texture::Texture;
+use crate::images::camera::Camera;
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::images::port::perf::Instant;
+use crate::images::port::{FramebufferData, PortReporterSend};
+use crate::images::render_pass::{
+    DepthFormat, DrawCommand, DrawIndexedIndirectArgs, DrawIndirectArgs, LoadOp, PassDescriptor,
+    PassList, clear_depth, dependency_order,
+};
+use crate::images::shader::software::{
+    ComputeInput, ComputeProgram, FragmentInput, FragmentProgram, Resource, Resources, VertexInput,
+    VertexOutput, VertexProgram,
+};
+use crate::images::vertex_layout::StepMode;
+use crate::imp;
+use crate::imp::Error;
+use crate::imp::OffscreenFormat;
+use crate::imp::software::buffer::DeviceBuffer;
+use crate::imp::software::raster::{self, ColorBuffer, RasterState, Target};
+use crate::pixel_formats::{BGRA8UNormSRGB, Float4, R32Float};
+use std::sync::Arc;
+use wasm_safe_thread::Mutex;
+
+#[derive(Debug)]
+pub struct Port {
+    internal: Mutex<PortInternal>,
+}
+
+/// A pass whose shaders have been resolved to software programs.
+#[derive(Debug)]
+struct PreparedPass {
+    descriptor: PassDescriptor,
+    vertex_program: VertexProgram,
+    fragment_program: FragmentProgram,
+}
+
+impl PreparedPass {
+    fn new(descriptor: PassDescriptor) -> Self {
+        //ports refuse passes without software programs
+        let vertex_program = descriptor
+            .vertex_shader
+            .software
+            .clone()
+            .expect("vertex shader has a software program");
+        let fragment_program = descriptor
+            .fragment_shader
+            .software
+            .clone()
+            .expect("fragment shader has a software program");
+        PreparedPass {
+            descriptor,
+            vertex_program,
+            fragment_program,
+        }
+    }
+}
+
+/// A compute pass whose shader has been resolved to a software program.
+#[derive(Debug)]
+struct PreparedComputePass {
+    descriptor: ComputePassDescriptor,
+    program: ComputeProgram,
+    workgroup_count: (u32, u32, u32),
+}
+
+impl PreparedComputePass {
+    fn new(descriptor: ComputePassDescriptor) -> Self {
+        //ports refuse compute passes without software programs
+        let program = descriptor
+            .shader
+            .software
+            .clone()
+            .expect("compute shader has a software program");
+        PreparedComputePass {
+            workgroup_count: descriptor.dispatch.workgroup_count(),
+            descriptor,
+            program,
+        }
+    }
+}
+
+/// Guards that keep dynamic resources on the GPU side while a frame renders.
+#[derive(Debug, Default)]
+struct FrameGuards {
+    buffers: Vec<crate::bindings::forward::dynamic::buffer::GPUAccess>,
+    textures: Vec<crate::bindings::forward::dynamic::frame_texture::GPUAccess>,
+    readbacks: Vec<crate::bindings::reverse::buffer::GPUAccess>,
+}
+
+#[derive(Debug)]
+struct PortInternal {
+    view: crate::images::view::View,
+    camera: Camera,
+    port_reporter_send: PortReporterSend,
+    frame: u32,
+    /// When the port was created, which the time binding counts from.
+    start: Instant,
+    passes: Vec<PreparedPass>,
+    /// Compute passes, which run before `passes` in the order they were added.
+    compute_passes: Vec<PreparedComputePass>,
+    /// The format of the view's depth buffer, or `None` if no pass into the view uses depth.
+    ///
+    /// Depth is always stored as `f32`; the format only decides whether there is stencil.
+    depth_format: Option<DepthFormat>,
+    color_format: OffscreenFormat,
+    /// What happens to the view's color at the start of each frame.
+    color_load: LoadOp<Float4>,
+    /// What happens to the view's depth at the start of each frame, or `None` to clear it
+    /// as the first depth pass needs.
+    depth_load: Option<LoadOp<f32>>,
+    /// The attachments of the most recent frame.
+    color: ColorBuffer,
+    depth: Texture<R32Float>,
+    stencil: Vec<u8>,
+}
+
+impl Port {
+    pub(crate) async fn new(
+        _engine: &Arc<crate::images::Engine>,
+        view: crate::images::view::View,
+        camera: Camera,
+        port_reporter_send: PortReporterSend,
+    ) -> Result<Self, Error> {
+        //like wgpu, views that are not offscreen render sRGB
+        let color_format = match view.offscreen_config() {
+            Some(offscreen) => offscreen.imp_format,
+            None => OffscreenFormat::new::<BGRA8UNormSRGB>(),
+        };
+        Ok(Port {
+            internal: Mutex::new(PortInternal {
+                view,
+                camera,
+                port_reporter_send,
+                frame: 0,
+                start: Instant::now(),
+                passes: Vec::new(),
+                compute_passes: Vec::new(),
+                depth_format: None,
+                color_format,
+                color_load: LoadOp::Clear(Float4::default()),
+                depth_load: None,
+                color: ColorBuffer::new(1, 1, color_format),
+                depth: Texture::new(1, 1, 1.0),
+                stencil: Vec::new(),
+            }),
+        })
+    }
+
+    /// Resolving programs is cheap, so every enabled pass is prepared again.
+    pub async fn set_passes(&self, passes: PassList) {
+        let mut internal = self.internal.lock_async().await;
+        //like wgpu, one depth pass enables the depth buffer for every pass into the view
+        internal.depth_format = passes.view_depth_format();
+        let descriptors: Vec<PassDescriptor> = passes
+            .enabled()
+            .map(|entry| entry.descriptor.clone())
+            .collect();
+        //keep passes in the order they run; ports refuse passes in a cycle
+        internal.passes = dependency_order(&descriptors)
+            .expect("passes have no cycle")
+            .into_iter()
+            .map(|p| PreparedPass::new(descriptors[p].clone()))
+            .collect();
+    }
+
+    pub async fn set_compute_passes(&self, descriptors: Vec<ComputePassDescriptor>) {
+        let mut internal = self.internal.lock_async().await;
+        internal.compute_passes = descriptors
+            .into_iter()
+            .map(PreparedComputePass::new)
+            .collect();
+    }
+
+    /// Renders a frame, returning it converted for capture if `capture` is set.
+    pub async fn render_frame(&self, capture: bool) -> Option<Result<FramebufferData, Error>> {
+        let mut internal = self.internal.lock_async().await;
+        internal.render_frame().await;
+        capture.then(|| Ok(internal.capture()))
+    }
+
+    pub async fn set_color_load(&self, load: LoadOp<Float4>) {
+        self.internal.lock_async().await.color_load = load;
+    }
+
+    pub async fn set_depth_load(&self, load: LoadOp<f32>) {
+        self.internal.lock_async().await.depth_load = Some(load);
+    }
+
+    /// The rasterizer takes one sample per pixel.
+    pub async fn set_sample_count(&self, sample_count: u32) -> u32 {
+        assert!(
+            matches!(sample_count, 1 | 2 | 4 | 8),
+            "Sample count must be 1, 2, 4 or 8, not {sample_count}"
+        );
+        1
+    }
+
+    pub async fn read_framebuffer(&self) -> Result<FramebufferData, Error> {
+        let internal = self.internal.lock_async().await;
+        Ok(FramebufferData {
+            width: internal.color.width(),
+            height: internal.color.height(),
+            color: internal.color.bytes().to_vec(),
+            depth: internal.depth_data(),
+        })
+    }
+}
+
+impl PortInternal {
+    fn depth_data(&self) -> Option<Vec<f32>> {
+        self.depth_format
+            .map(|_| self.depth.texture_data().to_vec())
+    }
+
+    fn capture(&self) -> FramebufferData {
+        FramebufferData {
+            width: self.color.width(),
+            height: self.color.height(),
+            color: self.color.to_srgba8(),
+            depth: self.depth_data(),
+        }
+    }
+
+    async fn render_frame(&mut self) {
+        logwise::debuginternal_sync!("Rendering software frame...");
+        let frame_guard = self.port_reporter_send.create_frame_guard(self.frame);
+        let (width, height, scale) = self.view.size_scale().await;
+        let width = ((width as f64 * scale) as u16).max(1);
+        let height = ((height as f64 * scale) as u16).max(1);
+        self.port_reporter_send.drawable_size((width, height));
+
+        //the attachments are kept between frames, for passes that load the previous frame
+        if (self.color.width(), self.color.height()) != (width, height) {
+            self.color = ColorBuffer::new(width, height, self.color_format);
+        }
+        if let Some(depth_format) = self.depth_format {
+            if (self.depth.width(), self.depth.height()) != (width, height) {
+                self.depth = Texture::new(width, height, 1.0);
+            }
+            let stencil_len = if depth_format.has_stencil() {
+                width as usize * height as usize
+            } else {
+                0
+            };
+            self.stencil.resize(stencil_len, 0);
+        }
+        let time = Instant::now().duration_since(&self.start).as_secs_f32();
+        let globals = Globals {
+            camera: Arc::new(camera_bytes(&self.camera)),
+            frame_counter: Arc::new(self.frame.to_ne_bytes().to_vec()),
+            time: Arc::new(time.to_ne_bytes().to_vec()),
+        };
+
+        let mut guards = FrameGuards::default();
+        //compute passes run first, so render passes can use their results
+        for pass in &self.compute_passes {
+            let bindings = acquire(&pass.descriptor.bind_style, &globals, &mut guards).await;
+            dispatch(pass, &bindings);
+            bindings.store_writable_buffers();
+        }
+        let mut view_loaded = false;
+        let mut cleared_textures: Vec<&imp::RenderTexture> = Vec::new();
+        for pass in &self.passes {
+            let mut bindings = acquire(&pass.descriptor.bind_style, &globals, &mut guards).await;
+            let descriptor = &pass.descriptor;
+            bindings.resources.immediates = descriptor.immediates.as_ref().map(|i| i.bytes());
+            match &descriptor.render_target {
+                None => {
+                    //load the view the first time it is rendered this frame
+                    let (color_load, depth_load) = if std::mem::replace(&mut view_loaded, true) {
+                        (LoadOp::Load, LoadOp::Load)
+                    } else {
+                        let descriptors = self.passes.iter().map(|p| &p.descriptor);
+                        let clear_depth = LoadOp::Clear(clear_depth(descriptors, &None));
+                        (self.color_load, self.depth_load.unwrap_or(clear_depth))
+                    };
+                    load_view(
+                        &mut self.color,
+                        &mut self.depth,
+                        &mut self.stencil,
+                        descriptor.color_load.unwrap_or(color_load),
+                        descriptor.depth_load.unwrap_or(depth_load),
+                    );
+                    let enable_depth = self.depth_format.is_some();
+                    let mut target = Target {
+                        color: &mut self.color,
+                        depth: enable_depth.then_some(&mut self.depth),
+                        stencil: (!self.stencil.is_empty()).then_some(&mut self.stencil[..]),
+                    };
+                    draw_pass(pass, &bindings, enable_depth, &mut target);
+                }
+                Some(texture) => {
+                    let mut attachments = texture.attachments.lock().unwrap();
+                    //clear each render texture the first time it is rendered this frame
+                    let (color_load, depth_load) = if cleared_textures.contains(&texture) {
+                        (LoadOp::Load, LoadOp::Load)
+                    } else {
+                        cleared_textures.push(texture);
+                        let descriptors = self.passes.iter().map(|p| &p.descriptor);
+                        let clear_depth = clear_depth(descriptors, &Some(texture.clone()));
+                        (LoadOp::Clear(Float4::default()), LoadOp::Clear(clear_depth))
+                    };
+                    attachments.load(
+                        descriptor.color_load.unwrap_or(color_load),
+                        descriptor.depth_load.unwrap_or(depth_load),
+                    );
+                    let attachments = &mut *attachments;
+                    let enable_depth = attachments.depth.is_some();
+                    let mut target = Target {
+                        color: &mut attachments.color,
+                        depth: attachments.depth.as_mut(),
+                        stencil: None,
+                    };
+                    draw_pass(pass, &bindings, enable_depth, &mut target);
+                }
+            }
+            bindings.store_writable_buffers();
+        }
+        if !view_loaded {
+            //no pass renders into the view, but it still needs clearing
+            load_view(
+                &mut self.color,
+                &mut self.depth,
+                &mut self.stencil,
+                self.color_load,
+                LoadOp::Load,
+            );
+        }
+        //copy reverse buffers back, now that every pass has written them
+        let bind_styles = self
+            .compute_passes
+            .iter()
+            .map(|pass| &pass.descriptor.bind_style)
+            .chain(self.passes.iter().map(|pass| &pass.descriptor.bind_style));
+        for bind_style in bind_styles {
+            for info in bind_style.binds.values() {
+                if let BindTarget::ReverseBuffer(render_side) = &info.target {
+                    // Safety: the guard is kept alive until the frame completes
+                    let mut gpu_access = unsafe { render_side.acquire_gpu_buffer() };
+                    if let Some((source, destination)) = gpu_access.readback() {
+                        source.copy_to_mappable_read_buffer(destination);
+                    }
+                    guards.readbacks.push(gpu_access);
+                }
+            }
+        }
+        frame_guard.mark_cpu_complete();
+        drop(guards);
+        frame_guard.mark_gpu_complete();
+        self.frame += 1;
+    }
+}
+
+/// Clears the view's attachments as `color` and `depth` say, before a pass draws.  Stencil
+/// clears to `0` along with depth.
+fn load_view(
+    color: &mut ColorBuffer,
+    depth: &mut Texture<R32Float>,
+    stencil: &mut [u8],
+    color_load: LoadOp<Float4>,
+    depth_load: LoadOp<f32>,
+) {
+    if let LoadOp::Clear(clear) = color_load {
+        color.clear([clear.r, clear.g, clear.b, clear.a]);
+    }
+    if let LoadOp::Clear(clear) = depth_load {
+        *depth = Texture::new(depth.width(), depth.height(), clear);
+        stencil.fill(0);
+    }
+}
+
+/// Encodes the camera projection the way the wgpu backend uploads it.
+fn camera_bytes(camera: &Camera) -> Vec<u8> {
+    let projection = camera.copy_projection_and_clear_dirty_bit();
+    let matrix = projection.matrix();
+    let mut bytes = Vec::with_capacity(64);
+    for column in matrix.columns() {
+        for value in [column.x(), column.y(), column.z(), column.w()] {
+            bytes.extend_from_slice(&value.to_ne_bytes());
+        }
+    }
+    bytes
+}
+
+/// The port's camera, frame counter and time for a frame, encoded as passes bind them.
+struct Globals {
+    camera: Arc<Vec<u8>>,
+    frame_counter: Arc<Vec<u8>>,
+    time: Arc<Vec<u8>>,
+}
+
+/// The resources a pass reads, captured at the start of the pass.
+struct PassBindings {
+    resources: Resources,
+    /// Buffers the pass may write, by binding slot.
+    writable_buffers: Vec<(BindSlot, Arc<DeviceBuffer>)>,
+    /// Vertex buffers as (bytes, stride, step mode), in binding slot order.
+    vertex_buffers: Vec<(Arc<Vec<u8>>, usize, StepMode)>,
+    index_buffer: Option<(Arc<Vec<u8>>, IndexFormat)>,
+    /// The arguments of indirect draws, as bytes.
+    indirect_buffer: Option<Arc<Vec<u8>>>,
+}
+
+/// Acquires the dynamic resources for a pass, copying any pending CPU writes,
+/// and snapshots every binding.
+async fn acquire(
+    bind_style: &BindStyle,
+    globals: &Globals,
+    guards: &mut FrameGuards,
+) -> PassBindings {
+    let mut resources = Resources::default();
+    let mut vertex_buffers = Vec::new();
+    let mut writable_buffers = Vec::new();
+    let mut copy_info = imp::CopyInfo::new();
+    for (bind_index, info) in &bind_style.binds {
+        let resource = match &info.target {
+            BindTarget::StaticBuffer(buffer) if buffer.is_writable() => writable(
+                *bind_index,
+                buffer.device_buffer_clone(),
+                &mut writable_buffers,
+            ),
+            BindTarget::StaticBuffer(buffer) => Resource::Buffer(buffer.snapshot()),
+            BindTarget::DynamicBuffer(buffer) => {
+                // Safety: the guard is kept alive until the frame completes
+                let mut gpu_access = unsafe { buffer.imp.acquire_gpu_buffer() };
+                // The CPU side writes shared storage directly; the guard only needs releasing.
+                drop(gpu_access.take_dirty_guard());
+                let resource = if gpu_access.as_ref().is_writable() {
+                    writable(
+                        *bind_index,
+                        gpu_access.as_ref().device_buffer_clone(),
+                        &mut writable_buffers,
+                    )
+                } else {
+                    Resource::Buffer(gpu_access.as_ref().snapshot())
+                };
+                guards.buffers.push(gpu_access);
+                resource
+            }
+            BindTarget::Camera => Resource::Buffer(globals.camera.clone()),
+            BindTarget::FrameCounter => Resource::Buffer(globals.frame_counter.clone()),
+            BindTarget::Time => Resource::Buffer(globals.time.clone()),
+            BindTarget::StaticTexture(render_side, _sampler) => {
+                Resource::Texture(render_side.storage.resource())
+            }
+            BindTarget::DynamicTexture(texture) => {
+                // Safety: the guard is kept alive until the frame completes
+                let mut gpu_access = unsafe { texture.acquire_gpu_texture() };
+                if let Some(mut dirty_guard) = gpu_access.take_dirty_guard() {
+                    let source: &mut dyn imp::MappableTextureWrapped = dirty_guard.as_imp();
+                    //safety: guards are live
+                    unsafe {
+                        gpu_access
+                            .as_imp()
+                            .copy_from_mappable(source, &mut copy_info)
+                    }
+                    .await
+                    .unwrap();
+                }
+                let resource = gpu_access.render_side.storage.resource();
+                guards.textures.push(gpu_access);
+                Resource::Texture(resource)
+            }
+            BindTarget::RenderTexture(texture, _sampler) => {
+                Resource::Texture(texture.color_resource())
+            }
+            BindTarget::RenderTextureDepth(texture) => Resource::Texture(texture.depth_resource()),
+            BindTarget::Sampler(_) => Resource::Sampler,
+            BindTarget::ReverseBuffer(render_side) => {
+                //safety: passes run one at a time, and this one is the only writer
+                let device_buffer = unsafe { render_side.unsafe_imp() }.device_buffer_clone();
+                writable(*bind_index, device_buffer, &mut writable_buffers)
+            }
+            BindTarget::VB(layout, step_mode, buffer) => {
+                vertex_buffers.push((
+                    *bind_index,
+                    buffer.snapshot(),
+                    layout.element_stride(),
+                    *step_mode,
+                ));
+                continue;
+            }
+            BindTarget::DynamicVB(layout, step_mode, buffer) => {
+                // Safety: the guard is kept alive until the frame completes
+                let mut gpu_access = unsafe { buffer.imp.acquire_gpu_buffer() };
+                drop(gpu_access.take_dirty_guard());
+                let bytes = gpu_access.as_ref().snapshot();
+                guards.buffers.push(gpu_access);
+                vertex_buffers.push((*bind_index, bytes, layout.element_stride(), *step_mode));
+                continue;
+            }
+        };
+        resources.bindings.insert(*bind_index, resource);
+    }
+    if let Some(group) = bind_style.global_group {
+        for (binding, bytes) in [
+            (0, &globals.camera),
+            (1, &globals.frame_counter),
+            (2, &globals.time),
+        ] {
+            let resource = Resource::Buffer(bytes.clone());
+            resources
+                .bindings
+                .insert(BindSlot::in_group(group, binding), resource);
+        }
+    }
+    vertex_buffers.sort_by_key(|(bind_index, ..)| *bind_index);
+    let index_buffer = bind_style.index_buffer.as_ref().map(|binding| {
+        let bytes = match &binding.target {
+            IndexTarget::Static(buffer) => buffer.snapshot(),
+            IndexTarget::Dynamic(buffer) => {
+                // Safety: the guard is kept alive until the frame completes
+                let mut gpu_access = unsafe { buffer.imp.acquire_gpu_buffer() };
+                drop(gpu_access.take_dirty_guard());
+                let bytes = gpu_access.as_ref().snapshot();
+                guards.buffers.push(gpu_access);
+                bytes
+            }
+        };
+        (bytes, binding.format)
+    });
+    let indirect_buffer = bind_style
+        .indirect_buffer
+        .as_ref()
+        .map(|binding| binding.buffer.snapshot());
+    PassBindings {
+        resources,
+        writable_buffers,
+        vertex_buffers: vertex_buffers
+            .into_iter()
+            .map(|(_, bytes, stride, step_mode)| (bytes, stride, step_mode))
+            .collect(),
+        index_buffer,
+        indirect_buffer,
+    }
+}
+
+/// Binds a buffer that the pass may write, to be stored by
+/// [`PassBindings::store_writable_buffers`].
+fn writable(
+    bind_index: BindSlot,
+    device_buffer: Arc<DeviceBuffer>,
+    writable_buffers: &mut Vec<(BindSlot, Arc<DeviceBuffer>)>,
+) -> Resource {
+    let bytes = device_buffer.snapshot();
+    writable_buffers.push((bind_index, device_buffer));
+    Resource::Storage(std::sync::Mutex::new(bytes.as_ref().clone()))
+}
+
+impl PassBindings {
+    /// Stores what the pass wrote into its writable buffers.
+    fn store_writable_buffers(self) {
+        let mut resources = self.resources;
+        for (bind_index, device_buffer) in self.writable_buffers {
+            if let Some(Resource::Storage(bytes)) = resources.bindings.remove(&bind_index) {
+                device_buffer.replace(bytes.into_inner().unwrap());
+            }
+        }
+    }
+}
+
+/// Runs the program of a compute pass once for each workgroup.
+fn dispatch(pass: &PreparedComputePass, bindings: &PassBindings) {
+    let (x, y, z) = pass.workgroup_count;
+    for workgroup_z in 0..z {
+        for workgroup_y in 0..y {
+            for workgroup_x in 0..x {
+                pass.program.run(&ComputeInput {
+                    workgroup_id: [workgroup_x, workgroup_y, workgroup_z],
+                    num_workgroups: [x, y, z],
+                    resources: &bindings.resources,
+                });
+            }
+        }
+    }
+}
+
+/// Returns `draw_command` with the arguments of an indirect draw read from the indirect buffer,
+/// and the first instance it draws.
+fn resolve_draw_command(draw_command: DrawCommand, bindings: &PassBindings) -> (DrawCommand, u32) {
+    let Some(draw) = draw_command.indirect_draw() else {
+        return (draw_command, 0);
+    };
+    let bytes = bindings
+        .indirect_buffer
+        .as_ref()
+        .expect("indirect draws have an indirect buffer");
+    let read = |size: usize| -> &[u8] {
+        let offset = draw as usize * size;
+        //ports refuse indirect draws past the end of the buffer
+        bytes
+            .get(offset..offset + size)
+            .expect("indirect draw is in bounds")
+    };
+    match draw_command {
+        DrawCommand::IndexedIndirect { topology, .. } => {
+            //safety: DrawIndexedIndirectArgs is plain data, and the range is its size
+            let args: DrawIndexedIndirectArgs = unsafe {
+                std::ptr::read_unaligned(
+                    read(std::mem::size_of::<DrawIndexedIndirectArgs>()).as_ptr() as *const _,
+                )
+            };
+            let draw_command = DrawCommand::Range {
+                topology,
+                first_vertex: 0,
+                first_index: args.first_index,
+                base_vertex: args.base_vertex,
+                count: args.index_count,
+                instances: args.instance_count,
+            };
+            (draw_command, args.first_instance)
+        }
+        _ => {
+            //safety: DrawIndirectArgs is plain data, and the range is its size
+            let args: DrawIndirectArgs = unsafe {
+                std::ptr::read_unaligned(
+                    read(std::mem::size_of::<DrawIndirectArgs>()).as_ptr() as *const _
+                )
+            };
+            let draw_command = DrawCommand::Range {
+                topology: draw_command.topology(),
+                first_vertex: args.first_vertex,
+                first_index: 0,
+                base_vertex: 0,
+                count: args.vertex_count,
+                instances: args.instance_count,
+            };
+            (draw_command, args.first_instance)
+        }
+    }
+}
+
+fn draw_pass(
+    pass: &PreparedPass,
+    bindings: &PassBindings,
+    enable_depth: bool,
+    target: &mut Target,
+) {
+    let (draw_command, first_instance) =
+        resolve_draw_command(pass.descriptor.draw_command, bindings);
+    //non-indexed indirect draws ignore any index buffer
+    let index_buffer = match pass.descriptor.draw_command {
+        DrawCommand::Indirect { .. } => None,
+        _ => bindings.index_buffer.as_ref(),
+    };
+    let vertex_indices: Vec<u32> = match index_buffer {
+        Some((indices, format)) => {
+            let range = draw_command.indices();
+            let index_size = match format {
+                IndexFormat::Uint16 => 2,
+                IndexFormat::Uint32 => 4,
+            };
+            indices
+                .chunks_exact(index_size)
+                .skip(range.start as usize)
+                .take(range.len())
+                .map(|index| {
+                    let index = match *format {
+                        IndexFormat::Uint16 => u16::from_ne_bytes([index[0], index[1]]) as i64,
+                        IndexFormat::Uint32 => u32::from_ne_bytes(index.try_into().unwrap()) as i64,
+                    };
+                    (index + draw_command.base_vertex() as i64) as u32
+                })
+                .collect()
+        }
+        None => draw_command.vertices().collect(),
+    };
+
+    let (width, height) = (target.color.width() as u32, target.color.height() as u32);
+    let state = RasterState {
+        topology: draw_command.topology(),
+        depth: enable_depth,
+        blend: pass.descriptor.blend,
+        write_mask: pass.descriptor.write_mask,
+        rasterizer: pass.descriptor.rasterizer,
+        depth_stencil: pass.descriptor.depth_stencil,
+        viewport: pass.descriptor.viewport_in(width, height),
+        scissor: pass.descriptor.scissor_in(width, height),
+    };
+    let mut attributes = Vec::new();
+    let instances = first_instance..first_instance.saturating_add(draw_command.instance_count());
+    for instance_index in instances {
+        let vertices: Vec<VertexOutput> = vertex_indices
+            .iter()
+            .map(|&vertex_index| {
+                attributes.clear();
+                for (bytes, stride, step_mode) in &bindings.vertex_buffers {
+                    let element = match step_mode {
+                        StepMode::Vertex => vertex_index,
+                        StepMode::Instance => instance_index,
+                    };
+                    let start = element as usize * stride;
+                    for offset in (start..start + stride).step_by(4) {
+                        let value = bytes
+                            .get(offset..offset + 4)
+                            .map(|b| f32::from_ne_bytes(b.try_into().unwrap()))
+                            .unwrap_or(0.0);
+                        attributes.push(value);
+                    }
+                }
+                pass.vertex_program.run(&VertexInput {
+                    vertex_index,
+                    instance_index,
+                    attributes: &attributes,
+                    resources: &bindings.resources,
+                })
+            })
+            .collect();
+
+        raster::draw(target, state, &vertices, &mut |fragment| {
+            pass.fragment_program.run(&FragmentInput {
+                position: fragment.position,
+                front_facing: fragment.front_facing,
+                varyings: fragment.varyings,
+                resources: &bindings.resources,
+            })
+        });
+    }
+}
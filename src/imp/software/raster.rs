@@ -0,0 +1,924 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Triangle, line and point rasterization for the software backend.
+//!
+//! This follows the WebGPU rules closely enough that a scene renders the same as on wgpu,
+//! up to rounding:
+//!
+//! * primitives are clipped against `0 <= z <= w` (or only `w > 0` with unclipped depth); x and
+//!   y are clipped by the framebuffer bounds
+//! * triangles are culled, filled and depth-biased according to the pass's [`RasterizerState`]
+//! * pixel centres are sampled, with the top-left fill rule
+//! * lines light one pixel per step along their major axis, and points light the pixel
+//!   containing them
+//! * varyings are interpolated perspective-correctly; depth is interpolated linearly in screen space
+//! * colors are blended in linear space and stored in the target's format
+
+use crate::bindings::software::texture::{Texel, Texture};
+use crate::images::render_pass::{
+    BlendComponent, BlendFactor, BlendMode, BlendOperation, ColorWriteMask, CullMode,
+    DepthStencilState, FrontFace, PolygonMode, RasterizerState, StencilOperation, Topology,
+};
+use crate::images::shader::software::{TextureResource, VertexOutput};
+use crate::imp::software::pixel_format::OffscreenFormat;
+use crate::pixel_formats::R32Float;
+
+/// Fixed-function state for one draw.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RasterState {
+    pub topology: Topology,
+    /// Test and write against the depth buffer, as `depth_stencil` says.
+    pub depth: bool,
+    pub blend: BlendMode,
+    pub write_mask: ColorWriteMask,
+    pub rasterizer: RasterizerState,
+    pub depth_stencil: DepthStencilState,
+    /// Where clip space maps to in the target, as `[x, y, width, height]` in pixels.
+    pub viewport: [f32; 4],
+    /// The pixels that may be drawn, as `[x, y, width, height]` within the target.
+    pub scissor: [u32; 4],
+}
+
+impl RasterState {
+    /// Whether the pixel at `x`, `y` is inside the scissor rectangle.
+    fn scissors(&self, x: f32, y: f32) -> bool {
+        let [left, top, width, height] = self.scissor.map(|v| v as f32);
+        x >= left && y >= top && x < left + width && y < top + height
+    }
+}
+
+/// A color attachment, stored in the bytes of its pixel format.
+///
+/// Storing the real format (rather than floats) means blending reads back exactly what
+/// a GPU would, and readback is a plain copy.
+#[derive(Debug, Clone)]
+pub(super) struct ColorBuffer {
+    width: u16,
+    height: u16,
+    format: OffscreenFormat,
+    data: Vec<u8>,
+}
+
+impl ColorBuffer {
+    /// Creates a buffer cleared to zero, which is transparent black in every format.
+    pub fn new(width: u16, height: u16, format: OffscreenFormat) -> Self {
+        ColorBuffer {
+            width,
+            height,
+            format,
+            data: vec![0; width as usize * height as usize * format.bytes_per_pixel],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The pixels, tightly packed in row-major order.
+    pub fn bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn range(&self, texel: Texel) -> std::ops::Range<usize> {
+        let start = (texel.y as usize * self.width as usize + texel.x as usize)
+            * self.format.bytes_per_pixel;
+        start..start + self.format.bytes_per_pixel
+    }
+
+    /// Converts every pixel to 8-bit sRGB, for frame captures.
+    pub fn to_srgba8(&self) -> Vec<u8> {
+        self.data
+            .chunks_exact(self.format.bytes_per_pixel)
+            .flat_map(self.format.to_srgba8)
+            .collect()
+    }
+
+    /// The pixels as seen by a shader sampling this buffer.
+    pub fn resource(&self) -> TextureResource {
+        TextureResource {
+            width: self.width,
+            height: self.height,
+            bytes_per_pixel: self.format.bytes_per_pixel,
+            decode: self.format.decode,
+            data: std::sync::Arc::new(self.data.clone()),
+        }
+    }
+
+    /// Sets every pixel to `color`, in linear RGBA.
+    pub fn clear(&mut self, color: [f32; 4]) {
+        let bytes_per_pixel = self.format.bytes_per_pixel;
+        let mut pixel = vec![0; bytes_per_pixel];
+        (self.format.encode)(color, &mut pixel);
+        for chunk in self.data.chunks_exact_mut(bytes_per_pixel) {
+            chunk.copy_from_slice(&pixel);
+        }
+    }
+
+    /// Reads one pixel as linear RGBA.
+    pub fn get(&self, texel: Texel) -> [f32; 4] {
+        (self.format.decode)(&self.data[self.range(texel)])
+    }
+
+    fn put(&mut self, texel: Texel, color: [f32; 4]) {
+        let range = self.range(texel);
+        (self.format.encode)(color, &mut self.data[range]);
+    }
+}
+
+/// The attachments a draw renders into.
+pub(super) struct Target<'a> {
+    pub color: &'a mut ColorBuffer,
+    pub depth: Option<&'a mut Texture<R32Float>>,
+    /// 8-bit stencil values, one per pixel in row-major order.
+    pub stencil: Option<&'a mut [u8]>,
+}
+
+/// Inputs to the fragment stage, as produced by the rasterizer.
+pub(super) struct Fragment<'a> {
+    pub position: [f32; 4],
+    pub front_facing: bool,
+    pub varyings: &'a [f32],
+}
+
+#[derive(Debug, Clone)]
+struct ClipVertex {
+    position: [f32; 4],
+    varyings: Vec<f32>,
+}
+
+impl ClipVertex {
+    fn lerp(&self, other: &ClipVertex, t: f32) -> ClipVertex {
+        ClipVertex {
+            position: std::array::from_fn(|i| {
+                self.position[i] + (other.position[i] - self.position[i]) * t
+            }),
+            varyings: self
+                .varyings
+                .iter()
+                .zip(&other.varyings)
+                .map(|(a, b)| a + (b - a) * t)
+                .collect(),
+        }
+    }
+}
+
+/// A vertex after the perspective divide and viewport transform.
+#[derive(Debug, Clone)]
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    z: f32,
+    inv_w: f32,
+    varyings: Vec<f32>,
+}
+
+/// Smallest w we allow through clipping, so the perspective divide stays finite.
+const MIN_W: f32 = 1e-6;
+
+type Plane = fn(&[f32; 4]) -> f32;
+
+/// The planes primitives are clipped against; each is positive on the visible side.
+///
+/// With unclipped depth only the w plane remains, and depth is clamped per fragment instead.
+fn clip_planes(unclipped_depth: bool) -> &'static [Plane] {
+    const PLANES: [Plane; 3] = [|p| p[3] - MIN_W, |p| p[2], |p| p[3] - p[2]];
+    if unclipped_depth {
+        &PLANES[..1]
+    } else {
+        &PLANES
+    }
+}
+
+/// Clips a polygon against the near, far and w planes (Sutherland–Hodgman).
+fn clip(polygon: Vec<ClipVertex>, unclipped_depth: bool) -> Vec<ClipVertex> {
+    let mut polygon = polygon;
+    for plane in clip_planes(unclipped_depth) {
+        if polygon.is_empty() {
+            break;
+        }
+        let mut output = Vec::with_capacity(polygon.len() + 1);
+        for i in 0..polygon.len() {
+            let current = &polygon[i];
+            let next = &polygon[(i + 1) % polygon.len()];
+            let d_current = plane(&current.position);
+            let d_next = plane(&next.position);
+            if d_current >= 0.0 {
+                output.push(current.clone());
+            }
+            if (d_current >= 0.0) != (d_next >= 0.0) {
+                let t = d_current / (d_current - d_next);
+                output.push(current.lerp(next, t));
+            }
+        }
+        polygon = output;
+    }
+    polygon
+}
+
+/// Assembles vertices into triangles, returning the indices of each triangle's vertices.
+fn assemble(topology: Topology, vertex_count: usize) -> Vec<[usize; 3]> {
+    match topology {
+        Topology::PointList | Topology::LineList | Topology::LineStrip => Vec::new(),
+        Topology::TriangleList => (0..vertex_count / 3)
+            .map(|t| [t * 3, t * 3 + 1, t * 3 + 2])
+            .collect(),
+        Topology::TriangleStrip => (0..vertex_count.saturating_sub(2))
+            .map(|i| {
+                if i % 2 == 0 {
+                    [i, i + 1, i + 2]
+                } else {
+                    //odd triangles are flipped to keep a consistent winding
+                    [i + 1, i, i + 2]
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Assembles vertices into lines, returning the indices of each line's vertices.
+fn assemble_lines(topology: Topology, vertex_count: usize) -> Vec<[usize; 2]> {
+    match topology {
+        Topology::LineList => (0..vertex_count / 2).map(|l| [l * 2, l * 2 + 1]).collect(),
+        Topology::LineStrip => (0..vertex_count.saturating_sub(1))
+            .map(|i| [i, i + 1])
+            .collect(),
+        Topology::PointList | Topology::TriangleList | Topology::TriangleStrip => Vec::new(),
+    }
+}
+
+/// Clips a line segment against the same planes as [`clip`], or returns `None` if
+/// nothing is left.
+fn clip_segment(
+    a: ClipVertex,
+    b: ClipVertex,
+    unclipped_depth: bool,
+) -> Option<(ClipVertex, ClipVertex)> {
+    let (mut a, mut b) = (a, b);
+    for plane in clip_planes(unclipped_depth) {
+        let d_a = plane(&a.position);
+        let d_b = plane(&b.position);
+        if d_a < 0.0 && d_b < 0.0 {
+            return None;
+        }
+        //both ends are found from the unclipped segment
+        let new_a = (d_a < 0.0).then(|| a.lerp(&b, d_a / (d_a - d_b)));
+        let new_b = (d_b < 0.0).then(|| b.lerp(&a, d_b / (d_b - d_a)));
+        if let Some(new_a) = new_a {
+            a = new_a;
+        }
+        if let Some(new_b) = new_b {
+            b = new_b;
+        }
+    }
+    Some((a, b))
+}
+
+/// Applies the perspective divide and the `[x, y, width, height]` viewport transform.
+fn to_screen(v: ClipVertex, viewport: [f32; 4]) -> ScreenVertex {
+    let [x, y, width, height] = viewport;
+    let inv_w = 1.0 / v.position[3];
+    ScreenVertex {
+        x: x + (v.position[0] * inv_w + 1.0) / 2.0 * width,
+        y: y + (1.0 - v.position[1] * inv_w) / 2.0 * height,
+        z: v.position[2] * inv_w,
+        inv_w,
+        varyings: v.varyings,
+    }
+}
+
+fn edge(a: &ScreenVertex, b: &ScreenVertex, x: f32, y: f32) -> f32 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+/// Whether pixels exactly on the edge from `a` to `b` belong to the triangle.
+///
+/// Assumes the triangle has positive area under [`edge`].
+fn is_top_left(a: &ScreenVertex, b: &ScreenVertex) -> bool {
+    let dx = b.x - a.x;
+    let dy = b.y - a.y;
+    (dy == 0.0 && dx > 0.0) || dy < 0.0
+}
+
+/// Twice the signed area of a polygon under [`edge`]'s convention.
+///
+/// Counter-clockwise polygons in clip space are negative, since y points down on screen.
+fn signed_area(polygon: &[ScreenVertex]) -> f32 {
+    (1..polygon.len().saturating_sub(1))
+        .map(|i| edge(&polygon[0], &polygon[i], polygon[i + 1].x, polygon[i + 1].y))
+        .sum()
+}
+
+/// Draws `vertices` (the vertex stage outputs, in draw order) into `target`.
+///
+/// `shade` runs the fragment stage and returns a linear color, or `None` to discard.
+pub(super) fn draw(
+    target: &mut Target,
+    state: RasterState,
+    vertices: &[VertexOutput],
+    shade: &mut dyn FnMut(&Fragment) -> Option<[f32; 4]>,
+) {
+    let viewport = state.viewport;
+    let unclipped_depth = state.rasterizer.unclipped_depth;
+    let clip_vertex = |i: usize| ClipVertex {
+        position: vertices[i].position,
+        varyings: vertices[i].varyings.clone(),
+    };
+    match state.topology {
+        Topology::PointList => {
+            for i in 0..vertices.len() {
+                let v = clip_vertex(i);
+                if clip_planes(unclipped_depth)
+                    .iter()
+                    .any(|plane| plane(&v.position) < 0.0)
+                {
+                    continue;
+                }
+                draw_point(target, state, &to_screen(v, viewport), true, shade);
+            }
+        }
+        Topology::LineList | Topology::LineStrip => {
+            for [a, b] in assemble_lines(state.topology, vertices.len()) {
+                if let Some((a, b)) = clip_segment(clip_vertex(a), clip_vertex(b), unclipped_depth)
+                {
+                    let a = to_screen(a, viewport);
+                    let b = to_screen(b, viewport);
+                    draw_line(target, state, [&a, &b], true, shade);
+                }
+            }
+        }
+        Topology::TriangleList | Topology::TriangleStrip => {
+            for [a, b, c] in assemble(state.topology, vertices.len()) {
+                let polygon = [a, b, c].into_iter().map(clip_vertex).collect();
+                let screen: Vec<ScreenVertex> = clip(polygon, unclipped_depth)
+                    .into_iter()
+                    .map(|v| to_screen(v, viewport))
+                    .collect();
+                let area = signed_area(&screen);
+                if area == 0.0 || !area.is_finite() {
+                    continue;
+                }
+                let front_facing = match state.rasterizer.front_face {
+                    FrontFace::CounterClockwise => area < 0.0,
+                    FrontFace::Clockwise => area > 0.0,
+                };
+                let culled = match state.rasterizer.cull_mode {
+                    CullMode::None => false,
+                    CullMode::Front => front_facing,
+                    CullMode::Back => !front_facing,
+                };
+                if culled {
+                    continue;
+                }
+                match state.rasterizer.polygon_mode {
+                    PolygonMode::Fill => {
+                        for i in 1..screen.len() - 1 {
+                            draw_triangle(
+                                target,
+                                state,
+                                [&screen[0], &screen[i], &screen[i + 1]],
+                                front_facing,
+                                shade,
+                            );
+                        }
+                    }
+                    PolygonMode::Line => {
+                        for i in 0..screen.len() {
+                            let next = &screen[(i + 1) % screen.len()];
+                            draw_line(target, state, [&screen[i], next], front_facing, shade);
+                        }
+                    }
+                    PolygonMode::Point => {
+                        for v in &screen {
+                            draw_point(target, state, v, front_facing, shade);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draws a one-pixel point into the pixel containing `v`.
+fn draw_point(
+    target: &mut Target,
+    state: RasterState,
+    v: &ScreenVertex,
+    front_facing: bool,
+    shade: &mut dyn FnMut(&Fragment) -> Option<[f32; 4]>,
+) {
+    let (x, y) = (v.x.floor(), v.y.floor());
+    if !state.scissors(x, y) {
+        return;
+    }
+    let texel = Texel {
+        x: x as u16,
+        y: y as u16,
+    };
+    let fragment = Fragment {
+        position: [x + 0.5, y + 0.5, v.z, v.inv_w],
+        front_facing,
+        varyings: &v.varyings,
+    };
+    write_fragment(target, state, texel, &fragment, shade);
+}
+
+/// Draws a one-pixel-wide line, lighting one pixel per step along its major axis.
+fn draw_line(
+    target: &mut Target,
+    state: RasterState,
+    vertices: [&ScreenVertex; 2],
+    front_facing: bool,
+    shade: &mut dyn FnMut(&Fragment) -> Option<[f32; 4]>,
+) {
+    let [v0, v1] = vertices;
+    let (dx, dy) = (v1.x - v0.x, v1.y - v0.y);
+    if (dx == 0.0 && dy == 0.0) || !dx.is_finite() || !dy.is_finite() {
+        return;
+    }
+    let x_major = dx.abs() >= dy.abs();
+    //pixel centers along the major axis, from start (inclusive) to end (exclusive)
+    let (start, end, delta) = if x_major {
+        (v0.x, v1.x, dx)
+    } else {
+        (v0.y, v1.y, dy)
+    };
+    let first = (start.min(end) - 0.5).ceil() as i64;
+    let last = (start.max(end) - 0.5).ceil() as i64;
+    let varying_count = v0.varyings.len();
+    let mut varyings = vec![0.0; varying_count];
+
+    for step in first..last {
+        let center = step as f32 + 0.5;
+        let t = (center - start) / delta;
+        let (px, py) = if x_major {
+            (center, v0.y + t * dy)
+        } else {
+            (v0.x + t * dx, center)
+        };
+        let (x, y) = (px.floor(), py.floor());
+        if !state.scissors(x, y) {
+            continue;
+        }
+        let z = v0.z + (v1.z - v0.z) * t;
+        let p0 = (1.0 - t) * v0.inv_w;
+        let p1 = t * v1.inv_w;
+        let inv_w = p0 + p1;
+        for (i, varying) in varyings.iter_mut().enumerate() {
+            *varying = (p0 * v0.varyings[i] + p1 * v1.varyings[i]) / inv_w;
+        }
+        let texel = Texel {
+            x: x as u16,
+            y: y as u16,
+        };
+        let fragment = Fragment {
+            position: [x + 0.5, y + 0.5, z, inv_w],
+            front_facing,
+            varyings: &varyings,
+        };
+        write_fragment(target, state, texel, &fragment, shade);
+    }
+}
+
+fn draw_triangle(
+    target: &mut Target,
+    state: RasterState,
+    vertices: [&ScreenVertex; 3],
+    front_facing: bool,
+    shade: &mut dyn FnMut(&Fragment) -> Option<[f32; 4]>,
+) {
+    let [v0, mut v1, mut v2] = vertices;
+    let mut area = edge(v0, v1, v2.x, v2.y);
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+    if area < 0.0 {
+        //rewind so the edge functions are positive inside
+        std::mem::swap(&mut v1, &mut v2);
+        area = -area;
+    }
+    let bias = depth_bias(state.rasterizer, [v0, v1, v2], area);
+
+    let [left, top, width, height] = state.scissor;
+    let min_x = (v0.x.min(v1.x).min(v2.x).floor().max(0.0) as u32).max(left);
+    let min_y = (v0.y.min(v1.y).min(v2.y).floor().max(0.0) as u32).max(top);
+    let max_x = (v0.x.max(v1.x).max(v2.x).ceil().max(0.0) as u32).min(left + width);
+    let max_y = (v0.y.max(v1.y).max(v2.y).ceil().max(0.0) as u32).min(top + height);
+
+    let top_left = [
+        is_top_left(v1, v2),
+        is_top_left(v2, v0),
+        is_top_left(v0, v1),
+    ];
+    let varying_count = v0.varyings.len();
+    let mut varyings = vec![0.0; varying_count];
+
+    for y in min_y..max_y {
+        let py = y as f32 + 0.5;
+        for x in min_x..max_x {
+            let px = x as f32 + 0.5;
+            let weights = [
+                edge(v1, v2, px, py),
+                edge(v2, v0, px, py),
+                edge(v0, v1, px, py),
+            ];
+            let inside = weights
+                .iter()
+                .zip(top_left)
+                .all(|(&w, top_left)| w > 0.0 || (w == 0.0 && top_left));
+            if !inside {
+                continue;
+            }
+            let [b0, b1, b2] = weights.map(|w| w / area);
+            let z = b0 * v0.z + b1 * v1.z + b2 * v2.z + bias;
+            let texel = Texel {
+                x: x as u16,
+                y: y as u16,
+            };
+            let p0 = b0 * v0.inv_w;
+            let p1 = b1 * v1.inv_w;
+            let p2 = b2 * v2.inv_w;
+            let inv_w = p0 + p1 + p2;
+            for (i, varying) in varyings.iter_mut().enumerate() {
+                *varying =
+                    (p0 * v0.varyings[i] + p1 * v1.varyings[i] + p2 * v2.varyings[i]) / inv_w;
+            }
+            let fragment = Fragment {
+                position: [px, py, z, inv_w],
+                front_facing,
+                varyings: &varyings,
+            };
+            write_fragment(target, state, texel, &fragment, shade);
+        }
+    }
+}
+
+/// The depth offset for a filled triangle, following WebGPU's formula for float depth.
+///
+/// `area` is the triangle's (positive) area under [`edge`].
+fn depth_bias(rasterizer: RasterizerState, vertices: [&ScreenVertex; 3], area: f32) -> f32 {
+    let bias = rasterizer.depth_bias;
+    if bias.constant == 0 && bias.slope_scale == 0.0 {
+        return 0.0;
+    }
+    let [v0, v1, v2] = vertices;
+    //gradient of the depth plane, in depth per pixel
+    let dz_dx = ((v1.z - v0.z) * (v2.y - v0.y) - (v2.z - v0.z) * (v1.y - v0.y)) / area;
+    let dz_dy = ((v2.z - v0.z) * (v1.x - v0.x) - (v1.z - v0.z) * (v2.x - v0.x)) / area;
+    let max_slope = dz_dx.abs().max(dz_dy.abs());
+    //one unit in the last place of the largest depth in the triangle
+    let max_z = v0.z.max(v1.z).max(v2.z).max(f32::MIN_POSITIVE);
+    let unit = 2.0f32.powi(max_z.log2().floor() as i32 - 23);
+    let offset = bias.constant as f32 * unit + bias.slope_scale * max_slope;
+    if bias.clamp > 0.0 {
+        offset.min(bias.clamp)
+    } else if bias.clamp < 0.0 {
+        offset.max(bias.clamp)
+    } else {
+        offset
+    }
+}
+
+/// Depth tests, shades and blends one fragment into `texel`.
+fn write_fragment(
+    target: &mut Target,
+    state: RasterState,
+    texel: Texel,
+    fragment: &Fragment,
+    shade: &mut dyn FnMut(&Fragment) -> Option<[f32; 4]>,
+) {
+    //fragment depth is clamped to the viewport's depth range, which matters with unclipped depth
+    let z = fragment.position[2].clamp(0.0, 1.0);
+    let depth_stencil = state.depth_stencil;
+    let depth_passes = !state.depth
+        || target
+            .depth
+            .as_ref()
+            .is_none_or(|depth| depth_stencil.compare.passes(z, depth[texel]));
+    let stencil = depth_stencil.stencil;
+    let face = if fragment.front_facing {
+        stencil.front
+    } else {
+        stencil.back
+    };
+    let stencil_index = texel.y as usize * target.color.width() as usize + texel.x as usize;
+    let stencil_passes = target.stencil.as_ref().is_none_or(|buffer| {
+        face.compare.passes(
+            stencil.reference & stencil.read_mask,
+            buffer[stencil_index] as u32 & stencil.read_mask,
+        )
+    });
+    let operation = match (stencil_passes, depth_passes) {
+        (false, _) => face.fail_op,
+        (true, false) => face.depth_fail_op,
+        (true, true) => face.pass_op,
+    };
+    let update_stencil = |target: &mut Target| {
+        if let Some(buffer) = &mut target.stencil {
+            let stored = &mut buffer[stencil_index];
+            *stored = stencil_operation(operation, *stored, stencil.reference, stencil.write_mask);
+        }
+    };
+    if !(stencil_passes && depth_passes) {
+        //discarded fragments leave the stencil alone, so shade only if that could matter
+        if target.stencil.is_some()
+            && operation != StencilOperation::Keep
+            && shade(fragment).is_some()
+        {
+            update_stencil(target);
+        }
+        return;
+    }
+    let Some(color) = shade(fragment) else {
+        return;
+    };
+    update_stencil(target);
+    if state.depth
+        && depth_stencil.depth_write
+        && let Some(depth) = &mut target.depth
+    {
+        depth[texel] = z;
+    }
+    if state.write_mask == ColorWriteMask::ALL && state.blend == BlendMode::Replace {
+        target.color.put(texel, color);
+        return;
+    }
+    let dst = target.color.get(texel);
+    let blended = match state.blend.components() {
+        Some((color_component, alpha_component)) => {
+            let mut blended = [0.0; 4];
+            for (channel, out) in blended.iter_mut().enumerate() {
+                let component = if channel == 3 {
+                    alpha_component
+                } else {
+                    color_component
+                };
+                *out = blend(component, channel, color, dst);
+            }
+            blended
+        }
+        None => color,
+    };
+    let mask = state.write_mask;
+    let written = [mask.red, mask.green, mask.blue, mask.alpha];
+    let color = std::array::from_fn(|channel| {
+        if written[channel] {
+            blended[channel]
+        } else {
+            dst[channel]
+        }
+    });
+    target.color.put(texel, color);
+}
+
+/// Applies a stencil operation to `stored`, changing only the bits in `write_mask`.
+fn stencil_operation(
+    operation: StencilOperation,
+    stored: u8,
+    reference: u32,
+    write_mask: u32,
+) -> u8 {
+    let new = match operation {
+        StencilOperation::Keep => stored,
+        StencilOperation::Zero => 0,
+        StencilOperation::Replace => reference as u8,
+        StencilOperation::Invert => !stored,
+        StencilOperation::IncrementClamp => stored.saturating_add(1),
+        StencilOperation::DecrementClamp => stored.saturating_sub(1),
+        StencilOperation::IncrementWrap => stored.wrapping_add(1),
+        StencilOperation::DecrementWrap => stored.wrapping_sub(1),
+    };
+    let write_mask = write_mask as u8;
+    (stored & !write_mask) | (new & write_mask)
+}
+
+/// Blends one channel of `src` into `dst`, as the GPU's blend unit does.
+fn blend(component: BlendComponent, channel: usize, src: [f32; 4], dst: [f32; 4]) -> f32 {
+    let factor = |factor| match factor {
+        BlendFactor::Zero => 0.0,
+        BlendFactor::One => 1.0,
+        BlendFactor::Src => src[channel],
+        BlendFactor::OneMinusSrc => 1.0 - src[channel],
+        BlendFactor::SrcAlpha => src[3],
+        BlendFactor::OneMinusSrcAlpha => 1.0 - src[3],
+        BlendFactor::Dst => dst[channel],
+        BlendFactor::OneMinusDst => 1.0 - dst[channel],
+        BlendFactor::DstAlpha => dst[3],
+        BlendFactor::OneMinusDstAlpha => 1.0 - dst[3],
+        BlendFactor::SrcAlphaSaturated if channel == 3 => 1.0,
+        BlendFactor::SrcAlphaSaturated => src[3].min(1.0 - dst[3]),
+    };
+    let s = src[channel] * factor(component.src_factor);
+    let d = dst[channel] * factor(component.dst_factor);
+    match component.operation {
+        BlendOperation::Add => s + d,
+        BlendOperation::Subtract => s - d,
+        BlendOperation::ReverseSubtract => d - s,
+        //min and max ignore the factors
+        BlendOperation::Min => src[channel].min(dst[channel]),
+        BlendOperation::Max => src[channel].max(dst[channel]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixel_formats::BGRA8UNormSRGB;
+
+    fn color_buffer(width: u16, height: u16) -> ColorBuffer {
+        ColorBuffer::new(width, height, OffscreenFormat::new::<BGRA8UNormSRGB>())
+    }
+
+    fn target_pixels(vertices: &[[f32; 2]], topology: Topology) -> ColorBuffer {
+        let mut color = color_buffer(4, 4);
+        let vertices: Vec<VertexOutput> = vertices
+            .iter()
+            .map(|&[x, y]| VertexOutput::new([x, y, 0.5, 1.0]))
+            .collect();
+        draw(
+            &mut Target {
+                color: &mut color,
+                depth: None,
+                stencil: None,
+            },
+            RasterState {
+                topology,
+                depth: false,
+                blend: BlendMode::Replace,
+                write_mask: ColorWriteMask::ALL,
+                rasterizer: RasterizerState::new(),
+                depth_stencil: DepthStencilState::new(),
+                viewport: [0.0, 0.0, 4.0, 4.0],
+                scissor: [0, 0, 4, 4],
+            },
+            &vertices,
+            &mut |_| Some([1.0, 1.0, 1.0, 1.0]),
+        );
+        color
+    }
+
+    fn covered(texture: &ColorBuffer) -> Vec<(u16, u16)> {
+        let mut covered = Vec::new();
+        for y in 0..texture.height() {
+            for x in 0..texture.width() {
+                if texture.get(Texel { x, y })[3] != 0.0 {
+                    covered.push((x, y));
+                }
+            }
+        }
+        covered
+    }
+
+    #[test]
+    fn triangle_covers_lower_left_half() {
+        //counter-clockwise: bottom-left, bottom-right, top-left
+        let texture = target_pixels(
+            &[[-1.0, -1.0], [1.0, -1.0], [-1.0, 1.0]],
+            Topology::TriangleList,
+        );
+        let covered = covered(&texture);
+        assert!(covered.contains(&(0, 3)));
+        assert!(!covered.contains(&(3, 0)));
+    }
+
+    #[test]
+    fn clockwise_triangle_is_culled() {
+        let texture = target_pixels(
+            &[[-1.0, -1.0], [-1.0, 1.0], [1.0, -1.0]],
+            Topology::TriangleList,
+        );
+        assert!(covered(&texture).is_empty());
+    }
+
+    #[test]
+    fn strip_fills_quad_without_overlap_or_gaps() {
+        let mut color = color_buffer(4, 4);
+        let vertices: Vec<VertexOutput> = [[-1.0, -1.0], [1.0, -1.0], [-1.0, 1.0], [1.0, 1.0]]
+            .iter()
+            .map(|&[x, y]| VertexOutput::new([x, y, 0.5, 1.0]))
+            .collect();
+        let mut fragments = 0;
+        draw(
+            &mut Target {
+                color: &mut color,
+                depth: None,
+                stencil: None,
+            },
+            RasterState {
+                topology: Topology::TriangleStrip,
+                depth: false,
+                blend: BlendMode::Replace,
+                write_mask: ColorWriteMask::ALL,
+                rasterizer: RasterizerState::new(),
+                depth_stencil: DepthStencilState::new(),
+                viewport: [0.0, 0.0, 4.0, 4.0],
+                scissor: [0, 0, 4, 4],
+            },
+            &vertices,
+            &mut |_| {
+                fragments += 1;
+                Some([1.0, 0.0, 0.0, 1.0])
+            },
+        );
+        assert_eq!(fragments, 16);
+        assert_eq!(covered(&color).len(), 16);
+        assert_eq!(color.get(Texel { x: 1, y: 2 }), [1.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn line_lights_one_pixel_per_column() {
+        //a shallow line from the bottom-left corner to the middle of the right edge
+        let texture = target_pixels(&[[-1.0, -1.0], [1.0, 0.0]], Topology::LineList);
+        assert_eq!(covered(&texture), vec![(2, 2), (3, 2), (0, 3), (1, 3)]);
+    }
+
+    #[test]
+    fn line_strip_connects_every_vertex() {
+        let texture = target_pixels(
+            &[[-1.0, 0.75], [0.75, 0.75], [0.75, -1.0]],
+            Topology::LineStrip,
+        );
+        //along the top row, then down the right column
+        assert_eq!(
+            covered(&texture),
+            vec![(0, 0), (1, 0), (2, 0), (3, 0), (3, 1), (3, 2), (3, 3)]
+        );
+    }
+
+    #[test]
+    fn point_lights_its_pixel() {
+        let texture = target_pixels(&[[-0.75, 0.75], [0.25, -0.75]], Topology::PointList);
+        assert_eq!(covered(&texture), vec![(0, 0), (2, 3)]);
+    }
+
+    #[test]
+    fn depth_test_keeps_nearer_fragment() {
+        let mut color = color_buffer(1, 1);
+        let mut depth = Texture::new(1, 1, 1.0);
+        let fullscreen = |z: f32| -> Vec<VertexOutput> {
+            [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]]
+                .iter()
+                .map(|&[x, y]| VertexOutput::with_varyings([x, y, z, 1.0], vec![z]))
+                .collect()
+        };
+        let state = RasterState {
+            topology: Topology::TriangleList,
+            depth: true,
+            blend: BlendMode::Replace,
+            write_mask: ColorWriteMask::ALL,
+            rasterizer: RasterizerState::new(),
+            depth_stencil: DepthStencilState::new(),
+            viewport: [0.0, 0.0, 1.0, 1.0],
+            scissor: [0, 0, 1, 1],
+        };
+        let mut target = Target {
+            color: &mut color,
+            depth: Some(&mut depth),
+            stencil: None,
+        };
+        let mut shade = |f: &Fragment| Some([f.varyings[0], 0.0, 0.0, 1.0]);
+        draw(&mut target, state, &fullscreen(0.2), &mut shade);
+        draw(&mut target, state, &fullscreen(0.8), &mut shade);
+        assert_eq!(depth[Texel::ZERO], 0.2);
+    }
+
+    #[test]
+    fn viewport_places_and_scissor_clips_coverage() {
+        let fullscreen: Vec<VertexOutput> = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]]
+            .iter()
+            .map(|&[x, y]| VertexOutput::new([x, y, 0.5, 1.0]))
+            .collect();
+        let covered_by = |viewport, scissor| {
+            let mut color = color_buffer(4, 4);
+            let state = RasterState {
+                topology: Topology::TriangleList,
+                depth: false,
+                blend: BlendMode::Replace,
+                write_mask: ColorWriteMask::ALL,
+                rasterizer: RasterizerState::new(),
+                depth_stencil: DepthStencilState::new(),
+                viewport,
+                scissor,
+            };
+            let mut target = Target {
+                color: &mut color,
+                depth: None,
+                stencil: None,
+            };
+            draw(&mut target, state, &fullscreen, &mut |_| {
+                Some([1.0, 1.0, 1.0, 1.0])
+            });
+            covered(&color)
+        };
+        assert_eq!(
+            covered_by([2.0, 0.0, 2.0, 2.0], [0, 0, 4, 4]),
+            vec![(2, 0), (3, 0), (2, 1), (3, 1)]
+        );
+        assert_eq!(
+            covered_by([0.0, 0.0, 4.0, 4.0], [1, 2, 2, 1]),
+            vec![(1, 2), (2, 2)]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Render textures, which passes render into and later passes sample.
+
+use crate::images::render_pass::LoadOp;
+use crate::images::shader::software::TextureResource;
+use crate::imp::Error;
+use crate::imp::software::pixel_format::{OffscreenFormat, PixelFormat};
+use crate::imp::software::raster::ColorBuffer;
+use crate::pixel_formats::pixel_as_bytes;
+use crate::pixel_formats::{Float4, R32Float};
+use std::sync::{Arc, Mutex};
+
+/// The attachments of a render texture.
+#[derive(Debug)]
+pub(super) struct Attachments {
+    pub(super) color: ColorBuffer,
+    pub(super) depth: Option<crate::bindings::software::texture::Texture<R32Float>>,
+}
+
+impl Attachments {
+    /// Clears the attachments as `color` and `depth` say, before a pass draws.
+    pub(super) fn load(&mut self, color: LoadOp<Float4>, depth: LoadOp<f32>) {
+        if let LoadOp::Clear(color) = color {
+            self.color.clear([color.r, color.g, color.b, color.a]);
+        }
+        if let (Some(attachment), LoadOp::Clear(depth)) = (&mut self.depth, depth) {
+            *attachment = crate::bindings::software::texture::Texture::new(
+                attachment.width(),
+                attachment.height(),
+                depth,
+            );
+        }
+    }
+}
+
+/**
+The CPU-side stand-in for a render texture.
+
+Clones share the same attachments.
+*/
+#[derive(Debug, Clone)]
+pub struct RenderTexture {
+    pub(super) attachments: Arc<Mutex<Attachments>>,
+    has_depth: bool,
+}
+
+impl RenderTexture {
+    pub async fn new<Format: crate::pixel_formats::sealed::PixelFormat>(
+        _bound_device: &Arc<crate::images::BoundDevice>,
+        width: u16,
+        height: u16,
+        depth: bool,
+        _debug_name: &str,
+    ) -> Result<Self, Error> {
+        let has_depth = depth;
+        let depth =
+            has_depth.then(|| crate::bindings::software::texture::Texture::new(width, height, 1.0));
+        Ok(RenderTexture {
+            attachments: Arc::new(Mutex::new(Attachments {
+                color: ColorBuffer::new(width, height, OffscreenFormat::new::<Format>()),
+                depth,
+            })),
+            has_depth,
+        })
+    }
+
+    pub fn has_depth(&self) -> bool {
+        self.has_depth
+    }
+
+    /// The format of the depth attachment, as passes see it; depth is stored as `f32`.
+    pub fn depth_format(&self) -> Option<crate::images::render_pass::DepthFormat> {
+        self.has_depth
+            .then_some(crate::images::render_pass::DepthFormat::Depth32Float)
+    }
+
+    /// The color attachment, as seen by a shader sampling it.
+    pub(super) fn color_resource(&self) -> TextureResource {
+        self.attachments.lock().unwrap().color.resource()
+    }
+
+    /// The depth attachment, as seen by a shader sampling it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the texture has no depth attachment.
+    pub(super) fn depth_resource(&self) -> TextureResource {
+        let attachments = self.attachments.lock().unwrap();
+        let depth = attachments
+            .depth
+            .as_ref()
+            .expect("render texture has no depth attachment");
+        TextureResource {
+            width: depth.width(),
+            height: depth.height(),
+            bytes_per_pixel: std::mem::size_of::<f32>(),
+            decode: <R32Float as PixelFormat>::decode,
+            data: Arc::new(pixel_as_bytes(depth.texture_data()).to_vec()),
+        }
+    }
+}
+
+impl PartialEq for RenderTexture {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.attachments, &other.attachments)
+    }
+}
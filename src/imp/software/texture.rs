@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::Priority;
+use crate::bindings::resource_tracking::sealed::Mappable;
+use crate::bindings::software::texture::Texel;
+use crate::bindings::visible_to::TextureConfig;
+use crate::images::shader::software::TextureResource;
+use crate::imp::{DirtyRect, Error};
+use crate::imp::{GPUableTextureWrapper, MappableTextureWrapper};
+use crate::pixel_formats::pixel_as_bytes;
+use crate::pixel_formats::sealed::PixelFormat;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Texel storage standing in for device memory.
+///
+/// Rows are tightly packed.  Mipmaps are not stored; the software backend always samples
+/// the base level.
+#[derive(Debug)]
+pub(super) struct Storage {
+    width: u16,
+    height: u16,
+    bytes_per_pixel: usize,
+    decode: fn(&[u8]) -> [f32; 4],
+    data: Mutex<Arc<Vec<u8>>>,
+}
+
+impl Storage {
+    fn new<Format: PixelFormat>(width: u16, height: u16, data: Vec<u8>) -> Self {
+        let bytes_per_pixel = std::mem::size_of::<Format::CPixel>();
+        assert_eq!(
+            data.len(),
+            width as usize * height as usize * bytes_per_pixel
+        );
+        Storage {
+            width,
+            height,
+            bytes_per_pixel,
+            decode: <Format as crate::imp::PixelFormat>::decode,
+            data: Mutex::new(Arc::new(data)),
+        }
+    }
+
+    fn with_initializer<Format: PixelFormat, I: Fn(Texel) -> Format::CPixel>(
+        width: u16,
+        height: u16,
+        initializer: I,
+    ) -> Self {
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(initializer(Texel { x, y }));
+            }
+        }
+        Self::new::<Format>(width, height, pixel_as_bytes(&pixels).to_vec())
+    }
+
+    /// Returns the current contents, as seen by shaders.
+    pub(super) fn resource(&self) -> TextureResource {
+        TextureResource {
+            width: self.width,
+            height: self.height,
+            bytes_per_pixel: self.bytes_per_pixel,
+            decode: self.decode,
+            data: self.data.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// CPU-side texture storage.
+pub struct MappableTexture2<Format> {
+    buffer: Box<[u8]>,
+    format: PhantomData<Format>,
+    width: u16,
+    height: u16,
+    dirty_rect: Option<DirtyRect>,
+}
+
+impl<Format> Mappable for MappableTexture2<Format> {
+    async fn map_read(&mut self) {
+        // No-op: we use direct CPU storage, no mapping needed
+    }
+
+    async fn map_write(&mut self) {
+        // No-op: we use direct CPU storage, no mapping needed
+    }
+
+    fn unmap(&mut self) {
+        // No-op: we use direct CPU storage
+    }
+}
+
+unsafe impl<Format> Send for MappableTexture2<Format> {}
+unsafe impl<Format> Sync for MappableTexture2<Format> {}
+
+impl<Format: PixelFormat> MappableTexture2<Format> {
+    pub async fn new<Initializer: Fn(Texel) -> Format::CPixel>(
+        _bound_device: &Arc<crate::images::BoundDevice>,
+        width: u16,
+        height: u16,
+        _debug_name: &str,
+        _priority: Priority,
+        initializer: Initializer,
+    ) -> Self {
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(initializer(Texel { x, y }));
+            }
+        }
+        Self {
+            buffer: pixel_as_bytes(&pixels).into(),
+            format: PhantomData,
+            width,
+            height,
+            dirty_rect: Some(DirtyRect::full(width, height)),
+        }
+    }
+
+    pub fn replace(&mut self, src_width: u16, dst_texel: Texel, data: &[Format::CPixel]) {
+        assert!(src_width > 0, "Source width must be greater than 0");
+        assert!(
+            data.len().is_multiple_of(src_width as usize),
+            "Data length ({}) must be divisible by source width ({})",
+            data.len(),
+            src_width
+        );
+        let src_height = data.len() / src_width as usize;
+        assert!(
+            dst_texel.x as usize + src_width as usize <= self.width as usize,
+            "Destination region exceeds texture width: dst_x({}) + src_width({}) > texture_width({})",
+            dst_texel.x,
+            src_width,
+            self.width
+        );
+        assert!(
+            dst_texel.y as usize + src_height <= self.height as usize,
+            "Destination region exceeds texture height: dst_y({}) + src_height({}) > texture_height({})",
+            dst_texel.y,
+            src_height,
+            self.height
+        );
+
+        let data_bytes = pixel_as_bytes(data);
+        let bytes_per_pixel = std::mem::size_of::<Format::CPixel>();
+        let src_bytes_per_row = src_width as usize * bytes_per_pixel;
+        let dst_bytes_per_row = self.width as usize * bytes_per_pixel;
+
+        for row in 0..src_height {
+            let src_offset = row * src_bytes_per_row;
+            let dst_offset = (dst_texel.y as usize + row) * dst_bytes_per_row
+                + dst_texel.x as usize * bytes_per_pixel;
+            self.buffer[dst_offset..dst_offset + src_bytes_per_row]
+                .copy_from_slice(&data_bytes[src_offset..src_offset + src_bytes_per_row]);
+        }
+
+        let new_dirty = DirtyRect {
+            x: dst_texel.x,
+            y: dst_texel.y,
+            width: src_width,
+            height: src_height as u16,
+        };
+        self.dirty_rect = Some(match self.dirty_rect {
+            Some(existing) => existing.union(new_dirty),
+            None => new_dirty,
+        });
+    }
+}
+
+impl<Format> Debug for MappableTexture2<Format> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappableTexture2")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("buffer_len", &self.buffer.len())
+            .finish()
+    }
+}
+
+impl<Format: Send + Sync> MappableTextureWrapper for MappableTexture2<Format> {}
+
+impl<Format: Send + Sync + 'static> crate::imp::MappableTextureWrapped
+    for MappableTexture2<Format>
+{
+    fn width(&self) -> u16 {
+        self.width
+    }
+
+    fn height(&self) -> u16 {
+        self.height
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn take_dirty_rect(&mut self) -> Option<DirtyRect> {
+        self.dirty_rect.take()
+    }
+}
+
+/**
+The GPU side of a dynamic texture.
+*/
+pub struct GPUableTexture2<Format> {
+    format: PhantomData<Format>,
+    storage: Arc<Storage>,
+}
+
+impl<Format> Debug for GPUableTexture2<Format> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GPUableTexture2")
+            .field("width", &self.storage.width)
+            .field("height", &self.storage.height)
+            .finish()
+    }
+}
+
+impl<Format> Clone for GPUableTexture2<Format> {
+    fn clone(&self) -> Self {
+        Self {
+            format: PhantomData,
+            storage: self.storage.clone(),
+        }
+    }
+}
+
+unsafe impl<Format> Send for GPUableTexture2<Format> {}
+unsafe impl<Format> Sync for GPUableTexture2<Format> {}
+
+impl<Format: PixelFormat> GPUableTexture2<Format> {
+    pub async fn new(
+        _bound_device: &Arc<crate::images::BoundDevice>,
+        config: TextureConfig<'_>,
+    ) -> Result<Self, Error> {
+        let bytes =
+            config.width as usize * config.height as usize * std::mem::size_of::<Format::CPixel>();
+        Ok(GPUableTexture2 {
+            format: PhantomData,
+            storage: Arc::new(Storage::new::<Format>(
+                config.width,
+                config.height,
+                vec![0; bytes],
+            )),
+        })
+    }
+
+    pub fn render_side(&self) -> RenderSide {
+        RenderSide {
+            storage: self.storage.clone(),
+        }
+    }
+}
+
+impl<Format> GPUableTextureWrapper for GPUableTexture2<Format> {}
+
+impl<Format: PixelFormat> crate::imp::GPUableTextureWrapped for GPUableTexture2<Format> {
+    fn format_matches(&self, other: &dyn crate::imp::MappableTextureWrapped) -> bool {
+        if self.storage.width != other.width() || self.storage.height != other.height() {
+            return false;
+        }
+        let other_any = other as &dyn std::any::Any;
+        other_any
+            .downcast_ref::<MappableTexture2<Format>>()
+            .is_some()
+    }
+
+    unsafe fn copy_from_mappable<'f>(
+        &'f self,
+        source: &'f mut dyn crate::imp::MappableTextureWrapped,
+        _copy_info: &'f mut crate::imp::CopyInfo<'_>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), String>> + 'f>> {
+        Box::pin(async move {
+            let Some(rect) = source.take_dirty_rect() else {
+                return Ok(());
+            };
+            if source.width() != self.storage.width || source.height() != self.storage.height {
+                return Err(format!(
+                    "Source is {}x{} but destination is {}x{}",
+                    source.width(),
+                    source.height(),
+                    self.storage.width,
+                    self.storage.height
+                ));
+            }
+            let bytes_per_pixel = self.storage.bytes_per_pixel;
+            let bytes_per_row = self.storage.width as usize * bytes_per_pixel;
+            let src = source.as_slice();
+            let mut data = self.storage.data.lock().unwrap();
+            let dst = Arc::make_mut(&mut data);
+            for y in rect.y as usize..(rect.y + rect.height) as usize {
+                let start = y * bytes_per_row + rect.x as usize * bytes_per_pixel;
+                let end = start + rect.width as usize * bytes_per_pixel;
+                dst[start..end].copy_from_slice(&src[start..end]);
+            }
+            Ok(())
+        })
+    }
+}
+
+/**
+A static texture, which is never written after creation.
+*/
+#[derive(Debug, Clone)]
+pub struct GPUableTexture2Static<Format> {
+    format: PhantomData<Format>,
+    storage: Arc<Storage>,
+}
+
+impl<Format: PixelFormat> GPUableTexture2Static<Format> {
+    #[allow(dead_code)]
+    pub async fn new(
+        _bound_device: &Arc<crate::images::BoundDevice>,
+        config: TextureConfig<'_>,
+    ) -> Result<Self, Error> {
+        let bytes =
+            config.width as usize * config.height as usize * std::mem::size_of::<Format::CPixel>();
+        Ok(GPUableTexture2Static {
+            format: PhantomData,
+            storage: Arc::new(Storage::new::<Format>(
+                config.width,
+                config.height,
+                vec![0; bytes],
+            )),
+        })
+    }
+
+    pub async fn new_with_data<I: Fn(Texel) -> Format::CPixel>(
+        _bound_device: &Arc<crate::images::BoundDevice>,
+        config: TextureConfig<'_>,
+        initializer: I,
+    ) -> Result<Self, Error> {
+        Ok(GPUableTexture2Static {
+            format: PhantomData,
+            storage: Arc::new(Storage::with_initializer::<Format, I>(
+                config.width,
+                config.height,
+                initializer,
+            )),
+        })
+    }
+
+    pub fn render_side(&self) -> RenderSide {
+        RenderSide {
+            storage: self.storage.clone(),
+        }
+    }
+}
+
+unsafe impl<Format> Send for GPUableTexture2Static<Format> {}
+unsafe impl<Format> Sync for GPUableTexture2Static<Format> {}
+
+impl<Format> PartialEq for GPUableTexture2Static<Format> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.storage, &other.storage)
+    }
+}
+
+impl<Format> Eq for GPUableTexture2Static<Format> {}
+
+impl<Format> std::hash::Hash for GPUableTexture2Static<Format> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.storage).hash(state);
+    }
+}
+
+impl<Format> GPUableTextureWrapper for GPUableTexture2Static<Format> {}
+
+#[derive(Debug, Clone)]
+pub struct RenderSide {
+    pub(super) storage: Arc<Storage>,
+}
+
+impl PartialEq for RenderSide {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.storage, &other.storage)
+    }
+}
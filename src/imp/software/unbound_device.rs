@@ -0,0 +1,13 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::images::view::View;
+
+pub struct UnboundDevice;
+
+impl UnboundDevice {
+    pub async fn pick(
+        _view: &View,
+        _entry_point: &crate::entry_point::EntryPoint,
+    ) -> Result<UnboundDevice, super::Error> {
+        Ok(UnboundDevice)
+    }
+}
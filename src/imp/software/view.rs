@@ -0,0 +1,22 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::images::view::ViewForImp;
+use std::sync::Arc;
+
+/// A view rendered by the software backend.
+///
+/// The software backend never presents to a window surface; frames are rendered offscreen.
+#[derive(Debug, Clone)]
+pub struct View {
+    pub(super) _parent: Arc<ViewForImp>,
+}
+
+impl View {
+    pub async fn from_surface(
+        _entrypoint: &crate::entry_point::EntryPoint,
+        view: ViewForImp,
+    ) -> Result<Self, super::Error> {
+        Ok(View {
+            _parent: Arc::new(view),
+        })
+    }
+}
@@ -8,17 +8,21 @@ mod entry_point;
 mod error;
 mod pixel_format;
 mod port;
+mod render_texture;
 mod texture;
 mod unbound_device;
 mod view;
 
 pub(crate) use bound_device::BoundDevice;
-pub(crate) use buffer::{CopyInfo, GPUableBuffer, GPUableBufferStatic, MappableBuffer2};
+pub(crate) use buffer::{
+    CopyInfo, GPUableBuffer, GPUableBufferStatic, MappableBuffer2, MappableReadBuffer,
+};
 pub(crate) use engine::Engine;
 pub(crate) use entry_point::EntryPoint;
 pub(crate) use error::Error;
-pub(crate) use pixel_format::PixelFormat;
+pub(crate) use pixel_format::{OffscreenFormat, PixelFormat};
 pub(crate) use port::Port;
+pub(crate) use render_texture::RenderTexture;
 pub(crate) use texture::RenderSide as TextureRenderSide;
 pub(crate) use texture::{GPUableTexture2, GPUableTexture2Static, MappableTexture2};
 pub(crate) use unbound_device::UnboundDevice;
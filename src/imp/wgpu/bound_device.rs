@@ -1,12 +1,15 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::images::render_pass::{PassDescriptor, PassError, PolygonMode};
 use crate::imp::Error;
 use crate::imp::wgpu::cell::WgpuCell;
 use crate::imp::wgpu::context::smuggle_async;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
 #[cfg(not(target_arch = "wasm32"))]
 use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(not(target_arch = "wasm32"))]
 use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
 #[cfg(not(target_arch = "wasm32"))]
 use std::thread::{self, JoinHandle};
 #[allow(unused_imports)]
@@ -20,6 +23,10 @@ struct BoundDeviceResources {
     pub(super) device: WgpuCell<wgpu::Device>,
     pub(super) queue: WgpuCell<wgpu::Queue>,
     pub(super) adapter: WgpuCell<wgpu::Adapter>,
+    /// The optional features the device was created with.
+    features: wgpu::Features,
+    /// The pipeline cache, if the engine opted into one and the device supports it.
+    pipeline_cache: OnceLock<PipelineCache>,
     #[cfg(not(target_arch = "wasm32"))]
     poll_thread: Option<JoinHandle<()>>,
     #[cfg(not(target_arch = "wasm32"))]
@@ -28,6 +35,28 @@ struct BoundDeviceResources {
     poll_trigger: Sender<()>,
 }
 
+/// A pipeline cache, and the file it is loaded from and saved to.
+#[derive(Debug)]
+struct PipelineCache {
+    cache: WgpuCell<wgpu::PipelineCache>,
+    path: PathBuf,
+}
+
+impl PipelineCache {
+    /// Writes the cache to its file, replacing the file only once the write has finished.
+    fn save(&self) -> std::io::Result<()> {
+        let Some(data) = self.cache.assume(|cache| cache.get_data()) else {
+            return Ok(());
+        };
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let temporary = self.path.with_extension("tmp");
+        std::fs::write(&temporary, &data)?;
+        std::fs::rename(&temporary, &self.path)
+    }
+}
+
 /// Cross-platform bound device that can be safely cloned
 /// Multiple instances share the same underlying GPU resources
 #[derive(Debug, Clone)]
@@ -43,7 +72,7 @@ impl BoundDevice {
         entry_point: Arc<crate::entry_point::EntryPoint>,
     ) -> Result<Self, Error> {
         let move_adapter = unbound_device.0.adapter.clone();
-        let (device, queue) = smuggle_async("create device".to_string(), || async move {
+        let (device, queue, features) = smuggle_async("create device".to_string(), || async move {
             let label = wgpu::Label::from("Bound Device");
             let mut limits = Limits::downlevel_webgl2_defaults();
             //webGL is quite serious about enforcing these, which
@@ -52,27 +81,62 @@ impl BoundDevice {
             limits.max_texture_dimension_1d = 4096;
             limits.max_texture_dimension_2d = 4096;
 
-            let descriptor = wgpu::DeviceDescriptor {
-                label,
-                required_features: Default::default(),
-                //todo: choose better limits?
-                required_limits: limits,
-                memory_hints: Default::default(),
-                trace: Trace::Off,
-                experimental_features: Default::default(),
-            };
-            let (device, queue) = move_adapter
+            let (device, queue, features) = move_adapter
                 .assume_async(|a: &wgpu::Adapter| {
+                    //storage buffers (e.g. reverse buffers) need limits that webGL lacks;
+                    //take whatever the adapter offers, which is still 0 there
+                    let adapter_limits = a.limits();
+                    limits.max_storage_buffers_per_shader_stage =
+                        adapter_limits.max_storage_buffers_per_shader_stage;
+                    limits.max_storage_buffer_binding_size =
+                        adapter_limits.max_storage_buffer_binding_size;
+                    //likewise for compute passes
+                    limits.max_compute_workgroup_storage_size =
+                        adapter_limits.max_compute_workgroup_storage_size;
+                    limits.max_compute_invocations_per_workgroup =
+                        adapter_limits.max_compute_invocations_per_workgroup;
+                    limits.max_compute_workgroup_size_x =
+                        adapter_limits.max_compute_workgroup_size_x;
+                    limits.max_compute_workgroup_size_y =
+                        adapter_limits.max_compute_workgroup_size_y;
+                    limits.max_compute_workgroup_size_z =
+                        adapter_limits.max_compute_workgroup_size_z;
+                    limits.max_compute_workgroups_per_dimension =
+                        adapter_limits.max_compute_workgroups_per_dimension;
+                    //rasterizer modes that passes may opt into, where the adapter has them,
+                    //sample counts beyond the ones WebGPU guarantees, immediate data,
+                    //pipeline caches, and indirect draws starting past instance 0
+                    let features = a.features()
+                        & (wgpu::Features::POLYGON_MODE_LINE
+                            | wgpu::Features::POLYGON_MODE_POINT
+                            | wgpu::Features::DEPTH_CLIP_CONTROL
+                            | wgpu::Features::INDIRECT_FIRST_INSTANCE
+                            | wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES
+                            | wgpu::Features::IMMEDIATES
+                            | wgpu::Features::PIPELINE_CACHE);
+                    if features.contains(wgpu::Features::IMMEDIATES) {
+                        limits.max_immediate_size = adapter_limits.max_immediate_size;
+                    }
+                    let descriptor = wgpu::DeviceDescriptor {
+                        label,
+                        required_features: features,
+                        //todo: choose better limits?
+                        required_limits: limits,
+                        memory_hints: Default::default(),
+                        trace: Trace::Off,
+                        experimental_features: Default::default(),
+                    };
                     let a_clone = a.clone();
                     async move {
-                        a_clone
+                        let (device, queue) = a_clone
                             .request_device(&descriptor)
                             .await
-                            .expect("failed to create device")
+                            .expect("failed to create device");
+                        (device, queue, features)
                     }
                 })
                 .await;
-            (WgpuCell::new(device), WgpuCell::new(queue))
+            (WgpuCell::new(device), WgpuCell::new(queue), features)
         })
         .await;
         #[cfg(not(target_arch = "wasm32"))]
@@ -106,6 +170,8 @@ impl BoundDevice {
                 device,
                 queue,
                 adapter: unbound_device.0.adapter,
+                features,
+                pipeline_cache: OnceLock::new(),
                 poll_thread: Some(poll_thread),
                 poll_shutdown,
                 poll_trigger: poll_sender,
@@ -122,6 +188,8 @@ impl BoundDevice {
                 device,
                 queue,
                 adapter: unbound_device.0.adapter,
+                features,
+                pipeline_cache: OnceLock::new(),
             };
             Ok(BoundDevice {
                 resources: Arc::new(resources),
@@ -172,11 +240,163 @@ impl BoundDevice {
     pub(super) fn adapter(&self) -> &WgpuCell<wgpu::Adapter> {
         &self.resources.adapter
     }
+
+    pub(crate) fn supports_polygon_mode(&self, polygon_mode: PolygonMode) -> bool {
+        match polygon_mode {
+            PolygonMode::Fill => true,
+            PolygonMode::Line => self
+                .resources
+                .features
+                .contains(wgpu::Features::POLYGON_MODE_LINE),
+            PolygonMode::Point => self
+                .resources
+                .features
+                .contains(wgpu::Features::POLYGON_MODE_POINT),
+        }
+    }
+
+    pub(crate) fn supports_unclipped_depth(&self) -> bool {
+        self.resources
+            .features
+            .contains(wgpu::Features::DEPTH_CLIP_CONTROL)
+    }
+
+    pub(crate) fn supports_indirect_first_instance(&self) -> bool {
+        self.resources
+            .features
+            .contains(wgpu::Features::INDIRECT_FIRST_INSTANCE)
+    }
+
+    /// Checks that this device can prepare `descriptor`, so pipeline creation doesn't fail
+    /// on the render thread.
+    pub(crate) fn check_pass(&self, descriptor: &PassDescriptor) -> Result<(), PassError> {
+        descriptor.validate().map_err(|error| PassError::Shader {
+            pass: descriptor.name().to_string(),
+            error,
+        })
+    }
+
+    /// Checks that this device can prepare the compute pass `descriptor`.
+    pub(crate) fn check_compute_pass(
+        &self,
+        descriptor: &ComputePassDescriptor,
+    ) -> Result<(), PassError> {
+        descriptor.validate().map_err(|error| PassError::Shader {
+            pass: descriptor.name().to_string(),
+            error,
+        })
+    }
+
+    /// Loads the pipeline cache for this adapter from `directory`, to create pipelines with.
+    ///
+    /// Does nothing where the device has no pipeline cache.  A missing or invalid file
+    /// starts an empty cache.
+    pub(crate) fn load_pipeline_cache(&self, directory: &Path) {
+        if !self
+            .resources
+            .features
+            .contains(wgpu::Features::PIPELINE_CACHE)
+        {
+            return;
+        }
+        let Some(key) = self
+            .adapter()
+            .assume(|adapter| wgpu::util::pipeline_cache_key(&adapter.get_info()))
+        else {
+            return;
+        };
+        let path = directory.join(key);
+        let data = std::fs::read(&path).ok();
+        let cache = self.device().assume(|device| {
+            //safety: the data was written by this type for an adapter with the same cache key,
+            //and fallback discards it if the driver rejects it
+            unsafe {
+                device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("Pipeline cache"),
+                    data: data.as_deref(),
+                    fallback: true,
+                })
+            }
+        });
+        logwise::info_sync!(
+            "Loaded pipeline cache from {path}",
+            path = logwise::privacy::LogIt(&path)
+        );
+        let cache = PipelineCache {
+            cache: WgpuCell::new(cache),
+            path,
+        };
+        if self.resources.pipeline_cache.set(cache).is_err() {
+            panic!("Already loaded a pipeline cache");
+        }
+    }
+
+    /// Writes the pipeline cache back to disk, if there is one.
+    pub(crate) fn save_pipeline_cache(&self) -> std::io::Result<()> {
+        match self.resources.pipeline_cache.get() {
+            Some(cache) => cache.save(),
+            None => Ok(()),
+        }
+    }
+
+    /// Calls `f` with the pipeline cache to create pipelines with, if there is one.
+    pub(super) fn with_pipeline_cache<R>(
+        &self,
+        f: impl FnOnce(Option<&wgpu::PipelineCache>) -> R,
+    ) -> R {
+        match self.resources.pipeline_cache.get() {
+            Some(cache) => cache.cache.assume(|cache| f(Some(cache))),
+            None => f(None),
+        }
+    }
+
+    /// Whether pipelines can declare `size` bytes of immediate data.
+    pub(super) fn supports_immediates(&self, size: u32) -> bool {
+        self.resources.features.contains(wgpu::Features::IMMEDIATES)
+            && size
+                <= self
+                    .device()
+                    .assume(|device| device.limits().max_immediate_size)
+    }
+
+    /// Whether attachments of `format` can be rendered with `sample_count` samples, and
+    /// resolved if they hold color.
+    pub(super) fn supports_sample_count(
+        &self,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> bool {
+        let features = if self
+            .resources
+            .features
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+        {
+            self.adapter()
+                .assume(|adapter| adapter.get_texture_format_features(format))
+        } else {
+            format.guaranteed_format_features(self.resources.features)
+        };
+        let resolves = format.is_depth_stencil_format()
+            || sample_count == 1
+            || features
+                .flags
+                .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE);
+        features.flags.sample_count_supported(sample_count) && resolves
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl Drop for BoundDeviceResources {
     fn drop(&mut self) {
+        if let Some(cache) = self.pipeline_cache.get()
+            && let Err(error) = cache.save()
+        {
+            logwise::warn_sync!(
+                "Can't save pipeline cache: {error}",
+                error = logwise::privacy::LogIt(&error)
+            );
+        }
+
         // Signal the polling thread to shut down
         self.poll_shutdown.store(true, Ordering::Relaxed);
 
@@ -26,6 +26,9 @@
 //! - Use `copy_from_buffer` when you need guaranteed completion and can transfer ownership
 //! - Use `copy_mappable_to_gpuable_buffer` when batching operations in render pipelines
 //! - The choice depends on whether you need immediate completion vs. batched efficiency
+//!
+//! Reverse buffers copy the other way, with **`copy_to_mappable_read_buffer`**, which records
+//! a GPU-to-staging copy into the render pipeline's encoder.
 
 use crate::bindings::visible_to::GPUBufferUsage;
 use crate::images::BoundDevice;
@@ -45,6 +48,10 @@ pub struct CopyInfo<'a> {
 pub(super) enum StorageType {
     Uniform,
     Storage,
+    /// A storage buffer that shaders may write.
+    StorageReadWrite,
+    /// A storage buffer that shaders may write, holding indirect draw arguments.
+    Indirect,
     Vertex,
     Index,
 }
@@ -138,9 +145,9 @@ impl MappableBuffer2 {
 }
 
 impl crate::bindings::resource_tracking::sealed::Mappable for MappableBuffer2 {
-    // async fn map_read(&mut self) {
-    //     self.map_read().await
-    // }
+    async fn map_read(&mut self) {
+        unreachable!("MappableBuffer2 writes straight to the GPU and is never read back")
+    }
 
     async fn map_write(&mut self) {
         self.map_write().await
@@ -161,6 +168,92 @@ impl AsRef<MappableBuffer2> for MappableBuffer2 {
     }
 }
 
+/**
+The CPU side of a reverse buffer.
+
+Results are copied from the GPU into a `MAP_READ` staging buffer.  Mapping copies them out
+into CPU memory and unmaps the staging buffer right away, so it is free for the next copy
+while the CPU reads.
+*/
+#[derive(Debug)]
+pub struct MappableReadBuffer {
+    bound_device: Arc<BoundDevice>,
+    staging_buffer: WgpuCell<wgpu::Buffer>,
+    data: Vec<u8>,
+}
+
+impl MappableReadBuffer {
+    /// Creates a staging buffer of `size` bytes; `initial` is read until the GPU writes.
+    pub async fn new(
+        bound_device: Arc<BoundDevice>,
+        size: usize,
+        debug_name: &str,
+        initial: Vec<u8>,
+    ) -> Result<Self, crate::imp::Error> {
+        let label = format!("{debug_name}_readback");
+        let move_device = bound_device.clone();
+        let staging_buffer = WgpuCell::new_on_thread(move || async move {
+            move_device.0.device().assume(|device| {
+                device.create_buffer(&BufferDescriptor {
+                    label: Some(&label),
+                    size: size as u64,
+                    usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            })
+        })
+        .await;
+        Ok(MappableReadBuffer {
+            bound_device,
+            staging_buffer,
+            data: initial,
+        })
+    }
+
+    /// The results most recently read back.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub async fn map_read(&mut self) {
+        let (sender, receiver) = r#continue::continuation();
+        self.staging_buffer
+            .with(move |buffer| {
+                buffer.map_async(wgpu::MapMode::Read, .., move |result| sender.send(result));
+            })
+            .await;
+        //for map_async to work, we need to combine with needs_poll
+        self.bound_device.0.set_needs_poll();
+        receiver.await.expect("Failed to map readback buffer");
+        self.data = self
+            .staging_buffer
+            .with(|buffer| {
+                let data = buffer.slice(..).get_mapped_range().to_vec();
+                buffer.unmap();
+                data
+            })
+            .await;
+    }
+
+    pub fn unmap(&mut self) {
+        // No-op: map_read already unmapped the staging buffer
+    }
+}
+
+impl crate::bindings::resource_tracking::sealed::Mappable for MappableReadBuffer {
+    async fn map_read(&mut self) {
+        self.map_read().await
+    }
+
+    async fn map_write(&mut self) {
+        unreachable!("MappableReadBuffer is only written by the GPU")
+    }
+
+    fn unmap(&mut self) {
+        self.unmap();
+    }
+}
+
 /**
 A buffer that holds a GPU device buffer.
 Uses queue.write_buffer() for efficient CPU-to-GPU transfers.
@@ -222,6 +315,21 @@ impl GPUableBuffer {
         logwise::trace_sync!("buffer_copy_data: no-op (write_buffer_with already performed)");
     }
 
+    /// Records a copy of this buffer into `destination`.
+    ///
+    /// The destination must not be mapped until the copy has executed.
+    pub(crate) fn copy_to_mappable_read_buffer(
+        &self,
+        destination: &MappableReadBuffer,
+        command_encoder: &mut CommandEncoder,
+    ) {
+        self.device_buffer.assume(|source| {
+            destination.staging_buffer.assume(|destination| {
+                command_encoder.copy_buffer_to_buffer(source, 0, destination, 0, source.size());
+            })
+        });
+    }
+
     /// Creates a new GPUableBuffer with initial data using mapped_at_creation.
     ///
     /// This is the most efficient way to create a buffer with initial data,
@@ -236,7 +344,9 @@ impl GPUableBuffer {
         let debug_name = debug_name.to_string();
         let move_bound_device = bound_device.clone();
         let storage_type = smuggle("create buffer with data".to_string(), move || match usage {
-            GPUBufferUsage::VertexShaderRead | GPUBufferUsage::FragmentShaderRead => {
+            GPUBufferUsage::VertexShaderRead
+            | GPUBufferUsage::FragmentShaderRead
+            | GPUBufferUsage::ComputeShaderRead => {
                 if move_bound_device
                     .0
                     .device()
@@ -251,17 +361,58 @@ impl GPUableBuffer {
             }
             GPUBufferUsage::VertexBuffer => StorageType::Vertex,
             GPUBufferUsage::Index => StorageType::Index,
+            GPUBufferUsage::ShaderReadWrite => StorageType::StorageReadWrite,
+            GPUBufferUsage::Indirect => StorageType::Indirect,
         })
         .await;
 
         let device_usage = BufferUsages::COPY_DST
             | match storage_type {
                 StorageType::Uniform => BufferUsages::UNIFORM,
-                StorageType::Storage => BufferUsages::STORAGE,
+                StorageType::Storage | StorageType::StorageReadWrite => BufferUsages::STORAGE,
                 StorageType::Vertex => BufferUsages::VERTEX,
                 StorageType::Index => BufferUsages::INDEX,
+                StorageType::Indirect => BufferUsages::INDIRECT | BufferUsages::STORAGE,
             };
+        Self::new_with_usage(
+            bound_device,
+            size,
+            storage_type,
+            device_usage,
+            &debug_name,
+            initializer,
+        )
+        .await
+    }
+
+    /// Creates a new storage buffer that shaders write into, with initial data.
+    ///
+    /// The buffer can be copied to a [`MappableReadBuffer`] to read the results back.
+    pub(crate) async fn new_gpu_writable<I: FnOnce(&mut [std::mem::MaybeUninit<u8>]) -> &[u8]>(
+        bound_device: Arc<crate::images::BoundDevice>,
+        size: usize,
+        debug_name: &str,
+        initializer: I,
+    ) -> Self {
+        Self::new_with_usage(
+            bound_device,
+            size,
+            StorageType::Storage,
+            BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+            debug_name,
+            initializer,
+        )
+        .await
+    }
 
+    async fn new_with_usage<I: FnOnce(&mut [std::mem::MaybeUninit<u8>]) -> &[u8]>(
+        bound_device: Arc<crate::images::BoundDevice>,
+        size: usize,
+        storage_type: StorageType,
+        device_usage: BufferUsages,
+        debug_name: &str,
+        initializer: I,
+    ) -> Self {
         // Prepare data for initialization
         let mut data = vec![std::mem::MaybeUninit::uninit(); size];
         let data_ptr = data.as_ptr();
@@ -378,7 +529,9 @@ impl GPUableBufferStatic {
         let storage_type = smuggle(
             "create static buffer with data".to_string(),
             move || match usage {
-                GPUBufferUsage::VertexShaderRead | GPUBufferUsage::FragmentShaderRead => {
+                GPUBufferUsage::VertexShaderRead
+                | GPUBufferUsage::FragmentShaderRead
+                | GPUBufferUsage::ComputeShaderRead => {
                     if move_bound_device
                         .0
                         .device()
@@ -393,6 +546,8 @@ impl GPUableBufferStatic {
                 }
                 GPUBufferUsage::VertexBuffer => StorageType::Vertex,
                 GPUBufferUsage::Index => StorageType::Index,
+                GPUBufferUsage::ShaderReadWrite => StorageType::StorageReadWrite,
+                GPUBufferUsage::Indirect => StorageType::Indirect,
             },
         )
         .await;
@@ -400,9 +555,10 @@ impl GPUableBufferStatic {
         let device_usage = BufferUsages::COPY_DST
             | match storage_type {
                 StorageType::Uniform => BufferUsages::UNIFORM,
-                StorageType::Storage => BufferUsages::STORAGE,
+                StorageType::Storage | StorageType::StorageReadWrite => BufferUsages::STORAGE,
                 StorageType::Vertex => BufferUsages::VERTEX,
                 StorageType::Index => BufferUsages::INDEX,
+                StorageType::Indirect => BufferUsages::INDIRECT | BufferUsages::STORAGE,
             };
 
         let device_debug_name = format!("{debug_name}_static_with_data");
@@ -7,6 +7,8 @@ pub enum Error {
     CreateSurface(#[from] wgpu::CreateSurfaceError),
     NoSuchAdapter,
     RequestDevice(#[from] wgpu::RequestDeviceError),
+    MapBuffer(#[from] wgpu::BufferAsyncError),
+    UnsupportedCaptureFormat(wgpu::TextureFormat),
 }
 
 impl Display for Error {
@@ -15,6 +17,10 @@ impl Display for Error {
             Error::CreateSurface(e) => write!(f, "{e}"),
             Error::NoSuchAdapter => write!(f, "No such adapter"),
             Error::RequestDevice(e) => write!(f, "{e}"),
+            Error::MapBuffer(e) => write!(f, "{e}"),
+            Error::UnsupportedCaptureFormat(format) => {
+                write!(f, "Frames in {format:?} can't be captured")
+            }
         }
     }
 }
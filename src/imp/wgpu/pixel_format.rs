@@ -8,6 +8,18 @@ pub trait PixelFormat {
     const WGPU_FORMAT: wgpu::TextureFormat;
 }
 
+/**
+The format of an offscreen render target.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffscreenFormat(pub(super) wgpu::TextureFormat);
+
+impl OffscreenFormat {
+    pub fn new<Format: crate::pixel_formats::sealed::PixelFormat>() -> Self {
+        OffscreenFormat(Format::WGPU_FORMAT)
+    }
+}
+
 impl PixelFormat for R8UNorm {
     const WGPU_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
 }
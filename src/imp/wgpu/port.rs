@@ -1,16 +1,20 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
-#[cfg(feature = "exfiltrate")]
-mod debug_capture;
+mod capture;
+pub mod globals;
 pub mod guards;
+pub mod immediates;
 pub mod internal;
+pub mod prepared_compute_pass;
 pub mod prepared_pass;
 pub mod types;
 
 use crate::images::camera::Camera;
-use crate::images::port::PortReporterSend;
-use crate::images::render_pass::PassDescriptor;
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::images::port::{FramebufferData, PortReporterSend};
+use crate::images::render_pass::{LoadOp, PassList};
 use crate::imp::Error;
 use crate::imp::wgpu::context::smuggle_async;
+use crate::pixel_formats::Float4;
 use internal::PortInternal;
 use std::sync::Arc;
 use wasm_safe_thread::Mutex;
@@ -36,13 +40,19 @@ impl Port {
         })
     }
 
-    pub async fn add_fixed_pass(&self, descriptor: PassDescriptor) {
+    pub async fn set_passes(&self, passes: PassList) {
         let mut guard = self.internal.lock_async().await;
         let internal = (*guard).as_mut().expect("Port internal missing");
-        internal.add_fixed_pass(descriptor).await;
+        internal.set_passes(passes).await;
     }
 
-    pub async fn render_frame(&self) {
+    pub async fn set_compute_passes(&self, descriptors: Vec<ComputePassDescriptor>) {
+        let mut guard = self.internal.lock_async().await;
+        let internal = (*guard).as_mut().expect("Port internal missing");
+        internal.set_compute_passes(descriptors).await;
+    }
+
+    pub async fn render_frame(&self, capture: bool) -> Option<Result<FramebufferData, Error>> {
         //logwise::info_sync!("Rendering frame...");
         let internal = self
             .internal
@@ -50,10 +60,56 @@ impl Port {
             .await
             .take()
             .expect("Port internal missing");
-        let internal = smuggle_async("render_frame".to_string(), || async move {
-            internal.render_frame().await
+        let (internal, capture) = smuggle_async("render_frame".to_string(), move || async move {
+            internal.render_frame(capture).await
+        })
+        .await;
+        *self.internal.lock_async().await = Some(internal);
+        capture
+    }
+
+    pub async fn set_color_load(&self, load: LoadOp<Float4>) {
+        let mut guard = self.internal.lock_async().await;
+        let internal = (*guard).as_mut().expect("Port internal missing");
+        internal.color_load = load;
+    }
+
+    pub async fn set_depth_load(&self, load: LoadOp<f32>) {
+        let mut guard = self.internal.lock_async().await;
+        let internal = (*guard).as_mut().expect("Port internal missing");
+        internal.depth_load = Some(load);
+    }
+
+    pub async fn set_sample_count(&self, sample_count: u32) -> u32 {
+        let mut internal = self
+            .internal
+            .lock_async()
+            .await
+            .take()
+            .expect("Port internal missing");
+        let (internal, sample_count) =
+            smuggle_async("set_sample_count".to_string(), move || async move {
+                let sample_count = internal.set_sample_count(sample_count);
+                (internal, sample_count)
+            })
+            .await;
+        *self.internal.lock_async().await = Some(internal);
+        sample_count
+    }
+
+    pub async fn read_framebuffer(&self) -> Result<FramebufferData, Error> {
+        let internal = self
+            .internal
+            .lock_async()
+            .await
+            .take()
+            .expect("Port internal missing");
+        let (internal, result) = smuggle_async("read_framebuffer".to_string(), || async move {
+            let result = internal.read_framebuffer().await;
+            (internal, result)
         })
         .await;
         *self.internal.lock_async().await = Some(internal);
+        result
     }
 }
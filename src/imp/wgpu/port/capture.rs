@@ -0,0 +1,319 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Copying rendered frames back to the CPU.
+
+use crate::bindings::software::texture::linear_to_srgb;
+use crate::images::port::FramebufferData;
+use crate::imp::Error;
+use wgpu::{CommandEncoder, TextureFormat};
+
+/// Copies a depth texture into an `Rgba8Unorm` texture, with a render pass.
+///
+/// Many devices (including WebGL) can't copy depth textures to buffers, or render to float
+/// textures, but every device can sample depth and render `Rgba8Unorm`.  So each texel holds
+/// the bits of the sampled `f32`, little-endian.
+pub(super) fn depth_to_float(
+    device: &wgpu::Device,
+    encoder: &mut CommandEncoder,
+    depth: &wgpu::Texture,
+) -> wgpu::Texture {
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("depth readback"),
+        source: wgpu::ShaderSource::Wgsl(
+            //texture_depth_2d can't be loaded on GL, so bind it as an unfilterable float texture
+            "@group(0) @binding(0) var depth: texture_2d<f32>;
+            @vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+                let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+                return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+            }
+            @fragment fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+                let bits = bitcast<u32>(textureLoad(depth, vec2<i32>(position.xy), 0).r);
+                let bytes = vec4<u32>(bits, bits >> 8u, bits >> 16u, bits >> 24u) & vec4<u32>(0xffu);
+                return vec4<f32>(bytes) / 255.0;
+            }"
+            .into(),
+        ),
+    });
+    let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("depth readback"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        }],
+    });
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("depth readback"),
+        bind_group_layouts: &[&bind_group_layout],
+        immediate_size: 0,
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("depth readback"),
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &module,
+            entry_point: None,
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        primitive: Default::default(),
+        depth_stencil: None,
+        multisample: Default::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: &module,
+            entry_point: None,
+            compilation_options: Default::default(),
+            targets: &[Some(TextureFormat::Rgba8Unorm.into())],
+        }),
+        multiview_mask: None,
+        cache: None,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("depth readback"),
+        layout: &bind_group_layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: wgpu::BindingResource::TextureView(
+                //stencil formats can only be sampled through their depth aspect
+                &depth.create_view(&wgpu::TextureViewDescriptor {
+                    aspect: wgpu::TextureAspect::DepthOnly,
+                    ..Default::default()
+                }),
+            ),
+        }],
+    });
+    let output = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("depth readback"),
+        size: depth.size(),
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let output_view = output.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("depth readback"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &output_view,
+            depth_slice: None,
+            resolve_target: None,
+            ops: Default::default(),
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+        multiview_mask: None,
+    });
+    render_pass.set_pipeline(&pipeline);
+    render_pass.set_bind_group(0, &bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+    drop(render_pass);
+    output
+}
+
+/// A buffer that a texture has been copied into, with rows padded to wgpu's alignment.
+#[derive(Debug)]
+pub(super) struct Readback {
+    buffer: wgpu::Buffer,
+    unpadded_bytes_per_row: u32,
+    padded_bytes_per_row: u32,
+    height: u32,
+}
+
+impl Readback {
+    pub(super) fn encode(
+        device: &wgpu::Device,
+        encoder: &mut CommandEncoder,
+        texture: &wgpu::Texture,
+        bytes_per_pixel: u32,
+    ) -> Self {
+        let unpadded_bytes_per_row = texture.width() * bytes_per_pixel;
+        let padded_bytes_per_row =
+            unpadded_bytes_per_row.next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("framebuffer readback"),
+            size: (padded_bytes_per_row * texture.height()) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        Readback {
+            buffer,
+            unpadded_bytes_per_row,
+            padded_bytes_per_row,
+            height: texture.height(),
+        }
+    }
+
+    /// Maps the buffer and returns its rows without padding.
+    pub(super) async fn read(
+        self,
+        bound_device: &crate::images::BoundDevice,
+    ) -> Result<Vec<u8>, Error> {
+        let (sender, receiver) = r#continue::continuation();
+        self.buffer
+            .map_async(wgpu::MapMode::Read, .., move |result| sender.send(result));
+        //for map_async to work, we need to combine with needs_poll
+        bound_device.0.set_needs_poll();
+        receiver.await?;
+        let mapped = self.buffer.slice(..).get_mapped_range();
+        let mut bytes = Vec::with_capacity((self.unpadded_bytes_per_row * self.height) as usize);
+        for row in mapped.chunks_exact(self.padded_bytes_per_row as usize) {
+            bytes.extend_from_slice(&row[..self.unpadded_bytes_per_row as usize]);
+        }
+        drop(mapped);
+        self.buffer.unmap();
+        Ok(bytes)
+    }
+}
+
+/// The copies of one frame's attachments, made for [`Port::capture_next_frame`].
+///
+/// [`Port::capture_next_frame`]: crate::images::port::Port::capture_next_frame
+#[derive(Debug)]
+pub struct FrameCapture {
+    color: Readback,
+    color_format: TextureFormat,
+    width: u32,
+    height: u32,
+    depth: Option<Readback>,
+}
+
+impl FrameCapture {
+    /// Encodes copies of `color` and, if given, `depth`.
+    ///
+    /// `color` needs `COPY_SRC` usage and `depth` needs `TEXTURE_BINDING` usage.
+    pub(super) fn encode(
+        device: &wgpu::Device,
+        encoder: &mut CommandEncoder,
+        color: &wgpu::Texture,
+        depth: Option<&wgpu::Texture>,
+    ) -> Self {
+        let bytes_per_pixel = color
+            .format()
+            .block_copy_size(None)
+            .expect("Color format has a single aspect");
+        FrameCapture {
+            color: Readback::encode(device, encoder, color, bytes_per_pixel),
+            color_format: color.format(),
+            width: color.width(),
+            height: color.height(),
+            depth: depth.map(|depth| {
+                let depth = depth_to_float(device, encoder, depth);
+                Readback::encode(device, encoder, &depth, 4)
+            }),
+        }
+    }
+
+    /// Waits for the copies, after the encoder has been submitted.
+    pub(super) async fn read(
+        self,
+        bound_device: &crate::images::BoundDevice,
+    ) -> Result<FramebufferData, Error> {
+        let color = to_srgba8(self.color_format, &self.color.read(bound_device).await?)?;
+        let depth = match self.depth {
+            Some(depth) => Some(read_depth(depth, bound_device).await?),
+            None => None,
+        };
+        Ok(FramebufferData {
+            width: self.width as u16,
+            height: self.height as u16,
+            color,
+            depth,
+        })
+    }
+}
+
+/// Reads back a texture written by [`depth_to_float`].
+pub(super) async fn read_depth(
+    readback: Readback,
+    bound_device: &crate::images::BoundDevice,
+) -> Result<Vec<f32>, Error> {
+    Ok(readback
+        .read(bound_device)
+        .await?
+        .chunks_exact(4)
+        .map(|d| f32::from_le_bytes([d[0], d[1], d[2], d[3]]))
+        .collect())
+}
+
+fn unorm8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn f32_at(bytes: &[u8], index: usize) -> f32 {
+    f32::from_le_bytes(bytes[index * 4..index * 4 + 4].try_into().unwrap())
+}
+
+fn f16_at(bytes: &[u8], index: usize) -> f32 {
+    half::f16::from_le_bytes([bytes[index * 2], bytes[index * 2 + 1]]).to_f32()
+}
+
+/// Encodes linear RGBA as 8-bit sRGB.
+fn linear(color: [f32; 4]) -> [u8; 4] {
+    let [r, g, b, a] = color;
+    let srgb = |c: f32| unorm8(linear_to_srgb(c.clamp(0.0, 1.0)));
+    [srgb(r), srgb(g), srgb(b), unorm8(a)]
+}
+
+/// Converts tightly-packed texels to 8-bit sRGB RGBA.
+///
+/// Normalized formats are assumed to hold display values already, so they are copied.
+/// Float formats hold linear values, which are encoded.  This matches the software backend.
+fn to_srgba8(format: TextureFormat, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    use TextureFormat::*;
+    let convert: fn(&[u8]) -> [u8; 4] = match format {
+        Rgba8Unorm | Rgba8UnormSrgb => |b| [b[0], b[1], b[2], b[3]],
+        Bgra8Unorm | Bgra8UnormSrgb => |b| [b[2], b[1], b[0], b[3]],
+        R8Unorm => |b| [b[0], 0, 0, 255],
+        Rgba16Unorm => |b| {
+            std::array::from_fn(|c| {
+                unorm8(u16::from_le_bytes([b[c * 2], b[c * 2 + 1]]) as f32 / 65535.0)
+            })
+        },
+        Rgb10a2Unorm => |b| {
+            let bits = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            let channel = |shift: u32| unorm8(((bits >> shift) & 0x3ff) as f32 / 1023.0);
+            [
+                channel(0),
+                channel(10),
+                channel(20),
+                unorm8((bits >> 30) as f32 / 3.0),
+            ]
+        },
+        Rgba16Float => |b| linear(std::array::from_fn(|c| f16_at(b, c))),
+        Rgba32Float => |b| linear(std::array::from_fn(|c| f32_at(b, c))),
+        R16Float => |b| linear([f16_at(b, 0), 0.0, 0.0, 1.0]),
+        R32Float => |b| linear([f32_at(b, 0), 0.0, 0.0, 1.0]),
+        Rg32Float => |b| linear([f32_at(b, 0), f32_at(b, 1), 0.0, 1.0]),
+        R32Sint => |b| {
+            let value = i32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            linear([value as f32, 0.0, 0.0, 1.0])
+        },
+        _ => return Err(Error::UnsupportedCaptureFormat(format)),
+    };
+    let bytes_per_pixel = format
+        .block_copy_size(None)
+        .expect("Color format has a single aspect") as usize;
+    Ok(bytes
+        .chunks_exact(bytes_per_pixel)
+        .flat_map(convert)
+        .collect())
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::bindings::forward::dynamic::buffer::Buffer;
+use crate::images::port::perf::Instant;
+use crate::imp;
+use crate::imp::wgpu::cell::WgpuCell;
+use std::collections::HashMap;
+use std::num::NonZero;
+use std::sync::Arc;
+use wgpu::{BindGroupLayoutEntry, BindingType, BufferBindingType};
+
+use super::guards::{AcquiredGuards, acquire_camera};
+use super::types::CameraProjection;
+
+/// The binding of the camera in the global group.
+const CAMERA_BINDING: u32 = 0;
+/// The binding of the frame counter in the global group.
+const FRAME_BINDING: u32 = 1;
+/// The binding of the time in the global group.
+const TIME_BINDING: u32 = 2;
+
+/// Uniform buffers are allocated in multiples of 16 bytes, as std140 would lay them out.
+const SCALAR_BUFFER_SIZE: u64 = 16;
+
+/**
+Resources the port shares with all its passes.
+
+The camera, frame counter and time are each written once per frame.  Passes may bind
+them individually, or all at once through the global group, which is created once and
+bound once for every pass that uses it.
+*/
+#[derive(Debug)]
+pub struct Globals {
+    pub camera_buffer: Buffer<CameraProjection>,
+    /// Holds the frame counter, as a `u32`.
+    pub frame_buffer: WgpuCell<wgpu::Buffer>,
+    /// Holds the seconds since the port was created, as an `f32`.
+    pub time_buffer: WgpuCell<wgpu::Buffer>,
+    pub mipmapped_sampler: WgpuCell<wgpu::Sampler>,
+    /// The layout of the global group, shared by every pipeline that binds it.
+    pub layout: WgpuCell<wgpu::BindGroupLayout>,
+    /// The global group, created with the first frame.
+    pub bind_group: Option<WgpuCell<wgpu::BindGroup>>,
+    /// Keeps the camera alive for the frame being encoded.
+    pub acquired_guards: Option<AcquiredGuards>,
+    start: Instant,
+}
+
+impl Globals {
+    pub fn new(
+        bind_device: &crate::images::BoundDevice,
+        camera_buffer: Buffer<CameraProjection>,
+        mipmapped_sampler: WgpuCell<wgpu::Sampler>,
+    ) -> Self {
+        let (frame_buffer, time_buffer, layout) = bind_device.0.device().assume(|device| {
+            let scalar_buffer = |label| {
+                WgpuCell::new(device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some(label),
+                    size: SCALAR_BUFFER_SIZE,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }))
+            };
+            let uniform = |binding, size| BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT | wgpu::ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZero::new(size),
+                },
+                count: None,
+            };
+            let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Global group"),
+                entries: &[
+                    uniform(
+                        CAMERA_BINDING,
+                        std::mem::size_of::<CameraProjection>() as u64,
+                    ),
+                    uniform(FRAME_BINDING, 4),
+                    uniform(TIME_BINDING, 4),
+                ],
+            });
+            (
+                scalar_buffer("Frame counter"),
+                scalar_buffer("Time"),
+                WgpuCell::new(layout),
+            )
+        });
+        Globals {
+            camera_buffer,
+            frame_buffer,
+            time_buffer,
+            mipmapped_sampler,
+            layout,
+            bind_group: None,
+            acquired_guards: None,
+            start: Instant::now(),
+        }
+    }
+
+    /// Writes the frame counter and time for `frame`, and copies the camera if it changed.
+    pub async fn prepare_frame(
+        &mut self,
+        bind_device: &crate::images::BoundDevice,
+        frame: u32,
+        copy_info: &mut imp::CopyInfo<'_>,
+    ) {
+        let time = Instant::now().duration_since(&self.start).as_secs_f32();
+        bind_device.0.queue().assume(|queue| {
+            self.frame_buffer.assume(|buffer| {
+                queue.write_buffer(buffer, 0, &frame.to_ne_bytes());
+            });
+            self.time_buffer.assume(|buffer| {
+                queue.write_buffer(buffer, 0, &time.to_ne_bytes());
+            });
+        });
+
+        let camera_guard = Arc::new(acquire_camera(&self.camera_buffer, copy_info).await);
+        if self.bind_group.is_none() {
+            let camera = camera_guard.underlying_guard.as_imp().buffer().clone();
+            let bind_group = bind_device.0.device().assume(|device| {
+                camera.assume(|camera| {
+                    self.frame_buffer.assume(|frame| {
+                        self.time_buffer.assume(|time| {
+                            self.layout.assume(|layout| {
+                                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                                    label: Some("Global group"),
+                                    layout,
+                                    entries: &[
+                                        wgpu::BindGroupEntry {
+                                            binding: CAMERA_BINDING,
+                                            resource: camera.as_entire_binding(),
+                                        },
+                                        wgpu::BindGroupEntry {
+                                            binding: FRAME_BINDING,
+                                            resource: frame.as_entire_binding(),
+                                        },
+                                        wgpu::BindGroupEntry {
+                                            binding: TIME_BINDING,
+                                            resource: time.as_entire_binding(),
+                                        },
+                                    ],
+                                })
+                            })
+                        })
+                    })
+                })
+            });
+            self.bind_group = Some(WgpuCell::new(bind_group));
+        }
+        self.acquired_guards = Some(AcquiredGuards {
+            buffer_guards: HashMap::new(),
+            texture_guards: HashMap::new(),
+            camera_guard: Some(camera_guard),
+            index_guard: None,
+        });
+    }
+}
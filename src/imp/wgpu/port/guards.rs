@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
-use crate::bindings::bind_style::BindTarget;
-use crate::bindings::forward::dynamic::buffer::{Buffer, SomeRenderSide};
+use crate::bindings::bind_style::{BindSlot, BindTarget, IndexBinding, IndexFormat, IndexTarget};
+use crate::bindings::forward::dynamic::buffer::{
+    Buffer, ErasedRenderSide, GPUAccess, SomeRenderSide,
+};
 use crate::bindings::sampler::SamplerType;
 use crate::imp;
 use crate::imp::wgpu::cell::WgpuCell;
@@ -10,6 +12,7 @@ use std::num::NonZero;
 use std::sync::Arc;
 use wgpu::{BindGroup, BindGroupEntry, BindingResource, BufferBinding};
 
+use super::globals::Globals;
 use super::types::CameraProjection;
 
 /**
@@ -17,12 +20,13 @@ Guards and resources acquired during the copy phase.
 */
 #[derive(Debug)]
 pub struct AcquiredGuards {
-    // Combined buffer and vertex buffer guards, keyed by bind index
-    pub buffer_guards: HashMap<u32, Arc<crate::bindings::forward::dynamic::buffer::GPUAccess>>,
-    // Texture guards, keyed by bind index
+    // Combined buffer and vertex buffer guards, keyed by bind slot
+    pub buffer_guards: HashMap<BindSlot, Arc<crate::bindings::forward::dynamic::buffer::GPUAccess>>,
+    // Texture guards, keyed by bind slot
     pub texture_guards:
-        HashMap<u32, Arc<crate::bindings::forward::dynamic::frame_texture::GPUAccess>>,
+        HashMap<BindSlot, Arc<crate::bindings::forward::dynamic::frame_texture::GPUAccess>>,
     pub camera_guard: Option<Arc<crate::bindings::forward::dynamic::buffer::GPUAccess>>,
+    pub index_guard: Option<Arc<crate::bindings::forward::dynamic::buffer::GPUAccess>>,
 }
 
 impl AcquiredGuards {
@@ -42,75 +46,22 @@ impl AcquiredGuards {
         for (bind_index, info) in &bind_style.binds {
             logwise::trace_sync!(
                 "Acquiring target {bind_index} {info}",
-                bind_index = *bind_index,
+                bind_index = logwise::privacy::LogIt(bind_index),
                 info = logwise::privacy::LogIt(info)
             );
             match &info.target {
                 BindTarget::DynamicBuffer(buf) => {
-                    // Safety: Keep the guard alive
-                    let mut gpu_access = unsafe { buf.imp.acquire_gpu_buffer() };
-
-                    // Handle the copy if there's a dirty guard
-                    if let Some(mut dirty_guard) = gpu_access.take_dirty_guard() {
-                        // Get the source buffer from the dirty guard
-                        let source: &mut imp::MappableBuffer2 = &mut dirty_guard;
-
-                        // Perform the copy operation using the new GPUableBuffer2 method
-                        gpu_access
-                            .underlying_guard
-                            .as_imp()
-                            .copy_from_mappable_buffer2(source, copy_info.command_encoder)
-                            .await;
-                        // Drop dirty_guard immediately after write_buffer completes.
-                        // This releases the CPU buffer back to UNUSED state, allowing
-                        // the producer to start writing the next frame's data.
-                        drop(dirty_guard);
-                    }
-
+                    let gpu_access = acquire_dynamic_buffer(buf, copy_info).await;
                     buffer_guards.insert(*bind_index, Arc::new(gpu_access));
                 }
 
                 BindTarget::Camera => {
-                    // Safety: Keep the guard alive
-                    let mut gpu_access =
-                        unsafe { camera_buffer.render_side().acquire_gpu_buffer() };
-
-                    // Handle the copy if there's a dirty guard
-                    if let Some(mut dirty_guard) = gpu_access.take_dirty_guard() {
-                        // Get the source buffer from the dirty guard
-                        let source: &mut imp::MappableBuffer2 = &mut dirty_guard;
-
-                        // Perform the copy operation using the new GPUableBuffer2 method
-                        gpu_access
-                            .underlying_guard
-                            .as_imp()
-                            .copy_from_mappable_buffer2(source, copy_info.command_encoder)
-                            .await;
-                        // Drop dirty_guard immediately after write_buffer completes.
-                        drop(dirty_guard);
-                    }
+                    let gpu_access = acquire_camera(camera_buffer, copy_info).await;
                     camera_guard = Some(Arc::new(gpu_access));
                 }
 
-                BindTarget::DynamicVB(_layout, render_side) => {
-                    // Safety: guard kept alive
-                    let mut gpu_access = unsafe { render_side.imp.acquire_gpu_buffer() };
-
-                    // Handle the copy if there's a dirty guard
-                    if let Some(mut dirty_guard) = gpu_access.take_dirty_guard() {
-                        // Get the source buffer from the dirty guard
-                        let source: &mut imp::MappableBuffer2 = &mut dirty_guard;
-
-                        // Perform the copy operation using the new GPUableBuffer2 method
-                        gpu_access
-                            .underlying_guard
-                            .as_imp()
-                            .copy_from_mappable_buffer2(source, copy_info.command_encoder)
-                            .await;
-                        // Drop dirty_guard immediately after write_buffer completes.
-                        drop(dirty_guard);
-                    }
-
+                BindTarget::DynamicVB(_layout, _step_mode, render_side) => {
+                    let gpu_access = acquire_dynamic_buffer(render_side, copy_info).await;
                     buffer_guards.insert(*bind_index, Arc::new(gpu_access));
                 }
                 BindTarget::DynamicTexture(texture) => {
@@ -139,21 +90,85 @@ impl AcquiredGuards {
             }
         }
 
+        let index_guard = match &bind_style.index_buffer {
+            Some(IndexBinding {
+                target: IndexTarget::Dynamic(render_side),
+                ..
+            }) => Some(Arc::new(
+                acquire_dynamic_buffer(render_side, copy_info).await,
+            )),
+            _ => None,
+        };
+
         AcquiredGuards {
             buffer_guards,
             camera_guard,
             texture_guards,
+            index_guard,
         }
     }
 }
 
+/// Acquires the camera for this frame, copying the projection if it changed.
+pub(super) async fn acquire_camera(
+    camera_buffer: &Buffer<CameraProjection>,
+    copy_info: &mut imp::CopyInfo<'_>,
+) -> GPUAccess {
+    // Safety: the caller keeps the guard alive
+    let mut gpu_access = unsafe { camera_buffer.render_side().acquire_gpu_buffer() };
+
+    // Handle the copy if there's a dirty guard
+    if let Some(mut dirty_guard) = gpu_access.take_dirty_guard() {
+        // Get the source buffer from the dirty guard
+        let source: &mut imp::MappableBuffer2 = &mut dirty_guard;
+
+        // Perform the copy operation using the new GPUableBuffer2 method
+        gpu_access
+            .underlying_guard
+            .as_imp()
+            .copy_from_mappable_buffer2(source, copy_info.command_encoder)
+            .await;
+        // Drop dirty_guard immediately after write_buffer completes.
+        drop(dirty_guard);
+    }
+    gpu_access
+}
+
+/// Acquires a dynamic buffer for this frame, copying any pending CPU writes to the GPU.
+async fn acquire_dynamic_buffer(
+    render_side: &ErasedRenderSide,
+    copy_info: &mut imp::CopyInfo<'_>,
+) -> GPUAccess {
+    // Safety: the caller keeps the guard alive
+    let mut gpu_access = unsafe { render_side.imp.acquire_gpu_buffer() };
+
+    // Handle the copy if there's a dirty guard
+    if let Some(mut dirty_guard) = gpu_access.take_dirty_guard() {
+        // Get the source buffer from the dirty guard
+        let source: &mut imp::MappableBuffer2 = &mut dirty_guard;
+
+        // Perform the copy operation using the new GPUableBuffer2 method
+        gpu_access
+            .underlying_guard
+            .as_imp()
+            .copy_from_mappable_buffer2(source, copy_info.command_encoder)
+            .await;
+        // Drop dirty_guard immediately after write_buffer completes.
+        // This releases the CPU buffer back to UNUSED state, allowing
+        // the producer to start writing the next frame's data.
+        drop(dirty_guard);
+    }
+    gpu_access
+}
+
 /**
-Wrapper type that contains the bind group
+Wrapper type that contains the bind groups
 and all guards that are needed to keep the resources alive.
 */
 #[derive(Debug, Clone)]
 pub struct BindGroupGuard {
-    pub bind_group: WgpuCell<BindGroup>,
+    /// The pass's own bind groups, by group.  The global group is not included.
+    pub bind_groups: Vec<(u32, WgpuCell<BindGroup>)>,
     #[allow(dead_code)] // guards keep resources alive during GPU execution
     pub guards: Vec<Arc<crate::bindings::forward::dynamic::buffer::GPUAccess>>,
     pub _guards_textures: Vec<Arc<crate::bindings::forward::dynamic::frame_texture::GPUAccess>>,
@@ -162,7 +177,11 @@ pub struct BindGroupGuard {
         u32,
         Arc<crate::bindings::forward::dynamic::buffer::GPUAccess>,
     )>,
-    pub index_buffer: Option<WgpuCell<wgpu::Buffer>>,
+    pub index_buffer: Option<(WgpuCell<wgpu::Buffer>, wgpu::IndexFormat)>,
+    #[allow(dead_code)] // guards keep resources alive during GPU execution
+    pub index_guard: Option<Arc<crate::bindings::forward::dynamic::buffer::GPUAccess>>,
+    /// The buffer indirect draws read their arguments from.
+    pub indirect_buffer: Option<WgpuCell<wgpu::Buffer>>,
 }
 
 impl BindGroupGuard {
@@ -171,12 +190,12 @@ impl BindGroupGuard {
         bind_device: &crate::images::BoundDevice,
         bind_style: &crate::bindings::bind_style::BindStyle,
         name: &str,
-        bind_group_layout: &wgpu::BindGroupLayout,
-        mipmapped_sampler: &WgpuCell<wgpu::Sampler>,
+        bind_group_layouts: &[(u32, wgpu::BindGroupLayout)],
+        globals: &Globals,
         acquired_guards: &mut AcquiredGuards,
         _copy_info: &mut imp::CopyInfo,
     ) -> Self {
-        let mut entries = Vec::new();
+        let mut entries: HashMap<u32, Vec<BindGroupEntry>> = HashMap::new();
         //these need to be kept alive during GPU execution
         let build_dynamic_buffers_gpu = StableAddressVec::with_capactiy(5);
         let build_dynamic_textures_gpu = StableAddressVec::with_capactiy(5);
@@ -191,14 +210,16 @@ impl BindGroupGuard {
         let camera_buffers = StableAddressVec::with_capactiy(5);
 
         let sampler_guards = StableAddressVec::with_capactiy(5);
+        let frame_buffer = globals.frame_buffer.assume(|buffer| buffer.clone());
+        let time_buffer = globals.time_buffer.assume(|buffer| buffer.clone());
 
-        for (pass_index, info) in &bind_style.binds {
+        for (slot, info) in &bind_style.binds {
             let resource = match &info.target {
                 BindTarget::DynamicBuffer(buf) => {
                     // Remove the guard from the acquired guards map
                     let build_buffer = acquired_guards
                         .buffer_guards
-                        .remove(pass_index)
+                        .remove(slot)
                         .expect("Dynamic buffer guard should be in acquired_guards");
                     let guard = build_dynamic_buffers_gpu.push(build_buffer);
                     let clone_buffer = clone_buffers.push(
@@ -224,6 +245,20 @@ impl BindGroupGuard {
                         size: Some(NonZero::new(stored_buffer.size()).unwrap()),
                     })
                 }
+                BindTarget::ReverseBuffer(render_side) => {
+                    //safe because the copy back to the CPU is sequenced after the pass, see finish_render_frame
+                    let clone_buffer = clone_buffers.push(unsafe {
+                        render_side
+                            .unsafe_imp()
+                            .buffer()
+                            .assume(|wgpu_guard| wgpu_guard.clone())
+                    });
+                    BindingResource::Buffer(BufferBinding {
+                        buffer: clone_buffer,
+                        offset: 0,
+                        size: Some(NonZero::new(render_side.byte_size as u64).unwrap()),
+                    })
+                }
                 BindTarget::Camera => {
                     let gpu_buffer = acquired_guards.camera_guard.as_ref().unwrap().clone();
                     let stored_buffer = build_dynamic_buffers_gpu.push(gpu_buffer);
@@ -241,9 +276,8 @@ impl BindGroupGuard {
                         ),
                     })
                 }
-                BindTarget::FrameCounter => {
-                    todo!()
-                }
+                BindTarget::FrameCounter => frame_buffer.as_entire_binding(),
+                BindTarget::Time => time_buffer.as_entire_binding(),
                 BindTarget::StaticTexture(texture_render_side, _sampler_type) => {
                     let view = texture_render_side.texture.assume(|texture| {
                         texture.create_view(&wgpu::TextureViewDescriptor {
@@ -265,7 +299,7 @@ impl BindGroupGuard {
                     // Remove the guard from the acquired texture guards map
                     let gpu_access = acquired_guards
                         .texture_guards
-                        .remove(pass_index)
+                        .remove(slot)
                         .expect("Dynamic texture guard should be in acquired_guards");
 
                     // Store the guard
@@ -288,9 +322,31 @@ impl BindGroupGuard {
                     let view = build_dynamic_texture_views.push(view);
                     BindingResource::TextureView(view)
                 }
+                BindTarget::RenderTexture(texture, _sampler_type) => {
+                    let view = texture.color.assume(|texture| {
+                        texture.create_view(&wgpu::TextureViewDescriptor::default())
+                    });
+                    let view = build_static_texture_views.push(view);
+                    BindingResource::TextureView(view)
+                }
+                BindTarget::RenderTextureDepth(texture) => {
+                    let view = texture
+                        .depth
+                        .as_ref()
+                        .expect("render texture has no depth attachment")
+                        .assume(|texture| {
+                            texture.create_view(&wgpu::TextureViewDescriptor {
+                                aspect: wgpu::TextureAspect::DepthOnly,
+                                ..Default::default()
+                            })
+                        });
+                    let view = build_static_texture_views.push(view);
+                    BindingResource::TextureView(view)
+                }
                 BindTarget::Sampler(sampler) => match sampler {
                     SamplerType::Mipmapped => {
-                        let guard = sampler_guards.push(mipmapped_sampler.assume(|e| e.clone()));
+                        let guard =
+                            sampler_guards.push(globals.mipmapped_sampler.assume(|e| e.clone()));
                         BindingResource::Sampler(guard)
                     }
                 },
@@ -300,35 +356,48 @@ impl BindGroupGuard {
             };
 
             let entry = BindGroupEntry {
-                binding: *pass_index,
+                binding: slot.pass_index,
                 resource,
             };
-            entries.push(entry);
+            entries.entry(slot.group).or_default().push(entry);
         }
 
-        let bind_group = bind_device.0.device().assume(|device| {
-            WgpuCell::new(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some(name),
-                layout: bind_group_layout,
-                entries: entries.as_slice(),
-            }))
+        //groups without entries are bound empty, to fill gaps before higher groups
+        let bind_groups = bind_device.0.device().assume(|device| {
+            bind_group_layouts
+                .iter()
+                .map(|(group, layout)| {
+                    let group_entries = entries.remove(group).unwrap_or_default();
+                    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: Some(name),
+                        layout,
+                        entries: group_entries.as_slice(),
+                    });
+                    (*group, WgpuCell::new(bind_group))
+                })
+                .collect()
         });
 
-        //find vertex buffers
+        //find vertex buffers, which are set by their position in slot order
         let mut vertex_buffers = Vec::new();
         let mut dynamic_vertex_buffers = Vec::new();
-        for (b, buffer) in &bind_style.binds {
-            match &buffer.target {
+        for (v, b) in bind_style.vertex_buffer_slots().iter().enumerate() {
+            let v = v as u32;
+            match &bind_style.binds[b].target {
                 BindTarget::StaticBuffer(_)
                 | BindTarget::DynamicBuffer(_)
                 | BindTarget::Camera
                 | BindTarget::FrameCounter
+                | BindTarget::Time
                 | BindTarget::DynamicTexture(_)
                 | BindTarget::StaticTexture(..)
-                | BindTarget::Sampler(_) => {}
-                BindTarget::VB(_layout, render_side) => {
+                | BindTarget::Sampler(_)
+                | BindTarget::ReverseBuffer(_)
+                | BindTarget::RenderTexture(..)
+                | BindTarget::RenderTextureDepth(_) => {}
+                BindTarget::VB(_layout, _step_mode, render_side) => {
                     let buffer = render_side.buffer();
-                    vertex_buffers.push((*b, buffer.clone()));
+                    vertex_buffers.push((v, buffer.clone()));
                 }
                 BindTarget::DynamicVB(..) => {
                     // Remove the guard from the acquired guards map
@@ -336,17 +405,31 @@ impl BindGroupGuard {
                         .buffer_guards
                         .remove(b)
                         .expect("Dynamic vertex buffer guard should be in acquired_guards");
-                    dynamic_vertex_buffers.push((*b, guard));
+                    dynamic_vertex_buffers.push((v, guard));
                 }
             }
         }
 
-        let index_buffer = if let Some(buffer) = &bind_style.index_buffer {
-            let buffer = buffer.buffer().clone();
-            Some(buffer)
-        } else {
-            None
-        };
+        let mut index_guard = None;
+        let index_buffer = bind_style.index_buffer.as_ref().map(|binding| {
+            let buffer = match &binding.target {
+                IndexTarget::Static(buffer) => buffer.buffer().clone(),
+                IndexTarget::Dynamic(_) => {
+                    let guard = acquired_guards
+                        .index_guard
+                        .take()
+                        .expect("Dynamic index buffer guard should be in acquired_guards");
+                    let buffer = guard.underlying_guard.as_imp().buffer().clone();
+                    index_guard = Some(guard);
+                    buffer
+                }
+            };
+            let format = match binding.format {
+                IndexFormat::Uint16 => wgpu::IndexFormat::Uint16,
+                IndexFormat::Uint32 => wgpu::IndexFormat::Uint32,
+            };
+            (buffer, format)
+        });
 
         // Convert StableAddressVec to Vec
         let gpu_guard_buffers = build_dynamic_buffers_gpu.into_vec();
@@ -354,12 +437,17 @@ impl BindGroupGuard {
         // dynamic_vertex_buffers is already in the correct format
 
         BindGroupGuard {
-            bind_group,
+            bind_groups,
             guards: gpu_guard_buffers,
             _guards_textures: gpu_guard_texture_views,
             vertex_buffers,
             dynamic_vertex_buffers,
             index_buffer,
+            index_guard,
+            indirect_buffer: bind_style
+                .indirect_buffer
+                .as_ref()
+                .map(|binding| binding.buffer.buffer().clone()),
         }
     }
 
@@ -367,21 +455,21 @@ impl BindGroupGuard {
         bind_device: &crate::images::BoundDevice,
         bind_style: &crate::bindings::bind_style::BindStyle,
         name: &str,
-        bind_group_layout: &wgpu::BindGroupLayout,
-        camera_buffer: &Buffer<CameraProjection>,
-        mipmapped_sampler: &WgpuCell<wgpu::Sampler>,
+        bind_group_layouts: &[(u32, wgpu::BindGroupLayout)],
+        globals: &Globals,
         copy_info: &mut imp::CopyInfo<'_>,
     ) -> (Self, AcquiredGuards) {
         // First acquire guards and perform copies
-        let mut acquired_guards = AcquiredGuards::new(bind_style, copy_info, camera_buffer).await;
+        let mut acquired_guards =
+            AcquiredGuards::new(bind_style, copy_info, &globals.camera_buffer).await;
 
         // Then create the bind group using the acquired guards
         let s = Self::new_from_guards(
             bind_device,
             bind_style,
             name,
-            bind_group_layout,
-            mipmapped_sampler,
+            bind_group_layouts,
+            globals,
             &mut acquired_guards,
             copy_info,
         );
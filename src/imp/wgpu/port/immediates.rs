@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::bindings::BindStyle;
+use crate::bindings::forward::dynamic::immediates::ErasedImmediates;
+use crate::imp::wgpu::cell::WgpuCell;
+use std::borrow::Cow;
+use std::num::NonZero;
+
+/// How WGSL declares immediate data.
+const IMMEDIATE_DECLARATION: &str = "var<immediate>";
+
+/// Uniform buffers are allocated in multiples of 16 bytes, as std140 would lay them out.
+const UNIFORM_ALIGNMENT: u64 = 16;
+
+/**
+A pass's immediate data, as the device sets it.
+
+Devices with [`wgpu::Features::IMMEDIATES`] set the bytes with each draw.  Elsewhere the bytes
+are written to a uniform buffer, bound in a group after the pass's own groups, and the shaders
+are rewritten to read the block from there.
+*/
+#[derive(Debug)]
+pub enum PreparedImmediates {
+    Native {
+        immediates: ErasedImmediates,
+        size: u32,
+    },
+    Emulated {
+        immediates: ErasedImmediates,
+        /// The group the uniform buffer is bound at.
+        group: u32,
+        buffer: WgpuCell<wgpu::Buffer>,
+        layout: WgpuCell<wgpu::BindGroupLayout>,
+        bind_group: WgpuCell<wgpu::BindGroup>,
+    },
+}
+
+impl PreparedImmediates {
+    pub fn new(
+        bind_device: &crate::images::BoundDevice,
+        immediates: &ErasedImmediates,
+        bind_style: &BindStyle,
+        name: &str,
+    ) -> Self {
+        let size = immediates.size();
+        if bind_device.0.supports_immediates(size) {
+            return PreparedImmediates::Native {
+                immediates: immediates.clone(),
+                size,
+            };
+        }
+        let group = bind_style.group_count();
+        let (buffer, layout, bind_group) = bind_device.0.device().assume(|device| {
+            let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(name),
+                size: (size as u64).next_multiple_of(UNIFORM_ALIGNMENT),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some(name),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: NonZero::new(size as u64),
+                    },
+                    count: None,
+                }],
+            });
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(name),
+                layout: &layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+            });
+            (
+                WgpuCell::new(buffer),
+                WgpuCell::new(layout),
+                WgpuCell::new(bind_group),
+            )
+        });
+        PreparedImmediates::Emulated {
+            immediates: immediates.clone(),
+            group,
+            buffer,
+            layout,
+            bind_group,
+        }
+    }
+
+    /// The size of the immediate data the pipeline layout declares.
+    pub fn immediate_size(&self) -> u32 {
+        match self {
+            PreparedImmediates::Native { size, .. } => *size,
+            PreparedImmediates::Emulated { .. } => 0,
+        }
+    }
+
+    /// The layout of the emulated uniform buffer's group, if any.
+    pub fn emulated_layout(&self) -> Option<&WgpuCell<wgpu::BindGroupLayout>> {
+        match self {
+            PreparedImmediates::Native { .. } => None,
+            PreparedImmediates::Emulated { layout, .. } => Some(layout),
+        }
+    }
+
+    /// The group and bind group of the emulated uniform buffer, if any.
+    pub fn emulated_bind_group(&self) -> Option<(u32, WgpuCell<wgpu::BindGroup>)> {
+        match self {
+            PreparedImmediates::Native { .. } => None,
+            PreparedImmediates::Emulated {
+                group, bind_group, ..
+            } => Some((*group, bind_group.clone())),
+        }
+    }
+
+    /// Returns `wgsl` as the device compiles it, declaring the block as a uniform if emulated.
+    pub fn shader_source<'a>(&self, wgsl: &'a str) -> Cow<'a, str> {
+        match self {
+            PreparedImmediates::Native { .. } => Cow::Borrowed(wgsl),
+            PreparedImmediates::Emulated { group, .. } => Cow::Owned(emulated_source(wgsl, *group)),
+        }
+    }
+
+    /// Sets the current value for the draws that follow in `render_pass`.
+    pub fn apply(&self, queue: &wgpu::Queue, render_pass: &mut wgpu::RenderPass<'_>) {
+        match self {
+            PreparedImmediates::Native { immediates, .. } => {
+                render_pass.set_immediates(0, &immediates.bytes());
+            }
+            PreparedImmediates::Emulated {
+                immediates, buffer, ..
+            } => {
+                buffer.assume(|buffer| queue.write_buffer(buffer, 0, &immediates.bytes()));
+            }
+        }
+    }
+}
+
+/// Declares the immediate data in `wgsl` as a uniform at binding 0 of `group`.
+fn emulated_source(wgsl: &str, group: u32) -> String {
+    wgsl.replace(
+        IMMEDIATE_DECLARATION,
+        &format!("@group({group}) @binding(0) var<uniform>"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::emulated_source;
+
+    #[test]
+    fn emulated_source_declares_a_uniform() {
+        let wgsl = "var<immediate> tint: vec4<f32>;\nfn f() -> vec4<f32> { return tint; }";
+        assert_eq!(
+            emulated_source(wgsl, 2),
+            "@group(2) @binding(0) var<uniform> tint: vec4<f32>;\nfn f() -> vec4<f32> { return tint; }"
+        );
+    }
+}
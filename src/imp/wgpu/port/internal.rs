@@ -1,46 +1,65 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::bindings::bind_style::BindTarget;
 use crate::bindings::forward::dynamic::buffer::Buffer;
 use crate::bindings::visible_to::GPUBufferUsage;
 use crate::images::camera::Camera;
-use crate::images::port::{FrameGuard, PortReporterSend};
-use crate::images::render_pass::PassDescriptor;
-#[cfg(feature = "exfiltrate")]
-use crate::imp::DUMP_NEXT_FRAME;
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::images::port::{FrameGuard, FramebufferData, PortReporterSend};
+use crate::images::render_pass::{
+    DepthFormat, DrawCommand, DrawIndexedIndirectArgs, DrawIndirectArgs, LoadOp, PassDescriptor,
+    PassHandle, PassList, StoreOp, clear_depth, dependency_order,
+};
 use crate::imp::wgpu::cell::WgpuCell;
 use crate::imp::wgpu::context::smuggle_async;
 use crate::imp::{CopyInfo, Error};
+use crate::pixel_formats::Float4;
+use std::collections::HashMap;
 use std::sync::Arc;
-#[cfg(feature = "exfiltrate")]
-use wgpu::wgt::BufferDescriptor;
 use wgpu::{
-    Color, CommandEncoder, CompositeAlphaMode, LoadOp, Operations,
-    RenderPassDepthStencilAttachment, StoreOp, TextureFormat,
+    CommandEncoder, CompositeAlphaMode, Operations, RenderPassDepthStencilAttachment, TextureFormat,
 };
 
+use super::capture::{FrameCapture, Readback, depth_to_float, read_depth};
+use super::globals::Globals;
 use super::guards::{AcquiredGuards, BindGroupGuard};
-use super::prepared_pass::PreparedPass;
-#[cfg(feature = "exfiltrate")]
-use super::types::DebugCaptureData;
-use super::types::{CameraProjection, PassConfig, RenderInput};
-
-#[cfg(feature = "exfiltrate")]
-use super::debug_capture::{DumpImageFormat, dump_image};
+use super::prepared_compute_pass::PreparedComputePass;
+use super::prepared_pass::{PreparedPass, depth_texture_format};
+use super::types::{CameraProjection, OffscreenTarget, PassConfig, RenderInput};
 
 #[derive(Debug)]
 pub struct PortInternal {
     pub engine: Arc<crate::images::Engine>,
     pub pass_config: RenderInput<PassConfig>,
+    /// The enabled passes, in the order they run.
     pub prepared_passes: Vec<PreparedPass>,
+    /// Disabled passes that were prepared, kept to enable them again without preparing them.
+    pub disabled_passes: Vec<PreparedPass>,
+    /// The descriptors the device could not build pipelines for, by pass, to warn once.
+    pub failed_passes: HashMap<PassHandle, PassDescriptor>,
+    pub prepared_compute_passes: Vec<PreparedComputePass>,
     pub view: crate::images::view::View,
     pub port_reporter_send: PortReporterSend,
     pub frame: u32,
     pub scaled_size: RenderInput<Option<(u32, u32)>>,
-    pub camera_buffer: Buffer<CameraProjection>,
+    /// The camera, frame counter and time, and the global group that binds them.
+    pub globals: Globals,
     pub camera: Camera,
-    pub mipmapped_sampler: WgpuCell<wgpu::Sampler>,
-    #[cfg(feature = "exfiltrate")]
-    pub next_frame_dump_oneshot: Option<wasm_safe_mutex::mpsc::Sender<crate::imp::DumpMessage>>,
+    /// Whether the frame being rendered is copied back for capture.
+    pub capture_next_frame: bool,
     pub surface_texture_usage: RenderInput<wgpu::TextureUsages>,
+    pub offscreen: Option<WgpuCell<OffscreenTarget>>,
+    /// The attachment passes into the view render to when they take more than one sample,
+    /// which is resolved into the view.
+    pub multisampled_color: Option<WgpuCell<wgpu::Texture>>,
+    /// What happens to the view's color at the start of each frame.
+    pub color_load: LoadOp<Float4>,
+    /// What happens to the view's depth at the start of each frame, or `None` to clear it
+    /// as the first depth pass needs.
+    pub depth_load: Option<LoadOp<f32>>,
+    /// The view's depth attachment, unless the view is offscreen.
+    pub depth: Option<WgpuCell<wgpu::Texture>>,
+    /// See [`Self::setup_retained_color`].
+    pub retained_color: Option<WgpuCell<wgpu::Texture>>,
 }
 
 impl PortInternal {
@@ -136,34 +155,58 @@ impl PortInternal {
             }
         };
 
+        let (color_format, offscreen) = match view.offscreen_config() {
+            Some(offscreen) => (
+                offscreen.imp_format.0,
+                Some(create_offscreen_target(
+                    engine.bound_device(),
+                    offscreen.width as u32,
+                    offscreen.height as u32,
+                    offscreen.imp_format.0,
+                )),
+            ),
+            //non-sRGB surfaces are rendered through an sRGB view
+            None if format.is_srgb() => (format, None),
+            None => (TextureFormat::Bgra8UnormSrgb, None),
+        };
+
         Ok(PortInternal {
             engine: engine.clone(),
-            camera_buffer,
+            globals: Globals::new(engine.bound_device(), camera_buffer, mipmapped_sampler),
             camera,
-            pass_config: RenderInput::new(PassConfig::new(format)),
+            pass_config: RenderInput::new(PassConfig::new(format, color_format)),
             prepared_passes: Vec::new(),
+            disabled_passes: Vec::new(),
+            failed_passes: HashMap::new(),
+            prepared_compute_passes: Vec::new(),
             view,
             port_reporter_send,
             frame: 0,
             scaled_size: RenderInput::new(None),
-            mipmapped_sampler,
-            #[cfg(feature = "exfiltrate")]
-            next_frame_dump_oneshot: None,
+            capture_next_frame: false,
             surface_texture_usage: RenderInput::new(wgpu::TextureUsages::empty()),
+            offscreen,
+            multisampled_color: None,
+            color_load: LoadOp::Clear(Float4::default()),
+            depth_load: None,
+            depth: None,
+            retained_color: None,
         })
     }
 
-    fn setup_depth_buffer(&self) -> (wgpu::Texture, wgpu::TextureView) {
-        let depth_extra_usage = if self.needs_framedump() {
-            wgpu::TextureUsages::COPY_SRC
-        } else {
-            wgpu::TextureUsages::empty()
-        };
-
-        let device = self.engine.bound_device().as_ref();
+    fn setup_depth_buffer(&mut self) -> (wgpu::Texture, wgpu::TextureView) {
+        if let Some(offscreen) = &self.offscreen {
+            let depth_texture = offscreen.assume(|offscreen| offscreen.depth.clone());
+            let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+            return (depth_texture, depth_view);
+        }
+        let sample_count = self.pass_config.requested.sample_count;
         let scaled_size = self.scaled_size.requested.unwrap();
-        let depth_texture = device.0.device().assume(|device| {
-            device.create_texture(&wgpu::TextureDescriptor {
+        //kept between frames, for passes that load the previous frame's depth
+        let depth_texture = cached_texture(
+            self.engine.bound_device(),
+            &mut self.depth,
+            &wgpu::TextureDescriptor {
                 label: Some("depth texture"),
                 size: wgpu::Extent3d {
                     width: scaled_size.0,
@@ -171,13 +214,21 @@ impl PortInternal {
                     depth_or_array_layers: 1,
                 },
                 mip_level_count: 1,
-                sample_count: 1,
+                sample_count,
                 dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Depth16Unorm,
-                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | depth_extra_usage,
+                format: depth_texture_format(
+                    self.pass_config.requested.depth_format.unwrap_or_default(),
+                ),
+                //read back by sampling, see `depth_to_float`; multisampled depth can't be
+                //sampled on GL
+                usage: if sample_count == 1 {
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+                } else {
+                    wgpu::TextureUsages::RENDER_ATTACHMENT
+                },
                 view_formats: &[],
-            })
-        });
+            },
+        );
 
         let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor {
             label: Some("depth view"),
@@ -194,28 +245,232 @@ impl PortInternal {
         (depth_texture, depth_view)
     }
 
+    /// Returns a view of the multisampled attachment that resolves into `target`, or `None`
+    /// if the view renders one sample.
+    ///
+    /// The attachment is recreated when the target's size changes.
+    fn setup_multisampled_color(&mut self, target: &wgpu::Texture) -> Option<wgpu::TextureView> {
+        let sample_count = self.pass_config.requested.sample_count;
+        if sample_count == 1 {
+            self.multisampled_color = None;
+            return None;
+        }
+        let texture = cached_texture(
+            self.engine.bound_device(),
+            &mut self.multisampled_color,
+            &wgpu::TextureDescriptor {
+                label: Some("multisampled color"),
+                size: target.size(),
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.pass_config.requested.color_format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            },
+        );
+        Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+    }
+
+    /// Returns the texture passes into the view render to when the next frame loads their
+    /// results, which is copied into `target` at the end of each frame.  Offscreen views
+    /// keep their contents already, and so do not need one.
+    fn setup_retained_color(&mut self, target: &wgpu::Texture) -> wgpu::Texture {
+        cached_texture(
+            self.engine.bound_device(),
+            &mut self.retained_color,
+            &wgpu::TextureDescriptor {
+                label: Some("retained color"),
+                size: target.size(),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.pass_config.requested.color_format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            },
+        )
+    }
+
+    /// Whether the next frame loads the view's color and depth from this one.
+    fn retains_view(&self) -> (bool, bool) {
+        let view_passes = || {
+            self.pass_config
+                .requested
+                .passes
+                .enabled()
+                .map(|entry| &entry.descriptor)
+                .filter(|p| p.render_target.is_none())
+        };
+        let color = self.color_load == LoadOp::Load
+            || view_passes().any(|p| p.color_load == Some(LoadOp::Load));
+        let depth = self.depth_load == Some(LoadOp::Load)
+            || view_passes().any(|p| p.depth_load == Some(LoadOp::Load));
+        (color, depth)
+    }
+
+    /// Renders passes into the view with `sample_count` samples per pixel, or the most
+    /// the device supports below it, and returns the count used.
+    pub fn set_sample_count(&mut self, sample_count: u32) -> u32 {
+        assert!(
+            matches!(sample_count, 1 | 2 | 4 | 8),
+            "Sample count must be 1, 2, 4 or 8, not {sample_count}"
+        );
+        let device = &self.engine.bound_device().as_ref().0;
+        let color_format = self.pass_config.requested.color_format;
+        //passes added later may change the depth format, so every format must support it
+        let depth_formats = [
+            DepthFormat::Depth16Unorm,
+            DepthFormat::Depth24Plus,
+            DepthFormat::Depth24PlusStencil8,
+            DepthFormat::Depth32Float,
+        ]
+        .map(depth_texture_format);
+        let sample_count = [8, 4, 2, 1]
+            .into_iter()
+            .filter(|&count| count <= sample_count)
+            .find(|&count| {
+                device.supports_sample_count(color_format, count)
+                    && depth_formats
+                        .iter()
+                        .all(|&format| device.supports_sample_count(format, count))
+            })
+            .expect("One sample is always supported");
+        let mut new_config = self.pass_config.requested.clone();
+        new_config.sample_count = sample_count;
+        self.pass_config.update(new_config);
+        sample_count
+    }
+
     async fn update_pass_configuration(
         &mut self,
-        enable_depth: bool,
+        depth_format: Option<DepthFormat>,
         copy_info: &mut CopyInfo<'_>,
     ) {
         if self.pass_config.is_dirty() {
-            self.prepared_passes.clear();
+            let requested = &self.pass_config.requested;
+            let submitted = self.pass_config.submitted.as_ref();
+            //prepared passes are kept while only the passes change
+            let mut previous: HashMap<PassHandle, PreparedPass> =
+                std::mem::take(&mut self.prepared_passes)
+                    .into_iter()
+                    .chain(std::mem::take(&mut self.disabled_passes))
+                    .map(|prepared| (prepared.handle, prepared))
+                    .collect();
+            if !submitted.is_some_and(|submitted| submitted.shares_pipelines_with(requested)) {
+                previous.clear();
+            }
+            let compute_changed = submitted.is_none_or(|submitted| {
+                submitted.compute_pass_descriptors != requested.compute_pass_descriptors
+            });
+            if compute_changed {
+                self.prepared_compute_passes.clear();
+            }
+            if let (Some(offscreen), Some(depth_format)) = (&mut self.offscreen, depth_format) {
+                let format = depth_texture_format(depth_format);
+                let sample_count = self.pass_config.requested.sample_count;
+                let current = offscreen
+                    .assume(|offscreen| (offscreen.depth.format(), offscreen.depth.sample_count()));
+                if current != (format, sample_count) {
+                    *offscreen = offscreen.assume(|offscreen| {
+                        self.engine.bound_device().0.device().assume(|device| {
+                            WgpuCell::new(OffscreenTarget {
+                                color: offscreen.color.clone(),
+                                depth: create_offscreen_depth(
+                                    device,
+                                    offscreen.color.size(),
+                                    format,
+                                    sample_count,
+                                ),
+                                width: offscreen.width,
+                                height: offscreen.height,
+                            })
+                        })
+                    });
+                }
+            }
 
             let device = self.engine.bound_device().as_ref();
-            for descriptor in &self.pass_config.requested.pass_descriptors {
-                let pipeline = PreparedPass::new(
-                    device,
-                    descriptor.clone(),
-                    enable_depth,
-                    &self.camera_buffer,
-                    &self.mipmapped_sampler,
-                    copy_info,
-                    &self.pass_config.requested,
-                )
-                .await;
-                self.prepared_passes.push(pipeline);
+            if compute_changed {
+                for descriptor in &self.pass_config.requested.compute_pass_descriptors {
+                    let pipeline = PreparedComputePass::new(
+                        device,
+                        descriptor.clone(),
+                        &self.globals,
+                        copy_info,
+                    )
+                    .await;
+                    self.prepared_compute_passes.push(pipeline);
+                }
+            }
+            let passes = &self.pass_config.requested.passes;
+            for entry in passes.entries().iter().filter(|entry| !entry.enabled) {
+                if let Some(mut prepared) = previous.remove(&entry.handle)
+                    && prepared.pass_descriptor == entry.descriptor
+                {
+                    //disabled passes don't hold their resources
+                    prepared.acquired_guards = None;
+                    self.disabled_passes.push(prepared);
+                }
             }
+            let enabled: Vec<_> = passes.enabled().collect();
+            let descriptors: Vec<PassDescriptor> = enabled
+                .iter()
+                .map(|entry| entry.descriptor.clone())
+                .collect();
+            //prepare passes in the order they run
+            //ports refuse passes that depend on each other in a cycle
+            let order = dependency_order(&descriptors).expect("passes have no cycle");
+            let mut failed_passes = HashMap::new();
+            for p in order {
+                let entry = enabled[p];
+                let mut prepared = match previous.remove(&entry.handle) {
+                    Some(prepared) if prepared.pass_descriptor == entry.descriptor => prepared,
+                    last_good => {
+                        match PreparedPass::new(
+                            device,
+                            entry,
+                            depth_format,
+                            &self.globals,
+                            copy_info,
+                            &self.pass_config.requested,
+                        )
+                        .await
+                        {
+                            Ok(prepared) => prepared,
+                            Err(error) => {
+                                if self.failed_passes.get(&entry.handle) != Some(&entry.descriptor)
+                                {
+                                    logwise::warn_sync!(
+                                        "Keeping the last good pipeline of pass {name}: {error}",
+                                        name = logwise::privacy::LogIt(entry.descriptor.name()),
+                                        error = logwise::privacy::LogIt(&error)
+                                    );
+                                }
+                                self.port_reporter_send.shader_error(Some(error));
+                                failed_passes.insert(entry.handle, entry.descriptor.clone());
+                                //without one, as after the sample count changes, the pass is
+                                //skipped until it changes again
+                                let Some(last_good) = last_good else {
+                                    continue;
+                                };
+                                last_good
+                            }
+                        }
+                    }
+                };
+                //passes enabled again acquire their resources for this frame
+                if prepared.acquired_guards.is_none() {
+                    prepared
+                        .recreate_acquired_guards(&self.globals.camera_buffer, copy_info)
+                        .await;
+                }
+                self.prepared_passes.push(prepared);
+            }
+            if failed_passes.is_empty() && !self.failed_passes.is_empty() {
+                self.port_reporter_send.shader_error(None);
+            }
+            self.failed_passes = failed_passes;
 
             self.pass_config.mark_submitted();
         }
@@ -245,129 +500,19 @@ impl PortInternal {
                     *projection.matrix().columns()[3].w(),
                 ],
             };
-            let mut write_guard = self.camera_buffer.access_write().await;
+            let mut write_guard = self.globals.camera_buffer.access_write().await;
             write_guard.write(&[camera_projection], 0).await;
         }
     }
 
-    fn needs_framedump(&self) -> bool {
-        #[cfg(feature = "exfiltrate")]
-        {
-            self.next_frame_dump_oneshot.is_some()
-        }
-        #[cfg(not(feature = "exfiltrate"))]
-        {
-            false
-        }
-    }
-
-    #[cfg(feature = "exfiltrate")]
-    fn setup_debug_framebuffer_capture(
-        &self,
-        encoder: &mut wgpu::CommandEncoder,
-        frame_texture: &wgpu::Texture,
-        depth_texture: &wgpu::Texture,
-    ) -> Option<DebugCaptureData> {
-        if !self.needs_framedump() {
-            return None;
-        }
-
-        let device = self.engine.bound_device().as_ref();
-        let scaled_size = self.scaled_size.requested.unwrap();
-
-        let wgpu_bytes_per_row_256 = (scaled_size.0 * 4)
-            .checked_add(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT - 1)
-            .unwrap()
-            .div_euclid(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-            .checked_mul(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-            .unwrap();
-
-        let buf = device.0.device().assume(|device| {
-            device.create_buffer(&BufferDescriptor {
-                label: "dump framebuffer".into(),
-                size: (scaled_size.1 * wgpu_bytes_per_row_256) as u64,
-                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-                mapped_at_creation: false,
-            })
-        });
-
-        encoder.copy_texture_to_buffer(
-            wgpu::TexelCopyTextureInfo {
-                texture: frame_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::TexelCopyBufferInfo {
-                buffer: &buf,
-                layout: wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(wgpu_bytes_per_row_256),
-                    rows_per_image: None,
-                },
-            },
-            wgpu::Extent3d {
-                width: scaled_size.0,
-                height: scaled_size.1,
-                depth_or_array_layers: 1,
-            },
-        );
-
-        let depth_wgpu_bytes_per_row_256 = (scaled_size.0 * 2)
-            .checked_add(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT - 1)
-            .unwrap()
-            .div_euclid(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-            .checked_mul(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
-            .unwrap();
-
-        let depth_buf = device.0.device().assume(|device| {
-            device.create_buffer(&BufferDescriptor {
-                label: "dump depth buffer".into(),
-                size: (scaled_size.1 * depth_wgpu_bytes_per_row_256) as u64,
-                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-                mapped_at_creation: false,
-            })
-        });
-
-        encoder.copy_texture_to_buffer(
-            wgpu::TexelCopyTextureInfo {
-                texture: depth_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            wgpu::TexelCopyBufferInfo {
-                buffer: &depth_buf,
-                layout: wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    bytes_per_row: Some(depth_wgpu_bytes_per_row_256),
-                    rows_per_image: None,
-                },
-            },
-            wgpu::Extent3d {
-                width: scaled_size.0,
-                height: scaled_size.1,
-                depth_or_array_layers: 1,
-            },
-        );
-
-        Some(DebugCaptureData {
-            dump_buf: buf,
-            dump_buff_bytes_per_row: wgpu_bytes_per_row_256,
-            depth_dump_buf: depth_buf,
-            depth_dump_buff_bytes_per_row: depth_wgpu_bytes_per_row_256,
-        })
-    }
-
-    #[cfg(feature = "exfiltrate")]
     fn submit_and_present_frame(
         &mut self,
         encoder: wgpu::CommandEncoder,
         frame: Option<wgpu::SurfaceTexture>,
         frame_bind_groups: Vec<BindGroupGuard>,
         frame_acquired_guards: Vec<AcquiredGuards>,
+        frame_readbacks: Vec<crate::bindings::reverse::buffer::GPUAccess>,
         frame_guard: std::sync::Arc<crate::images::port::FrameGuard>,
-        debug_capture: Option<DebugCaptureData>,
     ) {
         logwise::trace_sync!("submit_and_present_frame");
         let device = self.engine.bound_device().as_ref();
@@ -377,6 +522,7 @@ impl PortInternal {
         let callback_guard = frame_guard_for_callback.clone();
         //this closure requires Send but I don't think we actually do on wgpu
         let frame_acquired_guards = WgpuCell::new(frame_acquired_guards);
+        let frame_readbacks = WgpuCell::new(frame_readbacks);
 
         device.0.queue().assume(|queue| {
             queue.on_submitted_work_done(move || {
@@ -384,11 +530,13 @@ impl PortInternal {
                 //from a different thread
                 std::mem::drop(frame_bind_groups);
                 std::mem::drop(frame_acquired_guards);
+                //the copies have executed, so the CPU may read
+                std::mem::drop(frame_readbacks);
                 callback_guard.mark_gpu_complete();
             });
             queue.submit(std::iter::once(encoded));
         });
-        logwise::trace_sync!("submitted");
+
         if let Some(f) = frame {
             f.present();
         }
@@ -396,106 +544,19 @@ impl PortInternal {
 
         self.frame += 1;
 
-        #[cfg(feature = "exfiltrate")]
-        if let Some(debug_capture) = debug_capture.as_ref() {
-            let has_depth = self.pass_config.requested.enable_depth;
-            let expected_count = if has_depth { 2 } else { 1 };
-
-            if let Some(sender) = &self.next_frame_dump_oneshot {
-                let _ = sender.send_sync(crate::imp::DumpMessage::Expect(expected_count));
-            }
-
-            let color_buffer = debug_capture.dump_buf.clone();
-            let bytes_per_row = debug_capture.dump_buff_bytes_per_row;
-            let scaled_size = self.scaled_size.requested.unwrap();
-            let sender = self.next_frame_dump_oneshot.clone();
-            let surface_format = self.pass_config.requested.surface_format;
-            color_buffer
-                .clone()
-                .map_async(wgpu::MapMode::Read, .., move |result| {
-                    dump_image(
-                        result,
-                        color_buffer,
-                        bytes_per_row,
-                        scaled_size,
-                        sender,
-                        "Color buffer",
-                        DumpImageFormat::Color(surface_format),
-                    )
-                });
-            //for map_async to work, we need to combine with needs_poll, maybe others?
-            device.0.set_needs_poll();
-
-            if has_depth {
-                let bytes_per_row = debug_capture.depth_dump_buff_bytes_per_row;
-                let depth_buffer = debug_capture.depth_dump_buf.clone();
-                let scaled_size = self.scaled_size.requested.unwrap();
-                let sender = self.next_frame_dump_oneshot.clone();
-                depth_buffer
-                    .clone()
-                    .map_async(wgpu::MapMode::Read, .., move |result| {
-                        dump_image(
-                            result,
-                            depth_buffer,
-                            bytes_per_row,
-                            scaled_size,
-                            sender,
-                            "depth buffer",
-                            DumpImageFormat::Depth16Unorm,
-                        )
-                    });
-                //for map_async to work, we need to combine with needs_poll, maybe others?
-                device.0.set_needs_poll()
-            }
-        }
-
         frame_guard_for_callback.mark_cpu_complete();
         logwise::trace_sync!("submit_and_present_frame done");
     }
 
-    #[cfg(not(feature = "exfiltrate"))]
-    fn submit_and_present_frame(
-        &mut self,
-        encoder: wgpu::CommandEncoder,
-        frame: Option<wgpu::SurfaceTexture>,
-        frame_bind_groups: Vec<BindGroupGuard>,
-        frame_acquired_guards: Vec<AcquiredGuards>,
-        frame_guard: std::sync::Arc<crate::images::port::FrameGuard>,
-    ) {
-        logwise::trace_sync!("submit_and_present_frame");
-        let device = self.engine.bound_device().as_ref();
-        let encoded = encoder.finish();
-
-        let frame_guard_for_callback = frame_guard.clone();
-        let callback_guard = frame_guard_for_callback.clone();
-        //this closure requires Send but I don't think we actually do on wgpu
-        let frame_acquired_guards = WgpuCell::new(frame_acquired_guards);
-
-        device.0.queue().assume(|queue| {
-            queue.on_submitted_work_done(move || {
-                //at runtime, on non-wasm32 platforms, this is polled
-                //from a different thread
-                std::mem::drop(frame_bind_groups);
-                std::mem::drop(frame_acquired_guards);
-                callback_guard.mark_gpu_complete();
-            });
-            queue.submit(std::iter::once(encoded));
-        });
-
-        if let Some(f) = frame {
-            f.present();
-        }
-        logwise::trace_sync!("presented");
-
-        self.frame += 1;
-
-        frame_guard_for_callback.mark_cpu_complete();
-        logwise::trace_sync!("submit_and_present_frame done");
+    pub async fn set_passes(&mut self, passes: PassList) {
+        let mut new_config = self.pass_config.requested.clone();
+        new_config.set_passes(passes);
+        self.pass_config.update(new_config);
     }
 
-    pub async fn add_fixed_pass(&mut self, descriptor: PassDescriptor) {
+    pub async fn set_compute_passes(&mut self, descriptors: Vec<ComputePassDescriptor>) {
         let mut new_config = self.pass_config.requested.clone();
-        new_config.add_pass(descriptor);
+        new_config.compute_pass_descriptors = descriptors;
         self.pass_config.update(new_config);
     }
     pub async fn begin_render_frame_internal(&mut self) -> (CommandEncoder, FrameGuard) {
@@ -518,20 +579,32 @@ impl PortInternal {
             let mut copy_info = CopyInfo {
                 command_encoder: &mut encoder,
             };
+            self.globals
+                .prepare_frame(
+                    self.engine.bound_device().as_ref(),
+                    self.frame,
+                    &mut copy_info,
+                )
+                .await;
+            for prepared_pass in &mut self.prepared_compute_passes {
+                prepared_pass
+                    .recreate_acquired_guards(&self.globals.camera_buffer, &mut copy_info)
+                    .await
+            }
             for prepared_pass in &mut self.prepared_passes {
                 prepared_pass
-                    .recreate_acquired_guards(&self.camera_buffer, &mut copy_info)
+                    .recreate_acquired_guards(&self.globals.camera_buffer, &mut copy_info)
                     .await
             }
         }
-        let enable_depth = self.pass_config.requested.enable_depth;
+        let depth_format = self.pass_config.requested.depth_format;
 
         // Then update pass configuration and camera buffer (which creates bind groups with fresh buffer data)
         {
             let mut copy_info = CopyInfo {
                 command_encoder: &mut encoder,
             };
-            self.update_pass_configuration(enable_depth, &mut copy_info)
+            self.update_pass_configuration(depth_format, &mut copy_info)
                 .await;
         }
         (encoder, frame_guard)
@@ -543,7 +616,7 @@ impl PortInternal {
         mut encoder: wgpu::CommandEncoder,
         frame_guard: crate::images::port::FrameGuard,
         fast_size_scale: (u16, u16, f64),
-    ) {
+    ) -> Option<FrameCapture> {
         logwise::trace_sync!("finish_render_frame begin");
         // Setup frame reporting and surface configuration
         let current_scaled_size = (
@@ -551,21 +624,23 @@ impl PortInternal {
             (fast_size_scale.1 as f64 * fast_size_scale.2) as u32,
         );
         self.scaled_size.update(Some(current_scaled_size));
-        //move dump_frame from global object to struct
-        #[cfg(feature = "exfiltrate")]
-        DUMP_NEXT_FRAME.with_mut_sync(|e| self.next_frame_dump_oneshot = e.take());
 
+        let (retain_color, retain_depth) = self.retains_view();
+        let retain_color = retain_color && self.offscreen.is_none();
+        let mut extra_usage = wgpu::TextureUsages::empty();
+        if self.capture_next_frame {
+            extra_usage |= wgpu::TextureUsages::COPY_SRC;
+        }
+        if retain_color {
+            //see `setup_retained_color`
+            extra_usage |= wgpu::TextureUsages::COPY_DST;
+        }
         let surface = self.view.gpu_impl.as_ref().unwrap().surface.as_ref();
         match surface {
             None => {
                 logwise::debuginternal_sync!("Port surface not initialized");
             }
             Some(surface) => {
-                let extra_usage = if self.needs_framedump() {
-                    wgpu::TextureUsages::COPY_SRC
-                } else {
-                    wgpu::TextureUsages::empty()
-                };
                 self.surface_texture_usage.update(extra_usage);
                 if self.scaled_size.is_dirty() || self.surface_texture_usage.is_dirty() {
                     logwise::trace_sync!("Configuring surface for new size");
@@ -619,10 +694,18 @@ impl PortInternal {
         // Create per-frame resources
         let wgpu_view;
         let frame;
-        let color_attachment;
-        #[cfg(feature = "exfiltrate")]
         let frame_texture;
         match surface {
+            None if self.offscreen.is_some() => {
+                let texture = self
+                    .offscreen
+                    .as_ref()
+                    .unwrap()
+                    .assume(|offscreen| offscreen.color.clone());
+                wgpu_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                frame = None;
+                frame_texture = texture;
+            }
             None => {
                 let scaled_size = self.scaled_size.requested.unwrap();
                 let device = self.engine.bound_device().as_ref();
@@ -638,7 +721,7 @@ impl PortInternal {
                         sample_count: 1,
                         dimension: wgpu::TextureDimension::D2,
                         format: self.pass_config.requested.surface_format,
-                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | extra_usage,
                         view_formats: &[],
                     })
                 });
@@ -654,26 +737,14 @@ impl PortInternal {
                     array_layer_count: None,
                 });
                 frame = None;
-                #[cfg(feature = "exfiltrate")]
-                {
-                    frame_texture = texture;
-                }
-                color_attachment = wgpu::RenderPassColorAttachment {
-                    view: &wgpu_view,
-                    resolve_target: None,
-                    depth_slice: None,
-                    ops: Default::default(),
-                };
+                frame_texture = texture;
             }
             Some(surface) => {
                 logwise::trace_sync!("wgpu::port::A0");
                 let surface_texture = surface
                     .assume(|surface| surface.get_current_texture())
                     .expect("Acquire swapchain texture");
-                #[cfg(feature = "exfiltrate")]
-                {
-                    frame_texture = surface_texture.texture.clone();
-                }
+                frame_texture = surface_texture.texture.clone();
                 logwise::trace_sync!("wgpu::port::A1");
 
                 frame = Some(surface_texture);
@@ -699,46 +770,39 @@ impl PortInternal {
 
                 wgpu_view = frame.as_ref().unwrap().texture.create_view(&descriptor);
                 logwise::trace_sync!("wgpu::port::A3");
-                color_attachment = wgpu::RenderPassColorAttachment {
-                    view: &wgpu_view,
-                    depth_slice: None,
-                    resolve_target: None,
-                    ops: Operations {
-                        load: LoadOp::Clear(Color::TRANSPARENT),
-                        store: StoreOp::Store,
-                    },
-                };
             }
         };
         logwise::trace_sync!("port::A.5");
+        let retained_texture = retain_color.then(|| self.setup_retained_color(&frame_texture));
+        let retained_view = retained_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        //where passes into the view draw, or resolve to if multisampled
+        let view_target = retained_view.as_ref().unwrap_or(&wgpu_view);
+        let multisampled_view = self.setup_multisampled_color(&frame_texture);
         // Setup depth buffer
-        #[cfg(feature = "exfiltrate")]
         let (depth_texture, depth_view) = self.setup_depth_buffer();
-        #[cfg(not(feature = "exfiltrate"))]
-        let (_depth_texture, depth_view) = self.setup_depth_buffer();
         // Execute render passes
-        let depth_store = if self.needs_framedump() {
-            StoreOp::Store
+        let depth_store = if self.capture_next_frame || self.offscreen.is_some() || retain_depth {
+            wgpu::StoreOp::Store
         } else {
-            StoreOp::Discard
-        };
-        let depth_stencil_attachment = if self.prepared_passes.iter().any(|e| e.depth_pass) {
-            Some(RenderPassDepthStencilAttachment {
-                view: &depth_view,
-                depth_ops: Some(wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(1.0),
-                    store: depth_store,
-                }),
-                stencil_ops: None,
-            })
-        } else {
-            None
+            wgpu::StoreOp::Discard
         };
+        let has_depth = self
+            .prepared_passes
+            .iter()
+            .any(|e| e.depth_pass && e.pass_descriptor.render_target.is_none());
         logwise::trace_sync!("wgpu::port::B");
 
         // Extract bind groups and acquired guards from prepared passes
         let mut frame_bind_groups = Vec::new();
-        let mut frame_acquired_guards = Vec::new();
+        let mut frame_acquired_guards: Vec<AcquiredGuards> =
+            self.globals.acquired_guards.take().into_iter().collect();
+        let global_bind_group = self
+            .globals
+            .bind_group
+            .as_ref()
+            .expect("created at the start of the frame");
         for prepared in &mut self.prepared_passes {
             frame_bind_groups.push(prepared.bind_group_guard.clone());
             if let Some(acquired) = prepared.acquired_guards.take() {
@@ -746,98 +810,540 @@ impl PortInternal {
             }
         }
 
-        logwise::trace_sync!("wgpu::port::C");
-        // Encode render passes
-        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Port render"),
-            color_attachments: &[Some(color_attachment)],
-            depth_stencil_attachment,
-            timestamp_writes: None,
-            occlusion_query_set: None,
-            multiview_mask: None,
-        });
-
-        for (p, prepared) in self.prepared_passes.iter().enumerate() {
-            render_pass.push_debug_group(prepared.pass_descriptor.name());
-            prepared
-                .pipeline
-                .assume(|pipeline| render_pass.set_pipeline(pipeline));
-
-            let bind_group = &frame_bind_groups[p];
-            bind_group.bind_group.assume(|bind_group| {
-                render_pass.set_bind_group(0, bind_group, &[]);
+        // Dispatch compute passes first, so render passes can use their results
+        if !self.prepared_compute_passes.is_empty() {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Port compute"),
+                timestamp_writes: None,
             });
+            let mut bound_global = None;
+            for prepared in &mut self.prepared_compute_passes {
+                compute_pass.push_debug_group(prepared.pass_descriptor.name());
+                prepared
+                    .pipeline
+                    .assume(|pipeline| compute_pass.set_pipeline(pipeline));
+                let bind_style = prepared.pass_descriptor.bind_style();
+                set_bind_groups(
+                    &prepared.bind_group_guard,
+                    bind_style.global_group,
+                    global_bind_group,
+                    &mut bound_global,
+                    |group, bind_group| compute_pass.set_bind_group(group, bind_group, &[]),
+                );
+                let (x, y, z) = prepared.workgroup_count;
+                compute_pass.dispatch_workgroups(x, y, z);
+                compute_pass.pop_debug_group();
 
-            for (v, buffer) in &bind_group.vertex_buffers {
-                buffer.assume(|buffer| {
-                    render_pass.set_vertex_buffer(*v, buffer.slice(..));
-                })
+                //kept alive until the GPU has finished, like the render passes' bind groups
+                frame_bind_groups.push(prepared.bind_group_guard.clone());
+                if let Some(acquired) = prepared.acquired_guards.take() {
+                    frame_acquired_guards.push(acquired);
+                }
             }
-            for (v, buffer) in &bind_group.dynamic_vertex_buffers {
-                buffer.underlying_guard.as_imp().buffer().assume(|buffer| {
-                    let buffer_slice = buffer.slice(..);
-                    render_pass.set_vertex_buffer(*v, buffer_slice);
-                });
+        }
+
+        logwise::trace_sync!("wgpu::port::C");
+        // Encode render passes, one for each run of passes into the same target.
+        // Passes are already in dependency order, so render textures are written before they
+        // are sampled; wgpu inserts the transitions between the two.
+        let mut view_loaded = false;
+        let mut loaded_textures: Vec<&crate::imp::RenderTexture> = Vec::new();
+        let mut start = 0;
+        while start < self.prepared_passes.len() {
+            let target = &self.prepared_passes[start].pass_descriptor.render_target;
+            //a run also ends where a pass loads or stores its target its own way
+            let mut end = start + 1;
+            while end < self.prepared_passes.len() {
+                let previous = &self.prepared_passes[end - 1].pass_descriptor;
+                let next = &self.prepared_passes[end].pass_descriptor;
+                if next.render_target != *target
+                    || next.overrides_load()
+                    || previous.overrides_store()
+                {
+                    break;
+                }
+                end += 1;
             }
-            if let Some(buffer) = &bind_group.index_buffer {
-                buffer.assume(|buffer| {
-                    render_pass.set_index_buffer(buffer.slice(..), wgpu::IndexFormat::Uint16);
-                });
-                render_pass.draw_indexed(0..prepared.vertex_count, 0, 0..1);
+            let first = &self.prepared_passes[start].pass_descriptor;
+            let last = &self.prepared_passes[end - 1].pass_descriptor;
+            //clear each target the first time it is rendered this frame
+            let first_use = match target {
+                None => !std::mem::replace(&mut view_loaded, true),
+                Some(texture) if loaded_textures.contains(&texture) => false,
+                Some(texture) => {
+                    loaded_textures.push(texture);
+                    true
+                }
+            };
+            let (color_load, depth_load) = if first_use {
+                let clear_depth = LoadOp::Clear(clear_depth(
+                    self.prepared_passes.iter().map(|p| &p.pass_descriptor),
+                    target,
+                ));
+                match target {
+                    None => (self.color_load, self.depth_load.unwrap_or(clear_depth)),
+                    Some(_) => (LoadOp::Clear(Float4::default()), clear_depth),
+                }
             } else {
-                render_pass.draw(0..prepared.vertex_count, 0..1);
+                (LoadOp::Load, LoadOp::Load)
+            };
+            let color_load = color_load_op(first.color_load.unwrap_or(color_load));
+            let depth_load = first.depth_load.unwrap_or(depth_load);
+            //stencil clears along with depth
+            let stencil_load = match depth_load {
+                LoadOp::Clear(_) => wgpu::LoadOp::Clear(0),
+                LoadOp::Load => wgpu::LoadOp::Load,
+            };
+            let depth_load = match depth_load {
+                LoadOp::Clear(depth) => wgpu::LoadOp::Clear(depth),
+                LoadOp::Load => wgpu::LoadOp::Load,
+            };
+            let color_store = store_op(last.color_store.unwrap_or_default());
+            let target_views = target.as_ref().map(|texture| {
+                let color = texture
+                    .color
+                    .assume(|t| t.create_view(&wgpu::TextureViewDescriptor::default()));
+                let depth = texture.depth.as_ref().map(|depth| {
+                    depth.assume(|t| t.create_view(&wgpu::TextureViewDescriptor::default()))
+                });
+                (color, depth)
+            });
+            let (color_view, resolve_target, depth_stencil_attachment) = match &target_views {
+                None => {
+                    //a later run may draw over this one
+                    let later_view_pass = self.prepared_passes[end..]
+                        .iter()
+                        .any(|p| p.pass_descriptor.render_target.is_none());
+                    let depth_store = last.depth_store.map_or(
+                        if later_view_pass {
+                            wgpu::StoreOp::Store
+                        } else {
+                            depth_store
+                        },
+                        store_op,
+                    );
+                    let depth_attachment = has_depth.then_some(RenderPassDepthStencilAttachment {
+                        view: &depth_view,
+                        depth_ops: Some(Operations {
+                            load: depth_load,
+                            store: depth_store,
+                        }),
+                        stencil_ops: depth_texture.format().has_stencil_aspect().then_some(
+                            Operations {
+                                load: stencil_load,
+                                store: depth_store,
+                            },
+                        ),
+                    });
+                    match &multisampled_view {
+                        Some(multisampled) => (multisampled, Some(view_target), depth_attachment),
+                        None => (view_target, None, depth_attachment),
+                    }
+                }
+                Some((color, depth)) => {
+                    //later passes may sample the depth
+                    let depth_attachment =
+                        depth
+                            .as_ref()
+                            .map(|depth| RenderPassDepthStencilAttachment {
+                                view: depth,
+                                depth_ops: Some(Operations {
+                                    load: depth_load,
+                                    store: store_op(last.depth_store.unwrap_or_default()),
+                                }),
+                                stencil_ops: None,
+                            });
+                    (color, None, depth_attachment)
+                }
+            };
+            let (target_width, target_height) = match target {
+                None => (frame_texture.width(), frame_texture.height()),
+                Some(texture) => texture.color.assume(|t| (t.width(), t.height())),
+            };
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Port render"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: color_view,
+                    resolve_target,
+                    depth_slice: None,
+                    ops: Operations {
+                        load: color_load,
+                        store: color_store,
+                    },
+                })],
+                depth_stencil_attachment,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+            let mut bound_global = None;
+
+            for (p, prepared) in self
+                .prepared_passes
+                .iter()
+                .enumerate()
+                .take(end)
+                .skip(start)
+            {
+                render_pass.push_debug_group(prepared.pass_descriptor.name());
+                prepared
+                    .pipeline
+                    .assume(|pipeline| render_pass.set_pipeline(pipeline));
+                //set for every pass, since they last until the render pass ends
+                let [x, y, width, height] = prepared
+                    .pass_descriptor
+                    .viewport_in(target_width, target_height);
+                render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
+                let [x, y, width, height] = prepared
+                    .pass_descriptor
+                    .scissor_in(target_width, target_height);
+                render_pass.set_scissor_rect(x, y, width, height);
+                let depth_stencil = &prepared.pass_descriptor.depth_stencil;
+                if depth_stencil.uses_stencil() {
+                    render_pass.set_stencil_reference(depth_stencil.stencil.reference);
+                }
+
+                let draw_command = prepared.pass_descriptor.draw_command();
+                let bind_group = &frame_bind_groups[p];
+                set_bind_groups(
+                    bind_group,
+                    prepared.pass_descriptor.bind_style().global_group,
+                    global_bind_group,
+                    &mut bound_global,
+                    |group, bind_group| render_pass.set_bind_group(group, bind_group, &[]),
+                );
+
+                if let Some(immediates) = &prepared.immediates {
+                    self.engine
+                        .bound_device()
+                        .0
+                        .queue()
+                        .assume(|queue| immediates.apply(queue, &mut render_pass));
+                }
+
+                for (v, buffer) in &bind_group.vertex_buffers {
+                    buffer.assume(|buffer| {
+                        render_pass.set_vertex_buffer(*v, buffer.slice(..));
+                    })
+                }
+                for (v, buffer) in &bind_group.dynamic_vertex_buffers {
+                    buffer.underlying_guard.as_imp().buffer().assume(|buffer| {
+                        let buffer_slice = buffer.slice(..);
+                        render_pass.set_vertex_buffer(*v, buffer_slice);
+                    });
+                }
+                if let Some((buffer, format)) = &bind_group.index_buffer {
+                    buffer.assume(|buffer| {
+                        render_pass.set_index_buffer(buffer.slice(..), *format);
+                    });
+                }
+                if let Some(draw) = draw_command.indirect_draw() {
+                    let indirect_buffer = bind_group
+                        .indirect_buffer
+                        .as_ref()
+                        .expect("indirect draws have an indirect buffer");
+                    indirect_buffer.assume(|buffer| match draw_command {
+                        DrawCommand::IndexedIndirect { .. } => render_pass.draw_indexed_indirect(
+                            buffer,
+                            (draw as usize * std::mem::size_of::<DrawIndexedIndirectArgs>()) as u64,
+                        ),
+                        _ => render_pass.draw_indirect(
+                            buffer,
+                            (draw as usize * std::mem::size_of::<DrawIndirectArgs>()) as u64,
+                        ),
+                    });
+                } else if bind_group.index_buffer.is_some() {
+                    render_pass.draw_indexed(
+                        draw_command.indices(),
+                        draw_command.base_vertex(),
+                        0..draw_command.instance_count(),
+                    );
+                } else {
+                    render_pass.draw(draw_command.vertices(), 0..draw_command.instance_count());
+                }
+                render_pass.pop_debug_group();
             }
-            render_pass.pop_debug_group();
-        }
 
-        std::mem::drop(render_pass);
+            std::mem::drop(render_pass);
+            start = end;
+        }
+        if let (false, LoadOp::Clear(_)) = (view_loaded, self.color_load) {
+            //no pass renders into the view, but it still needs clearing
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Port clear"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: view_target,
+                    resolve_target: None,
+                    depth_slice: None,
+                    ops: Operations {
+                        load: color_load_op(self.color_load),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+        }
+        if let Some(retained) = &retained_texture {
+            encoder.copy_texture_to_texture(
+                retained.as_image_copy(),
+                frame_texture.as_image_copy(),
+                frame_texture.size(),
+            );
+        }
         logwise::trace_sync!("wgpu::port::D");
 
+        // Copy reverse buffers back, now that every pass has written them
+        let mut frame_readbacks = Vec::new();
+        let bind_styles = self
+            .prepared_compute_passes
+            .iter()
+            .map(|p| p.pass_descriptor.bind_style())
+            .chain(
+                self.prepared_passes
+                    .iter()
+                    .map(|p| p.pass_descriptor.bind_style()),
+            );
+        for bind_style in bind_styles {
+            for bind in bind_style.binds.values() {
+                if let BindTarget::ReverseBuffer(render_side) = &bind.target {
+                    //safe because we keep the guard until the GPU has finished
+                    let mut access = unsafe { render_side.acquire_gpu_buffer() };
+                    if let Some((source, destination)) = access.readback() {
+                        source.copy_to_mappable_read_buffer(destination, &mut encoder);
+                    }
+                    frame_readbacks.push(access);
+                }
+            }
+        }
+
         // Submit and present frame
         let frame_guard_arc = std::sync::Arc::new(frame_guard);
         logwise::trace_sync!("wgpu::port::E");
 
-        #[cfg(feature = "exfiltrate")]
-        {
-            // Setup debug framebuffer capture
-            let debug_capture =
-                self.setup_debug_framebuffer_capture(&mut encoder, &frame_texture, &depth_texture);
-
-            self.submit_and_present_frame(
-                encoder,
-                frame,
-                frame_bind_groups,
-                frame_acquired_guards,
-                frame_guard_arc,
-                debug_capture,
-            );
-        }
+        let capture = self.capture_next_frame.then(|| {
+            let device = self.engine.bound_device().as_ref();
+            device.0.device().assume(|device| {
+                FrameCapture::encode(
+                    device,
+                    &mut encoder,
+                    &frame_texture,
+                    //multisampled depth can't be sampled on GL
+                    (has_depth && depth_texture.sample_count() == 1).then_some(&depth_texture),
+                )
+            })
+        });
 
-        #[cfg(not(feature = "exfiltrate"))]
-        {
-            self.submit_and_present_frame(
-                encoder,
-                frame,
-                frame_bind_groups,
-                frame_acquired_guards,
-                frame_guard_arc,
-            );
-        }
+        self.submit_and_present_frame(
+            encoder,
+            frame,
+            frame_bind_groups,
+            frame_acquired_guards,
+            frame_readbacks,
+            frame_guard_arc,
+        );
         logwise::trace_sync!("finish_render_frame end");
+        capture
     }
 
-    pub async fn render_frame(mut self) -> Self {
+    /// Renders a frame, and copies it back if `capture` is set.
+    pub async fn render_frame(
+        mut self,
+        capture: bool,
+    ) -> (Self, Option<Result<FramebufferData, Error>>) {
         logwise::debuginternal_sync!("Rendering frame...");
-        smuggle_async("render_frame".to_string(), || async move {
+        smuggle_async("render_frame".to_string(), move || async move {
+            self.capture_next_frame = capture;
             let (encoder, frame_guard) = self.begin_render_frame_internal().await;
             let size_scale = self.view.size_scale().await;
 
-            crate::images::request_animation_frame::request_animation_frame_async(move || {
-                self.finish_render_frame(encoder, frame_guard, size_scale);
-                self
-            })
-            .await
+            let (internal, capture) =
+                crate::images::request_animation_frame::request_animation_frame_async(move || {
+                    let capture = self.finish_render_frame(encoder, frame_guard, size_scale);
+                    (self, capture)
+                })
+                .await;
+            let capture = match capture {
+                Some(capture) => Some(capture.read(internal.engine.bound_device()).await),
+                None => None,
+            };
+            (internal, capture)
         })
         .await
     }
 }
+
+/// Sets the bind groups of a pass, binding the global group only if the pass before didn't
+/// leave it bound at the same group.
+///
+/// `bound_global` tracks the group the global group is bound at in the current pass encoder.
+fn set_bind_groups(
+    guard: &BindGroupGuard,
+    global_group: Option<u32>,
+    global_bind_group: &WgpuCell<wgpu::BindGroup>,
+    bound_global: &mut Option<u32>,
+    mut set_bind_group: impl FnMut(u32, &wgpu::BindGroup),
+) {
+    for (group, bind_group) in &guard.bind_groups {
+        if *bound_global == Some(*group) {
+            *bound_global = None;
+        }
+        bind_group.assume(|bind_group| set_bind_group(*group, bind_group));
+    }
+    if let Some(group) = global_group
+        && *bound_global != Some(group)
+    {
+        global_bind_group.assume(|bind_group| set_bind_group(group, bind_group));
+        *bound_global = Some(group);
+    }
+}
+
+fn color_load_op(load: LoadOp<Float4>) -> wgpu::LoadOp<wgpu::Color> {
+    match load {
+        LoadOp::Clear(color) => wgpu::LoadOp::Clear(wgpu::Color {
+            r: color.r as f64,
+            g: color.g as f64,
+            b: color.b as f64,
+            a: color.a as f64,
+        }),
+        LoadOp::Load => wgpu::LoadOp::Load,
+    }
+}
+
+fn store_op(store: StoreOp) -> wgpu::StoreOp {
+    match store {
+        StoreOp::Store => wgpu::StoreOp::Store,
+        StoreOp::Discard => wgpu::StoreOp::Discard,
+    }
+}
+
+/// Returns the texture in `cache`, first replacing it with a new one if it doesn't match
+/// `descriptor`.
+fn cached_texture(
+    bound_device: &crate::images::BoundDevice,
+    cache: &mut Option<WgpuCell<wgpu::Texture>>,
+    descriptor: &wgpu::TextureDescriptor,
+) -> wgpu::Texture {
+    let matches = |texture: &wgpu::Texture| {
+        texture.size() == descriptor.size
+            && texture.format() == descriptor.format
+            && texture.sample_count() == descriptor.sample_count
+            && texture.usage() == descriptor.usage
+    };
+    if let Some(texture) = cache {
+        let texture = texture.assume(|texture| texture.clone());
+        if matches(&texture) {
+            return texture;
+        }
+    }
+    let texture = bound_device
+        .0
+        .device()
+        .assume(|device| device.create_texture(descriptor));
+    *cache = Some(WgpuCell::new(texture.clone()));
+    texture
+}
+
+fn create_offscreen_target(
+    bound_device: &crate::images::BoundDevice,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+) -> WgpuCell<OffscreenTarget> {
+    bound_device.0.device().assume(|device| {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let color = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen color"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        //replaced by `update_pass_configuration` if the passes use another format
+        let depth = create_offscreen_depth(device, size, TextureFormat::Depth16Unorm, 1);
+        WgpuCell::new(OffscreenTarget {
+            color,
+            depth,
+            width,
+            height,
+        })
+    })
+}
+
+fn create_offscreen_depth(
+    device: &wgpu::Device,
+    size: wgpu::Extent3d,
+    format: TextureFormat,
+    sample_count: u32,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("offscreen depth"),
+        size,
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        //read back by sampling, see `depth_to_float`; multisampled depth can't be sampled on GL
+        usage: if sample_count == 1 {
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+        },
+        view_formats: &[],
+    })
+}
+
+impl PortInternal {
+    /// Copies the offscreen attachments back to the CPU.
+    pub async fn read_framebuffer(&self) -> Result<FramebufferData, Error> {
+        let offscreen = self
+            .offscreen
+            .as_ref()
+            .expect("Only offscreen ports can be read");
+        let bound_device = self.engine.bound_device().as_ref();
+        let enable_depth = self.pass_config.requested.depth_format.is_some();
+        let (color, depth, width, height) = bound_device.0.device().assume(|device| {
+            offscreen.assume(|offscreen| {
+                let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("framebuffer readback"),
+                });
+                let bytes_per_pixel = offscreen
+                    .color
+                    .format()
+                    .block_copy_size(None)
+                    .expect("Color format has a single aspect");
+                let color =
+                    Readback::encode(device, &mut encoder, &offscreen.color, bytes_per_pixel);
+                let depth = (enable_depth && offscreen.depth.sample_count() == 1).then(|| {
+                    let depth = depth_to_float(device, &mut encoder, &offscreen.depth);
+                    Readback::encode(device, &mut encoder, &depth, 4)
+                });
+                bound_device
+                    .0
+                    .queue()
+                    .assume(|queue| queue.submit(std::iter::once(encoder.finish())));
+                (color, depth, offscreen.width, offscreen.height)
+            })
+        });
+        let color = color.read(bound_device).await?;
+        let depth = match depth {
+            Some(depth) => Some(read_depth(depth, bound_device).await?),
+            None => None,
+        };
+        Ok(FramebufferData {
+            width: width as u16,
+            height: height as u16,
+            color,
+            depth,
+        })
+    }
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+use crate::bindings::forward::dynamic::buffer::Buffer;
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::imp;
+use crate::imp::wgpu::cell::WgpuCell;
+use wgpu::{ComputePipeline, ComputePipelineDescriptor};
+
+use super::globals::Globals;
+use super::guards::{AcquiredGuards, BindGroupGuard};
+use super::prepared_pass::pipeline_layout;
+use super::types::CameraProjection;
+
+/**
+A compute pass that is prepared to be dispatched (compiled, layout calculated, etc.)
+*/
+#[derive(Debug)]
+pub struct PreparedComputePass {
+    pub pipeline: WgpuCell<ComputePipeline>,
+    pub pass_descriptor: ComputePassDescriptor,
+    pub workgroup_count: (u32, u32, u32),
+    pub bind_group_guard: BindGroupGuard,
+    pub acquired_guards: Option<AcquiredGuards>,
+}
+
+impl PreparedComputePass {
+    pub async fn new(
+        bind_device: &crate::images::BoundDevice,
+        descriptor: ComputePassDescriptor,
+        globals: &Globals,
+        copy_info: &mut imp::CopyInfo<'_>,
+    ) -> PreparedComputePass {
+        let (bind_group_layouts, pipeline_layout) = pipeline_layout(
+            bind_device,
+            descriptor.bind_style(),
+            descriptor.name(),
+            globals,
+            None,
+        );
+
+        let module = bind_device.0.device().assume(|device| {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(descriptor.shader.label),
+                source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
+                    &descriptor.shader.wgsl_code,
+                )),
+            })
+        });
+
+        let constants: Vec<(&str, f64)> = descriptor
+            .shader
+            .constants
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect();
+        let pipeline = bind_device.0.with_pipeline_cache(|cache| {
+            bind_device.0.device().assume(|device| {
+                device.create_compute_pipeline(&ComputePipelineDescriptor {
+                    label: Some(descriptor.name()),
+                    layout: Some(&pipeline_layout),
+                    module: &module,
+                    entry_point: descriptor.shader.entry_point.as_deref(),
+                    compilation_options: wgpu::PipelineCompilationOptions {
+                        constants: &constants,
+                        ..Default::default()
+                    },
+                    cache,
+                })
+            })
+        });
+        logwise::trace_sync!("Created compute pipeline");
+
+        let (bind_group_guard, acquired_guards) = BindGroupGuard::new(
+            bind_device,
+            descriptor.bind_style(),
+            descriptor.name(),
+            &bind_group_layouts,
+            globals,
+            copy_info,
+        )
+        .await;
+        PreparedComputePass {
+            pipeline: WgpuCell::new(pipeline),
+            workgroup_count: descriptor.dispatch.workgroup_count(),
+            pass_descriptor: descriptor,
+            bind_group_guard,
+            acquired_guards: Some(acquired_guards),
+        }
+    }
+
+    pub async fn recreate_acquired_guards(
+        &mut self,
+        camera_buffer: &Buffer<CameraProjection>,
+        copy_info: &mut imp::CopyInfo<'_>,
+    ) {
+        // Recreate only the acquired_guards field, leaving bind_group_guard unchanged
+        let new_acquired_guards =
+            AcquiredGuards::new(self.pass_descriptor.bind_style(), copy_info, camera_buffer).await;
+        self.acquired_guards = Some(new_acquired_guards);
+    }
+}
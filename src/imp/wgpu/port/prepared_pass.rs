@@ -1,8 +1,14 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
-use crate::bindings::bind_style::BindTarget;
+use crate::bindings::BindStyle;
+use crate::bindings::bind_style::{BindTarget, Stage};
 use crate::bindings::forward::dynamic::buffer::Buffer;
-use crate::images::render_pass::{DrawCommand, PassDescriptor};
-use crate::images::vertex_layout::VertexFieldType;
+use crate::images::render_pass::{
+    BlendComponent, BlendFactor, BlendOperation, ColorWriteMask, CompareFunction, CullMode,
+    DepthFormat, FrontFace, PassDescriptor, PassEntry, PassHandle, PolygonMode, StencilFaceState,
+    StencilOperation, Topology,
+};
+use crate::images::shader::validation::ShaderError;
+use crate::images::vertex_layout::{StepMode, VertexFieldType};
 use crate::imp;
 use crate::imp::wgpu::buffer::StorageType;
 use crate::imp::wgpu::cell::WgpuCell;
@@ -10,13 +16,14 @@ use crate::stable_address_vec::StableAddressVec;
 use std::num::NonZero;
 use wgpu::{
     BindGroupLayoutEntry, BindingType, BlendState, BufferBindingType, BufferSize, ColorTargetState,
-    CompareFunction, MultisampleState, PipelineLayoutDescriptor, PolygonMode, PrimitiveState,
-    PrimitiveTopology, RenderPipeline, RenderPipelineDescriptor, SamplerBindingType,
-    StencilFaceState, StencilState, TextureFormat, TextureSampleType, TextureViewDimension,
-    VertexAttribute, VertexBufferLayout, VertexState, VertexStepMode,
+    MultisampleState, PipelineLayoutDescriptor, PrimitiveState, PrimitiveTopology, RenderPipeline,
+    RenderPipelineDescriptor, SamplerBindingType, TextureFormat, TextureSampleType,
+    TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexState, VertexStepMode,
 };
 
+use super::globals::Globals;
 use super::guards::{AcquiredGuards, BindGroupGuard};
+use super::immediates::PreparedImmediates;
 use super::types::{CameraProjection, PassConfig};
 
 /**
@@ -25,260 +32,226 @@ A pass that is prepared to be rendered (compiled, layout calculated, etc.)
 #[derive(Debug)]
 pub struct PreparedPass {
     pub pipeline: WgpuCell<RenderPipeline>,
+    /// The port's handle for the pass, to reuse it while only other passes change.
+    pub handle: PassHandle,
     pub pass_descriptor: PassDescriptor,
-    #[allow(dead_code)] //instance counts are not used yet
-    pub instance_count: u32,
-    pub vertex_count: u32,
     pub depth_pass: bool,
     pub bind_group_guard: BindGroupGuard,
     pub acquired_guards: Option<AcquiredGuards>,
+    pub immediates: Option<PreparedImmediates>,
 }
 
 impl PreparedPass {
     pub async fn new(
         bind_device: &crate::images::BoundDevice,
-        descriptor: PassDescriptor,
-        enable_depth: bool,
-        camera_buffer: &Buffer<CameraProjection>,
-        mipmapped_sampler: &WgpuCell<wgpu::Sampler>,
+        entry: &PassEntry,
+        depth_format: Option<DepthFormat>,
+        globals: &Globals,
         copy_info: &mut imp::CopyInfo<'_>,
         pass_config: &PassConfig,
-    ) -> PreparedPass {
-        let mut layouts = Vec::new();
-
-        for (pass_index, info) in &descriptor.bind_style().binds {
-            let stage = match info.stage {
-                crate::bindings::bind_style::Stage::Fragment => wgpu::ShaderStages::FRAGMENT,
-                crate::bindings::bind_style::Stage::Vertex => wgpu::ShaderStages::VERTEX,
-            };
-            let binding_type = match &info.target {
-                BindTarget::DynamicBuffer(imp) => {
-                    //safe because we're not using the buffer
-                    let storage_type = unsafe { imp.imp.unsafe_imp().storage_type() };
-                    let buffer_binding_type = match storage_type {
-                        StorageType::Uniform => BufferBindingType::Uniform,
-                        StorageType::Storage => BufferBindingType::Storage { read_only: true },
-                        StorageType::Vertex | StorageType::Index => unreachable!(),
-                    };
-                    BindingType::Buffer {
-                        ty: buffer_binding_type,
-                        has_dynamic_offset: false,
-                        min_binding_size: Some(BufferSize::new(imp.element_size as u64).unwrap()),
-                    }
-                }
-                BindTarget::StaticBuffer(imp) => {
-                    let buffer_binding_type = match imp.storage_type() {
-                        StorageType::Uniform => BufferBindingType::Uniform,
-                        StorageType::Storage => BufferBindingType::Storage { read_only: true },
-                        StorageType::Vertex | StorageType::Index => unreachable!(),
-                    };
-
-                    BindingType::Buffer {
-                        ty: buffer_binding_type,
-                        has_dynamic_offset: false,
-                        min_binding_size: NonZero::new(imp.buffer().assume(|b| b.size())),
-                    }
-                }
-                BindTarget::Camera => {
-                    //I guess these are implemented with buffers for now...
-                    BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: Some(NonZero::new(64).unwrap()), //This value determined experimentally?
-                    }
-                }
-                BindTarget::FrameCounter => {
-                    //I guess these are implemented with buffers for now...
-                    BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: Some(NonZero::new(1).unwrap()), //???
-                    }
-                }
-                BindTarget::StaticTexture(_texture, sampler_type) => BindingType::Texture {
-                    sample_type: TextureSampleType::Float {
-                        filterable: sampler_type.is_some(),
-                    },
-                    view_dimension: TextureViewDimension::D2,
-                    multisampled: false,
-                },
-                BindTarget::DynamicTexture(_texture) => {
-                    BindingType::Texture {
-                        sample_type: TextureSampleType::Float { filterable: false }, //??
-                        view_dimension: TextureViewDimension::D2,
-                        multisampled: false,
-                    }
-                }
-                BindTarget::Sampler(_sampler) => {
-                    BindingType::Sampler(SamplerBindingType::Filtering)
-                }
-                BindTarget::VB(..) => {
-                    continue; //not considered as a binding
-                }
-                BindTarget::DynamicVB(..) => {
-                    continue; //not considered as a binding
-                }
-            };
-            let layout = BindGroupLayoutEntry {
-                binding: *pass_index,
-                visibility: stage,
-                ty: binding_type,
-                count: None, //not array
-            };
-            layouts.push(layout);
-        }
-        // println!("Will create bind group layout {:?}", layouts);
-
-        let bind_group_layout = bind_device.0.device().assume(|device| {
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                label: Some(descriptor.name()),
-                entries: layouts.as_slice(),
-            })
-        });
-
-        let pipeline_layout = bind_device.0.device().assume(|device| {
-            device.create_pipeline_layout(&PipelineLayoutDescriptor {
-                label: Some(descriptor.name()),
-                bind_group_layouts: &[&bind_group_layout],
-                immediate_size: 0, //not yet supported
-            })
+    ) -> Result<PreparedPass, ShaderError> {
+        //ports validate passes as they are added, but the device may still refuse them, so
+        //its errors are caught until the pipeline is built
+        let scope = bind_device
+            .0
+            .device()
+            .assume(|device| device.push_error_scope(wgpu::ErrorFilter::Validation));
+        let descriptor = &entry.descriptor;
+        let immediates = descriptor.immediates.as_ref().map(|immediates| {
+            PreparedImmediates::new(
+                bind_device,
+                immediates,
+                descriptor.bind_style(),
+                descriptor.name(),
+            )
         });
+        let (bind_group_layouts, pipeline_layout) = pipeline_layout(
+            bind_device,
+            descriptor.bind_style(),
+            descriptor.name(),
+            globals,
+            immediates.as_ref(),
+        );
+        let shader_source = |wgsl| match &immediates {
+            Some(immediates) => immediates.shader_source(wgsl),
+            None => std::borrow::Cow::Borrowed(wgsl),
+        };
 
         let vertex_module = bind_device.0.device().assume(|device| {
             device.create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: Some(descriptor.vertex_shader.label),
-                source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
+                source: wgpu::ShaderSource::Wgsl(shader_source(
                     &descriptor.vertex_shader.wgsl_code,
                 )),
             })
         });
 
-        //calculate vertex buffers
+        let constants: Vec<(&str, f64)> = descriptor
+            .constants
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+            .collect();
+        let compilation_options = || wgpu::PipelineCompilationOptions {
+            constants: &constants,
+            ..Default::default()
+        };
+
+        //calculate vertex buffers, in the order they are assigned to the shader.
+        //Shader locations continue across buffers, so a pass can mix per-vertex and
+        //per-instance buffers.
         let mut vertex_buffers = Vec::new();
         let all_vertex_attributes = StableAddressVec::with_capactiy(5);
+        let mut shader_location = 0;
 
-        for buffer in descriptor.bind_style.binds.values() {
-            match &buffer.target {
-                BindTarget::StaticBuffer(_)
-                | BindTarget::DynamicBuffer(_)
-                | BindTarget::Camera
-                | BindTarget::FrameCounter
-                | BindTarget::DynamicTexture(_)
-                | BindTarget::StaticTexture(..)
-                | BindTarget::Sampler(_) => {}
-                BindTarget::VB(layout, _) | BindTarget::DynamicVB(layout, _) => {
-                    let mut each_vertex_attributes = Vec::new();
-                    let mut offset = 0;
-                    for (f, field) in layout.fields.iter().enumerate() {
-                        let attribute = VertexAttribute {
-                            format: match field.r#type {
-                                VertexFieldType::F32 => wgpu::VertexFormat::Float32,
-                            },
-                            offset,
-                            shader_location: f as u32,
-                        };
-                        offset += field.r#type.stride() as u64;
-                        each_vertex_attributes.push(attribute);
-                    }
-                    let strong_vertex_attributes =
-                        all_vertex_attributes.push(each_vertex_attributes);
-                    let layout = VertexBufferLayout {
-                        array_stride: layout.element_stride() as u64,
-                        step_mode: VertexStepMode::Vertex,
-                        attributes: strong_vertex_attributes,
-                    };
-                    vertex_buffers.push(layout);
-                }
+        for slot in descriptor.bind_style.vertex_buffer_slots() {
+            let (layout, step_mode) = match &descriptor.bind_style.binds[&slot].target {
+                BindTarget::VB(layout, step_mode, _)
+                | BindTarget::DynamicVB(layout, step_mode, _) => (layout, step_mode),
+                _ => unreachable!("vertex_buffer_slots only returns vertex buffers"),
+            };
+            let mut each_vertex_attributes = Vec::new();
+            let mut offset = 0;
+            for field in &layout.fields {
+                let attribute = VertexAttribute {
+                    format: match field.r#type {
+                        VertexFieldType::F32 => wgpu::VertexFormat::Float32,
+                        VertexFieldType::F32x2 => wgpu::VertexFormat::Float32x2,
+                        VertexFieldType::F32x3 => wgpu::VertexFormat::Float32x3,
+                        VertexFieldType::F32x4 => wgpu::VertexFormat::Float32x4,
+                    },
+                    offset,
+                    shader_location,
+                };
+                offset += field.r#type.stride() as u64;
+                shader_location += 1;
+                each_vertex_attributes.push(attribute);
             }
+            let strong_vertex_attributes = all_vertex_attributes.push(each_vertex_attributes);
+            let layout = VertexBufferLayout {
+                array_stride: layout.element_stride() as u64,
+                step_mode: match step_mode {
+                    StepMode::Vertex => VertexStepMode::Vertex,
+                    StepMode::Instance => VertexStepMode::Instance,
+                },
+                attributes: strong_vertex_attributes,
+            };
+            vertex_buffers.push(layout);
         }
 
         let vertex_state = VertexState {
             module: &vertex_module,
-            entry_point: None,
-            compilation_options: Default::default(),
+            entry_point: descriptor.vertex_shader.entry_point.as_deref(),
+            compilation_options: compilation_options(),
             buffers: &vertex_buffers,
         };
-        let topology = match descriptor.draw_command() {
-            DrawCommand::TriangleStrip(_count) => PrimitiveTopology::TriangleStrip,
-            DrawCommand::TriangleList(..) => PrimitiveTopology::TriangleList,
-        };
-        let vertex_count = match descriptor.draw_command {
-            DrawCommand::TriangleStrip(count) => count * 3,
-            DrawCommand::TriangleList(count) => count * 3,
-        };
-        let instance_count = match descriptor.draw_command {
-            DrawCommand::TriangleStrip(..) => 1,
-            DrawCommand::TriangleList(..) => 1,
+        let topology = match descriptor.draw_command().topology() {
+            Topology::PointList => PrimitiveTopology::PointList,
+            Topology::LineList => PrimitiveTopology::LineList,
+            Topology::LineStrip => PrimitiveTopology::LineStrip,
+            Topology::TriangleList => PrimitiveTopology::TriangleList,
+            Topology::TriangleStrip => PrimitiveTopology::TriangleStrip,
         };
 
+        let rasterizer = descriptor.rasterizer;
+        //ports refuse passes the device does not support
         let primitive_state = PrimitiveState {
             topology,
             strip_index_format: None,
-            front_face: wgpu::FrontFace::Ccw, //?
-            cull_mode: Some(wgpu::Face::Back),
-            unclipped_depth: false,
-            polygon_mode: PolygonMode::Fill,
+            front_face: match rasterizer.front_face {
+                FrontFace::CounterClockwise => wgpu::FrontFace::Ccw,
+                FrontFace::Clockwise => wgpu::FrontFace::Cw,
+            },
+            cull_mode: match rasterizer.cull_mode {
+                CullMode::None => None,
+                CullMode::Front => Some(wgpu::Face::Front),
+                CullMode::Back => Some(wgpu::Face::Back),
+            },
+            unclipped_depth: rasterizer.unclipped_depth,
+            polygon_mode: match rasterizer.polygon_mode {
+                PolygonMode::Fill => wgpu::PolygonMode::Fill,
+                PolygonMode::Line => wgpu::PolygonMode::Line,
+                PolygonMode::Point => wgpu::PolygonMode::Point,
+            },
             conservative: false,
         };
 
-        //because everything is in one render pass, we need all the depth states to match
-        //enable depth if any pass wants it
-        let depth_state = if enable_depth {
-            Some(wgpu::DepthStencilState {
-                format: TextureFormat::Depth16Unorm,
-                depth_write_enabled: true,
-                depth_compare: CompareFunction::LessEqual,
-                stencil: StencilState {
-                    front: StencilFaceState::IGNORE,
-                    back: StencilFaceState::IGNORE,
-                    read_mask: 0,
-                    write_mask: 0,
-                },
-                bias: Default::default(),
-            })
-        } else {
-            None
+        //passes that render into the same target share a render pass, so we need all their
+        //depth states to match; enable depth if the target has it
+        let (color_format, depth_format) = match &descriptor.render_target {
+            Some(target) => (
+                target.format,
+                target
+                    .has_depth()
+                    .then_some(crate::imp::wgpu::render_texture::DEPTH_FORMAT),
+            ),
+            None => (
+                pass_config.color_format,
+                depth_format.map(depth_texture_format),
+            ),
         };
+        let depth_stencil = descriptor.depth_stencil;
+        let stencil = depth_stencil.stencil;
+        let depth_state = depth_format.map(|format| wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: depth_stencil.depth_write,
+            depth_compare: compare_function(depth_stencil.compare),
+            stencil: wgpu::StencilState {
+                front: stencil_face_state(stencil.front),
+                back: stencil_face_state(stencil.back),
+                read_mask: stencil.read_mask,
+                write_mask: stencil.write_mask,
+            },
+            bias: wgpu::DepthBiasState {
+                constant: rasterizer.depth_bias.constant,
+                slope_scale: rasterizer.depth_bias.slope_scale,
+                clamp: rasterizer.depth_bias.clamp,
+            },
+        });
 
         let multisample_state = MultisampleState {
-            count: 1,
+            count: match descriptor.render_target {
+                Some(_) => 1,
+                None => pass_config.sample_count,
+            },
             mask: !0,
             alpha_to_coverage_enabled: false,
         };
 
-        let fragment_module = bind_device.0.device().assume(|device| {
-            device.create_shader_module(wgpu::ShaderModuleDescriptor {
-                label: Some(descriptor.fragment_shader.label),
-                source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
-                    &descriptor.fragment_shader.wgsl_code,
-                )),
-            })
-        });
-        let blend = if descriptor.alpha {
-            Some(BlendState::ALPHA_BLENDING)
-        } else {
-            None
-        };
+        //shaders from one ShaderModule share their WGSL, which is compiled once
+        let fragment_module =
+            if descriptor.fragment_shader.wgsl_code == descriptor.vertex_shader.wgsl_code {
+                vertex_module.clone()
+            } else {
+                bind_device.0.device().assume(|device| {
+                    device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                        label: Some(descriptor.fragment_shader.label),
+                        source: wgpu::ShaderSource::Wgsl(shader_source(
+                            &descriptor.fragment_shader.wgsl_code,
+                        )),
+                    })
+                })
+            };
+        let blend = descriptor
+            .blend
+            .components()
+            .map(|(color, alpha)| BlendState {
+                color: blend_component(color),
+                alpha: blend_component(alpha),
+            });
         logwise::debuginternal_sync!(
             "surface format is {surface_format}",
             surface_format = logwise::privacy::LogIt(pass_config.surface_format)
         );
 
-        let color_target_format = if pass_config.surface_format.is_srgb() {
-            pass_config.surface_format
-        } else {
-            //in this case we accomplish this via view
-            TextureFormat::Bgra8UnormSrgb
-        };
         let color_target_state = ColorTargetState {
-            format: color_target_format,
+            format: color_format,
             blend,
-            write_mask: Default::default(),
+            write_mask: color_writes(descriptor.write_mask),
         };
         let fragment_state = wgpu::FragmentState {
             module: &fragment_module,
-            entry_point: None,
-            compilation_options: Default::default(),
+            entry_point: descriptor.fragment_shader.entry_point.as_deref(),
+            compilation_options: compilation_options(),
             targets: &[Some(color_target_state)],
         };
 
@@ -292,35 +265,48 @@ impl PreparedPass {
             multisample: multisample_state,
             fragment: Some(fragment_state),
             multiview_mask: None,
-            cache: None, //todo, caching?
+            cache: None,
         };
-        let pipeline = bind_device
-            .0
-            .device()
-            .assume(|device| device.create_render_pipeline(&render_descriptor));
+        let pipeline = bind_device.0.with_pipeline_cache(|cache| {
+            bind_device.0.device().assume(|device| {
+                device.create_render_pipeline(&RenderPipelineDescriptor {
+                    cache,
+                    ..render_descriptor.clone()
+                })
+            })
+        });
+        if let Some(error) = scope.pop().await {
+            return Err(ShaderError::Pipeline {
+                pass: descriptor.name().to_string(),
+                message: error.to_string(),
+            });
+        }
         logwise::trace_sync!("Created render pipeline");
 
-        // Create the BindGroupGuard using the constructed bind_group_layout
-        let (bind_group_guard, acquired_guards) = BindGroupGuard::new(
+        // Create the BindGroupGuard using the constructed bind_group_layouts
+        let (mut bind_group_guard, acquired_guards) = BindGroupGuard::new(
             bind_device,
             descriptor.bind_style(),
             descriptor.name(),
-            &bind_group_layout,
-            camera_buffer,
-            mipmapped_sampler,
+            &bind_group_layouts,
+            globals,
             copy_info,
         )
         .await;
         logwise::trace_sync!("Created bindgroup guard");
-        PreparedPass {
+        //the emulated immediates are bound like the pass's own groups
+        bind_group_guard
+            .bind_groups
+            .extend(immediates.iter().flat_map(|i| i.emulated_bind_group()));
+        Ok(PreparedPass {
             pipeline: WgpuCell::new(pipeline),
-            vertex_count,
-            instance_count,
+            handle: entry.handle,
             depth_pass: render_descriptor.depth_stencil.is_some(),
             pass_descriptor: descriptor.clone(),
             bind_group_guard,
             acquired_guards: Some(acquired_guards),
-        }
+            immediates,
+        })
     }
 
     pub async fn recreate_acquired_guards(
@@ -334,3 +320,243 @@ impl PreparedPass {
         self.acquired_guards = Some(new_acquired_guards);
     }
 }
+
+/// Creates the layouts of the bind groups in `bind_style`, and a pipeline layout that binds
+/// them along with the port's global group and any `immediates`.
+///
+/// Returns the layouts of the pass's own groups by group, including empty groups below
+/// the highest group bound.
+pub(super) fn pipeline_layout(
+    bind_device: &crate::images::BoundDevice,
+    bind_style: &BindStyle,
+    name: &str,
+    globals: &Globals,
+    immediates: Option<&PreparedImmediates>,
+) -> (Vec<(u32, wgpu::BindGroupLayout)>, wgpu::PipelineLayout) {
+    bind_device.0.device().assume(|device| {
+        let layouts: Vec<(u32, wgpu::BindGroupLayout)> = (0..bind_style.group_count())
+            .filter(|group| bind_style.global_group != Some(*group))
+            .map(|group| {
+                let entries = bind_group_layout_entries(bind_style, group);
+                let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some(name),
+                    entries: entries.as_slice(),
+                });
+                (group, layout)
+            })
+            .collect();
+        let create = |emulated_layout: Option<&wgpu::BindGroupLayout>| {
+            globals.layout.assume(|global_layout| {
+                let mut own_layouts = layouts.iter().map(|(_, layout)| layout);
+                let bind_group_layouts: Vec<&wgpu::BindGroupLayout> = (0..bind_style.group_count())
+                    .map(|group| match bind_style.global_group {
+                        Some(global) if global == group => global_layout,
+                        _ => own_layouts.next().expect("a layout for each group"),
+                    })
+                    //the emulated immediates follow the pass's own groups
+                    .chain(emulated_layout)
+                    .collect();
+                device.create_pipeline_layout(&PipelineLayoutDescriptor {
+                    label: Some(name),
+                    bind_group_layouts: &bind_group_layouts,
+                    immediate_size: immediates.map_or(0, |i| i.immediate_size()),
+                })
+            })
+        };
+        let pipeline_layout = match immediates.and_then(|i| i.emulated_layout()) {
+            Some(layout) => layout.assume(|layout| create(Some(layout))),
+            None => create(None),
+        };
+        (layouts, pipeline_layout)
+    })
+}
+
+/// Returns the bind group layout entries for the resources in `group` of `bind_style`.
+///
+/// Vertex and index buffers are not part of any bind group, so they are skipped.
+fn bind_group_layout_entries(bind_style: &BindStyle, group: u32) -> Vec<BindGroupLayoutEntry> {
+    let mut layouts = Vec::new();
+
+    for (slot, info) in bind_style
+        .binds
+        .iter()
+        .filter(|(slot, _)| slot.group == group)
+    {
+        let stage = match info.stage {
+            Stage::Fragment => wgpu::ShaderStages::FRAGMENT,
+            Stage::Vertex => wgpu::ShaderStages::VERTEX,
+            Stage::Compute => wgpu::ShaderStages::COMPUTE,
+        };
+        let binding_type = match &info.target {
+            BindTarget::DynamicBuffer(imp) => {
+                //safe because we're not using the buffer
+                let storage_type = unsafe { imp.imp.unsafe_imp().storage_type() };
+                let buffer_binding_type = buffer_binding_type(storage_type, info.stage);
+                BindingType::Buffer {
+                    ty: buffer_binding_type,
+                    has_dynamic_offset: false,
+                    min_binding_size: Some(BufferSize::new(imp.element_size as u64).unwrap()),
+                }
+            }
+            BindTarget::StaticBuffer(imp) => {
+                let buffer_binding_type = buffer_binding_type(imp.storage_type(), info.stage);
+
+                BindingType::Buffer {
+                    ty: buffer_binding_type,
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZero::new(imp.buffer().assume(|b| b.size())),
+                }
+            }
+            BindTarget::ReverseBuffer(render_side) => BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: Some(BufferSize::new(render_side.element_size as u64).unwrap()),
+            },
+            BindTarget::Camera => {
+                //I guess these are implemented with buffers for now...
+                BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: Some(NonZero::new(64).unwrap()), //This value determined experimentally?
+                }
+            }
+            BindTarget::FrameCounter | BindTarget::Time => BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: Some(NonZero::new(4).unwrap()),
+            },
+            BindTarget::StaticTexture(_texture, sampler_type) => BindingType::Texture {
+                sample_type: TextureSampleType::Float {
+                    filterable: sampler_type.is_some(),
+                },
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            },
+            BindTarget::DynamicTexture(_texture) => {
+                BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false }, //??
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                }
+            }
+            BindTarget::RenderTexture(_texture, sampler_type) => BindingType::Texture {
+                sample_type: TextureSampleType::Float {
+                    filterable: sampler_type.is_some(),
+                },
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            },
+            BindTarget::RenderTextureDepth(_texture) => BindingType::Texture {
+                sample_type: TextureSampleType::Depth,
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            },
+            BindTarget::Sampler(_sampler) => BindingType::Sampler(SamplerBindingType::Filtering),
+            BindTarget::VB(..) => {
+                continue; //not considered as a binding
+            }
+            BindTarget::DynamicVB(..) => {
+                continue; //not considered as a binding
+            }
+        };
+        let layout = BindGroupLayoutEntry {
+            binding: slot.pass_index,
+            visibility: stage,
+            ty: binding_type,
+            count: None, //not array
+        };
+        layouts.push(layout);
+    }
+    layouts
+}
+
+/// Returns how a buffer with `storage_type` binds in `stage`.
+fn buffer_binding_type(storage_type: StorageType, stage: Stage) -> BufferBindingType {
+    match storage_type {
+        StorageType::Uniform => BufferBindingType::Uniform,
+        StorageType::Storage => BufferBindingType::Storage { read_only: true },
+        //vertex shaders cannot write storage buffers without VERTEX_WRITABLE_STORAGE
+        StorageType::StorageReadWrite | StorageType::Indirect => BufferBindingType::Storage {
+            read_only: stage == Stage::Vertex,
+        },
+        StorageType::Vertex | StorageType::Index => unreachable!(),
+    }
+}
+
+fn blend_component(component: BlendComponent) -> wgpu::BlendComponent {
+    let factor = |factor| match factor {
+        BlendFactor::Zero => wgpu::BlendFactor::Zero,
+        BlendFactor::One => wgpu::BlendFactor::One,
+        BlendFactor::Src => wgpu::BlendFactor::Src,
+        BlendFactor::OneMinusSrc => wgpu::BlendFactor::OneMinusSrc,
+        BlendFactor::SrcAlpha => wgpu::BlendFactor::SrcAlpha,
+        BlendFactor::OneMinusSrcAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
+        BlendFactor::Dst => wgpu::BlendFactor::Dst,
+        BlendFactor::OneMinusDst => wgpu::BlendFactor::OneMinusDst,
+        BlendFactor::DstAlpha => wgpu::BlendFactor::DstAlpha,
+        BlendFactor::OneMinusDstAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
+        BlendFactor::SrcAlphaSaturated => wgpu::BlendFactor::SrcAlphaSaturated,
+    };
+    wgpu::BlendComponent {
+        src_factor: factor(component.src_factor),
+        dst_factor: factor(component.dst_factor),
+        operation: match component.operation {
+            BlendOperation::Add => wgpu::BlendOperation::Add,
+            BlendOperation::Subtract => wgpu::BlendOperation::Subtract,
+            BlendOperation::ReverseSubtract => wgpu::BlendOperation::ReverseSubtract,
+            BlendOperation::Min => wgpu::BlendOperation::Min,
+            BlendOperation::Max => wgpu::BlendOperation::Max,
+        },
+    }
+}
+
+/// The texture format of a depth buffer in `format`.
+pub(super) fn depth_texture_format(format: DepthFormat) -> TextureFormat {
+    match format {
+        DepthFormat::Depth16Unorm => TextureFormat::Depth16Unorm,
+        DepthFormat::Depth24Plus => TextureFormat::Depth24Plus,
+        DepthFormat::Depth24PlusStencil8 => TextureFormat::Depth24PlusStencil8,
+        DepthFormat::Depth32Float => TextureFormat::Depth32Float,
+    }
+}
+
+fn compare_function(compare: CompareFunction) -> wgpu::CompareFunction {
+    match compare {
+        CompareFunction::Never => wgpu::CompareFunction::Never,
+        CompareFunction::Less => wgpu::CompareFunction::Less,
+        CompareFunction::Equal => wgpu::CompareFunction::Equal,
+        CompareFunction::LessEqual => wgpu::CompareFunction::LessEqual,
+        CompareFunction::Greater => wgpu::CompareFunction::Greater,
+        CompareFunction::NotEqual => wgpu::CompareFunction::NotEqual,
+        CompareFunction::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+        CompareFunction::Always => wgpu::CompareFunction::Always,
+    }
+}
+
+fn stencil_face_state(face: StencilFaceState) -> wgpu::StencilFaceState {
+    let operation = |operation| match operation {
+        StencilOperation::Keep => wgpu::StencilOperation::Keep,
+        StencilOperation::Zero => wgpu::StencilOperation::Zero,
+        StencilOperation::Replace => wgpu::StencilOperation::Replace,
+        StencilOperation::Invert => wgpu::StencilOperation::Invert,
+        StencilOperation::IncrementClamp => wgpu::StencilOperation::IncrementClamp,
+        StencilOperation::DecrementClamp => wgpu::StencilOperation::DecrementClamp,
+        StencilOperation::IncrementWrap => wgpu::StencilOperation::IncrementWrap,
+        StencilOperation::DecrementWrap => wgpu::StencilOperation::DecrementWrap,
+    };
+    wgpu::StencilFaceState {
+        compare: compare_function(face.compare),
+        fail_op: operation(face.fail_op),
+        depth_fail_op: operation(face.depth_fail_op),
+        pass_op: operation(face.pass_op),
+    }
+}
+
+fn color_writes(mask: ColorWriteMask) -> wgpu::ColorWrites {
+    let mut writes = wgpu::ColorWrites::empty();
+    writes.set(wgpu::ColorWrites::RED, mask.red);
+    writes.set(wgpu::ColorWrites::GREEN, mask.green);
+    writes.set(wgpu::ColorWrites::BLUE, mask.blue);
+    writes.set(wgpu::ColorWrites::ALPHA, mask.alpha);
+    writes
+}
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
 use crate::bindings::forward::dynamic::buffer::CRepr;
-use crate::images::render_pass::PassDescriptor;
+use crate::images::compute_pass::ComputePassDescriptor;
+use crate::images::render_pass::{DepthFormat, PassList};
 use wgpu::TextureFormat;
 
 #[repr(C)]
@@ -11,39 +12,64 @@ pub struct CameraProjection {
 
 unsafe impl CRepr for CameraProjection {}
 
-#[cfg(feature = "exfiltrate")]
-#[derive(Debug)]
-pub struct DebugCaptureData {
-    pub dump_buf: wgpu::Buffer,
-    pub dump_buff_bytes_per_row: u32,
-    pub depth_dump_buf: wgpu::Buffer,
-    pub depth_dump_buff_bytes_per_row: u32,
-}
-
 #[derive(Debug, Clone, PartialEq)]
 pub struct PassConfig {
-    pub pass_descriptors: Vec<PassDescriptor>,
-    pub enable_depth: bool,
+    pub passes: PassList,
+    /// Compute passes, which run before the render passes in the order they were added.
+    pub compute_pass_descriptors: Vec<ComputePassDescriptor>,
+    /// The format of the view's depth buffer, or `None` if no pass into the view uses depth.
+    pub depth_format: Option<DepthFormat>,
     pub surface_format: TextureFormat,
+    /// The format pipelines render into.
+    ///
+    /// This differs from `surface_format` when a non-sRGB surface is viewed as sRGB,
+    /// and for offscreen views, which render their own format.
+    pub color_format: TextureFormat,
+    /// How many samples passes into the view render for each pixel.
+    ///
+    /// Passes into render textures always render one.
+    pub sample_count: u32,
 }
 
 impl PassConfig {
-    pub fn new(surface_format: TextureFormat) -> Self {
+    pub fn new(surface_format: TextureFormat, color_format: TextureFormat) -> Self {
         PassConfig {
-            pass_descriptors: Vec::new(),
-            enable_depth: false,
+            passes: PassList::default(),
+            compute_pass_descriptors: Vec::new(),
+            depth_format: None,
             surface_format,
+            color_format,
+            sample_count: 1,
         }
     }
 
-    pub fn add_pass(&mut self, descriptor: PassDescriptor) {
-        if descriptor.depth {
-            self.enable_depth = true;
-        }
-        self.pass_descriptors.push(descriptor);
+    pub fn set_passes(&mut self, passes: PassList) {
+        //passes into render textures use the texture's depth attachment instead
+        self.depth_format = passes.view_depth_format();
+        self.passes = passes;
+    }
+
+    /// Whether pipelines prepared for `other` can render with this configuration,
+    /// which holds when only the passes differ.
+    pub fn shares_pipelines_with(&self, other: &PassConfig) -> bool {
+        self.depth_format == other.depth_format
+            && self.surface_format == other.surface_format
+            && self.color_format == other.color_format
+            && self.sample_count == other.sample_count
     }
 }
 
+/**
+The persistent attachments of an offscreen view.
+*/
+#[derive(Debug)]
+pub struct OffscreenTarget {
+    pub color: wgpu::Texture,
+    pub depth: wgpu::Texture,
+    pub width: u32,
+    pub height: u32,
+}
+
 /**
 Provides state tracking.
 
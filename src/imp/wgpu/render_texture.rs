@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Render textures, which passes render into and later passes sample.
+
+use crate::imp::Error;
+use crate::imp::wgpu::cell::WgpuCell;
+use std::sync::Arc;
+use wgpu::TextureFormat;
+
+/// The format of render texture depth attachments.
+///
+/// Unlike the port's depth buffer, these are sampled by later passes (e.g. shadow maps),
+/// so we want the full precision.
+pub(super) const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+/**
+The GPU side of a render texture.
+
+Clones share the same attachments.
+*/
+#[derive(Debug, Clone)]
+pub struct RenderTexture {
+    pub(super) color: WgpuCell<wgpu::Texture>,
+    pub(super) depth: Option<WgpuCell<wgpu::Texture>>,
+    pub(super) format: TextureFormat,
+}
+
+impl RenderTexture {
+    pub async fn new<Format: crate::pixel_formats::sealed::PixelFormat>(
+        bound_device: &Arc<crate::images::BoundDevice>,
+        width: u16,
+        height: u16,
+        depth: bool,
+        debug_name: &str,
+    ) -> Result<Self, Error> {
+        let size = wgpu::Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        };
+        let color_label = format!("{debug_name}_color");
+        let move_device = bound_device.clone();
+        let color = WgpuCell::new_on_thread(move || async move {
+            move_device.0.device().assume(move |device| {
+                device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some(&color_label),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: Format::WGPU_FORMAT,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING
+                        | wgpu::TextureUsages::COPY_SRC,
+                    view_formats: &[],
+                })
+            })
+        })
+        .await;
+        let depth = if depth {
+            let depth_label = format!("{debug_name}_depth");
+            let move_device = bound_device.clone();
+            Some(
+                WgpuCell::new_on_thread(move || async move {
+                    move_device.0.device().assume(move |device| {
+                        device.create_texture(&wgpu::TextureDescriptor {
+                            label: Some(&depth_label),
+                            size,
+                            mip_level_count: 1,
+                            sample_count: 1,
+                            dimension: wgpu::TextureDimension::D2,
+                            format: DEPTH_FORMAT,
+                            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                                | wgpu::TextureUsages::TEXTURE_BINDING,
+                            view_formats: &[],
+                        })
+                    })
+                })
+                .await,
+            )
+        } else {
+            None
+        };
+        Ok(RenderTexture {
+            color,
+            depth,
+            format: Format::WGPU_FORMAT,
+        })
+    }
+
+    pub fn has_depth(&self) -> bool {
+        self.depth.is_some()
+    }
+
+    /// The format of the depth attachment, as passes see it, see [`DEPTH_FORMAT`].
+    pub fn depth_format(&self) -> Option<crate::images::render_pass::DepthFormat> {
+        self.has_depth()
+            .then_some(crate::images::render_pass::DepthFormat::Depth32Float)
+    }
+}
+
+impl PartialEq for RenderTexture {
+    fn eq(&self, other: &Self) -> bool {
+        self.color == other.color
+    }
+}
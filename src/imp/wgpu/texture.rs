@@ -45,6 +45,10 @@ pub struct MappableTexture2<Format> {
 }
 
 impl<Format> Mappable for MappableTexture2<Format> {
+    async fn map_read(&mut self) {
+        // No-op: we use direct CPU storage, no mapping needed
+    }
+
     async fn map_write(&mut self) {
         // No-op: we use direct CPU storage, no mapping needed
     }
@@ -149,8 +149,8 @@ assert_eq!(sw_texture.height(), 4);
 | Direction | Flow | Use Cases | Status |
 |-----------|------|-----------|---------|
 | **Forward** | CPU→GPU | Rendering data, textures, uniforms | ✅ Implemented |
-| **Reverse** | GPU→CPU | Screenshots, compute results, queries | ⏳ Planned |
-| **Sideways** | GPU→GPU | Render-to-texture, compute chains | ⏳ Planned |
+| **Reverse** | GPU→CPU | Screenshots, compute results, queries | ✅ Implemented |
+| **Sideways** | GPU→GPU | Render-to-texture, post-processing, shadow maps | ✅ Implemented |
 | **Omnidirectional** | CPU↔GPU | Interactive simulations, feedback | ⏳ Planned |
 
 ## Choosing the Right Type
@@ -168,8 +168,9 @@ To select the appropriate binding type:
 | Mesh geometry that never changes | `bindings::forward::static::Buffer` |
 | Textures loaded from disk | `bindings::forward::static::Texture` |
 | Camera matrices updated per frame | `bindings::forward::dynamic::Buffer` |
-| Render-to-texture targets | `bindings::forward::dynamic::FrameTexture` |
+| Render-to-texture targets | `bindings::sideways::RenderTexture` |
 | Particle positions (CPU generated) | `bindings::forward::dynamic::Buffer` |
+| Particle positions (compute generated) | Buffer with `GPUBufferUsage::ShaderReadWrite` |
 | Lookup tables for shaders | `bindings::forward::static::Buffer` or `Texture` |
 
 ## Implementation Status
@@ -179,6 +180,8 @@ Currently implemented:
 - Forward Static Texture ✅
 - Forward Dynamic Buffer ✅
 - Forward Dynamic FrameTexture ✅
+- Reverse Buffer ✅
+- Sideways RenderTexture ✅
 
 Examples include:
 
@@ -195,10 +198,11 @@ Examples include:
 ## Backend System
 
 images_and_words uses a backend abstraction that allows different GPU API implementations.
-Currently, two backends are available:
+Currently, three backends are available:
 
 - **`nop` backend**: A no-operation stub implementation useful for testing and as a template for new backends
 - **`wgpu` backend**: The main production backend built on [wgpu](https://wgpu.rs), providing broad platform support
+- **`software` backend**: A CPU rasterizer for machines without a GPU, such as CI.  It cannot run WGSL, so each shader also carries a Rust program (see `images::shader::software`)
 
 ```
 # if cfg!(not(feature="backend_wgpu")) { return; }
@@ -8,6 +8,9 @@ This implements a generic multibucffering algorithm. The main idea is,
 2.  We have one (or more) GPU-visible objects
 3.  We write to the CPU side and it triggers a copy to the GPU side.
 
+Reverse resources run the same machinery backwards: the GPU writes the GPU side, a copy
+lands in the CPU side, and the CPU reads it.
+
 The objects here are fully generic, and may support buffers or textures.
 
 */
@@ -17,42 +20,26 @@ use crate::bindings::resource_tracking;
 use crate::bindings::resource_tracking::ResourceTracker;
 use crate::bindings::resource_tracking::sealed::Mappable;
 use std::ops::{Deref, DerefMut};
-//
-// //We need to wrap the ResourceTracker types in a newtype so that we can implement multibuffering behaviors.
-// //primarily, we want to mark things dirty.
-// pub struct CPUReadGuard<'a, Element, U>
-// where
-//     Element: Mappable,
-//     U: Clone,
-// {
-//     //option so we can take on drop
-//     imp: Option<crate::bindings::resource_tracking::CPUReadGuard<'a, Element>>,
-//     _buffer: &'a Multibuffer<Element, U>,
-// }
-//
-// impl<'a, Element, U> Deref for CPUReadGuard<'a, Element, U>
-// where
-//     Element: Mappable,
-//     U: Clone,
-// {
-//     type Target = Element;
-//     fn deref(&self) -> &Self::Target {
-//         self.imp.as_ref().unwrap()
-//     }
-// }
-//
-// impl<'a, Element, U> Drop for CPUReadGuard<'a, Element, U>
-// where
-//     Element: Mappable,
-//     U: Clone,
-// {
-//     fn drop(&mut self) {
-//         // If we haven't taken the guard, panic
-//         if self.imp.is_some() && !std::thread::panicking() {
-//             panic!("Dropped CPUReadGuard without calling async_drop");
-//         }
-//     }
-// }
+#[derive(Debug)]
+pub struct CPUReadGuard<'a, Element, U>
+where
+    Element: Mappable,
+    U: Clone,
+{
+    imp: crate::bindings::resource_tracking::CPUReadGuard<'a, Element>,
+    _buffer: &'a Multibuffer<Element, U>,
+}
+
+impl<'a, Element, U> Deref for CPUReadGuard<'a, Element, U>
+where
+    Element: Mappable,
+    U: Clone,
+{
+    type Target = Element;
+    fn deref(&self) -> &Self::Target {
+        &self.imp
+    }
+}
 
 #[derive(Debug)]
 pub struct CPUWriteGuard<'a, Element, U>
@@ -161,18 +148,21 @@ where
         }
     }
 
-    // pub async fn access_read(&self) -> CPUReadGuard<T,U> where T: Mappable, U: GPUMultibuffer {
-    //     loop {
-    //         //insert first
-    //         let (s,f) = r#continue::continuation();
-    //         self.wake_list.lock().unwrap().push(s);
-    //         //then check
-    //         match self.mappable.cpu_read().await {
-    //             Ok(guard) => return CPUReadGuard{ imp: Some(guard), buffer: self },
-    //             Err(_) => f.await
-    //         }
-    //     }
-    // }
+    /**
+    Waits for the GPU to write the GPU side, and for the result to be copied to the CPU side.
+
+    Returns a guard providing read access to the CPU side.
+    */
+    pub async fn access_read(&self) -> CPUReadGuard<'_, T, U>
+    where
+        T: Mappable,
+    {
+        let underlying = self.mappable.cpu_read().await;
+        CPUReadGuard {
+            imp: underlying,
+            _buffer: self,
+        }
+    }
 
     /**
     Accesses the underlying data.
@@ -249,8 +239,37 @@ where
             }
         }
     }
+    /**
+    Accesses the underlying GPU data, for a pass that writes it.
+
+    The guard's dirty guard is present when the CPU side is free to receive a copy of the
+    results; callers copy into it and drop it once the GPU has finished.  When the CPU side
+    is still being read, there is no dirty guard and the results stay on the GPU, so a slow
+    reader never stalls rendering.
+
+    # Safety
+    Caller must guarantee that the guard is live for the duration of the GPU access.
+    */
+    #[allow(dead_code)] //nop implementation does not use
+    pub(crate) unsafe fn access_gpu_write(&self) -> GPUGuard<T, U>
+    where
+        T: Mappable,
+        U: Clone,
+    {
+        GPUGuard {
+            dirty_guard: self.mappable.poll_gpu_write().ok(),
+            gpu_buffer: self.gpu.clone(),
+            _debug_label: self.debug_label.clone(),
+        }
+    }
+
     ///Returns a [DirtyReceiver] that activates when the GPU side is dirty.
     pub(crate) fn gpu_dirty_receiver(&self) -> DirtyReceiver {
         DirtyReceiver::new(self.mappable.dirty_pending_cpu_to_gpu())
     }
+
+    ///Returns a [DirtyReceiver] that activates when the CPU side waits for the GPU to write.
+    pub(crate) fn cpu_dirty_receiver(&self) -> DirtyReceiver {
+        DirtyReceiver::new(self.mappable.dirty_pending_gpu_to_cpu())
+    }
 }
@@ -741,10 +741,14 @@ unsafe impl ReprC for RGBA8UnormSRGBPixel {}
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct RGBA8UnormSRGBPixel {
-    r: u8,
-    g: u8,
-    b: u8,
-    a: u8,
+    /// Red component in sRGB space (0-255).
+    pub r: u8,
+    /// Green component in sRGB space (0-255).
+    pub g: u8,
+    /// Blue component in sRGB space (0-255).
+    pub b: u8,
+    /// Alpha component (0-255).
+    pub a: u8,
 }
 
 impl From<RGBA8UnormSRGBPixel> for BGRA8UnormPixelSRGB {
@@ -1,5 +1,5 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
-use crate::pixel_formats::RGBA8UnormSRGB;
+use crate::pixel_formats::{R8UNorm, RGBA8UnormSRGB};
 use png::{BitDepth, ColorType};
 
 /**
@@ -21,3 +21,13 @@ unsafe impl PngPixelFormat for RGBA8UnormSRGB {
         BitDepth::Eight
     }
 }
+
+unsafe impl PngPixelFormat for R8UNorm {
+    fn png_color_type() -> ColorType {
+        ColorType::Grayscale
+    }
+
+    fn png_bit_depth() -> BitDepth {
+        BitDepth::Eight
+    }
+}
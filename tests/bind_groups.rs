@@ -0,0 +1,165 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Binds resources into groups other than `@group(0)`, and shares the port's global group.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::engine;
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::PassDescriptor;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// A pass drawing a rectangle from `left` to `right` in clip space with `fragment`, the body
+/// of a WGSL module whose `fs_main` returns the color.
+fn rectangle(left: f32, right: f32, fragment: &str, bind_style: BindStyle) -> PassDescriptor {
+    common::rectangle(left, right, 0.5, fragment, bind_style)
+}
+
+/// Renders a frame, and returns the pixels at each of `xs` on the second row.
+async fn row<const N: usize>(port: &Port, xs: [u16; N]) -> [[u8; 4]; N] {
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    xs.map(|x| {
+        let pixel = framebuffer.color[Texel { x, y: 1 }];
+        [pixel.r, pixel.g, pixel.b, pixel.a]
+    })
+}
+
+#[async_test]
+async fn slots_bind_into_any_group() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let color = Buffer::<f32>::new(
+        engine.bound_device().clone(),
+        4,
+        GPUBufferUsage::FragmentShaderRead,
+        "color",
+        |i| [0.0, 1.0, 0.0, 1.0][i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let mut bind_style = BindStyle::new();
+    //groups 0 and 1 are bound empty
+    bind_style.bind_static_buffer(BindSlot::in_group(2, 1), Stage::Fragment, &color);
+    port.add_fixed_pass(rectangle(
+        -1.0,
+        1.0,
+        "@group(2) @binding(1) var<uniform> color: vec4<f32>;
+        @fragment fn fs_main() -> @location(0) vec4<f32> {
+            return color;
+        }",
+        bind_style,
+    ))
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(row(port, [1]).await, [[0, 255, 0, 255]]);
+}
+
+#[async_test]
+async fn frame_counter_and_time_bind_per_pass() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_frame_counter(BindSlot::new(0), Stage::Fragment);
+    bind_style.bind_time(BindSlot::new(1), Stage::Fragment);
+    port.add_fixed_pass(rectangle(
+        -1.0,
+        1.0,
+        "@group(0) @binding(0) var<uniform> frame: u32;
+        @group(0) @binding(1) var<uniform> time: f32;
+        @fragment fn fs_main() -> @location(0) vec4<f32> {
+            let started = select(0.0, 1.0, time > 0.0);
+            return vec4<f32>(f32(frame % 256u) / 255.0, started, 0.0, 1.0);
+        }",
+        bind_style,
+    ))
+    .await
+    .expect("Failed to add pass");
+    let [[first, started, ..]] = row(port, [1]).await;
+    assert_eq!(started, 255);
+    let [[second, ..]] = row(port, [1]).await;
+    assert_eq!(second, first.wrapping_add(1));
+}
+
+#[async_test]
+async fn passes_share_the_global_group() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let frame_color = "fn frame_color() -> vec4<f32> {
+            let started = select(0.0, 1.0, time > 0.0 && camera[3][3] != 0.0);
+            return vec4<f32>(f32(frame % 256u) / 255.0, started, 0.0, 1.0);
+        }
+        @fragment fn fs_main() -> @location(0) vec4<f32> {
+            return frame_color();
+        }";
+    let in_group = |group: u32| {
+        format!(
+            "@group({group}) @binding(0) var<uniform> camera: mat4x4<f32>;
+            @group({group}) @binding(1) var<uniform> frame: u32;
+            @group({group}) @binding(2) var<uniform> time: f32;
+            {frame_color}"
+        )
+    };
+    let color = Buffer::<f32>::new(
+        engine.bound_device().clone(),
+        4,
+        GPUBufferUsage::FragmentShaderRead,
+        "color",
+        |i| [0.0, 0.0, 1.0, 1.0][i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    //the global group in group 0
+    let mut first = BindStyle::new();
+    first.bind_global_group(0);
+    port.add_fixed_pass(rectangle(-1.0, -0.5, &in_group(0), first.clone()))
+        .await
+        .expect("Failed to add pass");
+    //the global group in group 1, after the pass's own group 0
+    let mut second = BindStyle::new();
+    second.bind_static_buffer(BindSlot::new(0), Stage::Fragment, &color);
+    second.bind_global_group(1);
+    port.add_fixed_pass(rectangle(
+        -0.5,
+        0.5,
+        &format!(
+            "@group(0) @binding(0) var<uniform> color: vec4<f32>;
+            {}",
+            in_group(1).replace("return frame_color();", "return frame_color() + color;")
+        ),
+        second,
+    ))
+    .await
+    .expect("Failed to add pass");
+    //group 0 has to be bound again
+    port.add_fixed_pass(rectangle(0.5, 1.0, &in_group(0), first))
+        .await
+        .expect("Failed to add pass");
+
+    let [left, middle, right] = row(port, [0, 1, 3]).await;
+    assert_eq!(left[1], 255);
+    assert_eq!(middle, [left[0], 255, 255, 255]);
+    assert_eq!(right, left);
+    let [next, ..] = row(port, [0, 1, 3]).await;
+    assert_eq!(next[0], left[0].wrapping_add(1));
+}
+
+#[test]
+#[should_panic(expected = "Already bound resources in group 0")]
+fn global_group_cannot_share_a_group() {
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_frame_counter(BindSlot::new(0), Stage::Fragment);
+    bind_style.bind_global_group(0);
+}
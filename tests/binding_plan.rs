@@ -0,0 +1,94 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Binds resources by the names shaders give them, with vertex layouts read from the shader.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fullscreen_pass};
+use images_and_words::bindings::bind_style::BindSlot;
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::sampler::SamplerType;
+use images_and_words::bindings::sideways::RenderTexture;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+use images_and_words::images::shader::reflection::{BindingPlan, vertex_layout};
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::images::vertex_layout::StepMode;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+#[async_test]
+async fn plan_samples_an_earlier_pass() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let scene = RenderTexture::<RGBA8UNorm>::new(engine.bound_device(), 4, 4, false, "scene")
+        .await
+        .expect("Failed to create render texture");
+
+    let vertex_shader = VertexShader::new(
+        "vertices",
+        "struct Out { @builtin(position) position: vec4<f32>, @location(0) brightness: f32 }
+        @vertex fn vs_main(@location(0) position: vec2<f32>, @location(1) brightness: f32) -> Out {
+            return Out(vec4<f32>(position, 0.0, 1.0), brightness);
+        }"
+        .to_string(),
+    );
+    let fragment_shader = FragmentShader::new(
+        "sampled",
+        "@group(0) @binding(0) var scene: texture_2d<f32>;
+        @group(0) @binding(1) var scene_sampler: sampler;
+        @fragment fn fs_main(@builtin(position) p: vec4<f32>, @location(0) brightness: f32) -> @location(0) vec4<f32> {
+            return textureSample(scene, scene_sampler, p.xy / 4.0) * brightness;
+        }"
+        .to_string(),
+    );
+
+    //a full-screen triangle of x, y and brightness
+    let vertices = [-1.0, -1.0, 1.0, 3.0, -1.0, 1.0, -1.0, 3.0, 1.0];
+    let vertices = Buffer::<f32>::new(
+        engine.bound_device().clone(),
+        vertices.len(),
+        GPUBufferUsage::VertexBuffer,
+        "vertices",
+        |i| vertices[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let layout = vertex_layout(&vertex_shader).expect("Failed to read vertex layout");
+
+    let mut plan =
+        BindingPlan::new(&vertex_shader, &fragment_shader).expect("Failed to read bindings");
+    plan.bind("scene", &scene).expect("Failed to bind scene");
+    plan.bind("scene_sampler", SamplerType::Mipmapped)
+        .expect("Failed to bind sampler");
+    let mut bind_style = plan.into_bind_style().expect("Bindings are missing");
+    bind_style.bind_static_vertex_buffer(BindSlot::new(2), &vertices, layout, StepMode::Vertex);
+
+    let descriptor = PassDescriptor::new(
+        "sampled".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleList(1),
+        false,
+        false,
+    );
+    assert_eq!(descriptor.validate(), Ok(()));
+    port.add_fixed_pass(descriptor)
+        .await
+        .expect("Failed to add pass");
+    port.add_fixed_pass(fullscreen_pass(false).with_render_target(&scene))
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+}
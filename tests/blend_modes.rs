@@ -0,0 +1,105 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Blends a pass's output over an earlier pass with each blend mode and write mask.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill, fullscreen};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::render_pass::{
+    BlendComponent, BlendFactor, BlendMode, BlendOperation, ColorWriteMask,
+};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// The color the first pass fills the target with.
+const DST: [f32; 4] = [0.5, 0.25, 0.0, 1.0];
+/// The color the second pass blends over it.
+const SRC: [f32; 4] = [0.25, 0.25, 0.5, 0.5];
+
+/// Fills a 4x4 target with [`DST`], draws [`SRC`] over it with `blend` and `write_mask`,
+/// and returns the resulting color.
+async fn blend_over(blend: BlendMode, write_mask: ColorWriteMask) -> [u8; 4] {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(fullscreen("dst", 0.0, false, &fill(DST), BindStyle::new()))
+        .await
+        .expect("Failed to add pass");
+    port.add_fixed_pass(
+        fullscreen("src", 0.0, false, &fill(SRC), BindStyle::new())
+            .with_blend_mode(blend)
+            .with_write_mask(write_mask),
+    )
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 1, y: 2 }];
+    [pixel.r, pixel.g, pixel.b, pixel.a]
+}
+
+fn assert_close(actual: [u8; 4], expected: [f32; 4]) {
+    let expected = expected.map(|c| (c * 255.0).round() as i16);
+    assert!(
+        actual
+            .iter()
+            .zip(expected)
+            .all(|(&a, e)| (a as i16 - e).abs() <= 1),
+        "expected {expected:?}, got {actual:?}"
+    );
+}
+
+#[async_test]
+async fn presets_blend_over_the_target() {
+    let cases = [
+        (BlendMode::Replace, SRC),
+        (BlendMode::Alpha, [0.375, 0.25, 0.25, 1.0]),
+        (BlendMode::PremultipliedAlpha, [0.5, 0.375, 0.5, 1.0]),
+        (BlendMode::Additive, [0.75, 0.5, 0.5, 1.0]),
+        (BlendMode::Multiply, [0.125, 0.0625, 0.0, 0.5]),
+        (BlendMode::Min, [0.25, 0.25, 0.0, 0.5]),
+        (BlendMode::Max, [0.5, 0.25, 0.5, 1.0]),
+    ];
+    for (blend, expected) in cases {
+        assert_close(blend_over(blend, ColorWriteMask::ALL).await, expected);
+    }
+}
+
+#[async_test]
+async fn custom_blend_uses_separate_color_and_alpha_equations() {
+    let subtract = BlendMode::Custom {
+        color: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::ReverseSubtract,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::Zero,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        },
+    };
+    assert_close(
+        blend_over(subtract, ColorWriteMask::ALL).await,
+        [0.25, 0.0, 0.0, 1.0],
+    );
+}
+
+#[async_test]
+async fn write_mask_keeps_unwritten_channels() {
+    assert_close(
+        blend_over(BlendMode::Replace, ColorWriteMask::COLOR).await,
+        [0.25, 0.25, 0.5, 1.0],
+    );
+    assert_close(
+        blend_over(BlendMode::Additive, ColorWriteMask::NONE).await,
+        DST,
+    );
+}
@@ -16,7 +16,7 @@ use images_and_words::images::Engine;
 use images_and_words::images::projection::WorldCoord;
 use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
 use images_and_words::images::shader::{FragmentShader, VertexShader};
-use images_and_words::images::vertex_layout::{VertexFieldType, VertexLayout};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
 use images_and_words::images::view::View;
 use logwise::declare_logging_domain;
 use std::sync::Arc;
@@ -83,8 +83,16 @@ fn main() {
             let mut bind_style = BindStyle::new();
             let mut layout = VertexLayout::new();
             layout.add_field("x", VertexFieldType::F32);
-
-            bind_style.bind_dynamic_vertex_buffer(BindSlot::new(0), &test_buffer, layout);
+            layout.add_field("y", VertexFieldType::F32);
+            layout.add_field("z", VertexFieldType::F32);
+            layout.add_field("w", VertexFieldType::F32);
+
+            bind_style.bind_dynamic_vertex_buffer(
+                BindSlot::new(0),
+                &test_buffer,
+                layout,
+                StepMode::Vertex,
+            );
 
             let vertex_shader = VertexShader::new(
                 "texture_alignment_test",
@@ -126,7 +134,11 @@ fn main() {
                 false,
                 false,
             );
-            engine.main_port().add_fixed_pass(descriptor).await;
+            engine
+                .main_port()
+                .add_fixed_pass(descriptor)
+                .await
+                .expect("Failed to add pass");
 
             logwise::info_sync!("=== Testing buffer write performance ===");
 
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Fixtures shared by the integration tests, which include this with `mod common;`.
+//!
+//! Each test uses only some of them.
+#![allow(dead_code)]
+
+use images_and_words::bindings::BindStyle;
+use images_and_words::images::Engine;
+use images_and_words::images::projection::WorldCoord;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::images::view::View;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use std::sync::Arc;
+
+/// An engine rendering to a 4x4 offscreen view.
+pub async fn engine() -> Arc<Engine> {
+    engine_with_view(View::offscreen(4, 4, RGBA8UNorm)).await
+}
+
+pub async fn engine_with_view(view: View) -> Arc<Engine> {
+    Engine::rendering_to(view, WorldCoord::new(0.0, 0.0, 10.0))
+        .await
+        .expect("Failed to create engine")
+}
+
+/// A pass drawing a rectangle from `left` to `right` in clip space, covering the view's
+/// height at depth `z`, with `fragment`, the body of a WGSL module whose `fs_main` returns
+/// the color.
+pub fn rectangle(
+    left: f32,
+    right: f32,
+    z: f32,
+    fragment: &str,
+    bind_style: BindStyle,
+) -> PassDescriptor {
+    let vertex_shader = VertexShader::new(
+        "rectangle",
+        format!(
+            "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {{
+                var xs = array<f32, 6>({left:?}, {right:?}, {left:?}, {left:?}, {right:?}, {right:?});
+                var ys = array<f32, 6>(-1.0, -1.0, 1.0, 1.0, -1.0, 1.0);
+                return vec4<f32>(xs[i], ys[i], {z:?}, 1.0);
+            }}"
+        ),
+    );
+    let fragment_shader = FragmentShader::new("rectangle", fragment.to_string());
+    PassDescriptor::new(
+        "rectangle".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleList(2),
+        false,
+        false,
+    )
+}
+
+/// A WGSL module whose `fs_main` returns `color`, for [`rectangle`].
+pub fn fill(color: [f32; 4]) -> String {
+    let [r, g, b, a] = color;
+    format!(
+        "@fragment fn fs_main() -> @location(0) vec4<f32> {{
+            return vec4<f32>({r:?}, {g:?}, {b:?}, {a:?});
+        }}"
+    )
+}
+
+/// A full-screen triangle named `name` at depth `z`, with `fragment`, a WGSL module whose
+/// `fs_main` returns the color.
+pub fn fullscreen(
+    name: &'static str,
+    z: f32,
+    depth: bool,
+    fragment: &str,
+    bind_style: BindStyle,
+) -> PassDescriptor {
+    let vertex_shader = VertexShader::new(
+        "fullscreen",
+        format!(
+            "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {{
+                let uv = vec2<f32>(f32((i << 1u) & 2u), f32(i & 2u));
+                return vec4<f32>(uv * 2.0 - 1.0, {z:?}, 1.0);
+            }}"
+        ),
+    );
+    let fragment_shader = FragmentShader::new(name, fragment.to_string());
+    PassDescriptor::new(
+        name.to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleList(1),
+        depth,
+        false,
+    )
+}
+
+/// A full-screen red triangle at depth 0.25.
+pub fn fullscreen_pass(depth: bool) -> PassDescriptor {
+    fullscreen(
+        "fullscreen",
+        0.25,
+        depth,
+        &fill([1.0, 0.0, 0.0, 1.0]),
+        BindStyle::new(),
+    )
+}
@@ -0,0 +1,240 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Runs compute passes before the render passes of a port.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fullscreen};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::forward::dynamic::buffer::Buffer;
+use images_and_words::bindings::reverse;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::compute_pass::{ComputePassDescriptor, Dispatch};
+use images_and_words::images::render_pass::PassError;
+use images_and_words::images::shader::ComputeShader;
+use images_and_words::images::shader::validation::ShaderError;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+#[async_test]
+async fn doubles_buffer_elements() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let values =
+        reverse::buffer::Buffer::<u32>::new(engine.bound_device().clone(), 100, "values", |i| {
+            i as u32
+        })
+        .await
+        .expect("Failed to create buffer");
+    let shader = ComputeShader::new(
+        "double",
+        "@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+        @compute @workgroup_size(64) fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+            if (id.x < arrayLength(&values)) {
+                values[id.x] = values[id.x] * 2u;
+            }
+        }"
+        .to_string(),
+    );
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Compute, &values);
+    port.add_compute_pass(ComputePassDescriptor::new(
+        "double".to_string(),
+        shader,
+        bind_style,
+        Dispatch::for_elements(values.len(), 64),
+    ))
+    .await
+    .expect("Failed to add compute pass");
+    port.force_render().await;
+
+    let expected: Vec<u32> = (0..100).map(|i| i * 2).collect();
+    assert_eq!(values.access_read().await.to_vec(), expected);
+}
+
+#[async_test]
+async fn render_pass_reads_compute_results() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let color = Buffer::<f32>::new(
+        engine.bound_device().clone(),
+        4,
+        GPUBufferUsage::ShaderReadWrite,
+        "color",
+        |_| 0.0,
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    //added before the compute pass, but compute passes always run first
+    let mut render_binds = BindStyle::new();
+    render_binds.bind_dynamic_buffer(BindSlot::new(0), Stage::Fragment, &color);
+    port.add_fixed_pass(fullscreen(
+        "show",
+        0.0,
+        false,
+        "@group(0) @binding(0) var<storage, read_write> color: array<f32>;
+        @fragment fn fs_main() -> @location(0) vec4<f32> {
+            return vec4<f32>(color[0], color[1], color[2], color[3]);
+        }",
+        render_binds,
+    ))
+    .await
+    .expect("Failed to add pass");
+
+    let shader = ComputeShader::new(
+        "fill",
+        "@group(0) @binding(0) var<storage, read_write> color: array<f32>;
+        @compute @workgroup_size(1) fn cs_main() {
+            color[0] = 0.0;
+            color[1] = 1.0;
+            color[2] = 0.0;
+            color[3] = 1.0;
+        }"
+        .to_string(),
+    );
+    let mut compute_binds = BindStyle::new();
+    compute_binds.bind_dynamic_buffer(BindSlot::new(0), Stage::Compute, &color);
+    port.add_compute_pass(ComputePassDescriptor::new(
+        "fill".to_string(),
+        shader,
+        compute_binds,
+        Dispatch::Workgroups(1, 1, 1),
+    ))
+    .await
+    .expect("Failed to add compute pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 255, 0, 255));
+}
+
+/// A compute shader adding `amount` to each of its values.
+fn add_shader(amount: u32) -> ComputeShader {
+    ComputeShader::new(
+        "add",
+        format!(
+            "@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+            @compute @workgroup_size(64) fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {{
+                if (id.x < arrayLength(&values)) {{
+                    values[id.x] = values[id.x] + {amount}u;
+                }}
+            }}"
+        ),
+    )
+}
+
+/// A compute pass adding `amount` to each of `values` every frame.
+fn add(values: &reverse::buffer::Buffer<u32>, amount: u32) -> ComputePassDescriptor {
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Compute, values);
+    ComputePassDescriptor::new(
+        format!("add {amount}"),
+        add_shader(amount),
+        bind_style,
+        Dispatch::for_elements(values.len(), 64),
+    )
+}
+
+#[async_test]
+async fn compute_passes_are_removed_and_replaced_by_handle() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let values =
+        reverse::buffer::Buffer::<u32>::new(engine.bound_device().clone(), 4, "values", |_| 0)
+            .await
+            .expect("Failed to create buffer");
+    let one = port
+        .add_compute_pass(add(&values, 1))
+        .await
+        .expect("Failed to add compute pass");
+    let ten = port
+        .add_compute_pass(add(&values, 10))
+        .await
+        .expect("Failed to add compute pass");
+    port.force_render().await;
+    assert_eq!(values.access_read().await.get(0), 11);
+
+    port.remove_compute_pass(ten)
+        .await
+        .expect("Failed to remove compute pass");
+    port.force_render().await;
+    assert_eq!(values.access_read().await.get(0), 12);
+
+    port.replace_compute_pass(one, add(&values, 100))
+        .await
+        .expect("Failed to replace compute pass");
+    port.force_render().await;
+    assert_eq!(values.access_read().await.get(0), 112);
+
+    assert_eq!(
+        port.remove_compute_pass(ten).await,
+        Err(PassError::StaleHandle { handle: ten })
+    );
+}
+
+#[async_test]
+async fn port_refuses_compute_passes_that_do_not_match_their_bindings() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    //the shader reads binding 0, which nothing is bound to
+    let unbound = ComputePassDescriptor::new(
+        "add 1".to_string(),
+        add_shader(1),
+        BindStyle::new(),
+        Dispatch::Workgroups(1, 1, 1),
+    );
+    let added = port.add_compute_pass(unbound).await;
+    assert!(matches!(
+        added,
+        Err(PassError::Shader {
+            error: ShaderError::Unbound { .. },
+            ..
+        })
+    ));
+}
+
+#[async_test]
+async fn entry_point_and_constants_specialize_the_shader() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let values =
+        reverse::buffer::Buffer::<u32>::new(engine.bound_device().clone(), 4, "values", |_| 1)
+            .await
+            .expect("Failed to create buffer");
+    let shader = ComputeShader::new(
+        "arithmetic",
+        "override amount: u32;
+        @group(0) @binding(0) var<storage, read_write> values: array<u32>;
+        @compute @workgroup_size(4) fn add(@builtin(global_invocation_id) id: vec3<u32>) {
+            values[id.x] = values[id.x] + amount;
+        }
+        @compute @workgroup_size(4) fn multiply(@builtin(global_invocation_id) id: vec3<u32>) {
+            values[id.x] = values[id.x] * amount;
+        }"
+        .to_string(),
+    )
+    .with_entry_point("multiply")
+    .with_constant("amount", 7.0);
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Compute, &values);
+    port.add_compute_pass(ComputePassDescriptor::new(
+        "multiply".to_string(),
+        shader,
+        bind_style,
+        Dispatch::Workgroups(1, 1, 1),
+    ))
+    .await
+    .expect("Failed to add compute pass");
+    port.force_render().await;
+
+    assert_eq!(values.access_read().await.to_vec(), vec![7; 4]);
+}
@@ -0,0 +1,225 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Tests depth and stencil with a pass's depth-stencil state.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::sideways::RenderTexture;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{
+    ColorWriteMask, CompareFunction, DepthFormat, DepthStencilState, PassDescriptor, PassError,
+    StencilFaceState, StencilOperation, StencilState,
+};
+use images_and_words::pixel_formats::{RGBA8UNorm, Unorm4};
+use test_executors::async_test;
+
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+
+/// A depth-tested rectangle from `left` to `right` in clip space, covering the view's
+/// height, at depth `z`.
+fn rectangle(left: f32, right: f32, z: f32, color: [f32; 4]) -> PassDescriptor {
+    common::rectangle(left, right, z, &fill(color), BindStyle::new())
+        .with_depth_stencil_state(DepthStencilState::new())
+}
+
+async fn pixel(port: &Port, x: u16) -> Unorm4 {
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    framebuffer.color[Texel { x, y: 1 }]
+}
+
+fn is_red(pixel: Unorm4) -> bool {
+    pixel.r == 255 && pixel.g == 0
+}
+
+fn is_green(pixel: Unorm4) -> bool {
+    pixel.r == 0 && pixel.g == 255
+}
+
+#[async_test]
+async fn passes_without_depth_write_do_not_hide_later_passes() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(
+        rectangle(-1.0, 1.0, 0.25, RED)
+            .with_depth_stencil_state(DepthStencilState::new().with_depth_write(false)),
+    )
+    .await
+    .expect("Failed to add pass");
+    port.add_fixed_pass(rectangle(-1.0, 1.0, 0.5, GREEN))
+        .await
+        .expect("Failed to add pass");
+    assert!(is_green(pixel(port, 1).await));
+
+    let engine = self::engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(rectangle(-1.0, 1.0, 0.25, RED))
+        .await
+        .expect("Failed to add pass");
+    port.add_fixed_pass(rectangle(-1.0, 1.0, 0.5, GREEN))
+        .await
+        .expect("Failed to add pass");
+    assert!(is_red(pixel(port, 1).await));
+}
+
+#[async_test]
+async fn reverse_z_clears_to_zero_and_keeps_the_greatest_depth() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let reverse_z = DepthStencilState::new()
+        .with_format(DepthFormat::Depth32Float)
+        .with_compare(CompareFunction::Greater);
+    port.add_fixed_pass(rectangle(-1.0, 0.0, 0.25, RED).with_depth_stencil_state(reverse_z))
+        .await
+        .expect("Failed to add pass");
+    port.add_fixed_pass(rectangle(-1.0, 0.0, 0.75, GREEN).with_depth_stencil_state(reverse_z))
+        .await
+        .expect("Failed to add pass");
+    assert!(is_green(pixel(port, 1).await));
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let depth = framebuffer.depth.expect("Depth is enabled");
+    assert!((depth[Texel { x: 1, y: 1 }] - 0.75).abs() < 0.001);
+    assert_eq!(depth[Texel { x: 3, y: 1 }], 0.0);
+}
+
+#[async_test]
+async fn stencil_masks_later_passes() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let stencil = |face, reference| {
+        DepthStencilState::new()
+            .with_format(DepthFormat::Depth24PlusStencil8)
+            .with_compare(CompareFunction::Always)
+            .with_stencil(StencilState::new(face, reference))
+    };
+    //marks the left half of the view, without drawing it
+    let mark = StencilFaceState {
+        compare: CompareFunction::Always,
+        pass_op: StencilOperation::Replace,
+        ..StencilFaceState::IGNORE
+    };
+    port.add_fixed_pass(
+        rectangle(-1.0, 0.0, 0.5, RED)
+            .with_depth_stencil_state(stencil(mark, 1))
+            .with_write_mask(ColorWriteMask::NONE),
+    )
+    .await
+    .expect("Failed to add pass");
+    //draws everywhere the stencil is not marked
+    let outside = StencilFaceState {
+        compare: CompareFunction::NotEqual,
+        ..StencilFaceState::IGNORE
+    };
+    port.add_fixed_pass(
+        rectangle(-1.0, 1.0, 0.5, GREEN).with_depth_stencil_state(stencil(outside, 1)),
+    )
+    .await
+    .expect("Failed to add pass");
+
+    assert!(!is_green(pixel(port, 1).await));
+    assert!(is_green(pixel(port, 3).await));
+}
+
+#[async_test]
+async fn port_refuses_depth_states_their_targets_cannot_use() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let mark = StencilState::new(
+        StencilFaceState {
+            compare: CompareFunction::Always,
+            pass_op: StencilOperation::Replace,
+            ..StencilFaceState::IGNORE
+        },
+        1,
+    );
+    let without_stencil_format = DepthStencilState::new().with_stencil(mark);
+    let added = port
+        .add_fixed_pass(
+            rectangle(-1.0, 1.0, 0.5, RED).with_depth_stencil_state(without_stencil_format),
+        )
+        .await;
+    assert!(matches!(added, Err(PassError::StencilFormat { .. })));
+
+    let device = engine.bound_device();
+    let target = RenderTexture::<RGBA8UNorm>::new(device, 4, 4, true, "target")
+        .await
+        .expect("Failed to create render texture");
+    //the builder order does not matter
+    let stencil = without_stencil_format.with_format(DepthFormat::Depth24PlusStencil8);
+    for pass in [
+        rectangle(-1.0, 1.0, 0.5, RED)
+            .with_depth_stencil_state(stencil)
+            .with_render_target(&target),
+        rectangle(-1.0, 1.0, 0.5, RED)
+            .with_render_target(&target)
+            .with_depth_stencil_state(stencil),
+    ] {
+        let added = port.add_fixed_pass(pass).await;
+        assert!(matches!(added, Err(PassError::RenderTargetStencil { .. })));
+    }
+
+    let without_depth = RenderTexture::<RGBA8UNorm>::new(device, 4, 4, false, "without_depth")
+        .await
+        .expect("Failed to create render texture");
+    let added = port
+        .add_fixed_pass(rectangle(-1.0, 1.0, 0.5, RED).with_render_target(&without_depth))
+        .await;
+    assert!(matches!(added, Err(PassError::NoDepthAttachment { .. })));
+
+    //render textures have 32-bit float depth
+    let depth = |format| DepthStencilState::new().with_format(format);
+    let added = port
+        .add_fixed_pass(
+            rectangle(-1.0, 1.0, 0.5, RED)
+                .with_render_target(&target)
+                .with_depth_stencil_state(depth(DepthFormat::Depth16Unorm)),
+        )
+        .await;
+    assert!(matches!(
+        added,
+        Err(PassError::DepthFormat {
+            expected: DepthFormat::Depth32Float,
+            ..
+        })
+    ));
+    port.add_fixed_pass(
+        rectangle(-1.0, 1.0, 0.5, RED)
+            .with_render_target(&target)
+            .with_depth_stencil_state(depth(DepthFormat::Depth32Float)),
+    )
+    .await
+    .expect("Failed to add pass");
+
+    //passes into the view share one depth buffer
+    port.add_fixed_pass(rectangle(-1.0, 1.0, 0.5, GREEN))
+        .await
+        .expect("Failed to add pass");
+    let added = port
+        .add_fixed_pass(
+            rectangle(-1.0, 1.0, 0.25, RED)
+                .with_depth_stencil_state(depth(DepthFormat::Depth32Float)),
+        )
+        .await;
+    assert!(matches!(
+        added,
+        Err(PassError::DepthFormat {
+            format: DepthFormat::Depth32Float,
+            expected: DepthFormat::Depth16Unorm,
+            ..
+        })
+    ));
+    assert!(is_green(pixel(port, 1).await));
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Captures rendered frames with `Port::capture_next_frame`.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine_with_view, fullscreen_pass};
+use images_and_words::bindings::software::texture::{Texel, Texture};
+use images_and_words::images::view::View;
+use images_and_words::pixel_formats::{BGRA8UNormSRGB, RGBA8UnormSRGB};
+use test_executors::async_test;
+
+#[async_test]
+async fn concurrent_captures_receive_the_same_frame() {
+    //BGRA, so the capture has to swizzle
+    let engine = engine_with_view(View::offscreen(100, 20, BGRA8UNormSRGB)).await;
+    let port = engine.main_port();
+    port.add_fixed_pass(fullscreen_pass(true))
+        .await
+        .expect("Failed to add pass");
+
+    let first = port.capture_next_frame();
+    let second = port.capture_next_frame();
+    port.force_render().await;
+
+    for capture in [first.await, second.await] {
+        let frame = capture.expect("Capture frame");
+        assert_eq!((frame.color.width(), frame.color.height()), (100, 20));
+        let pixel = frame.color[Texel { x: 99, y: 19 }];
+        assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+        let depth = frame.depth.expect("Depth is enabled");
+        //Depth16Unorm
+        assert!((depth[Texel { x: 50, y: 10 }] - 0.25).abs() < 0.001);
+    }
+}
+
+#[async_test]
+async fn captures_non_offscreen_views() {
+    let engine = engine_with_view(View::for_testing()).await;
+    let port = engine.main_port();
+    port.add_fixed_pass(fullscreen_pass(false))
+        .await
+        .expect("Failed to add pass");
+
+    let capture = port.capture_next_frame();
+    port.force_render().await;
+    let frame = capture.await.expect("Capture frame");
+    assert_eq!((frame.color.width(), frame.color.height()), (800, 600));
+    let pixel = frame.color[Texel { x: 0, y: 0 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+    assert!(frame.depth.is_none());
+}
+
+#[async_test]
+async fn capture_wakes_running_port() {
+    let engine = engine_with_view(View::offscreen(8, 8, RGBA8UnormSRGB)).await;
+    let port = engine.main_port();
+    port.add_fixed_pass(fullscreen_pass(false))
+        .await
+        .expect("Failed to add pass");
+
+    //nothing is dirty after the first frame, so only the capture can render another
+    let (started, frame) = futures::join!(port.start(), async {
+        let frame = port.capture_next_frame().await;
+        port.stop();
+        frame
+    });
+    started.expect("Port ran");
+    let pixel = frame.expect("Capture frame").color[Texel { x: 4, y: 4 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+}
+
+#[async_test]
+async fn capture_saves_png() {
+    let engine = engine_with_view(View::offscreen(8, 8, RGBA8UnormSRGB)).await;
+    let port = engine.main_port();
+    port.add_fixed_pass(fullscreen_pass(true))
+        .await
+        .expect("Failed to add pass");
+
+    let capture = port.capture_next_frame();
+    port.force_render().await;
+    let frame = capture.await.expect("Capture frame");
+
+    let path = std::env::temp_dir().join("images_and_words_capture_saves_png.png");
+    frame.save_png(&path).expect("Save PNG");
+    let loaded =
+        Texture::<RGBA8UnormSRGB>::new_from_path(&path, async_file::Priority::unit_test()).await;
+    assert_eq!(
+        loaded[Texel { x: 3, y: 3 }],
+        frame.color[Texel { x: 3, y: 3 }]
+    );
+
+    let depth = frame.depth_image().expect("Depth is enabled");
+    assert_eq!(depth[Texel { x: 3, y: 3 }], 64);
+    assert_eq!(&depth.encode_png()[1..4], b"PNG");
+}
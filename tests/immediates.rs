@@ -0,0 +1,107 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Sets small per-pass values as immediate data, and redraws when they change.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, rectangle};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::forward::dynamic::buffer::CRepr;
+use images_and_words::bindings::forward::dynamic::immediates::Immediates;
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::port::Port;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+struct Tint {
+    rgba: [f32; 4],
+}
+// Safety: Tint is repr(C) and contains only f32s
+unsafe impl CRepr for Tint {}
+
+/// Renders a frame, and returns the pixel in the middle of the view.
+async fn center(port: &Port) -> [u8; 4] {
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    [pixel.r, pixel.g, pixel.b, pixel.a]
+}
+
+#[async_test]
+async fn each_frame_draws_the_latest_value() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let tint = Immediates::new(Tint {
+        rgba: [1.0, 0.0, 0.0, 1.0],
+    });
+    port.add_fixed_pass(
+        rectangle(
+            -1.0,
+            1.0,
+            0.5,
+            "var<immediate> tint: vec4<f32>;
+            @fragment fn fs_main() -> @location(0) vec4<f32> {
+                return tint;
+            }",
+            BindStyle::new(),
+        )
+        .with_immediates(&tint),
+    )
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(center(port).await, [255, 0, 0, 255]);
+
+    tint.set(Tint {
+        rgba: [0.0, 1.0, 0.0, 1.0],
+    });
+    assert_eq!(tint.get().rgba, [0.0, 1.0, 0.0, 1.0]);
+    assert_eq!(center(port).await, [0, 255, 0, 255]);
+}
+
+#[async_test]
+async fn immediates_follow_bound_groups() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let color = Buffer::<f32>::new(
+        engine.bound_device().clone(),
+        4,
+        GPUBufferUsage::FragmentShaderRead,
+        "color",
+        |i| [0.0, 0.0, 1.0, 1.0][i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_buffer(BindSlot::in_group(1, 0), Stage::Fragment, &color);
+    let highlighted = Immediates::new(1u32);
+    port.add_fixed_pass(
+        rectangle(
+            -1.0,
+            1.0,
+            0.5,
+            "@group(1) @binding(0) var<uniform> color: vec4<f32>;
+            var<immediate> highlighted: u32;
+            @fragment fn fs_main() -> @location(0) vec4<f32> {
+                return color + vec4<f32>(f32(highlighted), 0.0, 0.0, 0.0);
+            }",
+            bind_style,
+        )
+        .with_immediates(&highlighted),
+    )
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(center(port).await, [255, 0, 255, 255]);
+
+    highlighted.set(0);
+    assert_eq!(center(port).await, [0, 0, 255, 255]);
+}
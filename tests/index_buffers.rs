@@ -0,0 +1,152 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Draws with 32-bit and dynamic index buffers.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::BindSlot;
+use images_and_words::bindings::forward::dynamic;
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// The corners of the bottom-left quarter of clip space, then the top-right quarter,
+/// counter-clockwise.
+const CORNERS: [[f32; 2]; 8] = [
+    [-1.0, -1.0],
+    [0.0, -1.0],
+    [0.0, 0.0],
+    [-1.0, 0.0],
+    [0.0, 0.0],
+    [1.0, 0.0],
+    [1.0, 1.0],
+    [0.0, 1.0],
+];
+
+/// Adds a pass drawing two red triangles from `vertices` (as x, y pairs) and `bind_style`,
+/// which should bind an index buffer.
+async fn add_red_pass(port: &Port, mut bind_style: BindStyle, vertices: &Buffer<f32>) {
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    bind_style.bind_static_vertex_buffer(BindSlot::new(0), vertices, layout, StepMode::Vertex);
+    let vertex_shader = VertexShader::new(
+        "xy",
+        "@vertex fn vs_main(@location(0) x: f32, @location(1) y: f32) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(x, y, 0.0, 1.0);
+        }"
+        .to_string(),
+    );
+    let fragment_shader = FragmentShader::new("red", fill([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(PassDescriptor::new(
+        "indexed".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleList(2),
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+}
+
+/// Returns whether the bottom-left and top-right quarters of the framebuffer are red.
+async fn red_quarters(port: &Port) -> (bool, bool) {
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let red = |x, y| framebuffer.color[Texel { x, y }].r == 255;
+    (red(1, 2), red(2, 1))
+}
+
+#[async_test]
+async fn u32_indices_reach_past_65536_vertices() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device().clone();
+    //the top-right quad's corners are the last 4 of 70,000 vertices
+    const VERTICES: usize = 70_000;
+    let vertices = Buffer::<f32>::new(
+        device.clone(),
+        VERTICES * 2,
+        GPUBufferUsage::VertexBuffer,
+        "vertices",
+        |i| {
+            //unused vertices all sit on the quad's first corner
+            let corner = (i / 2 + 8).saturating_sub(VERTICES).max(4);
+            CORNERS[corner][i % 2]
+        },
+    )
+    .await
+    .expect("Failed to create buffer");
+    let first = (VERTICES - 4) as u32;
+    let indices = [0, 1, 2, 0, 2, 3].map(|i| first + i);
+    let indices = Buffer::<u32>::new(
+        device,
+        indices.len(),
+        GPUBufferUsage::Index,
+        "indices",
+        |i| indices[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_index_buffer(&indices);
+    add_red_pass(port, bind_style, &vertices).await;
+    port.force_render().await;
+
+    assert_eq!(red_quarters(port).await, (false, true));
+}
+
+#[async_test]
+async fn dynamic_index_buffer_rebuilds_without_new_pass() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device().clone();
+    let vertices = Buffer::<f32>::new(
+        device.clone(),
+        CORNERS.len() * 2,
+        GPUBufferUsage::VertexBuffer,
+        "vertices",
+        |i| CORNERS[i / 2][i % 2],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let bottom_left: [u16; 6] = [0, 1, 2, 0, 2, 3];
+    let indices = dynamic::buffer::Buffer::<u16>::new(
+        device,
+        bottom_left.len(),
+        GPUBufferUsage::Index,
+        "indices",
+        |i| bottom_left[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_dynamic_index_buffer(&indices);
+    add_red_pass(port, bind_style, &vertices).await;
+    port.force_render().await;
+    assert_eq!(red_quarters(port).await, (true, false));
+
+    indices
+        .access_write()
+        .await
+        .write(&[4, 5, 6, 4, 6, 7], 0)
+        .await;
+    port.force_render().await;
+    assert_eq!(red_quarters(port).await, (false, true));
+}
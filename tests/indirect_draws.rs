@@ -0,0 +1,323 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Draws with arguments read from buffers, including arguments a compute pass wrote.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::engine;
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::forward::dynamic::buffer::CRepr;
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::Engine;
+use images_and_words::images::compute_pass::{ComputePassDescriptor, Dispatch};
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{
+    DrawCommand, DrawIndexedIndirectArgs, DrawIndirectArgs, PassDescriptor, PassError, Topology,
+};
+use images_and_words::images::shader::{ComputeShader, FragmentShader, VertexShader};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+fn green_fragment_shader() -> FragmentShader {
+    FragmentShader::new(
+        "green",
+        "@fragment fn fs_main() -> @location(0) vec4<f32> {
+            return vec4<f32>(0.0, 1.0, 0.0, 1.0);
+        }"
+        .to_string(),
+    )
+}
+
+/// A green pass drawing `draw_command`, where vertices 0 to 2 cover the view and vertices
+/// 3 to 5 cover nothing.
+fn green(bind_style: BindStyle, draw_command: DrawCommand) -> PassDescriptor {
+    let vertex_shader = VertexShader::new(
+        "fullscreen",
+        "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+            var xs = array<f32, 6>(-1.0, 3.0, -1.0, 0.0, 0.0, 0.0);
+            var ys = array<f32, 6>(-1.0, -1.0, 3.0, 0.0, 0.0, 0.0);
+            return vec4<f32>(xs[i], ys[i], 0.5, 1.0);
+        }"
+        .to_string(),
+    );
+    PassDescriptor::new(
+        "green".to_string(),
+        vertex_shader,
+        green_fragment_shader(),
+        bind_style,
+        draw_command,
+        false,
+        false,
+    )
+}
+
+/// Renders a frame, and returns the green channel in the middle of the view.
+async fn center_green(port: &Port) -> u8 {
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    framebuffer.color[Texel { x: 2, y: 2 }].g
+}
+
+async fn indirect_buffer<Args: CRepr + Copy>(engine: &Engine, args: Vec<Args>) -> Buffer<Args> {
+    Buffer::new(
+        engine.bound_device().clone(),
+        args.len(),
+        GPUBufferUsage::Indirect,
+        "indirect",
+        |i| args[i],
+    )
+    .await
+    .expect("Failed to create buffer")
+}
+
+#[async_test]
+async fn draws_the_chosen_arguments() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let args = indirect_buffer(
+        &engine,
+        vec![
+            DrawIndirectArgs {
+                vertex_count: 3,
+                instance_count: 1,
+                first_vertex: 3,
+                first_instance: 0,
+            },
+            DrawIndirectArgs {
+                vertex_count: 3,
+                instance_count: 1,
+                first_vertex: 0,
+                first_instance: 0,
+            },
+        ],
+    )
+    .await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_indirect_buffer(&args);
+    let handle = port
+        .add_fixed_pass(green(
+            bind_style.clone(),
+            DrawCommand::Indirect {
+                topology: Topology::TriangleList,
+                draw: 0,
+            },
+        ))
+        .await
+        .expect("Failed to add pass");
+    assert_eq!(center_green(port).await, 0);
+
+    port.replace_pass(
+        handle,
+        green(
+            bind_style,
+            DrawCommand::Indirect {
+                topology: Topology::TriangleList,
+                draw: 1,
+            },
+        ),
+    )
+    .await
+    .expect("Failed to replace pass");
+    assert_eq!(center_green(port).await, 255);
+}
+
+#[async_test]
+async fn draws_indexed_arguments() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let indices = Buffer::<u16>::new(
+        engine.bound_device().clone(),
+        6,
+        GPUBufferUsage::Index,
+        "indices",
+        |i| [3, 4, 5, 0, 1, 2][i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let args = indirect_buffer(
+        &engine,
+        vec![DrawIndexedIndirectArgs {
+            index_count: 3,
+            instance_count: 1,
+            first_index: 3,
+            base_vertex: 0,
+            first_instance: 0,
+        }],
+    )
+    .await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_index_buffer(&indices);
+    bind_style.bind_indirect_buffer(&args);
+    port.add_fixed_pass(green(
+        bind_style,
+        DrawCommand::IndexedIndirect {
+            topology: Topology::TriangleList,
+            draw: 0,
+        },
+    ))
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(center_green(port).await, 255);
+}
+
+#[async_test]
+async fn compute_pass_writes_the_arguments() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    //draws nothing until the compute pass runs
+    let args = indirect_buffer(&engine, vec![DrawIndirectArgs::default()]).await;
+    let shader = ComputeShader::new(
+        "cull",
+        "struct DrawIndirectArgs {
+            vertex_count: u32,
+            instance_count: u32,
+            first_vertex: u32,
+            first_instance: u32,
+        }
+        @group(0) @binding(0) var<storage, read_write> args: DrawIndirectArgs;
+        @compute @workgroup_size(1) fn cs_main() {
+            args.vertex_count = 3u;
+            args.instance_count = 1u;
+        }"
+        .to_string(),
+    );
+    let mut compute_binds = BindStyle::new();
+    compute_binds.bind_static_buffer(BindSlot::new(0), Stage::Compute, &args);
+    port.add_compute_pass(ComputePassDescriptor::new(
+        "cull".to_string(),
+        shader,
+        compute_binds,
+        Dispatch::Workgroups(1, 1, 1),
+    ))
+    .await
+    .expect("Failed to add compute pass");
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_indirect_buffer(&args);
+    port.add_fixed_pass(green(
+        bind_style,
+        DrawCommand::Indirect {
+            topology: Topology::TriangleList,
+            draw: 0,
+        },
+    ))
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(center_green(port).await, 255);
+}
+
+#[async_test]
+async fn first_instance_offsets_the_instances() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    if !engine.bound_device().supports_indirect_first_instance() {
+        return;
+    }
+    let args = indirect_buffer(
+        &engine,
+        vec![DrawIndirectArgs {
+            vertex_count: 3,
+            instance_count: 1,
+            first_vertex: 0,
+            first_instance: 1,
+        }],
+    )
+    .await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_indirect_buffer(&args);
+    let vertex_shader = VertexShader::new(
+        "second_instance",
+        "@vertex fn vs_main(@builtin(vertex_index) i: u32, @builtin(instance_index) instance: u32) -> @builtin(position) vec4<f32> {
+            var xs = array<f32, 3>(-1.0, 3.0, -1.0);
+            var ys = array<f32, 3>(-1.0, -1.0, 3.0);
+            //only instance 1 covers the view
+            let scale = select(0.0, 1.0, instance == 1u);
+            return vec4<f32>(xs[i] * scale, ys[i] * scale, 0.5, 1.0);
+        }"
+        .to_string(),
+    );
+    port.add_fixed_pass(PassDescriptor::new(
+        "second_instance".to_string(),
+        vertex_shader,
+        green_fragment_shader(),
+        bind_style,
+        DrawCommand::Indirect {
+            topology: Topology::TriangleList,
+            draw: 0,
+        },
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(center_green(port).await, 255);
+}
+
+#[async_test]
+async fn port_refuses_draws_past_the_indirect_buffer() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let args = indirect_buffer(&engine, vec![DrawIndirectArgs::default(); 2]).await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_indirect_buffer(&args);
+    let added = port
+        .add_fixed_pass(green(
+            bind_style,
+            DrawCommand::Indirect {
+                topology: Topology::TriangleList,
+                draw: 2,
+            },
+        ))
+        .await;
+    assert!(matches!(
+        added,
+        Err(PassError::IndirectDraw {
+            draw: 2,
+            len: 2,
+            ..
+        })
+    ));
+}
+
+#[async_test]
+async fn port_refuses_indirect_draws_without_their_buffers() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let added = port
+        .add_fixed_pass(green(
+            BindStyle::new(),
+            DrawCommand::Indirect {
+                topology: Topology::TriangleList,
+                draw: 0,
+            },
+        ))
+        .await;
+    assert!(matches!(
+        added,
+        Err(PassError::IndirectBuffer {
+            arguments: "DrawIndirectArgs",
+            ..
+        })
+    ));
+
+    let args = indirect_buffer(&engine, vec![DrawIndexedIndirectArgs::default()]).await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_indirect_buffer(&args);
+    let added = port
+        .add_fixed_pass(green(
+            bind_style,
+            DrawCommand::IndexedIndirect {
+                topology: Topology::TriangleList,
+                draw: 0,
+            },
+        ))
+        .await;
+    assert!(matches!(added, Err(PassError::IndexBuffer { .. })));
+}
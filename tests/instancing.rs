@@ -0,0 +1,171 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Draws several copies of one mesh, placed by a per-instance vertex buffer.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::BindSlot;
+use images_and_words::bindings::forward::dynamic::buffer::Buffer;
+use images_and_words::bindings::forward::r#static;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// A quad covering the bottom-left quarter of clip space, as two counter-clockwise triangles.
+const QUAD: [f32; 12] = [
+    -1.0, -1.0, 0.0, -1.0, 0.0, 0.0, //
+    -1.0, -1.0, 0.0, 0.0, -1.0, 0.0,
+];
+
+fn xy_layout() -> VertexLayout {
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    layout
+}
+
+fn shaders() -> (VertexShader, FragmentShader) {
+    let vertex_shader = VertexShader::new(
+        "offset_quad",
+        "@vertex fn vs_main(@location(0) x: f32, @location(1) y: f32,
+            @location(2) dx: f32, @location(3) dy: f32) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(x + dx, y + dy, 0.0, 1.0);
+        }"
+        .to_string(),
+    );
+    let fragment_shader = FragmentShader::new("red", fill([1.0, 0.0, 0.0, 1.0]));
+    (vertex_shader, fragment_shader)
+}
+
+/// Returns which quarters of the framebuffer are red, as
+/// (top left, top right, bottom left, bottom right).
+async fn red_quarters(port: &Port) -> (bool, bool, bool, bool) {
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let red = |x, y| framebuffer.color[Texel { x, y }].r == 255;
+    (red(1, 1), red(2, 1), red(1, 2), red(2, 2))
+}
+
+#[async_test]
+async fn instance_buffer_offsets_each_copy() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device().clone();
+    let quad = r#static::buffer::Buffer::<f32>::new(
+        device.clone(),
+        QUAD.len(),
+        GPUBufferUsage::VertexBuffer,
+        "quad",
+        |i| QUAD[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    //copies at the bottom left and top right
+    let offsets = [0.0, 0.0, 1.0, 1.0];
+    let offsets = r#static::buffer::Buffer::<f32>::new(
+        device,
+        offsets.len(),
+        GPUBufferUsage::VertexBuffer,
+        "offsets",
+        |i| offsets[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(BindSlot::new(0), &quad, xy_layout(), StepMode::Vertex);
+    bind_style.bind_static_vertex_buffer(
+        BindSlot::new(1),
+        &offsets,
+        xy_layout(),
+        StepMode::Instance,
+    );
+    let (vertex_shader, fragment_shader) = shaders();
+    port.add_fixed_pass(PassDescriptor::new(
+        "quads".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleListInstanced {
+            triangles: 2,
+            instances: 2,
+        },
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    assert_eq!(red_quarters(port).await, (false, true, true, false));
+}
+
+#[async_test]
+async fn dynamic_instance_buffer_moves_copies() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device().clone();
+    let quad = r#static::buffer::Buffer::<f32>::new(
+        device.clone(),
+        QUAD.len(),
+        GPUBufferUsage::VertexBuffer,
+        "quad",
+        |i| QUAD[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    //one copy at the bottom right
+    let offsets = [1.0, 0.0];
+    let offsets = Buffer::<f32>::new(
+        device,
+        offsets.len(),
+        GPUBufferUsage::VertexBuffer,
+        "offsets",
+        |i| offsets[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    //slots need not start at zero
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(BindSlot::new(3), &quad, xy_layout(), StepMode::Vertex);
+    bind_style.bind_dynamic_vertex_buffer(
+        BindSlot::new(5),
+        &offsets,
+        xy_layout(),
+        StepMode::Instance,
+    );
+    let (vertex_shader, fragment_shader) = shaders();
+    port.add_fixed_pass(PassDescriptor::new(
+        "quads".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleListInstanced {
+            triangles: 2,
+            instances: 1,
+        },
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+    assert_eq!(red_quarters(port).await, (false, false, false, true));
+
+    //move the copy to the top left
+    offsets.access_write().await.write(&[0.0, 1.0], 0).await;
+    port.force_render().await;
+    assert_eq!(red_quarters(port).await, (true, false, false, false));
+}
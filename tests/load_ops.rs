@@ -0,0 +1,144 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Clears or keeps a port's view each frame, and overrides loads and stores per pass.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, engine_with_view, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{BlendMode, DepthStencilState, LoadOp, PassDescriptor};
+use images_and_words::images::view::View;
+use images_and_words::pixel_formats::{Float4, RGBA8UNorm};
+use test_executors::async_test;
+
+const BLUE: Float4 = Float4 {
+    r: 0.0,
+    g: 0.0,
+    b: 1.0,
+    a: 1.0,
+};
+
+/// A rectangle from `left` to `right` in clip space, covering the view's height, at depth `z`.
+fn rectangle(left: f32, right: f32, z: f32, color: [f32; 4]) -> PassDescriptor {
+    common::rectangle(left, right, z, &fill(color), BindStyle::new())
+}
+
+/// Renders a frame, and returns the pixels at each of `xs` on the second row.
+async fn row(port: &Port, xs: [u16; 2]) -> [[u8; 4]; 2] {
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    xs.map(|x| {
+        let pixel = framebuffer.color[Texel { x, y: 1 }];
+        [pixel.r, pixel.g, pixel.b, pixel.a]
+    })
+}
+
+#[async_test]
+async fn views_clear_to_the_port_color() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    assert_eq!(row(port, [0, 3]).await, [[0, 0, 0, 0]; 2]);
+
+    port.set_color_load(LoadOp::Clear(BLUE)).await;
+    assert_eq!(row(port, [0, 3]).await, [[0, 0, 255, 255]; 2]);
+    //passes draw over the background
+    port.add_fixed_pass(rectangle(-1.0, 0.0, 0.5, [1.0, 0.0, 0.0, 1.0]))
+        .await
+        .expect("Failed to add pass");
+    assert_eq!(
+        row(port, [0, 3]).await,
+        [[255, 0, 0, 255], [0, 0, 255, 255]]
+    );
+}
+
+#[async_test]
+async fn loading_the_view_accumulates_across_frames() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.set_color_load(LoadOp::Load).await;
+    port.add_fixed_pass(
+        rectangle(-1.0, 1.0, 0.5, [0.25, 0.0, 0.0, 0.25]).with_blend_mode(BlendMode::Additive),
+    )
+    .await
+    .expect("Failed to add pass");
+    let reds: Vec<u8> = [
+        row(port, [1, 1]).await,
+        row(port, [1, 1]).await,
+        row(port, [1, 1]).await,
+    ]
+    .iter()
+    .map(|pixels| pixels[0][0])
+    .collect();
+    assert!(reds[0].abs_diff(64) <= 1, "{reds:?}");
+    assert!(reds[1].abs_diff(128) <= 1, "{reds:?}");
+    assert!(reds[2].abs_diff(191) <= 1, "{reds:?}");
+}
+
+#[async_test]
+async fn loading_accumulates_in_non_offscreen_views() {
+    let engine = engine_with_view(View::for_testing()).await;
+    let port = engine.main_port();
+    port.set_color_load(LoadOp::Load).await;
+    port.add_fixed_pass(
+        rectangle(-1.0, 1.0, 0.5, [0.25, 0.0, 0.0, 0.25]).with_blend_mode(BlendMode::Additive),
+    )
+    .await
+    .expect("Failed to add pass");
+    let mut reds = Vec::new();
+    for _ in 0..2 {
+        let capture = port.capture_next_frame();
+        port.force_render().await;
+        let frame = capture.await.expect("Capture frame");
+        reds.push(frame.color[Texel { x: 400, y: 300 }].r);
+    }
+    assert!(reds[1] > reds[0], "{reds:?}");
+}
+
+#[async_test]
+async fn passes_override_the_load() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(rectangle(-1.0, 1.0, 0.5, [1.0, 0.0, 0.0, 1.0]))
+        .await
+        .expect("Failed to add pass");
+    port.add_fixed_pass(
+        rectangle(-1.0, 0.0, 0.5, [0.0, 1.0, 0.0, 1.0]).with_color_load(LoadOp::Clear(BLUE)),
+    )
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(
+        row(port, [0, 3]).await,
+        [[0, 255, 0, 255], [0, 0, 255, 255]]
+    );
+}
+
+#[async_test]
+async fn passes_override_the_depth_load() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(
+        rectangle(-1.0, 1.0, 0.25, [1.0, 0.0, 0.0, 1.0])
+            .with_depth_stencil_state(DepthStencilState::new()),
+    )
+    .await
+    .expect("Failed to add pass");
+    //hidden behind the first pass, unless depth is cleared first
+    port.add_fixed_pass(
+        rectangle(-1.0, 0.0, 0.5, [0.0, 1.0, 0.0, 1.0])
+            .with_depth_stencil_state(DepthStencilState::new())
+            .with_depth_load(LoadOp::Clear(1.0)),
+    )
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(
+        row(port, [0, 3]).await,
+        [[0, 255, 0, 255], [255, 0, 0, 255]]
+    );
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Anti-aliases the edges of passes into a view with a port's sample count.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::port::Framebuffer;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// Draws a red triangle over the lower-right half of a 4x4 view, whose edge crosses the
+/// pixels on the view's anti-diagonal, with `depth` enabled.  Returns the sample count used
+/// and the frame.
+async fn draw_diagonal(sample_count: u32, depth: bool) -> (u32, Framebuffer<RGBA8UNorm>) {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let sample_count = port.set_sample_count(sample_count).await;
+    let vertex_shader = VertexShader::new(
+        "diagonal",
+        "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+            var positions = array<vec2<f32>, 3>(vec2(-1.0, -1.0), vec2(1.0, -1.0), vec2(1.0, 1.0));
+            return vec4<f32>(positions[i], 0.5, 1.0);
+        }"
+        .to_string(),
+    );
+    let fragment_shader = FragmentShader::new("red", fill([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(PassDescriptor::new(
+        "diagonal".to_string(),
+        vertex_shader,
+        fragment_shader,
+        BindStyle::new(),
+        DrawCommand::TriangleList(1),
+        depth,
+        depth,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    (sample_count, framebuffer)
+}
+
+/// The red of each pixel the triangle's edge crosses.
+fn edge(framebuffer: &Framebuffer<RGBA8UNorm>) -> [u8; 4] {
+    std::array::from_fn(|i| {
+        framebuffer.color[Texel {
+            x: i as u16,
+            y: 3 - i as u16,
+        }]
+        .r
+    })
+}
+
+#[async_test]
+async fn one_sample_draws_hard_edges() {
+    let (sample_count, framebuffer) = draw_diagonal(1, false).await;
+    assert_eq!(sample_count, 1);
+    assert!(edge(&framebuffer).iter().all(|&r| r == 0 || r == 255));
+}
+
+#[async_test]
+async fn multisampling_blends_edges() {
+    let (sample_count, framebuffer) = draw_diagonal(4, false).await;
+    //WebGPU guarantees 4 samples
+    assert_eq!(sample_count, 4);
+    assert!(edge(&framebuffer).iter().all(|&r| r > 0 && r < 255));
+    //pixels away from the edge are unaffected
+    assert_eq!(framebuffer.color[Texel { x: 3, y: 3 }].r, 255);
+    assert_eq!(framebuffer.color[Texel { x: 0, y: 0 }].r, 0);
+}
+
+#[async_test]
+async fn unsupported_counts_are_clamped() {
+    let (sample_count, _) = draw_diagonal(8, false).await;
+    assert!(sample_count == 4 || sample_count == 8);
+}
+
+#[async_test]
+async fn multisampled_depth_is_not_read_back() {
+    let (sample_count, framebuffer) = draw_diagonal(4, true).await;
+    assert_eq!(sample_count, 4);
+    assert!(framebuffer.depth.is_none());
+    assert_eq!(framebuffer.color[Texel { x: 3, y: 3 }].r, 255);
+
+    let (_, framebuffer) = draw_diagonal(1, true).await;
+    assert!(framebuffer.depth.is_some());
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Renders into offscreen views and reads the framebuffer back.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine_with_view, fullscreen_pass};
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::view::View;
+use images_and_words::pixel_formats::{BGRA8UNormSRGB, RGBA8UNorm};
+use test_executors::async_test;
+
+#[async_test]
+async fn reads_back_rendered_pixels() {
+    //100 pixels is 400 bytes per row, which wgpu pads to 512
+    let engine = engine_with_view(View::offscreen(100, 20, RGBA8UNorm)).await;
+    let port = engine.main_port();
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read before rendering");
+    let pixel = framebuffer.color[Texel { x: 50, y: 10 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 0, 0, 0));
+
+    port.add_fixed_pass(fullscreen_pass(false))
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    assert_eq!(framebuffer.color.width(), 100);
+    assert_eq!(framebuffer.color.height(), 20);
+    assert!(framebuffer.depth.is_none());
+    for texel in [Texel { x: 0, y: 0 }, Texel { x: 99, y: 19 }] {
+        let pixel = framebuffer.color[texel];
+        assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+    }
+
+    assert!(port.read_framebuffer::<BGRA8UNormSRGB>().await.is_err());
+}
+
+#[async_test]
+async fn reads_back_depth() {
+    let engine = engine_with_view(View::offscreen(8, 8, RGBA8UNorm)).await;
+    let port = engine.main_port();
+    port.add_fixed_pass(fullscreen_pass(true))
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let depth = framebuffer.depth.expect("Depth is enabled");
+    assert!((depth[Texel { x: 4, y: 4 }] - 0.25).abs() < 0.001);
+}
+
+#[async_test]
+async fn testing_view_cannot_be_read() {
+    let engine = engine_with_view(View::for_testing()).await;
+    assert!(
+        engine
+            .main_port()
+            .read_framebuffer::<BGRA8UNormSRGB>()
+            .await
+            .is_err()
+    );
+}
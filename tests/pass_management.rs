@@ -0,0 +1,145 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Removes, replaces, moves and toggles a port's passes through their handles.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{PassDescriptor, PassError};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+/// A rectangle from `left` to `right` in clip space, covering the view's height.
+fn rectangle(left: f32, right: f32, color: [f32; 4]) -> PassDescriptor {
+    common::rectangle(left, right, 0.5, &fill(color), BindStyle::new())
+}
+
+/// Renders a frame, and returns the colors of the left and right edges of the second row.
+async fn edges(port: &Port) -> [[u8; 3]; 2] {
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    [0, 3].map(|x| {
+        let pixel = framebuffer.color[Texel { x, y: 1 }];
+        [pixel.r, pixel.g, pixel.b]
+    })
+}
+
+#[async_test]
+async fn removed_passes_stop_drawing() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let handles = port
+        .add_fixed_passes(vec![rectangle(-1.0, 1.0, GREEN), rectangle(-1.0, 0.0, RED)])
+        .await
+        .expect("Failed to add passes");
+    assert_eq!(edges(port).await, [[255, 0, 0], [0, 255, 0]]);
+
+    port.remove_pass(handles[1])
+        .await
+        .expect("Failed to remove pass");
+    assert_eq!(edges(port).await, [[0, 255, 0], [0, 255, 0]]);
+    port.remove_pass(handles[0])
+        .await
+        .expect("Failed to remove pass");
+    assert_eq!(edges(port).await, [[0, 0, 0], [0, 0, 0]]);
+}
+
+#[async_test]
+async fn removed_handles_are_refused() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let handle = port
+        .add_fixed_pass(rectangle(-1.0, 1.0, RED))
+        .await
+        .expect("Failed to add pass");
+    port.remove_pass(handle)
+        .await
+        .expect("Failed to remove pass");
+
+    let stale = Err(PassError::StaleHandle { handle });
+    assert_eq!(port.remove_pass(handle).await, stale);
+    assert_eq!(port.move_pass(handle, 0).await, stale);
+    assert_eq!(port.set_pass_enabled(handle, false).await, stale);
+    assert_eq!(
+        port.replace_pass(handle, rectangle(-1.0, 1.0, GREEN)).await,
+        stale
+    );
+}
+
+#[async_test]
+async fn replaced_passes_keep_their_place() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let background = port
+        .add_fixed_pass(rectangle(-1.0, 1.0, RED))
+        .await
+        .expect("Failed to add pass");
+    port.add_fixed_pass(rectangle(-1.0, 0.0, GREEN))
+        .await
+        .expect("Failed to add pass");
+
+    port.replace_pass(background, rectangle(-1.0, 1.0, BLUE))
+        .await
+        .expect("Failed to replace pass");
+    assert_eq!(edges(port).await, [[0, 255, 0], [0, 0, 255]]);
+}
+
+#[async_test]
+async fn moved_passes_change_what_is_drawn_on_top() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(rectangle(-1.0, 1.0, RED))
+        .await
+        .expect("Failed to add pass");
+    let green = port
+        .add_fixed_pass(rectangle(-1.0, 0.0, GREEN))
+        .await
+        .expect("Failed to add pass");
+    assert_eq!(edges(port).await, [[0, 255, 0], [255, 0, 0]]);
+
+    port.move_pass(green, 0).await.expect("Failed to move pass");
+    assert_eq!(edges(port).await, [[255, 0, 0], [255, 0, 0]]);
+    //indices past the end move the pass last
+    port.move_pass(green, 10)
+        .await
+        .expect("Failed to move pass");
+    assert_eq!(edges(port).await, [[0, 255, 0], [255, 0, 0]]);
+}
+
+#[async_test]
+async fn disabled_passes_do_not_draw_until_enabled() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(rectangle(-1.0, 1.0, RED))
+        .await
+        .expect("Failed to add pass");
+    let overlay = port
+        .add_fixed_pass(rectangle(-1.0, 0.0, GREEN))
+        .await
+        .expect("Failed to add pass");
+
+    port.set_pass_enabled(overlay, false)
+        .await
+        .expect("Failed to toggle pass");
+    assert_eq!(edges(port).await, [[255, 0, 0], [255, 0, 0]]);
+    //disabled passes keep their place
+    port.add_fixed_pass(rectangle(0.0, 1.0, BLUE))
+        .await
+        .expect("Failed to add pass");
+    port.set_pass_enabled(overlay, true)
+        .await
+        .expect("Failed to toggle pass");
+    assert_eq!(edges(port).await, [[0, 255, 0], [0, 0, 255]]);
+}
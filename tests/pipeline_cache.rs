@@ -0,0 +1,51 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Loads and saves the pipeline cache, which is a no-op on devices that can't cache pipelines.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::fullscreen_pass;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::projection::WorldCoord;
+use images_and_words::images::view::View;
+use images_and_words::images::{Engine, EngineOptions};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use std::path::Path;
+use test_executors::async_test;
+
+#[async_test]
+async fn engines_render_with_and_save_a_pipeline_cache() {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join("pipeline_cache");
+    for _ in 0..2 {
+        let engine = Engine::rendering_to_with_options(
+            View::offscreen(4, 4, RGBA8UNorm),
+            WorldCoord::new(0.0, 0.0, 10.0),
+            EngineOptions::new().with_pipeline_cache(&directory),
+        )
+        .await
+        .expect("Failed to create engine");
+        let port = engine.main_port();
+        port.add_fixed_pass(fullscreen_pass(false))
+            .await
+            .expect("Failed to add pass");
+        port.force_render().await;
+        let framebuffer = port
+            .read_framebuffer::<RGBA8UNorm>()
+            .await
+            .expect("Failed to read framebuffer");
+        assert_eq!(framebuffer.color[Texel { x: 2, y: 2 }].r, 255);
+        engine
+            .save_pipeline_cache()
+            .expect("Failed to save pipeline cache");
+    }
+    //devices without a pipeline cache write nothing, and this can't tell whether the
+    //second engine read what the first saved
+    if let Ok(entries) = std::fs::read_dir(&directory) {
+        for entry in entries {
+            let metadata = entry.expect("Failed to read entry").metadata().unwrap();
+            assert!(metadata.len() > 0);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Culls, winds and fills triangles according to a pass's rasterizer state.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::BindSlot;
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::Engine;
+use images_and_words::images::render_pass::{
+    CullMode, DrawCommand, FrontFace, PassDescriptor, PassError, PolygonMode, RasterizerState,
+};
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// A triangle over the lower-left half of the view, wound counter-clockwise.
+const COUNTER_CLOCKWISE: [f32; 9] = [-0.75, -0.75, 0.5, 0.75, -0.75, 0.5, -0.75, 0.75, 0.5];
+/// The same triangle, wound clockwise.
+const CLOCKWISE: [f32; 9] = [-0.75, -0.75, 0.5, -0.75, 0.75, 0.5, 0.75, -0.75, 0.5];
+
+/// A pass drawing one triangle (as x, y, z triples) in red.
+async fn red_triangle(
+    engine: &Engine,
+    vertices: [f32; 9],
+    rasterizer: RasterizerState,
+) -> PassDescriptor {
+    let device = engine.bound_device().clone();
+    let vertex_buffer = Buffer::<f32>::new(
+        device,
+        vertices.len(),
+        GPUBufferUsage::VertexBuffer,
+        "vertices",
+        |i| vertices[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    layout.add_field("z", VertexFieldType::F32);
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(
+        BindSlot::new(0),
+        &vertex_buffer,
+        layout,
+        StepMode::Vertex,
+    );
+
+    let vertex_shader = VertexShader::new(
+        "xyz",
+        "@vertex fn vs_main(@location(0) x: f32, @location(1) y: f32, @location(2) z: f32) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(x, y, z, 1.0);
+        }"
+        .to_string(),
+    );
+    let fragment_shader = FragmentShader::new("red", fill([1.0, 0.0, 0.0, 1.0]));
+    PassDescriptor::new(
+        "triangle".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleList(1),
+        false,
+        false,
+    )
+    .with_rasterizer_state(rasterizer)
+}
+
+/// Draws one triangle (as x, y, z triples) in red into a 4x4 framebuffer, and returns
+/// which pixels are red, as rows from the top.
+async fn draw_red(vertices: [f32; 9], rasterizer: RasterizerState) -> [[bool; 4]; 4] {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(red_triangle(&engine, vertices, rasterizer).await)
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    std::array::from_fn(|y| {
+        std::array::from_fn(|x| {
+            framebuffer.color[Texel {
+                x: x as u16,
+                y: y as u16,
+            }]
+            .r == 255
+        })
+    })
+}
+
+fn any_red(pixels: [[bool; 4]; 4]) -> bool {
+    pixels.iter().flatten().any(|&red| red)
+}
+
+#[async_test]
+async fn back_faces_are_culled_by_default() {
+    let front = draw_red(COUNTER_CLOCKWISE, RasterizerState::new()).await;
+    let back = draw_red(CLOCKWISE, RasterizerState::new()).await;
+    assert!(any_red(front));
+    assert!(!any_red(back));
+}
+
+#[async_test]
+async fn clockwise_front_face_draws_clockwise_triangles() {
+    let clockwise = RasterizerState::new().with_front_face(FrontFace::Clockwise);
+    let front = draw_red(CLOCKWISE, clockwise).await;
+    let back = draw_red(COUNTER_CLOCKWISE, clockwise).await;
+    assert!(any_red(front));
+    assert!(!any_red(back));
+}
+
+#[async_test]
+async fn cull_modes_choose_which_faces_are_drawn() {
+    let none = RasterizerState::new().with_cull_mode(CullMode::None);
+    let front = RasterizerState::new().with_cull_mode(CullMode::Front);
+    let both_windings = [
+        draw_red(COUNTER_CLOCKWISE, none).await,
+        draw_red(CLOCKWISE, none).await,
+    ];
+    assert_eq!(both_windings[0], both_windings[1]);
+    assert!(any_red(both_windings[0]));
+    assert!(!any_red(draw_red(COUNTER_CLOCKWISE, front).await));
+    assert!(any_red(draw_red(CLOCKWISE, front).await));
+}
+
+#[async_test]
+async fn line_polygon_mode_draws_only_edges() {
+    let wireframe = RasterizerState::new().with_polygon_mode(PolygonMode::Line);
+    if !engine()
+        .await
+        .bound_device()
+        .supports_polygon_mode(PolygonMode::Line)
+    {
+        return;
+    }
+    let edges = draw_red(COUNTER_CLOCKWISE, wireframe).await;
+    let filled = draw_red(COUNTER_CLOCKWISE, RasterizerState::new()).await;
+    //(1, 2) is inside the triangle, away from its edges
+    assert!(filled[2][1]);
+    assert!(!edges[2][1]);
+    assert!(any_red(edges));
+}
+
+#[async_test]
+async fn unclipped_depth_draws_beyond_the_far_plane() {
+    let mut far = COUNTER_CLOCKWISE;
+    for z in far.iter_mut().skip(2).step_by(3) {
+        *z = 1.5;
+    }
+    let unclipped = RasterizerState::new().with_unclipped_depth(true);
+    if !engine().await.bound_device().supports_unclipped_depth() {
+        return;
+    }
+    assert!(any_red(draw_red(far, unclipped).await));
+    assert!(!any_red(draw_red(far, RasterizerState::new()).await));
+}
+
+#[async_test]
+async fn port_refuses_rasterizer_states_the_device_does_not_support() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device();
+
+    let wireframe = RasterizerState::new().with_polygon_mode(PolygonMode::Point);
+    let added = port
+        .add_fixed_pass(red_triangle(&engine, COUNTER_CLOCKWISE, wireframe).await)
+        .await;
+    if device.supports_polygon_mode(PolygonMode::Point) {
+        added.expect("Failed to add pass");
+    } else {
+        assert!(matches!(added, Err(PassError::PolygonMode { .. })));
+    }
+
+    let unclipped = RasterizerState::new().with_unclipped_depth(true);
+    let added = port
+        .add_fixed_pass(red_triangle(&engine, COUNTER_CLOCKWISE, unclipped).await)
+        .await;
+    if device.supports_unclipped_depth() {
+        added.expect("Failed to add pass");
+    } else {
+        assert!(matches!(added, Err(PassError::UnclippedDepth { .. })));
+    }
+}
@@ -0,0 +1,157 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Renders into render textures and samples them in later passes.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill, fullscreen};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::sideways::RenderTexture;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::render_pass::PassError;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// A WGSL module whose `fs_main` copies the texture bound at slot 0.
+const COPY: &str = "@group(0) @binding(0) var source: texture_2d<f32>;
+    @fragment fn fs_main(@builtin(position) p: vec4<f32>) -> @location(0) vec4<f32> {
+        return textureLoad(source, vec2<u32>(p.xy), 0);
+    }";
+
+#[async_test]
+async fn later_pass_samples_earlier_pass() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let scene = RenderTexture::<RGBA8UNorm>::new(engine.bound_device(), 4, 4, false, "scene")
+        .await
+        .expect("Failed to create render texture");
+
+    //added first, but it samples `scene`, so it runs second
+    let mut post_binds = BindStyle::new();
+    post_binds.bind_render_texture(BindSlot::new(0), Stage::Fragment, &scene, None);
+    port.add_fixed_pass(fullscreen(
+        "invert",
+        0.0,
+        false,
+        "@group(0) @binding(0) var source: texture_2d<f32>;
+        @fragment fn fs_main(@builtin(position) p: vec4<f32>) -> @location(0) vec4<f32> {
+            let color = textureLoad(source, vec2<u32>(p.xy), 0);
+            return vec4<f32>(1.0 - color.rgb, 1.0);
+        }",
+        post_binds,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.add_fixed_pass(
+        fullscreen(
+            "scene",
+            0.0,
+            false,
+            &fill([1.0, 0.0, 0.0, 1.0]),
+            BindStyle::new(),
+        )
+        .with_render_target(&scene),
+    )
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 255, 255, 255));
+}
+
+#[async_test]
+async fn passes_into_one_texture_share_depth() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let scene = RenderTexture::<RGBA8UNorm>::new(engine.bound_device(), 4, 4, true, "scene")
+        .await
+        .expect("Failed to create render texture");
+
+    port.add_fixed_pass(
+        fullscreen(
+            "near",
+            0.25,
+            true,
+            &fill([1.0, 0.0, 0.0, 1.0]),
+            BindStyle::new(),
+        )
+        .with_render_target(&scene),
+    )
+    .await
+    .expect("Failed to add pass");
+    let mut copy_binds = BindStyle::new();
+    copy_binds.bind_render_texture(BindSlot::new(0), Stage::Fragment, &scene, None);
+    port.add_fixed_pass(fullscreen("copy", 0.0, false, COPY, copy_binds))
+        .await
+        .expect("Failed to add pass");
+    //behind "near", so it fails the depth test
+    port.add_fixed_pass(
+        fullscreen(
+            "far",
+            0.5,
+            true,
+            &fill([0.0, 1.0, 0.0, 1.0]),
+            BindStyle::new(),
+        )
+        .with_render_target(&scene),
+    )
+    .await
+    .expect("Failed to add pass");
+    //render twice, to check the texture is cleared between frames
+    port.force_render().await;
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 1, y: 3 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+    //the view has no depth, since only the render texture's passes use it
+    assert!(framebuffer.depth.is_none());
+}
+
+#[async_test]
+async fn port_refuses_passes_in_a_cycle() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let a = RenderTexture::<RGBA8UNorm>::new(engine.bound_device(), 4, 4, false, "a")
+        .await
+        .expect("Failed to create render texture");
+    let b = RenderTexture::<RGBA8UNorm>::new(engine.bound_device(), 4, 4, false, "b")
+        .await
+        .expect("Failed to create render texture");
+    let copy = |name, from: &RenderTexture<RGBA8UNorm>, into| {
+        let mut binds = BindStyle::new();
+        binds.bind_render_texture(BindSlot::new(0), Stage::Fragment, from, None);
+        fullscreen(name, 0.0, false, COPY, binds).with_render_target(into)
+    };
+
+    let first = port
+        .add_fixed_pass(copy("first", &b, &a))
+        .await
+        .expect("Failed to add pass");
+    assert_eq!(
+        port.add_fixed_pass(copy("second", &a, &b)).await,
+        Err(PassError::Cycle {
+            passes: vec!["first".to_string(), "second".to_string()]
+        })
+    );
+    //a pass that samples its own target
+    assert_eq!(
+        port.replace_pass(first, copy("first", &a, &a)).await,
+        Err(PassError::Cycle {
+            passes: vec!["first".to_string()]
+        })
+    );
+    //the port still runs the passes it kept
+    port.force_render().await;
+}
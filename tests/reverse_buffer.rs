@@ -0,0 +1,82 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Writes reverse buffers from shaders and reads them back on the CPU.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fullscreen};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::reverse::buffer::Buffer;
+use images_and_words::images::render_pass::PassDescriptor;
+use test_executors::async_test;
+
+/// A full-screen pass that counts its fragments into bin 0 of `counts`.
+fn counting_pass(counts: &Buffer<u32>) -> PassDescriptor {
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Fragment, counts);
+    fullscreen(
+        "count",
+        0.0,
+        false,
+        "@group(0) @binding(0) var<storage, read_write> counts: array<atomic<u32>>;
+        @fragment fn fs_main() -> @location(0) vec4<f32> {
+            atomicAdd(&counts[0], 1u);
+            return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+        }",
+        bind_style,
+    )
+}
+
+#[async_test]
+async fn reads_back_shader_writes() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let counts = Buffer::<u32>::new(engine.bound_device().clone(), 2, "counts", |i| i as u32)
+        .await
+        .expect("Failed to create buffer");
+    port.add_fixed_pass(counting_pass(&counts))
+        .await
+        .expect("Failed to add pass");
+
+    port.force_render().await;
+    {
+        let read_guard = counts.access_read().await;
+        assert_eq!(read_guard.to_vec(), vec![16, 1]);
+    }
+
+    //results persist on the GPU between frames
+    port.force_render().await;
+    let read_guard = counts.access_read().await;
+    assert_eq!(read_guard.len(), 2);
+    assert_eq!(read_guard.get(0), 32);
+}
+
+#[async_test]
+async fn slow_reader_does_not_stall_rendering() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let counts = Buffer::<u32>::new(engine.bound_device().clone(), 1, "counts", |_| 0)
+        .await
+        .expect("Failed to create buffer");
+    port.add_fixed_pass(counting_pass(&counts))
+        .await
+        .expect("Failed to add pass");
+
+    port.force_render().await;
+    let read_guard = counts.access_read().await;
+    assert_eq!(read_guard.get(0), 16);
+
+    //these frames render while the results are being read, so they are not copied back
+    port.force_render().await;
+    port.force_render().await;
+    assert_eq!(read_guard.get(0), 16);
+    drop(read_guard);
+
+    //the next read waits for the next frame, which sees every earlier frame's writes
+    port.force_render().await;
+    let read_guard = counts.access_read().await;
+    assert_eq!(read_guard.get(0), 64);
+}
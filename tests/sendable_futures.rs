@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+#![cfg(feature = "backend_wgpu")]
 //! Tests to ensure that futures returned by buffer mapping operations are Send.
 //!
 //! This is critical for async code that needs to spawn tasks or work with thread pools.
@@ -21,7 +22,7 @@ use images_and_words::images::Engine;
 use images_and_words::images::projection::WorldCoord;
 use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
 use images_and_words::images::shader::{FragmentShader, VertexShader};
-use images_and_words::images::vertex_layout::{VertexFieldType, VertexLayout};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
 use images_and_words::images::view::View;
 use std::sync::Arc;
 
@@ -73,7 +74,12 @@ fn main() {
             let mut layout = VertexLayout::new();
             layout.add_field("x", VertexFieldType::F32);
 
-            bind_style.bind_dynamic_vertex_buffer(BindSlot::new(0), &test_buffer, layout);
+            bind_style.bind_dynamic_vertex_buffer(
+                BindSlot::new(0),
+                &test_buffer,
+                layout,
+                StepMode::Vertex,
+            );
 
             let vertex_shader = VertexShader::new(
                 "texture_alignment_test",
@@ -115,7 +121,11 @@ fn main() {
                 false,
                 false,
             );
-            engine.main_port().add_fixed_pass(descriptor).await;
+            engine
+                .main_port()
+                .add_fixed_pass(descriptor)
+                .await
+                .expect("Failed to add pass");
             engine.main_port().force_render().await;
 
             // Test sending the buffer and its access future across a task boundary
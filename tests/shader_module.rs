@@ -0,0 +1,61 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Draws with named entry points of one WGSL module, specialized with overrides.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::engine;
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+use images_and_words::images::shader::ShaderModule;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+const VARIANTS: &str = "override green: f32;
+@id(3) override blue: bool = false;
+
+@vertex fn vs_hidden(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(2.0, 2.0, 0.5, 1.0);
+}
+@vertex fn vs_fullscreen(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    var xs = array<f32, 3>(-1.0, 3.0, -1.0);
+    var ys = array<f32, 3>(-1.0, -1.0, 3.0);
+    return vec4<f32>(xs[i], ys[i], 0.5, 1.0);
+}
+@fragment fn fs_red() -> @location(0) vec4<f32> { return vec4<f32>(1.0, 0.0, 0.0, 1.0); }
+@fragment fn fs_tint() -> @location(0) vec4<f32> {
+    return vec4<f32>(0.0, green, select(0.0, 1.0, blue), 1.0);
+}";
+
+#[async_test]
+async fn draws_named_entry_points_with_constants() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let module = ShaderModule::new("variants", VARIANTS.to_string());
+    let descriptor = PassDescriptor::new(
+        "tint".to_string(),
+        module.vertex("vs_fullscreen"),
+        module.fragment("fs_tint"),
+        BindStyle::new(),
+        DrawCommand::TriangleList(1),
+        false,
+        false,
+    )
+    .with_constant("green", 1.0)
+    .with_constant("3", 1.0);
+    assert_eq!(descriptor.validate(), Ok(()));
+    port.add_fixed_pass(descriptor)
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 255, 255, 255));
+}
@@ -0,0 +1,189 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Reloads shaders read from files as the files change.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::engine;
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor};
+use images_and_words::images::shader::validation::ShaderError;
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use test_executors::async_test;
+
+const FULLSCREEN: &str =
+    "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    var xs = array<f32, 3>(-1.0, 3.0, -1.0);
+    var ys = array<f32, 3>(-1.0, -1.0, 3.0);
+    return vec4<f32>(xs[i], ys[i], 0.5, 1.0);
+}";
+
+fn fragment(rgba: &str) -> String {
+    format!("@fragment fn fs_main() -> @location(0) vec4<f32> {{ return vec4<f32>({rgba}); }}")
+}
+
+/// Writes `wgsl` to a fresh `tint.wgsl` in `directory`, and returns its path.
+fn shader_file(directory: &str, wgsl: &str) -> PathBuf {
+    let directory = Path::new(env!("CARGO_TARGET_TMPDIR")).join(directory);
+    std::fs::create_dir_all(&directory).expect("Failed to create directory");
+    let path = directory.join("tint.wgsl");
+    std::fs::write(&path, wgsl).expect("Failed to write shader");
+    path
+}
+
+/// A pass covering the view with `fragment_shader`.
+fn tint(fragment_shader: FragmentShader) -> PassDescriptor {
+    PassDescriptor::new(
+        "tint".to_string(),
+        VertexShader::new("fullscreen", FULLSCREEN.to_string()),
+        fragment_shader,
+        BindStyle::new(),
+        DrawCommand::TriangleList(1),
+        false,
+        false,
+    )
+}
+
+async fn center(port: &Port) -> (u8, u8, u8) {
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    (pixel.r, pixel.g, pixel.b)
+}
+
+#[async_test]
+async fn edits_apply_and_errors_keep_the_last_good_shader() {
+    let path = shader_file("shader_reload", &fragment("1.0, 0.0, 0.0, 1.0"));
+    let engine = engine().await;
+    let port = engine.main_port();
+    let fragment_shader = FragmentShader::from_path("tint", &path)
+        .await
+        .expect("Failed to read shader");
+    port.add_fixed_pass(tint(fragment_shader))
+        .await
+        .expect("Failed to add pass");
+    assert_eq!(center(port).await, (255, 0, 0));
+    //nothing changed
+    assert_eq!(port.reload_shaders().await, 0);
+
+    std::fs::write(&path, fragment("0.0, 1.0, 0.0, 1.0")).expect("Failed to write shader");
+    assert_eq!(port.reload_shaders().await, 1);
+    assert_eq!(center(port).await, (0, 255, 0));
+
+    std::fs::write(&path, fragment("0.0, 0.0, 1.0")).expect("Failed to write shader");
+    assert_eq!(port.reload_shaders().await, 0);
+    let mut shader_error = port.port_reporter().shader_error().clone();
+    assert!(matches!(
+        shader_error.current_value(),
+        Some(Some(ShaderError::Invalid { shader: "tint", .. }))
+    ));
+    assert_eq!(center(port).await, (0, 255, 0));
+
+    std::fs::write(&path, fragment("0.0, 0.0, 1.0, 1.0")).expect("Failed to write shader");
+    assert_eq!(port.reload_shaders().await, 1);
+    assert_eq!(shader_error.current_value(), Some(None));
+    assert_eq!(center(port).await, (0, 0, 255));
+}
+
+#[async_test]
+async fn renamed_entry_points_keep_the_last_good_shader() {
+    let path = shader_file("shader_reload_entry_point", &fragment("1.0, 0.0, 0.0, 1.0"));
+    let engine = engine().await;
+    let port = engine.main_port();
+    let fragment_shader = FragmentShader::from_path("tint", &path)
+        .await
+        .expect("Failed to read shader")
+        .with_entry_point("fs_main");
+    port.add_fixed_pass(tint(fragment_shader))
+        .await
+        .expect("Failed to add pass");
+    assert_eq!(center(port).await, (255, 0, 0));
+
+    //still valid WGSL, but the pass draws with fs_main
+    let renamed = fragment("0.0, 1.0, 0.0, 1.0").replace("fs_main", "fs_tint");
+    std::fs::write(&path, renamed).expect("Failed to write shader");
+    assert_eq!(port.reload_shaders().await, 0);
+    let mut shader_error = port.port_reporter().shader_error().clone();
+    assert!(matches!(
+        shader_error.current_value(),
+        Some(Some(ShaderError::MissingEntryPoint { shader: "tint", .. }))
+    ));
+    assert_eq!(center(port).await, (255, 0, 0));
+
+    std::fs::write(&path, fragment("0.0, 1.0, 0.0, 1.0")).expect("Failed to write shader");
+    assert_eq!(port.reload_shaders().await, 1);
+    assert_eq!(shader_error.current_value(), Some(None));
+    assert_eq!(center(port).await, (0, 255, 0));
+}
+
+#[async_test]
+async fn pipelines_the_device_refuses_keep_the_last_good_pipeline() {
+    let path = shader_file("shader_reload_pipeline", &fragment("1.0, 0.0, 0.0, 1.0"));
+    let engine = engine().await;
+    let port = engine.main_port();
+    let fragment_shader = FragmentShader::from_path("tint", &path)
+        .await
+        .expect("Failed to read shader");
+    port.add_fixed_pass(tint(fragment_shader))
+        .await
+        .expect("Failed to add pass");
+    assert_eq!(center(port).await, (255, 0, 0));
+
+    //valid WGSL, but integers can't be written to the view's normalized format
+    let integers =
+        "@fragment fn fs_main() -> @location(0) vec4<i32> { return vec4<i32>(0, 1, 0, 1); }";
+    std::fs::write(&path, integers).expect("Failed to write shader");
+    assert_eq!(port.reload_shaders().await, 1);
+    assert_eq!(center(port).await, (255, 0, 0));
+    let mut shader_error = port.port_reporter().shader_error().clone();
+    assert!(matches!(
+        shader_error.current_value(),
+        Some(Some(ShaderError::Pipeline { .. }))
+    ));
+    //polling again changes nothing
+    assert_eq!(port.reload_shaders().await, 0);
+    assert_eq!(center(port).await, (255, 0, 0));
+
+    std::fs::write(&path, fragment("0.0, 1.0, 0.0, 1.0")).expect("Failed to write shader");
+    assert_eq!(port.reload_shaders().await, 1);
+    assert_eq!(center(port).await, (0, 255, 0));
+    assert_eq!(shader_error.current_value(), Some(None));
+}
+
+#[async_test]
+async fn watching_stops_when_asked() {
+    let path = shader_file("shader_reload_watch", &fragment("1.0, 0.0, 0.0, 1.0"));
+    let engine = engine().await;
+    let port = engine.main_port();
+    let fragment_shader = FragmentShader::from_path("tint", &path)
+        .await
+        .expect("Failed to read shader");
+    port.add_fixed_pass(tint(fragment_shader))
+        .await
+        .expect("Failed to add pass");
+
+    let ((), watched) = futures::join!(port.watch_shaders(Duration::from_millis(10)), async {
+        std::fs::write(&path, fragment("0.0, 1.0, 0.0, 1.0")).expect("Failed to write shader");
+        let mut watched = false;
+        for _ in 0..500 {
+            if center(port).await == (0, 255, 0) {
+                watched = true;
+                break;
+            }
+            portable_async_sleep::async_sleep(Duration::from_millis(10)).await;
+        }
+        port.stop_watching_shaders();
+        watched
+    });
+    assert!(watched);
+}
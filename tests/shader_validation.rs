@@ -0,0 +1,221 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Checks passes' shaders against the buffers and vertex layouts they bind.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::engine;
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::forward::dynamic::buffer::CRepr;
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::Engine;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor, PassError};
+use images_and_words::images::shader::validation::ShaderError;
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+struct Tint {
+    rgba: [f32; 4],
+}
+// Safety: Tint is repr(C) and contains only f32s
+unsafe impl CRepr for Tint {}
+
+const FULLSCREEN: &str =
+    "@vertex fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4<f32> {
+    var xs = array<f32, 3>(-1.0, 3.0, -1.0);
+    var ys = array<f32, 3>(-1.0, -1.0, 3.0);
+    return vec4<f32>(xs[i], ys[i], 0.5, 1.0);
+}";
+
+fn pass(vertex: &str, fragment: &str, bind_style: BindStyle) -> PassDescriptor {
+    PassDescriptor::new(
+        "validated".to_string(),
+        VertexShader::new("vertex", vertex.to_string()),
+        FragmentShader::new("fragment", fragment.to_string()),
+        bind_style,
+        DrawCommand::TriangleList(1),
+        false,
+        false,
+    )
+}
+
+async fn tint_buffer(engine: &Engine, usage: GPUBufferUsage) -> Buffer<Tint> {
+    Buffer::new(engine.bound_device().clone(), 1, usage, "tint", |_| Tint {
+        rgba: [0.0, 1.0, 0.0, 1.0],
+    })
+    .await
+    .expect("Failed to create buffer")
+}
+
+#[async_test]
+async fn valid_pass_renders() {
+    let engine = engine().await;
+    let tint = tint_buffer(&engine, GPUBufferUsage::FragmentShaderRead).await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_buffer(BindSlot::new(0), Stage::Fragment, &tint);
+    let descriptor = pass(
+        FULLSCREEN,
+        "@group(0) @binding(0) var<uniform> tint: vec4<f32>;
+        @fragment fn fs_main() -> @location(0) vec4<f32> { return tint; }",
+        bind_style,
+    );
+    assert_eq!(descriptor.validate(), Ok(()));
+
+    let port = engine.main_port();
+    port.add_fixed_pass(descriptor)
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    assert_eq!(framebuffer.color[Texel { x: 2, y: 2 }].g, 255);
+}
+
+#[async_test]
+async fn port_refuses_mismatched_pass() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let green =
+        "@fragment fn fs_main() -> @location(0) vec4<f32> { return vec4(0.0, 1.0, 0.0, 1.0); }";
+    let handle = port
+        .add_fixed_pass(pass(FULLSCREEN, green, BindStyle::new()))
+        .await
+        .expect("Failed to add pass");
+    //nothing is bound at the tint's slot
+    let unbound = pass(
+        FULLSCREEN,
+        "@group(0) @binding(0) var<uniform> tint: vec4<f32>;
+        @fragment fn fs_main() -> @location(0) vec4<f32> { return tint; }",
+        BindStyle::new(),
+    );
+    let error = ShaderError::Unbound {
+        shader: "fragment",
+        variable: "tint".to_string(),
+        slot: BindSlot::new(0),
+    };
+    assert_eq!(
+        port.add_fixed_pass(unbound.clone()).await,
+        Err(PassError::Shader {
+            pass: "validated".to_string(),
+            error: error.clone(),
+        })
+    );
+    assert_eq!(
+        port.replace_pass(handle, unbound).await,
+        Err(PassError::Shader {
+            pass: "validated".to_string(),
+            error,
+        })
+    );
+
+    //the port keeps the pass it had
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    assert_eq!(framebuffer.color[Texel { x: 2, y: 2 }].g, 255);
+}
+
+#[async_test]
+async fn reports_struct_of_another_size() {
+    let engine = engine().await;
+    let tint = tint_buffer(&engine, GPUBufferUsage::ShaderReadWrite).await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_buffer(BindSlot::new(0), Stage::Fragment, &tint);
+    let descriptor = pass(
+        FULLSCREEN,
+        "struct Tint { rgb: vec3<f32>, alpha: f32, scale: f32 }
+        @group(0) @binding(0) var<storage, read_write> tints: array<Tint>;
+        @fragment fn fs_main() -> @location(0) vec4<f32> {
+            return vec4(tints[0].rgb, tints[0].alpha);
+        }",
+        bind_style,
+    );
+    assert_eq!(
+        descriptor.validate(),
+        Err(ShaderError::Size {
+            shader: "fragment",
+            variable: "tints".to_string(),
+            slot: BindSlot::new(0),
+            declared_size: 32,
+            element_size: 16,
+        })
+    );
+}
+
+#[async_test]
+async fn reports_read_write_buffer_that_is_read_only() {
+    let engine = engine().await;
+    let tint = tint_buffer(&engine, GPUBufferUsage::FragmentShaderRead).await;
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_buffer(BindSlot::new(0), Stage::Fragment, &tint);
+    let descriptor = pass(
+        FULLSCREEN,
+        "@group(0) @binding(0) var<storage, read_write> tint: vec4<f32>;
+        @fragment fn fs_main() -> @location(0) vec4<f32> { return tint; }",
+        bind_style,
+    );
+    assert_eq!(
+        descriptor.validate().unwrap_err().to_string(),
+        "fragment declares `tint` at slot 0 as var<storage, read_write> vec4<f32>, but a uniform or read-only storage buffer is bound there"
+    );
+}
+
+#[async_test]
+async fn checks_vertex_attributes() {
+    let engine = engine().await;
+    //vertices are flat arrays of floats, two to a vertex
+    let positions = Buffer::new(
+        engine.bound_device().clone(),
+        6,
+        GPUBufferUsage::VertexBuffer,
+        "positions",
+        |i| [-1.0f32, -1.0, 3.0, -1.0, -1.0, 3.0][i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(BindSlot::new(0), &positions, layout, StepMode::Vertex);
+    let fragment = "@fragment fn fs_main() -> @location(0) vec4<f32> { return vec4(1.0); }";
+
+    let floats = pass(
+        "@vertex fn vs_main(@location(0) x: f32, @location(1) y: f32) -> @builtin(position) vec4<f32> {
+            return vec4(x, y, 0.5, 1.0);
+        }",
+        fragment,
+        bind_style.clone(),
+    );
+    assert_eq!(floats.validate(), Ok(()));
+
+    let integers = pass(
+        "@vertex fn vs_main(@location(0) x: f32, @location(1) y: i32) -> @builtin(position) vec4<f32> {
+            return vec4(x, f32(y), 0.5, 1.0);
+        }",
+        fragment,
+        bind_style,
+    );
+    assert_eq!(
+        integers.validate(),
+        Err(ShaderError::AttributeType {
+            shader: "vertex",
+            location: 1,
+            declared: "i32".to_string(),
+            provided: VertexFieldType::F32,
+        })
+    );
+}
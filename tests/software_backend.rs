@@ -0,0 +1,1101 @@
+#![cfg(all(feature = "backend_software", not(feature = "backend_wgpu")))]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Tests for the CPU rasterizer backend.
+//!
+//! Run with `cargo test --no-default-features --features backend_software`.
+
+mod common;
+
+use common::{engine, engine_with_view};
+use images_and_words::Priority;
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::{BindSlot, Stage};
+use images_and_words::bindings::forward::dynamic;
+use images_and_words::bindings::forward::dynamic::frame_texture::FrameTexture;
+use images_and_words::bindings::forward::dynamic::immediates::Immediates;
+use images_and_words::bindings::forward::r#static;
+use images_and_words::bindings::reverse::buffer::Buffer;
+use images_and_words::bindings::sideways::RenderTexture;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::{
+    CPUStrategy, GPUBufferUsage, TextureConfig, TextureUsage,
+};
+use images_and_words::images::compute_pass::{ComputePassDescriptor, Dispatch};
+use images_and_words::images::render_pass::{
+    BlendMode, ColorWriteMask, CompareFunction, CullMode, DepthFormat, DepthStencilState,
+    DrawCommand, DrawIndirectArgs, LoadOp, PassDescriptor, PassError, RasterizerState,
+    StencilFaceState, StencilOperation, StencilState, TargetRect, Topology,
+};
+use images_and_words::images::shader::software::VertexOutput;
+use images_and_words::images::shader::{ComputeShader, FragmentShader, VertexShader};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
+use images_and_words::images::view::View;
+use images_and_words::pixel_formats::{Float4, RGBA8UNorm, Unorm4};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use test_executors::async_test;
+
+/// A full-screen pass named `name`, shading with `fragment_shader`.
+fn fullscreen(
+    name: &str,
+    fragment_shader: FragmentShader,
+    bind_style: BindStyle,
+) -> PassDescriptor {
+    PassDescriptor::new(
+        name.to_string(),
+        fullscreen_vertex_shader(),
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleList(1),
+        false,
+        false,
+    )
+}
+
+fn fullscreen_vertex_shader() -> VertexShader {
+    VertexShader::new("fullscreen", String::new()).with_software(|input| {
+        let positions = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+        let [x, y] = positions[input.vertex_index as usize];
+        VertexOutput::new([x, y, 0.0, 1.0])
+    })
+}
+
+#[async_test]
+async fn fullscreen_triangle_shades_every_pixel() {
+    let engine = engine_with_view(View::for_testing()).await;
+    let port = engine.main_port();
+
+    let fragments = Arc::new(AtomicUsize::new(0));
+    let move_fragments = fragments.clone();
+    let fragment_shader = FragmentShader::new("count", String::new()).with_software(move |_| {
+        move_fragments.fetch_add(1, Ordering::Relaxed);
+        Some([1.0, 0.0, 0.0, 1.0])
+    });
+
+    port.add_fixed_pass(fullscreen("fullscreen", fragment_shader, BindStyle::new()))
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+
+    assert_eq!(fragments.load(Ordering::Relaxed), 800 * 600);
+    assert_eq!(port.port_reporter().drawable_size(), (800, 600));
+}
+
+#[async_test]
+async fn passes_without_software_programs_are_refused() {
+    let engine = engine_with_view(View::for_testing()).await;
+    let port = engine.main_port();
+
+    let added = port
+        .add_fixed_pass(fullscreen(
+            "wgsl_only",
+            FragmentShader::new("wgsl_only", String::new()),
+            BindStyle::new(),
+        ))
+        .await;
+    assert!(matches!(added, Err(PassError::NoSoftwareProgram { .. })));
+    let added = port
+        .add_compute_pass(ComputePassDescriptor::new(
+            "wgsl_only".to_string(),
+            ComputeShader::new("wgsl_only", String::new()),
+            BindStyle::new(),
+            Dispatch::Workgroups(1, 1, 1),
+        ))
+        .await;
+    assert!(matches!(added, Err(PassError::NoSoftwareProgram { .. })));
+    //the port keeps rendering its other passes
+    port.force_render().await;
+}
+
+#[async_test]
+async fn dynamic_texture_writes_reach_fragment_program() {
+    let engine = engine_with_view(View::for_testing()).await;
+    let device = engine.bound_device();
+    let port = engine.main_port();
+
+    let mut frame_texture = FrameTexture::<RGBA8UNorm>::new(
+        device,
+        TextureConfig {
+            width: 2,
+            height: 2,
+            visible_to: TextureUsage::FragmentShaderRead,
+            debug_name: "software_texture",
+            priority: Priority::UserInitiated,
+            cpu_strategy: CPUStrategy::WontRead,
+            mipmaps: false,
+        },
+        |_| Unorm4 {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        },
+    )
+    .await;
+
+    let observed = Arc::new(Mutex::new(None));
+    let move_observed = observed.clone();
+    let fragment_shader = FragmentShader::new("load", String::new()).with_software(move |input| {
+        let texel = input.resources.texture_load(0, 1, 1);
+        *move_observed.lock().unwrap() = Some(texel);
+        Some(texel)
+    });
+
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_dynamic_texture(BindSlot::new(0), Stage::Fragment, &frame_texture);
+    port.add_fixed_pass(fullscreen("load", fragment_shader, bind_style))
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+    assert_eq!(*observed.lock().unwrap(), Some([0.0, 0.0, 0.0, 1.0]));
+
+    {
+        let mut write_guard = frame_texture.dequeue().await;
+        write_guard.replace(
+            1,
+            Texel { x: 1, y: 1 },
+            &[Unorm4 {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 255,
+            }],
+        );
+    }
+    port.force_render().await;
+    assert_eq!(*observed.lock().unwrap(), Some([1.0, 0.0, 0.0, 1.0]));
+}
+
+#[async_test]
+async fn offscreen_framebuffer_reads_back_color_and_depth() {
+    let engine = engine_with_view(View::offscreen(10, 10, RGBA8UNorm)).await;
+    let port = engine.main_port();
+
+    let vertex_shader = VertexShader::new("fullscreen", String::new()).with_software(|input| {
+        let positions = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+        let [x, y] = positions[input.vertex_index as usize];
+        VertexOutput::new([x, y, 0.25, 1.0])
+    });
+    let fragment_shader =
+        FragmentShader::new("red", String::new()).with_software(|_| Some([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(PassDescriptor::new(
+        "fullscreen".to_string(),
+        vertex_shader,
+        fragment_shader,
+        BindStyle::new(),
+        DrawCommand::TriangleList(1),
+        true,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 9, y: 0 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (255, 0, 0, 255));
+    let depth = framebuffer.depth.expect("Depth is enabled");
+    assert_eq!(depth[Texel { x: 5, y: 5 }], 0.25);
+}
+
+#[async_test]
+async fn captured_frames_are_converted_to_srgb() {
+    let engine = engine_with_view(View::for_testing()).await;
+    let port = engine.main_port();
+    let fragment_shader =
+        FragmentShader::new("half", String::new()).with_software(|_| Some([0.5, 0.0, 1.0, 1.0]));
+    port.add_fixed_pass(fullscreen("fullscreen", fragment_shader, BindStyle::new()))
+        .await
+        .expect("Failed to add pass");
+
+    let first = port.capture_next_frame();
+    let second = port.capture_next_frame();
+    port.force_render().await;
+    for capture in [first.await, second.await] {
+        let frame = capture.expect("Capture frame");
+        assert_eq!((frame.color.width(), frame.color.height()), (800, 600));
+        //the testing view renders BGRA sRGB, where linear 0.5 encodes to 188
+        let pixel = frame.color[Texel { x: 400, y: 300 }];
+        assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (188, 0, 255, 255));
+        assert!(frame.depth.is_none());
+    }
+}
+
+#[async_test]
+async fn fragment_program_writes_reverse_buffer() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let counts = Buffer::<u32>::new(engine.bound_device().clone(), 1, "counts", |_| 0)
+        .await
+        .expect("Failed to create buffer");
+
+    let fragment_shader = FragmentShader::new("count", String::new()).with_software(|input| {
+        let count: u32 = input.resources.element(0, 0);
+        input.resources.store(0, 0, count + 1);
+        Some([1.0, 0.0, 0.0, 1.0])
+    });
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_reverse_buffer(BindSlot::new(0), Stage::Fragment, &counts);
+    port.add_fixed_pass(fullscreen("count", fragment_shader, bind_style))
+        .await
+        .expect("Failed to add pass");
+
+    port.force_render().await;
+    assert_eq!(counts.access_read().await.get(0), 16);
+    port.force_render().await;
+    assert_eq!(counts.access_read().await.get(0), 32);
+}
+
+#[async_test]
+async fn render_texture_feeds_later_pass() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let scene = RenderTexture::<RGBA8UNorm>::new(engine.bound_device(), 4, 4, true, "scene")
+        .await
+        .expect("Failed to create render texture");
+
+    //added first, but it samples `scene`, so it runs second
+    let fragment_shader = FragmentShader::new("combine", String::new()).with_software(|input| {
+        let [r, g, b, _] = input.resources.texture_load(0, 2, 2);
+        let [depth, ..] = input.resources.texture_load(1, 2, 2);
+        Some([1.0 - r, g + depth, b, 1.0])
+    });
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_render_texture(BindSlot::new(0), Stage::Fragment, &scene, None);
+    bind_style.bind_render_texture_depth(BindSlot::new(1), Stage::Fragment, &scene);
+    port.add_fixed_pass(fullscreen("combine", fragment_shader, bind_style))
+        .await
+        .expect("Failed to add pass");
+
+    let vertex_shader = VertexShader::new("fullscreen", String::new()).with_software(|input| {
+        let positions = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+        let [x, y] = positions[input.vertex_index as usize];
+        VertexOutput::new([x, y, 0.5, 1.0])
+    });
+    let fragment_shader =
+        FragmentShader::new("red", String::new()).with_software(|_| Some([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(
+        PassDescriptor::new(
+            "scene".to_string(),
+            vertex_shader,
+            fragment_shader,
+            BindStyle::new(),
+            DrawCommand::TriangleList(1),
+            true,
+            false,
+        )
+        .with_render_target(&scene),
+    )
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 1, y: 1 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 128, 0, 255));
+    //only the render texture's pass uses depth
+    assert!(framebuffer.depth.is_none());
+}
+
+#[async_test]
+async fn compute_pass_runs_before_render_passes() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let color = r#static::buffer::Buffer::<f32>::new(
+        engine.bound_device().clone(),
+        4,
+        GPUBufferUsage::ShaderReadWrite,
+        "color",
+        |_| 0.0,
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    let fragment_shader = FragmentShader::new("show", String::new()).with_software(|input| {
+        Some(std::array::from_fn(|c| {
+            input.resources.element::<f32>(0, c)
+        }))
+    });
+    let mut render_binds = BindStyle::new();
+    render_binds.bind_static_buffer(BindSlot::new(0), Stage::Fragment, &color);
+    port.add_fixed_pass(fullscreen("show", fragment_shader, render_binds))
+        .await
+        .expect("Failed to add pass");
+
+    //two workgroups of two invocations each
+    let workgroups = Arc::new(AtomicUsize::new(0));
+    let move_workgroups = workgroups.clone();
+    let shader = ComputeShader::new("fill", String::new()).with_software(move |input| {
+        move_workgroups.fetch_add(1, Ordering::Relaxed);
+        let [workgroup, ..] = input.workgroup_id;
+        for invocation in 0..2 {
+            let index = workgroup as usize * 2 + invocation;
+            let value: f32 = [0.0, 1.0, 0.0, 1.0][index];
+            input.resources.store(0, index, value);
+        }
+    });
+    let mut compute_binds = BindStyle::new();
+    compute_binds.bind_static_buffer(BindSlot::new(0), Stage::Compute, &color);
+    port.add_compute_pass(ComputePassDescriptor::new(
+        "fill".to_string(),
+        shader,
+        compute_binds,
+        Dispatch::for_elements(color.len(), 2),
+    ))
+    .await
+    .expect("Failed to add compute pass");
+    port.force_render().await;
+
+    assert_eq!(workgroups.load(Ordering::Relaxed), 2);
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 255, 0, 255));
+}
+
+#[async_test]
+async fn instance_buffer_offsets_each_copy() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device().clone();
+    //a quad covering the bottom-left quarter of clip space
+    let quad = [
+        -1.0, -1.0, 0.0, -1.0, 0.0, 0.0, //
+        -1.0, -1.0, 0.0, 0.0, -1.0, 0.0,
+    ];
+    let quad = r#static::buffer::Buffer::<f32>::new(
+        device.clone(),
+        quad.len(),
+        GPUBufferUsage::VertexBuffer,
+        "quad",
+        |i| quad[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    //copies at the bottom left and top right
+    let offsets = [0.0, 0.0, 1.0, 1.0];
+    let offsets = r#static::buffer::Buffer::<f32>::new(
+        device,
+        offsets.len(),
+        GPUBufferUsage::VertexBuffer,
+        "offsets",
+        |i| offsets[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(BindSlot::new(0), &quad, layout.clone(), StepMode::Vertex);
+    bind_style.bind_static_vertex_buffer(BindSlot::new(1), &offsets, layout, StepMode::Instance);
+    let vertex_shader = VertexShader::new("offset_quad", String::new()).with_software(|input| {
+        let [x, y, dx, dy] = input.attributes.try_into().unwrap();
+        VertexOutput::new([x + dx, y + dy, 0.0, 1.0])
+    });
+    let fragment_shader =
+        FragmentShader::new("red", String::new()).with_software(|_| Some([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(PassDescriptor::new(
+        "quads".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleListInstanced {
+            triangles: 2,
+            instances: 2,
+        },
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let red = |x, y| framebuffer.color[Texel { x, y }].r == 255;
+    assert_eq!(
+        (red(1, 1), red(2, 1), red(1, 2), red(2, 2)),
+        (false, true, true, false)
+    );
+}
+
+#[async_test]
+async fn range_draws_part_of_an_index_buffer() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device().clone();
+    //the corners of the bottom-left quad, then the top-right quad
+    let corners = [
+        -1.0, -1.0, 0.0, -1.0, 0.0, 0.0, -1.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0,
+    ];
+    let corners = r#static::buffer::Buffer::<f32>::new(
+        device.clone(),
+        corners.len(),
+        GPUBufferUsage::VertexBuffer,
+        "corners",
+        |i| corners[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let indices = [0, 1, 2, 0, 2, 3, 0, 1, 2, 0, 2, 3];
+    let indices = r#static::buffer::Buffer::<u16>::new(
+        device,
+        indices.len(),
+        GPUBufferUsage::Index,
+        "indices",
+        |i| indices[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(BindSlot::new(0), &corners, layout, StepMode::Vertex);
+    bind_style.bind_static_index_buffer(&indices);
+    let vertex_shader = VertexShader::new("xy", String::new()).with_software(|input| {
+        let [x, y] = input.attributes.try_into().unwrap();
+        VertexOutput::new([x, y, 0.0, 1.0])
+    });
+    let fragment_shader =
+        FragmentShader::new("red", String::new()).with_software(|_| Some([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(PassDescriptor::new(
+        "second_quad".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::Range {
+            topology: Topology::TriangleList,
+            first_vertex: 0,
+            first_index: 6,
+            base_vertex: 4,
+            count: 6,
+            instances: 1,
+        },
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let red = |x, y| framebuffer.color[Texel { x, y }].r == 255;
+    assert_eq!(
+        (red(1, 1), red(2, 1), red(1, 2), red(2, 2)),
+        (false, true, false, false)
+    );
+}
+
+#[async_test]
+async fn dynamic_u32_index_buffer_is_reread_each_frame() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let device = engine.bound_device().clone();
+    //the corners of the bottom-left quad, then the top-right quad
+    let corners = [
+        -1.0, -1.0, 0.0, -1.0, 0.0, 0.0, -1.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0,
+    ];
+    let corners = r#static::buffer::Buffer::<f32>::new(
+        device.clone(),
+        corners.len(),
+        GPUBufferUsage::VertexBuffer,
+        "corners",
+        |i| corners[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let bottom_left: [u32; 6] = [0, 1, 2, 0, 2, 3];
+    let indices = dynamic::buffer::Buffer::<u32>::new(
+        device,
+        bottom_left.len(),
+        GPUBufferUsage::Index,
+        "indices",
+        |i| bottom_left[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(BindSlot::new(0), &corners, layout, StepMode::Vertex);
+    bind_style.bind_dynamic_index_buffer(&indices);
+    let vertex_shader = VertexShader::new("xy", String::new()).with_software(|input| {
+        let [x, y] = input.attributes.try_into().unwrap();
+        VertexOutput::new([x, y, 0.0, 1.0])
+    });
+    let fragment_shader =
+        FragmentShader::new("red", String::new()).with_software(|_| Some([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(PassDescriptor::new(
+        "quad".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::TriangleList(2),
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+
+    let mut red_quarters = Vec::new();
+    for next in [[4, 5, 6, 4, 6, 7], [0; 6]] {
+        port.force_render().await;
+        let framebuffer = port
+            .read_framebuffer::<RGBA8UNorm>()
+            .await
+            .expect("Read framebuffer");
+        let red = |x, y| framebuffer.color[Texel { x, y }].r == 255;
+        red_quarters.push((red(1, 2), red(2, 1)));
+        indices.access_write().await.write(&next, 0).await;
+    }
+    assert_eq!(red_quarters, vec![(true, false), (false, true)]);
+}
+
+#[async_test]
+async fn unculled_clockwise_triangle_is_back_facing() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    //the fullscreen triangle, wound the other way
+    let vertex_shader = VertexShader::new("clockwise", String::new()).with_software(|input| {
+        let positions = [[-1.0, -1.0], [-1.0, 3.0], [3.0, -1.0]];
+        let [x, y] = positions[input.vertex_index as usize];
+        VertexOutput::new([x, y, 0.0, 1.0])
+    });
+    let back_faces = Arc::new(AtomicUsize::new(0));
+    let move_back_faces = back_faces.clone();
+    let fragment_shader = FragmentShader::new("count", String::new()).with_software(move |input| {
+        if !input.front_facing {
+            move_back_faces.fetch_add(1, Ordering::Relaxed);
+        }
+        Some([1.0, 0.0, 0.0, 1.0])
+    });
+    port.add_fixed_pass(
+        PassDescriptor::new(
+            "unculled".to_string(),
+            vertex_shader,
+            fragment_shader,
+            BindStyle::new(),
+            DrawCommand::TriangleList(1),
+            false,
+            false,
+        )
+        .with_rasterizer_state(RasterizerState::new().with_cull_mode(CullMode::None)),
+    )
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    assert_eq!(back_faces.load(Ordering::Relaxed), 16);
+}
+
+#[async_test]
+async fn blend_mode_and_write_mask_combine_with_the_target() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let fill = |name: &'static str, color: [f32; 4]| {
+        fullscreen(
+            name,
+            FragmentShader::new(name, String::new()).with_software(move |_| Some(color)),
+            BindStyle::new(),
+        )
+    };
+    port.add_fixed_pass(fill("dst", [0.5, 0.25, 0.0, 1.0]))
+        .await
+        .expect("Failed to add pass");
+    port.add_fixed_pass(
+        fill("src", [0.25, 0.25, 0.5, 0.5])
+            .with_blend_mode(BlendMode::Additive)
+            .with_write_mask(ColorWriteMask::COLOR),
+    )
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    //red is 128/255 + 0.25, since the first pass's 0.5 was stored as 128
+    assert_eq!(
+        framebuffer.color[Texel { x: 1, y: 2 }],
+        Unorm4 {
+            r: 192,
+            g: 128,
+            b: 128,
+            a: 255
+        }
+    );
+}
+
+#[async_test]
+async fn stencil_and_depth_state_mask_later_passes() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    //a rectangle from `left` to 1.0 across the view, at depth 0.5
+    let rectangle = |name: &'static str, left: f32, color: [f32; 4], state: DepthStencilState| {
+        let vertex_shader = VertexShader::new(name, String::new()).with_software(move |input| {
+            let corners = [
+                [left, -1.0],
+                [1.0, -1.0],
+                [left, 1.0],
+                [left, 1.0],
+                [1.0, -1.0],
+                [1.0, 1.0],
+            ];
+            let [x, y] = corners[input.vertex_index as usize];
+            VertexOutput::new([x, y, 0.5, 1.0])
+        });
+        PassDescriptor::new(
+            name.to_string(),
+            vertex_shader,
+            FragmentShader::new(name, String::new()).with_software(move |_| Some(color)),
+            BindStyle::new(),
+            DrawCommand::TriangleList(2),
+            true,
+            false,
+        )
+        .with_depth_stencil_state(state)
+    };
+    let stencil = |face, reference| {
+        DepthStencilState::new()
+            .with_format(DepthFormat::Depth24PlusStencil8)
+            .with_stencil(StencilState::new(face, reference))
+    };
+    //marks the right half, without writing depth
+    let mark = StencilFaceState {
+        compare: CompareFunction::Always,
+        pass_op: StencilOperation::Replace,
+        ..StencilFaceState::IGNORE
+    };
+    port.add_fixed_pass(rectangle(
+        "mark",
+        0.0,
+        [1.0, 0.0, 0.0, 1.0],
+        stencil(mark, 7).with_depth_write(false),
+    ))
+    .await
+    .expect("Failed to add pass");
+    //draws only where the stencil is unmarked, and depth passes since "mark" wrote none
+    let outside = StencilFaceState {
+        compare: CompareFunction::NotEqual,
+        ..StencilFaceState::IGNORE
+    };
+    port.add_fixed_pass(rectangle(
+        "outside",
+        -1.0,
+        [0.0, 1.0, 0.0, 1.0],
+        stencil(outside, 7).with_compare(CompareFunction::Less),
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let pixel = |x| framebuffer.color[Texel { x, y: 1 }];
+    assert_eq!((pixel(1).g, pixel(3).r), (255, 255));
+    let depth = framebuffer.depth.expect("Depth is enabled");
+    assert_eq!(
+        (depth[Texel { x: 1, y: 1 }], depth[Texel { x: 3, y: 1 }]),
+        (0.5, 1.0)
+    );
+}
+
+#[async_test]
+async fn multisampling_falls_back_to_one_sample() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    assert_eq!(port.set_sample_count(4).await, 1);
+
+    let fragment_shader =
+        FragmentShader::new("red", String::new()).with_software(|_| Some([1.0, 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(PassDescriptor::new(
+        "fullscreen".to_string(),
+        fullscreen_vertex_shader(),
+        fragment_shader,
+        BindStyle::new(),
+        DrawCommand::TriangleList(1),
+        true,
+        true,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    //with one sample, depth is still read back
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    assert_eq!(framebuffer.color[Texel { x: 2, y: 2 }].r, 255);
+    assert!(framebuffer.depth.is_some());
+}
+
+#[async_test]
+async fn clear_color_and_pass_loads_apply() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let blue = Float4 {
+        r: 0.0,
+        g: 0.0,
+        b: 1.0,
+        a: 1.0,
+    };
+    port.set_color_load(LoadOp::Clear(blue)).await;
+    let read = || async {
+        port.force_render().await;
+        let framebuffer = port
+            .read_framebuffer::<RGBA8UNorm>()
+            .await
+            .expect("Read framebuffer");
+        framebuffer.color[Texel { x: 1, y: 1 }]
+    };
+    assert_eq!(
+        read().await,
+        Unorm4 {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 255
+        }
+    );
+
+    //red, added to the view each frame
+    port.set_color_load(LoadOp::Load).await;
+    port.add_fixed_pass(
+        PassDescriptor::new(
+            "accumulate".to_string(),
+            fullscreen_vertex_shader(),
+            FragmentShader::new("accumulate", String::new())
+                .with_software(|_| Some([0.25, 0.0, 0.0, 0.0])),
+            BindStyle::new(),
+            DrawCommand::TriangleList(1),
+            false,
+            false,
+        )
+        .with_blend_mode(BlendMode::Additive),
+    )
+    .await
+    .expect("Failed to add pass");
+    let first = read().await;
+    let second = read().await;
+    assert_eq!(first.b, 255);
+    assert!(second.r > first.r, "{first:?} {second:?}");
+
+    //a pass that clears before drawing discards what accumulated
+    port.add_fixed_pass(
+        PassDescriptor::new(
+            "overwrite".to_string(),
+            fullscreen_vertex_shader(),
+            FragmentShader::new("overwrite", String::new())
+                .with_software(|_| Some([0.0, 1.0, 0.0, 1.0])),
+            BindStyle::new(),
+            DrawCommand::TriangleList(0),
+            false,
+            false,
+        )
+        .with_color_load(LoadOp::Clear(Float4::default())),
+    )
+    .await
+    .expect("Failed to add pass");
+    assert_eq!(
+        read().await,
+        Unorm4 {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0
+        }
+    );
+}
+
+#[async_test]
+async fn passes_are_removed_moved_and_toggled() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let fill = |name: &'static str, color: [f32; 4]| {
+        fullscreen(
+            name,
+            FragmentShader::new(name, String::new()).with_software(move |_| Some(color)),
+            BindStyle::new(),
+        )
+    };
+    let red = port
+        .add_fixed_pass(fill("red", [1.0, 0.0, 0.0, 1.0]))
+        .await
+        .expect("Failed to add pass");
+    let green = port
+        .add_fixed_pass(fill("green", [0.0, 1.0, 0.0, 1.0]))
+        .await
+        .expect("Failed to add pass");
+    let read = || async {
+        port.force_render().await;
+        let framebuffer = port
+            .read_framebuffer::<RGBA8UNorm>()
+            .await
+            .expect("Read framebuffer");
+        let pixel = framebuffer.color[Texel { x: 1, y: 1 }];
+        [pixel.r, pixel.g, pixel.b]
+    };
+    assert_eq!(read().await, [0, 255, 0]);
+
+    port.set_pass_enabled(green, false)
+        .await
+        .expect("Failed to toggle pass");
+    assert_eq!(read().await, [255, 0, 0]);
+    port.set_pass_enabled(green, true)
+        .await
+        .expect("Failed to toggle pass");
+    port.move_pass(green, 0).await.expect("Failed to move pass");
+    assert_eq!(read().await, [255, 0, 0]);
+    port.replace_pass(red, fill("blue", [0.0, 0.0, 1.0, 1.0]))
+        .await
+        .expect("Failed to replace pass");
+    assert_eq!(read().await, [0, 0, 255]);
+    port.remove_pass(red).await.expect("Failed to remove pass");
+    assert_eq!(read().await, [0, 255, 0]);
+}
+
+#[async_test]
+async fn viewport_and_scissor_limit_a_pass() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let fragment_shader =
+        FragmentShader::new("red", String::new()).with_software(|_| Some([1.0, 0.0, 0.0, 1.0]));
+    //the right half of the view, without its top row
+    port.add_fixed_pass(
+        fullscreen("fullscreen", fragment_shader, BindStyle::new())
+            .with_viewport(TargetRect::Fraction {
+                x: 0.5,
+                y: 0.0,
+                width: 0.5,
+                height: 1.0,
+            })
+            .with_scissor(TargetRect::Pixels {
+                x: 0,
+                y: 1,
+                width: 4,
+                height: 3,
+            }),
+    )
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let red: Vec<(u16, u16)> = (0..4)
+        .flat_map(|y| (0..4).map(move |x| (x, y)))
+        .filter(|&(x, y)| framebuffer.color[Texel { x, y }].r == 255)
+        .collect();
+    assert_eq!(red, vec![(2, 1), (3, 1), (2, 2), (3, 2), (2, 3), (3, 3)]);
+}
+
+#[async_test]
+async fn resources_are_addressed_by_group() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let color = r#static::buffer::Buffer::<f32>::new(
+        engine.bound_device().clone(),
+        4,
+        GPUBufferUsage::FragmentShaderRead,
+        "color",
+        |i| [0.0, 0.0, 1.0, 1.0][i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let frames = Arc::new(Mutex::new(Vec::new()));
+    let move_frames = frames.clone();
+    let fragment_shader = FragmentShader::new("blue", String::new()).with_software(move |input| {
+        let resources = input.resources;
+        //the camera, frame counter and time of the global group
+        assert_eq!(resources.element_count::<f32>(BindSlot::in_group(1, 0)), 16);
+        move_frames
+            .lock()
+            .unwrap()
+            .push(resources.element::<u32>(BindSlot::in_group(1, 1), 0));
+        assert!(resources.element::<f32>(BindSlot::in_group(1, 2), 0) > 0.0);
+        Some(std::array::from_fn(|c| {
+            resources.element::<f32>(BindSlot::in_group(2, 3), c)
+        }))
+    });
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_global_group(1);
+    bind_style.bind_static_buffer(BindSlot::in_group(2, 3), Stage::Fragment, &color);
+    port.add_fixed_pass(fullscreen("fullscreen", fragment_shader, bind_style))
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 0, 255, 255));
+    let frames = frames.lock().unwrap();
+    assert_eq!(frames.last(), Some(&(frames[0] + 1)));
+}
+
+#[async_test]
+async fn immediates_are_read_each_frame() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let brightness = Immediates::new(0.0f32);
+    let fragment_shader = FragmentShader::new("brightness", String::new())
+        .with_software(|input| Some([input.resources.immediates::<f32>(), 0.0, 0.0, 1.0]));
+    port.add_fixed_pass(
+        fullscreen("fullscreen", fragment_shader, BindStyle::new()).with_immediates(&brightness),
+    )
+    .await
+    .expect("Failed to add pass");
+
+    for (value, expected) in [(1.0, 255), (0.0, 0)] {
+        brightness.set(value);
+        port.force_render().await;
+        let framebuffer = port
+            .read_framebuffer::<RGBA8UNorm>()
+            .await
+            .expect("Read framebuffer");
+        assert_eq!(framebuffer.color[Texel { x: 2, y: 2 }].r, expected);
+    }
+}
+
+#[async_test]
+async fn indirect_draws_read_compute_results() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    //draws nothing until the compute pass runs
+    let args = r#static::buffer::Buffer::new(
+        engine.bound_device().clone(),
+        1,
+        GPUBufferUsage::Indirect,
+        "args",
+        |_| DrawIndirectArgs::default(),
+    )
+    .await
+    .expect("Failed to create buffer");
+    let shader = ComputeShader::new("cull", String::new()).with_software(|input| {
+        let args = DrawIndirectArgs {
+            vertex_count: 3,
+            instance_count: 1,
+            ..Default::default()
+        };
+        input.resources.store(0, 0, args);
+    });
+    let mut compute_binds = BindStyle::new();
+    compute_binds.bind_static_buffer(BindSlot::new(0), Stage::Compute, &args);
+    port.add_compute_pass(ComputePassDescriptor::new(
+        "cull".to_string(),
+        shader,
+        compute_binds,
+        Dispatch::Workgroups(1, 1, 1),
+    ))
+    .await
+    .expect("Failed to add compute pass");
+    let fragment_shader =
+        FragmentShader::new("green", String::new()).with_software(|_| Some([0.0, 1.0, 0.0, 1.0]));
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_indirect_buffer(&args);
+    port.add_fixed_pass(PassDescriptor::new(
+        "fullscreen".to_string(),
+        fullscreen_vertex_shader(),
+        fragment_shader,
+        bind_style,
+        DrawCommand::Indirect {
+            topology: Topology::TriangleList,
+            draw: 0,
+        },
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    let pixel = framebuffer.color[Texel { x: 2, y: 2 }];
+    assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (0, 255, 0, 255));
+}
+
+#[async_test]
+async fn indirect_draws_start_at_first_instance() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    let args = r#static::buffer::Buffer::new(
+        engine.bound_device().clone(),
+        1,
+        GPUBufferUsage::Indirect,
+        "args",
+        |_| DrawIndirectArgs {
+            vertex_count: 3,
+            instance_count: 1,
+            first_vertex: 0,
+            first_instance: 1,
+        },
+    )
+    .await
+    .expect("Failed to create buffer");
+    //only instance 1 covers the view
+    let vertex_shader =
+        VertexShader::new("second_instance", String::new()).with_software(|input| {
+            let positions = [[-1.0, -1.0], [3.0, -1.0], [-1.0, 3.0]];
+            let [x, y] = positions[input.vertex_index as usize];
+            let scale = if input.instance_index == 1 { 1.0 } else { 0.0 };
+            VertexOutput::new([x * scale, y * scale, 0.0, 1.0])
+        });
+    let fragment_shader =
+        FragmentShader::new("green", String::new()).with_software(|_| Some([0.0, 1.0, 0.0, 1.0]));
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_indirect_buffer(&args);
+    port.add_fixed_pass(PassDescriptor::new(
+        "second_instance".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        DrawCommand::Indirect {
+            topology: Topology::TriangleList,
+            draw: 0,
+        },
+        false,
+        false,
+    ))
+    .await
+    .expect("Failed to add pass");
+    port.force_render().await;
+
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Read framebuffer");
+    assert_eq!(framebuffer.color[Texel { x: 2, y: 2 }].g, 255);
+}
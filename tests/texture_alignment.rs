@@ -54,6 +54,11 @@ async fn test_texture_alignment_ok_width_128() {
 }
 
 /// Helper function to test a specific problematic width
+#[allow(
+    clippy::manual_is_multiple_of,
+    clippy::needless_borrow,
+    clippy::unnecessary_cast
+)]
 async fn test_problematic_width(width: u16) {
     // Calculate bytes per row for RGBA8 format (4 bytes per pixel)
     let bytes_per_row = width as u32 * 4;
@@ -150,7 +155,8 @@ async fn test_problematic_width(width: u16) {
         false,
         false,
     ))
-    .await;
+    .await
+    .expect("Failed to add pass");
 
     //pump renderloop
     port.force_render().await;
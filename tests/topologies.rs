@@ -0,0 +1,216 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Draws lines, points and sub-ranges of shared buffers.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine, fill};
+use images_and_words::bindings::BindStyle;
+use images_and_words::bindings::bind_style::BindSlot;
+use images_and_words::bindings::forward::r#static::buffer::Buffer;
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::bindings::visible_to::GPUBufferUsage;
+use images_and_words::images::Engine;
+use images_and_words::images::port::Port;
+use images_and_words::images::render_pass::{DrawCommand, PassDescriptor, PassError, Topology};
+use images_and_words::images::shader::{FragmentShader, VertexShader};
+use images_and_words::images::vertex_layout::{StepMode, VertexFieldType, VertexLayout};
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// Draws `vertices` (as x, y pairs) in red into a 4x4 framebuffer, and returns which
+/// pixels are red, as rows from the top.
+async fn draw_red(
+    vertices: &[f32],
+    indices: Option<&[u16]>,
+    draw_command: DrawCommand,
+) -> [[bool; 4]; 4] {
+    let engine = engine().await;
+    let port = engine.main_port();
+    port.add_fixed_pass(red(&engine, vertices, indices, draw_command).await)
+        .await
+        .expect("Failed to add pass");
+    port.force_render().await;
+    red_pixels(port).await
+}
+
+/// A red pass drawing `vertices` (as x, y pairs), through `indices` if given.
+async fn red(
+    engine: &Engine,
+    vertices: &[f32],
+    indices: Option<&[u16]>,
+    draw_command: DrawCommand,
+) -> PassDescriptor {
+    let device = engine.bound_device().clone();
+    let vertex_buffer = Buffer::<f32>::new(
+        device.clone(),
+        vertices.len(),
+        GPUBufferUsage::VertexBuffer,
+        "vertices",
+        |i| vertices[i],
+    )
+    .await
+    .expect("Failed to create buffer");
+    let mut layout = VertexLayout::new();
+    layout.add_field("x", VertexFieldType::F32);
+    layout.add_field("y", VertexFieldType::F32);
+    let mut bind_style = BindStyle::new();
+    bind_style.bind_static_vertex_buffer(
+        BindSlot::new(0),
+        &vertex_buffer,
+        layout,
+        StepMode::Vertex,
+    );
+    if let Some(indices) = indices {
+        let index_buffer = Buffer::<u16>::new(
+            device,
+            indices.len(),
+            GPUBufferUsage::Index,
+            "indices",
+            |i| indices[i],
+        )
+        .await
+        .expect("Failed to create buffer");
+        bind_style.bind_static_index_buffer(&index_buffer);
+    }
+
+    let vertex_shader = VertexShader::new(
+        "xy",
+        "@vertex fn vs_main(@location(0) x: f32, @location(1) y: f32) -> @builtin(position) vec4<f32> {
+            return vec4<f32>(x, y, 0.0, 1.0);
+        }"
+        .to_string(),
+    );
+    let fragment_shader = FragmentShader::new("red", fill([1.0, 0.0, 0.0, 1.0]));
+    PassDescriptor::new(
+        "draw".to_string(),
+        vertex_shader,
+        fragment_shader,
+        bind_style,
+        draw_command,
+        false,
+        false,
+    )
+}
+
+async fn red_pixels(port: &Port) -> [[bool; 4]; 4] {
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    std::array::from_fn(|y| {
+        std::array::from_fn(|x| {
+            framebuffer.color[Texel {
+                x: x as u16,
+                y: y as u16,
+            }]
+            .r == 255
+        })
+    })
+}
+
+/// Two quads, as counter-clockwise triangle lists: the bottom-left quarter of clip space,
+/// then the top-right quarter.
+const TWO_QUADS: [f32; 24] = [
+    -1.0, -1.0, 0.0, -1.0, 0.0, 0.0, //
+    -1.0, -1.0, 0.0, 0.0, -1.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, 1.0, 1.0, //
+    0.0, 0.0, 1.0, 1.0, 0.0, 1.0,
+];
+
+#[async_test]
+async fn line_list_draws_one_pixel_rows() {
+    //a horizontal line through the centers of the second row
+    let pixels = draw_red(&[-1.0, 0.25, 1.0, 0.25], None, DrawCommand::LineList(1)).await;
+    assert_eq!(pixels[1], [true; 4]);
+    for row in [0, 2, 3] {
+        assert_eq!(pixels[row], [false; 4], "row {row}");
+    }
+}
+
+#[async_test]
+async fn point_list_draws_single_pixels() {
+    //the centers of pixels (0, 0) and (2, 3)
+    let pixels = draw_red(&[-0.75, 0.75, 0.25, -0.75], None, DrawCommand::PointList(2)).await;
+    let red: Vec<(usize, usize)> = (0..4)
+        .flat_map(|y| (0..4).map(move |x| (x, y)))
+        .filter(|&(x, y)| pixels[y][x])
+        .collect();
+    assert_eq!(red, vec![(0, 0), (2, 3)]);
+}
+
+#[async_test]
+async fn range_draws_part_of_a_vertex_buffer() {
+    let pixels = draw_red(
+        &TWO_QUADS,
+        None,
+        DrawCommand::Range {
+            topology: Topology::TriangleList,
+            first_vertex: 6,
+            first_index: 0,
+            base_vertex: 0,
+            count: 6,
+            instances: 1,
+        },
+    )
+    .await;
+    //only the top-right quad
+    assert!(pixels[0][3] && pixels[1][2]);
+    assert!(!pixels[3][0] && !pixels[2][1]);
+}
+
+#[async_test]
+async fn range_draws_part_of_an_index_buffer() {
+    //the first 4 vertices of each quad make it up as a fan
+    let corners: Vec<f32> = [0, 1, 2, 5, 6, 7, 8, 11]
+        .iter()
+        .flat_map(|&v| [TWO_QUADS[v * 2], TWO_QUADS[v * 2 + 1]])
+        .collect();
+    let pixels = draw_red(
+        &corners,
+        Some(&[0, 1, 2, 0, 2, 3, 0, 1, 2, 0, 2, 3]),
+        DrawCommand::Range {
+            topology: Topology::TriangleList,
+            first_vertex: 0,
+            first_index: 6,
+            base_vertex: 4,
+            count: 6,
+            instances: 1,
+        },
+    )
+    .await;
+    //only the top-right quad
+    assert!(pixels[0][3] && pixels[1][2]);
+    assert!(!pixels[3][0] && !pixels[2][1]);
+}
+
+#[async_test]
+async fn port_refuses_draws_that_count_past_u32_max() {
+    let engine = engine().await;
+    let port = engine.main_port();
+    for draw_command in [
+        DrawCommand::TriangleList(u32::MAX / 2),
+        DrawCommand::LineStrip(u32::MAX),
+        DrawCommand::Range {
+            topology: Topology::PointList,
+            first_vertex: u32::MAX,
+            first_index: 0,
+            base_vertex: 0,
+            count: 1,
+            instances: 1,
+        },
+    ] {
+        let added = port
+            .add_fixed_pass(red(&engine, &TWO_QUADS, None, draw_command).await)
+            .await;
+        assert_eq!(
+            added,
+            Err(PassError::DrawCount {
+                pass: "draw".to_string(),
+                draw_command,
+            })
+        );
+    }
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "backend_wgpu")]
+// SPDX-License-Identifier: Parity-7.0.0 OR PolyForm-Noncommercial-1.0.0
+//! Places passes in part of the view with viewports, and clips them with scissor rectangles.
+#[cfg(target_arch = "wasm32")]
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+mod common;
+
+use common::{engine_with_view, fullscreen_pass};
+use images_and_words::bindings::software::texture::Texel;
+use images_and_words::images::render_pass::{PassDescriptor, TargetRect};
+use images_and_words::images::view::View;
+use images_and_words::pixel_formats::RGBA8UNorm;
+use test_executors::async_test;
+
+/// Renders `passes` into a `size` by `size` view, and returns the red pixels.
+async fn red_pixels(size: u16, passes: Vec<PassDescriptor>) -> Vec<(u16, u16)> {
+    let engine = engine_with_view(View::offscreen(size, size, RGBA8UNorm)).await;
+    let port = engine.main_port();
+    port.add_fixed_passes(passes)
+        .await
+        .expect("Failed to add passes");
+    port.force_render().await;
+    let framebuffer = port
+        .read_framebuffer::<RGBA8UNorm>()
+        .await
+        .expect("Failed to read framebuffer");
+    let mut red = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            if framebuffer.color[Texel { x, y }].r == 255 {
+                red.push((x, y));
+            }
+        }
+    }
+    red
+}
+
+#[async_test]
+async fn viewports_place_passes_in_part_of_the_view() {
+    let top_right = TargetRect::Fraction {
+        x: 0.5,
+        y: 0.0,
+        width: 0.5,
+        height: 0.5,
+    };
+    assert_eq!(
+        red_pixels(4, vec![fullscreen_pass(false).with_viewport(top_right)]).await,
+        vec![(2, 0), (3, 0), (2, 1), (3, 1)]
+    );
+}
+
+#[async_test]
+async fn scissors_clip_passes_to_the_view() {
+    let middle = TargetRect::Pixels {
+        x: 1,
+        y: 1,
+        width: 2,
+        height: 2,
+    };
+    assert_eq!(
+        red_pixels(4, vec![fullscreen_pass(false).with_scissor(middle)]).await,
+        vec![(1, 1), (2, 1), (1, 2), (2, 2)]
+    );
+    //rectangles past the view are clipped to it
+    let corner = TargetRect::Pixels {
+        x: 3,
+        y: 3,
+        width: 10,
+        height: 10,
+    };
+    assert_eq!(
+        red_pixels(4, vec![fullscreen_pass(false).with_scissor(corner)]).await,
+        vec![(3, 3)]
+    );
+}
+
+#[async_test]
+async fn viewports_and_scissors_apply_only_to_their_pass() {
+    let left = TargetRect::Fraction {
+        x: 0.0,
+        y: 0.0,
+        width: 0.25,
+        height: 1.0,
+    };
+    let red = red_pixels(
+        4,
+        vec![
+            fullscreen_pass(false).with_scissor(left),
+            fullscreen_pass(false),
+        ],
+    )
+    .await;
+    assert_eq!(red.len(), 16);
+}
+
+#[async_test]
+async fn fractions_scale_with_the_view() {
+    let bottom_half = TargetRect::Fraction {
+        x: 0.0,
+        y: 0.5,
+        width: 1.0,
+        height: 0.5,
+    };
+    let small = red_pixels(4, vec![fullscreen_pass(false).with_scissor(bottom_half)]).await;
+    assert_eq!(small.len(), 8);
+    assert!(small.iter().all(|&(_, y)| y >= 2));
+    let large = red_pixels(8, vec![fullscreen_pass(false).with_scissor(bottom_half)]).await;
+    assert_eq!(large.len(), 32);
+    assert!(large.iter().all(|&(_, y)| y >= 4));
+}